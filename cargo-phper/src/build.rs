@@ -0,0 +1,195 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Build the extension by `cargo build`.
+
+use anyhow::{bail, Context};
+use cargo_metadata::{Artifact, Message, MetadataCommand};
+use std::{
+    env,
+    ffi::OsStr,
+    fs,
+    io::BufReader,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+#[derive(clap::Args)]
+pub(crate) struct BuildArgs {
+    /// Path to Cargo.toml.
+    #[arg(long, value_name = "PATH")]
+    manifest_path: Option<PathBuf>,
+
+    /// Package of the extension to build.
+    #[arg(short, long, value_name = "SPEC")]
+    package: Option<String>,
+
+    /// Build in release mode.
+    #[arg(short, long)]
+    release: bool,
+
+    /// Build for the target triple.
+    #[arg(long, value_name = "TRIPLE")]
+    target: Option<String>,
+
+    /// Path to `php-config`, also used by the build of `phper-sys`.
+    #[arg(long, env = "PHP_CONFIG", default_value = "php-config")]
+    pub(crate) php_config: PathBuf,
+
+    /// Extra arguments passed to `cargo build`.
+    #[arg(last = true)]
+    cargo_args: Vec<String>,
+}
+
+/// The built extension.
+pub(crate) struct Extension {
+    /// Name of the extension, the same as the name of lib target.
+    pub(crate) name: String,
+    /// Version of the package.
+    pub(crate) version: String,
+    /// Directory of the package.
+    pub(crate) package_dir: PathBuf,
+    /// Path of the renamed extension file.
+    pub(crate) path: PathBuf,
+}
+
+impl Extension {
+    /// File name of the extension, like `hello.so`.
+    pub(crate) fn file_name(&self) -> &OsStr {
+        self.path.file_name().unwrap()
+    }
+
+    /// The ini snippet to enable the extension.
+    pub(crate) fn ini(&self) -> String {
+        format!(
+            "; Enable the {} extension.\nextension={}\n",
+            self.name,
+            self.file_name().to_string_lossy()
+        )
+    }
+}
+
+/// Run `cargo build`, and copy the built `cdylib` next to itself with the
+/// file name PHP expects, `<name>.so` (`<name>.dll` on Windows).
+pub(crate) fn build(args: &BuildArgs) -> anyhow::Result<Extension> {
+    let artifact = cargo_build(args)?;
+
+    let lib_path = artifact
+        .filenames
+        .iter()
+        .map(|path| path.as_std_path())
+        .find(|path| {
+            matches!(
+                path.extension().and_then(OsStr::to_str),
+                Some("so" | "dylib" | "dll")
+            )
+        })
+        .context("the dynamic library of `cdylib` isn't found")?;
+
+    let name = artifact.target.name.clone();
+    let file_name = if lib_path.extension() == Some(OsStr::new("dll")) {
+        format!("{}.dll", name)
+    } else {
+        format!("{}.so", name)
+    };
+    let path = lib_path.with_file_name(file_name);
+    fs::copy(lib_path, &path)
+        .with_context(|| format!("failed to copy extension to `{}`", path.display()))?;
+
+    let mut metadata = MetadataCommand::new();
+    metadata.no_deps();
+    if let Some(manifest_path) = &args.manifest_path {
+        metadata.manifest_path(manifest_path);
+    }
+    let metadata = metadata.exec()?;
+    let package = metadata
+        .packages
+        .iter()
+        .find(|package| package.id == artifact.package_id)
+        .with_context(|| format!("package `{}` isn't found", artifact.package_id))?;
+
+    eprintln!("Built extension `{}`", path.display());
+
+    Ok(Extension {
+        name,
+        version: package.version.to_string(),
+        package_dir: package
+            .manifest_path
+            .parent()
+            .map(|dir| dir.as_std_path().to_path_buf())
+            .unwrap_or_default(),
+        path,
+    })
+}
+
+fn cargo_build(args: &BuildArgs) -> anyhow::Result<Artifact> {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut command = Command::new(cargo);
+    command.args(["build", "--lib", "--message-format=json-render-diagnostics"]);
+    if let Some(manifest_path) = &args.manifest_path {
+        command.arg("--manifest-path").arg(manifest_path);
+    }
+    if let Some(package) = &args.package {
+        command.args(["--package", package]);
+    }
+    if args.release {
+        command.arg("--release");
+    }
+    if let Some(target) = &args.target {
+        command.args(["--target", target]);
+    }
+    command
+        .args(&args.cargo_args)
+        .env("PHP_CONFIG", &args.php_config)
+        .stdout(Stdio::piped());
+
+    let mut child = command.spawn().context("failed to execute `cargo build`")?;
+
+    let mut artifacts = Vec::new();
+    let reader = BufReader::new(child.stdout.take().unwrap());
+    for message in Message::parse_stream(reader) {
+        if let Message::CompilerArtifact(artifact) = message? {
+            if artifact.target.kind.iter().any(|kind| kind == "cdylib") {
+                artifacts.push(artifact);
+            }
+        }
+    }
+
+    let status = child.wait()?;
+    if !status.success() {
+        bail!("`cargo build` failed, {}", status);
+    }
+
+    match artifacts.len() {
+        0 => bail!(
+            "no `cdylib` is built, please add `crate-type = [\"cdylib\"]` to the `[lib]` of \
+             Cargo.toml"
+        ),
+        1 => Ok(artifacts.pop().unwrap()),
+        _ => bail!("multiple `cdylib` are built, please specify the package by `--package`"),
+    }
+}
+
+/// Execute `php-config` with the argument, like `--extension-dir`.
+pub(crate) fn php_config(php_config: &Path, arg: &str) -> anyhow::Result<String> {
+    let output = Command::new(php_config)
+        .arg(arg)
+        .output()
+        .with_context(|| format!("failed to execute `{} {}`", php_config.display(), arg))?;
+    if !output.status.success() {
+        bail!(
+            "`{} {}` failed, {}",
+            php_config.display(),
+            arg,
+            output.status
+        );
+    }
+    Ok(String::from_utf8(output.stdout)?.trim().to_owned())
+}
@@ -0,0 +1,69 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Install the extension into the extension directory of PHP.
+
+use crate::build::{self, php_config, BuildArgs};
+use anyhow::{bail, Context};
+use std::{fs, path::PathBuf};
+
+#[derive(clap::Args)]
+pub(crate) struct InstallArgs {
+    #[command(flatten)]
+    build: BuildArgs,
+
+    /// Write the ini file to enable the extension, into `php-config --ini-dir`
+    /// by default.
+    #[arg(long)]
+    ini: bool,
+
+    /// Directory to write the ini file.
+    #[arg(long, value_name = "DIR", requires = "ini")]
+    ini_dir: Option<PathBuf>,
+}
+
+pub(crate) fn install(args: &InstallArgs) -> anyhow::Result<()> {
+    let extension = build::build(&args.build)?;
+
+    let extension_dir = PathBuf::from(php_config(&args.build.php_config, "--extension-dir")?);
+    let path = extension_dir.join(extension.file_name());
+
+    // Copy to the temporary file and rename it, rather than overwriting the
+    // extension in place, which may be mapped by the running PHP processes.
+    let tmp_path = path.with_extension("tmp");
+    fs::copy(&extension.path, &tmp_path)
+        .and_then(|_| fs::rename(&tmp_path, &path))
+        .with_context(|| format!("failed to install extension to `{}`", path.display()))?;
+
+    eprintln!("Installed extension `{}`", path.display());
+
+    if args.ini {
+        let ini_dir = match &args.ini_dir {
+            Some(ini_dir) => ini_dir.clone(),
+            None => {
+                let ini_dir = php_config(&args.build.php_config, "--ini-dir")?;
+                if ini_dir.is_empty() {
+                    bail!(
+                        "PHP is built without the scan directory of ini files, please specify \
+                         `--ini-dir`"
+                    );
+                }
+                PathBuf::from(ini_dir)
+            }
+        };
+        let ini_path = ini_dir.join(format!("{}.ini", extension.name));
+        fs::write(&ini_path, extension.ini())
+            .with_context(|| format!("failed to write ini file `{}`", ini_path.display()))?;
+
+        eprintln!("Written ini file `{}`", ini_path.display());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+#![warn(rust_2018_idioms)]
+#![warn(clippy::dbg_macro)]
+#![doc = include_str!("../README.md")]
+
+mod build;
+mod install;
+mod package;
+mod test;
+
+use clap::{Parser, Subcommand};
+
+#[derive(Parser)]
+#[command(name = "cargo", bin_name = "cargo")]
+enum Cargo {
+    /// Build, install and package PHP extensions using phper.
+    Phper(PhperArgs),
+}
+
+#[derive(clap::Args)]
+#[command(version, about)]
+struct PhperArgs {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Build the extension, and copy it as `<name>.so` (`<name>.dll` on
+    /// Windows).
+    Build(build::BuildArgs),
+    /// Build the extension, and install it into `php-config --extension-dir`.
+    Install(install::InstallArgs),
+    /// Build the extension, and package it with the stubs into a tarball.
+    Package(package::PackageArgs),
+    /// Run `cargo test` against each of the PHP versions.
+    Test(test::TestArgs),
+}
+
+fn main() -> anyhow::Result<()> {
+    let Cargo::Phper(args) = Cargo::parse();
+    match args.command {
+        Command::Build(args) => {
+            build::build(&args)?;
+        }
+        Command::Install(args) => install::install(&args)?,
+        Command::Package(args) => package::package(&args)?,
+        Command::Test(args) => test::test(&args)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,89 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Package the extension into a tarball.
+
+use crate::build::{self, BuildArgs};
+use anyhow::Context;
+use flate2::{write::GzEncoder, Compression};
+use std::{fs::File, path::PathBuf};
+
+#[derive(clap::Args)]
+pub(crate) struct PackageArgs {
+    #[command(flatten)]
+    build: BuildArgs,
+
+    /// Directory of PHP stubs to include, `stubs` in the package directory by
+    /// default if exists.
+    #[arg(long, value_name = "DIR")]
+    stubs: Option<PathBuf>,
+
+    /// Directory to write the tarball, the directory of the built extension by
+    /// default.
+    #[arg(short, long, value_name = "DIR")]
+    output: Option<PathBuf>,
+}
+
+/// Package the extension into `<name>-<version>.tgz`, layout:
+///
+/// ```text
+/// <name>-<version>/
+/// ├── modules/<name>.so
+/// ├── stubs/
+/// └── <name>.ini
+/// ```
+pub(crate) fn package(args: &PackageArgs) -> anyhow::Result<()> {
+    let extension = build::build(&args.build)?;
+
+    let prefix = format!("{}-{}", extension.name, extension.version);
+    let output = match &args.output {
+        Some(output) => output.clone(),
+        None => extension.path.parent().unwrap().to_path_buf(),
+    };
+    let path = output.join(format!("{}.tgz", prefix));
+
+    let file = File::create(&path)
+        .with_context(|| format!("failed to create tarball `{}`", path.display()))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    builder.append_path_with_name(
+        &extension.path,
+        PathBuf::from(&prefix)
+            .join("modules")
+            .join(extension.file_name()),
+    )?;
+
+    let ini = extension.ini();
+    let mut header = tar::Header::new_gnu();
+    header.set_size(ini.len().try_into().unwrap());
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(
+        &mut header,
+        PathBuf::from(&prefix).join(format!("{}.ini", extension.name)),
+        ini.as_bytes(),
+    )?;
+
+    let stubs = match &args.stubs {
+        Some(stubs) => Some(stubs.clone()),
+        None => Some(extension.package_dir.join("stubs")).filter(|stubs| stubs.is_dir()),
+    };
+    if let Some(stubs) = stubs {
+        builder
+            .append_dir_all(PathBuf::from(&prefix).join("stubs"), &stubs)
+            .with_context(|| format!("failed to package stubs `{}`", stubs.display()))?;
+    }
+
+    builder.into_inner()?.finish()?;
+
+    eprintln!("Packaged extension `{}`", path.display());
+
+    Ok(())
+}
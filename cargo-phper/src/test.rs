@@ -0,0 +1,49 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Run the tests against multiple PHP versions.
+
+use anyhow::bail;
+use phper_test::matrix::{self, PhpInstallation, PHP_BINARIES_ENV};
+
+#[derive(clap::Args)]
+pub(crate) struct TestArgs {
+    /// Comma separated PHP binaries to test with, like `php7.4,php8.2`.
+    #[arg(long, env = PHP_BINARIES_ENV, value_delimiter = ',', required = true)]
+    php_binaries: Vec<String>,
+
+    /// Extra arguments passed to `cargo build` and `cargo test`.
+    #[arg(allow_hyphen_values = true)]
+    cargo_args: Vec<String>,
+
+    /// Extra arguments passed to the test binaries.
+    #[arg(last = true)]
+    test_args: Vec<String>,
+}
+
+pub(crate) fn test(args: &TestArgs) -> anyhow::Result<()> {
+    let installations = args
+        .php_binaries
+        .iter()
+        .map(PhpInstallation::from_php_bin)
+        .collect::<Vec<_>>();
+
+    let results = matrix::run_cargo_test(&installations, &args.cargo_args, &args.test_args);
+
+    let failed = results.iter().filter(|result| !result.success).count();
+    if failed > 0 {
+        bail!(
+            "tests failed with {} of {} PHP versions",
+            failed,
+            results.len()
+        );
+    }
+    Ok(())
+}
@@ -9,11 +9,5 @@
 // See the Mulan PSL v2 for more details.
 
 fn main() {
-    phper_build::register_configures();
-
-    #[cfg(target_os = "macos")]
-    {
-        println!("cargo:rustc-link-arg=-undefined");
-        println!("cargo:rustc-link-arg=dynamic_lookup");
-    }
+    phper_build::register_all();
 }
@@ -0,0 +1,164 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper_sys::*;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The size of the header prefixed to the counted allocations.
+const HEADER: usize = 16;
+
+/// The generation of the request, never zero, unique across the threads, so
+/// the allocation is only uncounted by the request counting it.
+static NEXT_GENERATION: AtomicUsize = AtomicUsize::new(1);
+
+thread_local! {
+    static GENERATION: Cell<usize> = const { Cell::new(0) };
+    static USAGE: Cell<usize> = const { Cell::new(0) };
+    static EXCEEDED: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// The opt-in global allocator adapter, counts the Rust allocations made by
+/// the request thread against `memory_limit`, like the allocations of the Zend
+/// memory manager.
+///
+/// The memory is allocated by [`System`] rather than `emalloc`, because the
+/// allocations of the arbitrary code may be held across requests or dropped by
+/// the other threads, while the `emalloc` memory is reclaimed in bulk at the
+/// end of request, and exhausting it bails out by `longjmp` through the Rust
+/// frames. For the same reason, exceeding the limit doesn't fail the
+/// allocation, the `Error` is thrown when the function handler returns.
+///
+/// Use [`EBox`](crate::EBox), [`EVec`](crate::EVec) and
+/// [`EString`](crate::EString) for the data reclaimed at the end of request.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper_alloc::EAllocator;
+///
+/// #[global_allocator]
+/// static GLOBAL: EAllocator = EAllocator;
+/// ```
+pub struct EAllocator;
+
+impl EAllocator {
+    /// Mark entering the request, called by `phper` in `RINIT`, which also
+    /// enables the `emalloc` allocations of [`EBox`](crate::EBox),
+    /// [`EVec`](crate::EVec) and [`EString`](crate::EString).
+    #[doc(hidden)]
+    pub fn enter_request() {
+        let generation = NEXT_GENERATION.fetch_add(1, Ordering::Relaxed);
+        let _ = GENERATION.try_with(|g| g.set(generation));
+        let _ = USAGE.try_with(|u| u.set(0));
+        let _ = EXCEEDED.try_with(|e| e.set(None));
+    }
+
+    /// Mark leaving the request, called by `phper` in `RSHUTDOWN`.
+    #[doc(hidden)]
+    pub fn leave_request() {
+        let _ = GENERATION.try_with(|g| g.set(0));
+    }
+
+    /// Takes the `memory_limit` exceeded by the allocations since last taken,
+    /// called by `phper` when the function handler returns.
+    #[doc(hidden)]
+    #[inline]
+    pub fn take_exceeded() -> Option<usize> {
+        EXCEEDED.try_with(Cell::take).unwrap_or_default()
+    }
+
+    /// Gets the bytes of the live allocations counted by the current request.
+    pub fn request_usage() -> usize {
+        USAGE.try_with(Cell::get).unwrap_or_default()
+    }
+
+    fn generation() -> usize {
+        GENERATION.try_with(Cell::get).unwrap_or_default()
+    }
+
+    unsafe fn count(size: usize) {
+        let usage = USAGE.try_with(|u| {
+            let usage = u.get() + size;
+            u.set(usage);
+            usage
+        });
+        let limit = phper_memory_limit();
+        if let (Ok(usage), Ok(limit)) = (usage, usize::try_from(limit)) {
+            if phper_zend_memory_usage() + usage > limit {
+                let _ = EXCEEDED.try_with(|e| e.set(Some(limit)));
+            }
+        }
+    }
+
+    fn uncount(size: usize) {
+        let _ = USAGE.try_with(|u| u.set(u.get().saturating_sub(size)));
+    }
+}
+
+/// Asserts the current thread is running the request, before allocating by
+/// `emalloc`.
+#[track_caller]
+pub(crate) fn assert_in_request() {
+    assert_ne!(
+        EAllocator::generation(),
+        0,
+        "emalloc is only usable during the request"
+    );
+}
+
+// The allocation made in the request, with alignment not greater than
+// `HEADER`, is prefixed with a header, records the generation of the request,
+// because the allocation may be deallocated after leaving the request, or by
+// the other threads.
+unsafe impl GlobalAlloc for EAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.align() > HEADER {
+            return System.alloc(layout);
+        }
+
+        let size = match layout.size().checked_add(HEADER) {
+            Some(size) => size,
+            None => return std::ptr::null_mut(),
+        };
+        let ptr = System.alloc(Layout::from_size_align_unchecked(size, HEADER));
+        if ptr.is_null() {
+            return ptr;
+        }
+
+        let generation = Self::generation();
+        if generation != 0 {
+            Self::count(layout.size());
+        }
+
+        ptr.cast::<usize>().write(generation);
+        ptr.add(HEADER)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if layout.align() > HEADER {
+            return System.dealloc(ptr, layout);
+        }
+
+        let ptr = ptr.sub(HEADER);
+        let generation = ptr.cast::<usize>().read();
+        if generation != 0 && generation == Self::generation() {
+            Self::uncount(layout.size());
+        }
+
+        System.dealloc(
+            ptr,
+            Layout::from_size_align_unchecked(layout.size() + HEADER, HEADER),
+        );
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Debug helpers for finding the memory bugs, enabled by feature `debug`.
+//!
+//! The allocations made via `emalloc` by [`EBox`](crate::EBox) and
+//! [`EVec`](crate::EVec) are tracked per thread, and the freed memory is
+//! poisoned with [`POISON_BYTE`], so the use after free is noisy rather than
+//! silent.
+//!
+//! Run with the environment variable `USE_ZEND_ALLOC=0`, the `emalloc` is
+//! backed by the system allocator, so the tools like valgrind and ASAN can
+//! inspect every allocation.
+
+use phper_sys::*;
+use std::cell::Cell;
+
+/// The byte written over the freed memory.
+pub const POISON_BYTE: u8 = 0x5a;
+
+thread_local! {
+    static LIVE_COUNT: Cell<isize> = const { Cell::new(0) };
+    static LIVE_BYTES: Cell<isize> = const { Cell::new(0) };
+}
+
+/// The statistics of the live tracked allocations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocationStats {
+    /// Count of the live allocations.
+    pub count: isize,
+    /// Total bytes of the live allocations.
+    pub bytes: isize,
+}
+
+impl AllocationStats {
+    /// Returns `true` if there is no live allocation.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+}
+
+/// Gets the statistics of the live tracked allocations of current thread.
+pub fn live_allocations() -> AllocationStats {
+    AllocationStats {
+        count: LIVE_COUNT.try_with(Cell::get).unwrap_or_default(),
+        bytes: LIVE_BYTES.try_with(Cell::get).unwrap_or_default(),
+    }
+}
+
+/// Reset the statistics, called by `phper` in `RINIT`, because the
+/// allocations of the previous request are reclaimed.
+#[doc(hidden)]
+pub fn reset() {
+    let _ = LIVE_COUNT.try_with(|c| c.set(0));
+    let _ = LIVE_BYTES.try_with(|c| c.set(0));
+}
+
+/// Returns `true` if the Zend memory manager is used by `emalloc`, `false`
+/// when running with `USE_ZEND_ALLOC=0`.
+pub fn is_zend_mm() -> bool {
+    unsafe { phper_is_zend_mm() }
+}
+
+fn update(count: isize, bytes: isize) {
+    let _ = LIVE_COUNT.try_with(|c| c.set(c.get() + count));
+    let _ = LIVE_BYTES.try_with(|c| c.set(c.get() + bytes));
+}
+
+pub(crate) fn track_alloc(size: usize) {
+    update(1, size as isize);
+}
+
+pub(crate) fn track_realloc(old_size: usize, new_size: usize) {
+    update(0, new_size as isize - old_size as isize);
+}
+
+pub(crate) fn track_dealloc(size: usize) {
+    update(-1, -(size as isize));
+}
+
+/// Poison the memory going to be freed.
+pub(crate) unsafe fn poison(ptr: *mut u8, size: usize) {
+    ptr.write_bytes(POISON_BYTE, size);
+}
@@ -14,6 +14,17 @@
 
 #[macro_use]
 mod macros;
+mod allocator;
+#[cfg(feature = "debug")]
+pub mod debug;
+mod scoped;
+mod string;
+mod vec;
+
+pub use allocator::EAllocator;
+pub use scoped::Scoped;
+pub use string::EString;
+pub use vec::EVec;
 
 use phper_sys::*;
 use std::{
@@ -22,6 +33,22 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
+/// The proof that the current thread is running the request, so the memory
+/// can be allocated by `emalloc`, which is only usable between `RINIT` and
+/// `RSHUTDOWN`, and reclaimed at the end of request.
+///
+/// The `*_in` constructors of [`EBox`], [`EVec`] and [`EString`] require it,
+/// and return the [`Scoped`] allocations which can't outlive it, so they can't
+/// be used outside of the request. The other constructors, and the `Default`,
+/// `From` and `FromIterator` impls, are checked at runtime instead, allocating
+/// outside of the request panics.
+///
+/// # Safety
+///
+/// Only implement it for the marker types which can only be obtained during
+/// the request, like `phper::contexts::RequestContext`.
+pub unsafe trait InRequest {}
+
 /// The Box which use php `emalloc` and `efree` to manage memory.
 ///
 /// TODO Now feature `allocator_api` is still unstable, implement myself, use
@@ -35,24 +62,39 @@ impl<T> EBox<T> {
     ///
     /// # Panic
     ///
-    /// Panic if `size_of::<T>()` equals zero.
+    /// Panic if `size_of::<T>()` equals zero, or outside of the request.
     #[allow(clippy::useless_conversion)]
     pub fn new(x: T) -> Self {
         unsafe {
             assert_ne!(size_of::<T>(), 0);
+            allocator::assert_in_request();
             let ptr: *mut T = phper_emalloc(size_of::<T>().try_into().unwrap()).cast();
             // TODO Deal with ptr is zero, when memory limit is reached.
             ptr.write(x);
+            #[cfg(feature = "debug")]
+            debug::track_alloc(size_of::<T>());
             Self { ptr }
         }
     }
 
+    /// Allocates heap memory using `emalloc` then places `x` into it, which
+    /// can't outlive the request marker `ctx`.
+    ///
+    /// # Panic
+    ///
+    /// Panic if `size_of::<T>()` equals zero.
+    pub fn new_in<'ctx>(ctx: &'ctx impl InRequest, x: T) -> Scoped<'ctx, Self> {
+        Scoped::new(ctx, Self::new(x))
+    }
+
     /// Constructs from a raw pointer.
     ///
     /// # Safety
     ///
     /// Make sure the pointer is from `into_raw`, or created from `emalloc`.
     pub unsafe fn from_raw(raw: *mut T) -> Self {
+        #[cfg(feature = "debug")]
+        debug::track_alloc(size_of::<T>());
         Self { ptr: raw }
     }
 
@@ -60,12 +102,21 @@ impl<T> EBox<T> {
     ///
     /// Will leak memory.
     pub fn into_raw(b: EBox<T>) -> *mut T {
+        #[cfg(feature = "debug")]
+        debug::track_dealloc(size_of::<T>());
         ManuallyDrop::new(b).ptr
     }
 
     /// Consumes the `EBox`, returning the wrapped value.
     pub fn into_inner(self) -> T {
-        unsafe { self.ptr.read() }
+        let b = ManuallyDrop::new(self);
+        unsafe {
+            let x = b.ptr.read();
+            #[cfg(feature = "debug")]
+            debug::track_dealloc(size_of::<T>());
+            phper_efree(b.ptr.cast());
+            x
+        }
     }
 }
 
@@ -87,6 +138,11 @@ impl<T> Drop for EBox<T> {
     fn drop(&mut self) {
         unsafe {
             self.ptr.drop_in_place();
+            #[cfg(feature = "debug")]
+            {
+                debug::track_dealloc(size_of::<T>());
+                debug::poison(self.ptr.cast(), size_of::<T>());
+            }
             phper_efree(self.ptr.cast());
         }
     }
@@ -8,16 +8,20 @@
 // NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
 // See the Mulan PSL v2 for more details.
 
-/// Wrapper of `EBox::new`.
+/// Wrapper of `EBox::new`, or `EBox::new_in` with the request marker.
 ///
 /// # Examples
 ///
 /// ```no_test
 /// let _ = ebox!(1);
+/// let _ = ebox!(ctx, 1);
 /// ```
 #[macro_export]
 macro_rules! ebox {
     ($arg:tt) => {{
         $crate::EBox::new($arg)
     }};
+    ($ctx:expr, $arg:tt) => {{
+        $crate::EBox::new_in($ctx, $arg)
+    }};
 }
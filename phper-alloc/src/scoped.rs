@@ -0,0 +1,192 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use crate::{EBox, EString, EVec, InRequest};
+use std::{
+    fmt::{self, Debug, Display},
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+/// The allocation of [`EBox`], [`EVec`] or [`EString`] made with the request
+/// marker, which can't outlive the marker, so it can't be moved into the
+/// `static` or the module state and used after `RSHUTDOWN`.
+///
+/// It dereferences to the content rather than the container, so the container
+/// can't be moved out by `mem::replace` and the like.
+pub struct Scoped<'ctx, T> {
+    inner: T,
+    _p: PhantomData<&'ctx ()>,
+}
+
+impl<'ctx, T> Scoped<'ctx, T> {
+    pub(crate) fn new(_ctx: &'ctx impl InRequest, inner: T) -> Self {
+        Self {
+            inner,
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<T> Scoped<'_, EBox<T>> {
+    /// Consumes the `EBox`, returning the wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner()
+    }
+}
+
+impl<T> Scoped<'_, EVec<T>> {
+    /// Returns the number of elements the vector can hold without
+    /// reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` more elements.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional);
+    }
+
+    /// Reserves capacity for exactly `additional` more elements.
+    #[inline]
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.inner.reserve_exact(additional);
+    }
+
+    /// Appends an element to the back.
+    #[inline]
+    pub fn push(&mut self, value: T) {
+        self.inner.push(value);
+    }
+
+    /// Removes the last element and returns it, or `None` if it is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.inner.pop()
+    }
+
+    /// Inserts an element at position `index`, shifting all elements after it
+    /// to the right.
+    #[inline]
+    pub fn insert(&mut self, index: usize, value: T) {
+        self.inner.insert(index, value);
+    }
+
+    /// Removes and returns the element at position `index`, shifting all
+    /// elements after it to the left.
+    #[inline]
+    pub fn remove(&mut self, index: usize) -> T {
+        self.inner.remove(index)
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping the
+    /// rest.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        self.inner.truncate(len);
+    }
+
+    /// Clears the vector, removing all values, keep the allocated memory.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+}
+
+impl<T> Extend<T> for Scoped<'_, EVec<T>> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.inner.extend(iter);
+    }
+}
+
+impl Scoped<'_, EString> {
+    /// Returns the capacity in bytes.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` bytes more.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional);
+    }
+
+    /// Appends the string slice onto the end.
+    #[inline]
+    pub fn push_str(&mut self, s: &str) {
+        self.inner.push_str(s);
+    }
+
+    /// Appends the char onto the end.
+    #[inline]
+    pub fn push(&mut self, c: char) {
+        self.inner.push(c);
+    }
+
+    /// Truncates the string to zero length, keep the allocated memory.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+}
+
+impl fmt::Write for Scoped<'_, EString> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.push_str(s);
+        Ok(())
+    }
+}
+
+impl<T: Deref> Deref for Scoped<'_, T> {
+    type Target = T::Target;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T: DerefMut> DerefMut for Scoped<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T: Clone> Clone for Scoped<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for Scoped<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<T: Eq> Eq for Scoped<'_, T> {}
+
+impl<T: Debug> Debug for Scoped<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+
+impl<T: Display> Display for Scoped<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.inner, f)
+    }
+}
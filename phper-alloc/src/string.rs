@@ -0,0 +1,172 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use crate::{EVec, InRequest, Scoped};
+use std::{
+    fmt::{self, Debug, Display},
+    ops::{Deref, DerefMut},
+    str,
+};
+
+/// The String which use php `emalloc`, `erealloc` and `efree` to manage
+/// memory, so the allocations count against `memory_limit`, and are reclaimed
+/// at the end of request.
+///
+/// *Don't hold it across requests, allocating outside of the request panics,
+/// use the `*_in` constructors to check it at compile time.*
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct EString {
+    vec: EVec<u8>,
+}
+
+impl EString {
+    /// Constructs a new, empty `EString`, will not allocate until bytes are
+    /// pushed.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { vec: EVec::new() }
+    }
+
+    /// Constructs a new, empty `EString` with at least the specified
+    /// capacity.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            vec: EVec::with_capacity(capacity),
+        }
+    }
+
+    /// Constructs a new, empty `EString`, which can't outlive the request
+    /// marker `ctx`.
+    #[inline]
+    pub fn new_in<'ctx>(ctx: &'ctx impl InRequest) -> Scoped<'ctx, Self> {
+        Scoped::new(ctx, Self::new())
+    }
+
+    /// Constructs a new, empty `EString` with at least the specified
+    /// capacity, which can't outlive the request marker `ctx`.
+    #[inline]
+    pub fn with_capacity_in<'ctx>(
+        ctx: &'ctx impl InRequest, capacity: usize,
+    ) -> Scoped<'ctx, Self> {
+        Scoped::new(ctx, Self::with_capacity(capacity))
+    }
+
+    /// Returns the capacity in bytes.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` bytes more.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.vec.reserve(additional);
+    }
+
+    /// Appends the string slice onto the end.
+    pub fn push_str(&mut self, s: &str) {
+        self.vec.extend(s.bytes());
+    }
+
+    /// Appends the char onto the end.
+    pub fn push(&mut self, c: char) {
+        self.push_str(c.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Truncates the string to zero length, keep the allocated memory.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear();
+    }
+
+    /// Extracts a string slice containing the entire string.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(&self.vec) }
+    }
+
+    /// Extracts a mutable string slice containing the entire string.
+    #[inline]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        unsafe { str::from_utf8_unchecked_mut(&mut self.vec) }
+    }
+
+    /// Converts into the bytes vector.
+    #[inline]
+    pub fn into_bytes(self) -> EVec<u8> {
+        self.vec
+    }
+}
+
+impl Deref for EString {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl DerefMut for EString {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_str()
+    }
+}
+
+impl AsRef<str> for EString {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<[u8]> for EString {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl From<&str> for EString {
+    fn from(s: &str) -> Self {
+        let mut es = Self::with_capacity(s.len());
+        es.push_str(s);
+        es
+    }
+}
+
+impl PartialEq<str> for EString {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for EString {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl fmt::Write for EString {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+impl Debug for EString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Display for EString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
@@ -0,0 +1,349 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use crate::{allocator, InRequest, Scoped};
+use phper_sys::*;
+use std::{
+    fmt::{self, Debug},
+    mem::{align_of, size_of, ManuallyDrop},
+    ops::{Deref, DerefMut},
+    ptr::{self, NonNull},
+    slice,
+};
+
+/// The alignment guaranteed by `emalloc`.
+pub(crate) const MIN_ALIGN: usize = 8;
+
+/// The Vec which use php `emalloc`, `erealloc` and `efree` to manage memory,
+/// so the allocations count against `memory_limit`, and are reclaimed at the
+/// end of request.
+///
+/// *Don't hold it across requests, allocating outside of the request panics,
+/// use the `*_in` constructors to check it at compile time.*
+pub struct EVec<T> {
+    ptr: NonNull<T>,
+    len: usize,
+    cap: usize,
+}
+
+impl<T> EVec<T> {
+    const IS_ZST: bool = size_of::<T>() == 0;
+
+    /// Constructs a new, empty `EVec`, will not allocate until elements are
+    /// pushed.
+    pub const fn new() -> Self {
+        Self {
+            ptr: NonNull::dangling(),
+            len: 0,
+            cap: if Self::IS_ZST { usize::MAX } else { 0 },
+        }
+    }
+
+    /// Constructs a new, empty `EVec` with at least the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut v = Self::new();
+        v.reserve_exact(capacity);
+        v
+    }
+
+    /// Constructs a new, empty `EVec`, which can't outlive the request marker
+    /// `ctx`.
+    #[inline]
+    pub fn new_in<'ctx>(ctx: &'ctx impl InRequest) -> Scoped<'ctx, Self> {
+        Scoped::new(ctx, Self::new())
+    }
+
+    /// Constructs a new, empty `EVec` with at least the specified capacity,
+    /// which can't outlive the request marker `ctx`.
+    #[inline]
+    pub fn with_capacity_in<'ctx>(
+        ctx: &'ctx impl InRequest, capacity: usize,
+    ) -> Scoped<'ctx, Self> {
+        Scoped::new(ctx, Self::with_capacity(capacity))
+    }
+
+    /// Returns the number of elements the vector can hold without
+    /// reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Reserves capacity for at least `additional` more elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self.len.checked_add(additional).expect("capacity overflow");
+        if required <= self.cap {
+            return;
+        }
+        let new_cap = required.max(self.cap * 2).max(4);
+        self.grow(new_cap);
+    }
+
+    /// Reserves capacity for exactly `additional` more elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        let required = self.len.checked_add(additional).expect("capacity overflow");
+        if required <= self.cap {
+            return;
+        }
+        self.grow(required);
+    }
+
+    #[allow(clippy::useless_conversion)]
+    fn grow(&mut self, new_cap: usize) {
+        assert!(
+            align_of::<T>() <= MIN_ALIGN,
+            "alignment is greater than emalloc guaranteed"
+        );
+        allocator::assert_in_request();
+
+        let size = new_cap
+            .checked_mul(size_of::<T>())
+            .expect("capacity overflow");
+        unsafe {
+            let ptr = if self.cap == 0 {
+                #[cfg(feature = "debug")]
+                crate::debug::track_alloc(size);
+                phper_emalloc(size.try_into().unwrap())
+            } else {
+                #[cfg(feature = "debug")]
+                crate::debug::track_realloc(self.cap * size_of::<T>(), size);
+                phper_erealloc(self.ptr.as_ptr().cast(), size.try_into().unwrap())
+            };
+            self.ptr = NonNull::new(ptr.cast()).expect("out of memory");
+        }
+        self.cap = new_cap;
+    }
+
+    /// Appends an element to the back.
+    pub fn push(&mut self, value: T) {
+        if self.len == self.cap {
+            self.reserve(1);
+        }
+        unsafe {
+            self.ptr.as_ptr().add(self.len).write(value);
+        }
+        self.len += 1;
+    }
+
+    /// Removes the last element and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            unsafe { Some(self.ptr.as_ptr().add(self.len).read()) }
+        }
+    }
+
+    /// Inserts an element at position `index`, shifting all elements after it
+    /// to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, value: T) {
+        assert!(index <= self.len, "insertion index is out of bounds");
+        if self.len == self.cap {
+            self.reserve(1);
+        }
+        unsafe {
+            let p = self.ptr.as_ptr().add(index);
+            ptr::copy(p, p.add(1), self.len - index);
+            p.write(value);
+        }
+        self.len += 1;
+    }
+
+    /// Removes and returns the element at position `index`, shifting all
+    /// elements after it to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= len`.
+    pub fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "removal index is out of bounds");
+        unsafe {
+            self.len -= 1;
+            let p = self.ptr.as_ptr().add(index);
+            let value = p.read();
+            ptr::copy(p.add(1), p, self.len - index);
+            value
+        }
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping the
+    /// rest.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        unsafe {
+            let tail = ptr::slice_from_raw_parts_mut(self.ptr.as_ptr().add(len), self.len - len);
+            self.len = len;
+            ptr::drop_in_place(tail);
+        }
+    }
+
+    /// Clears the vector, removing all values, keep the allocated memory.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Extracts a slice containing the entire vector.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Extracts a mutable slice of the entire vector.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Decomposes into raw components `(pointer, length, capacity)`.
+    ///
+    /// Will leak memory.
+    pub fn into_raw_parts(self) -> (*mut T, usize, usize) {
+        #[cfg(feature = "debug")]
+        if !Self::IS_ZST && self.cap > 0 {
+            crate::debug::track_dealloc(self.cap * size_of::<T>());
+        }
+        let v = ManuallyDrop::new(self);
+        (v.ptr.as_ptr(), v.len, v.cap)
+    }
+
+    /// Constructs from the raw components.
+    ///
+    /// # Safety
+    ///
+    /// Make sure the raw components are from `into_raw_parts`.
+    pub unsafe fn from_raw_parts(ptr: *mut T, len: usize, cap: usize) -> Self {
+        #[cfg(feature = "debug")]
+        if !Self::IS_ZST && cap > 0 {
+            crate::debug::track_alloc(cap * size_of::<T>());
+        }
+        Self {
+            ptr: NonNull::new_unchecked(ptr),
+            len,
+            cap,
+        }
+    }
+}
+
+impl<T> Default for EVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Deref for EVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T> DerefMut for EVec<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: Debug> Debug for EVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_slice(), f)
+    }
+}
+
+impl<T: Clone> Clone for EVec<T> {
+    fn clone(&self) -> Self {
+        self.as_slice().into()
+    }
+}
+
+impl<T: PartialEq> PartialEq for EVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq> Eq for EVec<T> {}
+
+impl<T: Clone> From<&[T]> for EVec<T> {
+    fn from(s: &[T]) -> Self {
+        let mut v = Self::with_capacity(s.len());
+        v.extend(s.iter().cloned());
+        v
+    }
+}
+
+impl<T> Extend<T> for EVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for EVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut v = Self::new();
+        v.extend(iter);
+        v
+    }
+}
+
+impl<'a, T> IntoIterator for &'a EVec<T> {
+    type IntoIter = slice::Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut EVec<T> {
+    type IntoIter = slice::IterMut<'a, T>;
+    type Item = &'a mut T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> Drop for EVec<T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.as_mut_slice());
+            if !Self::IS_ZST && self.cap > 0 {
+                #[cfg(feature = "debug")]
+                {
+                    let size = self.cap * size_of::<T>();
+                    crate::debug::track_dealloc(size);
+                    crate::debug::poison(self.ptr.as_ptr().cast(), size);
+                }
+                phper_efree(self.ptr.as_ptr().cast());
+            }
+        }
+    }
+}
@@ -13,6 +13,7 @@
 #![doc = include_str!("../README.md")]
 
 use phper_sys::*;
+use std::env;
 
 /// Register all php build relative configure parameters, used in `build.rs`.
 pub fn register_all() {
@@ -20,7 +21,26 @@ pub fn register_all() {
     register_configures();
 }
 
+/// The minor versions of PHP supported by phper, used to emit the cumulative
+/// `phper_php_version_at_least` cfg.
+const PHP_VERSIONS: &[(u32, u32)] = &[
+    (7, 0),
+    (7, 1),
+    (7, 2),
+    (7, 3),
+    (7, 4),
+    (8, 0),
+    (8, 1),
+    (8, 2),
+    (8, 3),
+];
+
 /// Register useful rust cfg for project using phper.
+///
+/// Besides the version parts, `phper_php_version = "8.1"` is the minor version
+/// of PHP, and `phper_php_version_at_least = "x.y"` is emitted for every
+/// supported version not newer than it, so the apis introduced since some
+/// version can be gated like `#[cfg(phper_php_version_at_least = "8.1")]`.
 pub fn register_configures() {
     // versions
     println!(
@@ -35,6 +55,18 @@ pub fn register_configures() {
         "cargo:rustc-cfg=phper_release_version=\"{}\"",
         PHP_RELEASE_VERSION
     );
+    println!(
+        "cargo:rustc-cfg=phper_php_version=\"{}.{}\"",
+        PHP_MAJOR_VERSION, PHP_MINOR_VERSION
+    );
+    for (major, minor) in PHP_VERSIONS {
+        if (*major, *minor) <= (PHP_MAJOR_VERSION, PHP_MINOR_VERSION) {
+            println!(
+                "cargo:rustc-cfg=phper_php_version_at_least=\"{}.{}\"",
+                major, minor
+            );
+        }
+    }
 
     if PHP_DEBUG != 0 {
         println!("cargo:rustc-cfg=phper_debug");
@@ -46,10 +78,33 @@ pub fn register_configures() {
 }
 
 /// Register link arguments for os-specified situation.
+///
+/// On macOS, the symbols of PHP are resolved when the extension is loaded, so
+/// link with `-undefined dynamic_lookup`.
+///
+/// On musl targets (like Alpine), the `cdylib` can't be built with the static
+/// C runtime, which is enabled by default for `*-unknown-linux-musl`, so warn
+/// to build with `-C target-feature=-crt-static`. Nothing else is changed for
+/// musl here, the glibc-only apis are guarded in `phper-sys`.
+///
+/// The target is read from the environment variables set by cargo for the
+/// build script, so it works in cross compiling.
 pub fn register_link_args() {
-    #[cfg(target_os = "macos")]
-    {
+    if env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("macos") {
         println!("cargo:rustc-link-arg=-undefined");
         println!("cargo:rustc-link-arg=dynamic_lookup");
     }
+
+    if env::var("CARGO_CFG_TARGET_ENV").as_deref() == Ok("musl") && is_crt_static() {
+        println!(
+            "cargo:warning=The static C runtime is enabled for musl target, the `cdylib` of PHP \
+             extension can't be built, please set `RUSTFLAGS=\"-C target-feature=-crt-static\"`."
+        );
+    }
+}
+
+fn is_crt_static() -> bool {
+    env::var("CARGO_CFG_TARGET_FEATURE")
+        .map(|features| features.split(',').any(|feature| feature == "crt-static"))
+        .unwrap_or_default()
 }
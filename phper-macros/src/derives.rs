@@ -7,3 +7,85 @@
 // KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
 // NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
 // See the Mulan PSL v2 for more details.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Attribute, Data, DataStruct, DeriveInput, Fields, LitStr};
+
+pub(crate) fn derive_ini_config(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match ini_config(&input) {
+        Ok(result) => result.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn ini_config(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let prefix = parse_ini_attr(&input.attrs, "prefix")?.ok_or_else(|| {
+        syn::Error::new_spanned(&input.ident, "missing attribute `#[ini(prefix = \"...\")]`")
+    })?;
+
+    let fields = match &input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "`IniConfig` only supports the struct with named fields",
+            ));
+        }
+    };
+
+    let mut idents = Vec::new();
+    let mut tys = Vec::new();
+    let mut names = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let name = match parse_ini_attr(&field.attrs, "rename")? {
+            Some(name) => name,
+            None => ident.to_string(),
+        };
+        idents.push(ident);
+        tys.push(&field.ty);
+        names.push(format!("{}.{}", prefix, name));
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::phper::ini::IniConfig for #ident #ty_generics #where_clause {
+            fn ini_entries() -> ::std::vec::Vec<(&'static str, ::std::string::String)> {
+                let defaults = <Self as ::std::default::Default>::default();
+                ::std::vec![
+                    #((#names, ::phper::ini::IntoIniValue::into_ini_value(defaults.#idents)),)*
+                ]
+            }
+
+            fn load() -> Self {
+                Self {
+                    #(#idents: <#tys as ::phper::ini::FromIniValue>::from_ini_value(#names),)*
+                }
+            }
+        }
+    })
+}
+
+/// Parses the value of `#[ini(key = "value")]`, the other keys are rejected.
+fn parse_ini_attr(attrs: &[Attribute], key: &str) -> syn::Result<Option<String>> {
+    let mut value = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("ini")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident(key) {
+                value = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error(format!("unsupported ini attribute, expect `{}`", key)))
+            }
+        })?;
+    }
+    Ok(value)
+}
@@ -54,6 +54,11 @@ pub fn c_str_ptr(input: TokenStream) -> TokenStream {
 
 /// PHP module entry, wrap the `phper::modules::Module` write operation.
 ///
+/// One crate (cdylib) can only define one PHP extension, the exported symbol
+/// `get_module` is unique, so define it twice fails to compile or link. The
+/// module entry of another extension built in the same library fails its
+/// `MINIT`.
+///
 /// # Examples
 ///
 /// ```no_test
@@ -76,3 +81,28 @@ pub fn c_str_ptr(input: TokenStream) -> TokenStream {
 pub fn php_get_module(attr: TokenStream, input: TokenStream) -> TokenStream {
     inner::php_get_module(attr, input)
 }
+
+/// Derive `phper::ini::IniConfig` for the struct with named fields, the
+/// fields are mapped to the ini entries named `{prefix}.{field_name}`.
+///
+/// - `#[ini(prefix = "...")]` on the struct is required.
+///
+/// - `#[ini(rename = "...")]` on the field overrides the name of entry.
+///
+/// The struct should implement [`Default`] for the default values.
+///
+/// # Examples
+///
+/// ```no_test
+/// #[derive(Default, phper::IniConfig)]
+/// #[ini(prefix = "foo")]
+/// struct FooConfig {
+///     enable: bool,
+///     #[ini(rename = "max_size")]
+///     size: i64,
+/// }
+/// ```
+#[proc_macro_derive(IniConfig, attributes(ini))]
+pub fn derive_ini_config(input: TokenStream) -> TokenStream {
+    derives::derive_ini_config(input)
+}
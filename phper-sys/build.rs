@@ -19,7 +19,17 @@ fn main() {
     let php_config = env::var("PHP_CONFIG").unwrap_or_else(|_| "php-config".to_string());
 
     let includes = execute_command(&[php_config.as_str(), "--includes"]);
-    let includes = includes.split(' ').collect::<Vec<_>>();
+    let mut includes = includes
+        .split(' ')
+        .map(ToOwned::to_owned)
+        .collect::<Vec<_>>();
+
+    // Enable the apis of optional extensions, require their headers.
+    for feature in ["PDO", "SESSION"] {
+        if env::var_os(format!("CARGO_FEATURE_{}", feature)).is_some() {
+            includes.push(format!("-DPHPER_{}", feature));
+        }
+    }
 
     // Generate libphpwrapper.a.
 
@@ -32,6 +42,7 @@ fn main() {
     // Generate bindgen file.
     let include_dirs = includes
         .iter()
+        .filter(|include| include.starts_with("-I"))
         .map(|include| &include[2..])
         .collect::<Vec<_>>();
 
@@ -13,15 +13,55 @@
 #include <php.h>
 #include <php_ini.h>
 
+#include <ext/hash/php_hash.h>
 #include <ext/standard/info.h>
 #include <main/SAPI.h>
+#include <zend_closures.h>
 #include <zend_exceptions.h>
+#include <zend_extensions.h>
+#include <zend_highlight.h>
 #include <zend_interfaces.h>
+#include <zend_smart_str.h>
 
 #if PHP_MAJOR_VERSION >= 8
 #include <zend_observer.h>
 #endif
 
+#if PHP_VERSION_ID >= 80100
+#include <zend_enum.h>
+#endif
+
+#if PHP_VERSION_ID >= 80200
+#include <ext/random/php_random.h>
+#else
+#include <ext/standard/php_random.h>
+#endif
+
+#if defined(PHPER_PDO) && PHP_VERSION_ID >= 80100
+#include <ext/pdo/php_pdo.h>
+#include <ext/pdo/php_pdo_driver.h>
+#endif
+
+#if defined(PHPER_SESSION) && PHP_VERSION_ID >= 70100
+#include <ext/session/php_session.h>
+#endif
+
+#ifndef PHP_WIN32
+#include <fcntl.h>
+#include <pthread.h>
+#include <signal.h>
+#include <sys/time.h>
+#include <sys/wait.h>
+#include <time.h>
+#include <unistd.h>
+// The `backtrace` isn't provided by musl, keep the glibc-only apis guarded, so
+// the extensions can be loaded by the PHP built with musl (like Alpine).
+#if defined(__GLIBC__) || defined(__APPLE__)
+#include <execinfo.h>
+#define PHPER_HAVE_BACKTRACE 1
+#endif
+#endif
+
 typedef ZEND_INI_MH(phper_zend_ini_mh);
 
 typedef zend_class_entry *
@@ -55,6 +95,10 @@ bool phper_z_refcounted_p(zval *zval_ptr) {
     return Z_REFCOUNTED_P(zval_ptr);
 }
 
+uint32_t phper_z_refcount_p(zval *zval_ptr) {
+    return Z_REFCOUNT_P(zval_ptr);
+}
+
 int phper_z_res_handle_p(const zval *val) {
     return Z_RES_HANDLE_P(val);
 }
@@ -183,14 +227,142 @@ void phper_convert_to_string(zval *op) {
     convert_to_string(op);
 }
 
+void phper_zval_make_ref(zval *zv) {
+    ZVAL_MAKE_REF(zv);
+}
+
 void phper_separate_array(zval *zv) {
     SEPARATE_ARRAY(zv);
 }
 
+void phper_zval_separate(zval *zv) {
+    switch (Z_TYPE_P(zv)) {
+    case IS_STRING: {
+        zend_string *str = Z_STR_P(zv);
+        if (ZSTR_IS_INTERNED(str) || GC_REFCOUNT(str) > 1) {
+            ZVAL_NEW_STR(zv, zend_string_init(ZSTR_VAL(str), ZSTR_LEN(str), 0));
+            zend_string_release(str);
+        }
+        break;
+    }
+    case IS_ARRAY:
+        SEPARATE_ARRAY(zv);
+        break;
+    }
+}
+
+// Follows the indirect zval of the symbol tables and the property tables, null
+// if the slot is undefined, like the unset variable.
+zval *phper_zval_defined(zval *zv) {
+    if (zv != NULL && Z_TYPE_P(zv) == IS_INDIRECT) {
+        zv = Z_INDIRECT_P(zv);
+    }
+    if (zv == NULL || Z_TYPE_P(zv) == IS_UNDEF) {
+        return NULL;
+    }
+    return zv;
+}
+
+#if PHP_VERSION_ID >= 80100
+// The position of argument for the deprecation messages, `0` if the zval isn't
+// the argument of the executing function.
+static uint32_t phper_zpp_arg_num(const zval *arg) {
+    zend_execute_data *ex = EG(current_execute_data);
+    if (ex == NULL || ex->func == NULL) {
+        return 0;
+    }
+    zval *first = ZEND_CALL_ARG(ex, 1);
+    uint32_t num_args = ZEND_CALL_NUM_ARGS(ex);
+    if (arg < first || arg >= first + num_args) {
+        return 0;
+    }
+    return (uint32_t)(arg - first) + 1;
+}
+#endif
+
+bool phper_zpp_long(zval *arg, zend_long *dest) {
+    if (EXPECTED(Z_TYPE_P(arg) == IS_LONG)) {
+        *dest = Z_LVAL_P(arg);
+        return true;
+    }
+#if PHP_VERSION_ID >= 80100
+    return zend_parse_arg_long_slow(arg, dest, phper_zpp_arg_num(arg));
+#else
+    return zend_parse_arg_long_slow(arg, dest);
+#endif
+}
+
+bool phper_zpp_double(zval *arg, double *dest) {
+    if (EXPECTED(Z_TYPE_P(arg) == IS_DOUBLE)) {
+        *dest = Z_DVAL_P(arg);
+        return true;
+    }
+#if PHP_VERSION_ID >= 80100
+    return zend_parse_arg_double_slow(arg, dest, phper_zpp_arg_num(arg));
+#else
+    return zend_parse_arg_double_slow(arg, dest);
+#endif
+}
+
+bool phper_zpp_bool(zval *arg, bool *dest) {
+    if (EXPECTED(Z_TYPE_P(arg) == IS_TRUE || Z_TYPE_P(arg) == IS_FALSE)) {
+        *dest = Z_TYPE_P(arg) == IS_TRUE;
+        return true;
+    }
+#if PHP_VERSION_ID >= 80100
+    return zend_parse_arg_bool_slow(arg, dest, phper_zpp_arg_num(arg));
+#else
+    zend_bool b;
+    if (!zend_parse_arg_bool_slow(arg, &b)) {
+        return false;
+    }
+    *dest = b;
+    return true;
+#endif
+}
+
+zend_string *phper_zpp_str(zval *arg) {
+    zend_string *dest = NULL;
+    if (EXPECTED(Z_TYPE_P(arg) == IS_STRING)) {
+        return Z_STR_P(arg);
+    }
+#if PHP_VERSION_ID >= 80100
+    if (!zend_parse_arg_str_slow(arg, &dest, phper_zpp_arg_num(arg))) {
+        return NULL;
+    }
+#else
+    if (!zend_parse_arg_str_slow(arg, &dest)) {
+        return NULL;
+    }
+#endif
+    return dest;
+}
+
 // ==================================================
 // string apis:
 // ==================================================
 
+void phper_smart_str_appendl(smart_str *dest, const char *src, size_t len) {
+    smart_str_appendl(dest, src, len);
+}
+
+void phper_smart_str_alloc(smart_str *str, size_t len) {
+    smart_str_alloc(str, len, 0);
+}
+
+// Take the built string, the `str` is reset to empty.
+zend_string *phper_smart_str_extract(smart_str *str) {
+    zend_string *s;
+    smart_str_0(str);
+    if (!str->s) {
+        return ZSTR_EMPTY_ALLOC();
+    }
+    s = str->s;
+    str->s = NULL;
+    str->a = 0;
+    return s;
+}
+
 zend_string *phper_zend_new_interned_string(zend_string *str) {
     return zend_new_interned_string(str);
 }
@@ -208,6 +380,22 @@ void phper_zend_string_release(zend_string *s) {
     return zend_string_release(s);
 }
 
+zend_string *phper_zend_string_init_interned(const char *str, size_t len) {
+#if PHP_VERSION_ID >= 70300
+    return zend_string_init_interned(str, len, 1);
+#else
+    return zend_new_interned_string(zend_string_init(str, len, 1));
+#endif
+}
+
+bool phper_zstr_is_interned(const zend_string *s) {
+    return ZSTR_IS_INTERNED(s);
+}
+
+bool phper_zstr_is_persistent(const zend_string *s) {
+    return (GC_FLAGS(s) & IS_STR_PERSISTENT) != 0;
+}
+
 #if PHP_VERSION_ID < 80000
 static zend_string *phper_zend_string_concat3(const char *str1, size_t str1_len,
                                               const char *str2, size_t str2_len,
@@ -242,6 +430,10 @@ zend_string *phper_zend_string_copy(zend_string *s) {
     return zend_string_copy(s);
 }
 
+bool phper_zend_string_equals(const zend_string *s1, const zend_string *s2) {
+    return zend_string_equals((zend_string *)s1, (zend_string *)s2);
+}
+
 // ==================================================
 // array apis:
 // ==================================================
@@ -274,7 +466,7 @@ bool phper_zend_hash_str_exists(const HashTable *ht, const char *str,
 }
 
 bool phper_zend_hash_index_exists(const HashTable *ht, zend_ulong h) {
-    return zend_hash_index_exists(ht, h) != 0;
+    return phper_zval_defined(zend_hash_index_find(ht, h)) != NULL;
 }
 
 zend_array *phper_zend_new_array(uint32_t size) {
@@ -287,12 +479,114 @@ zend_array *phper_zend_new_array(uint32_t size) {
 #endif
 }
 
+zend_array *phper_zend_new_persistent_array(uint32_t size) {
+    HashTable *ht = pemalloc(sizeof(HashTable), 1);
+    zend_hash_init(ht, size, NULL, ZVAL_INTERNAL_PTR_DTOR, 1);
+    return ht;
+}
+
+bool phper_zend_array_is_persistent(const zend_array *ht) {
+#if PHP_VERSION_ID >= 70300
+    return (GC_FLAGS(ht) & IS_ARRAY_PERSISTENT) != 0;
+#else
+    return (ht->u.flags & HASH_FLAG_PERSISTENT) != 0;
+#endif
+}
+
+static bool phper_zval_is_persistent_array(const zval *zv) {
+    return Z_TYPE_P(zv) == IS_ARRAY &&
+           phper_zend_array_is_persistent(Z_ARR_P(zv));
+}
+
+static void phper_zend_persistent_array_free(zend_array *ht) {
+    zval *val;
+
+    // The nested persistent arrays are immutable, so they aren't released by
+    // the destructor of the items, free them explicitly.
+    ZEND_HASH_FOREACH_VAL(ht, val) {
+        if (phper_zval_is_persistent_array(val)) {
+            phper_zend_persistent_array_free(Z_ARR_P(val));
+            ZVAL_NULL(val);
+        }
+    }
+    ZEND_HASH_FOREACH_END();
+
+    zend_hash_destroy(ht);
+    pefree(ht, 1);
+}
+
+zend_array *phper_zend_persistent_array_to_request(const zend_array *ht) {
+    zend_array *copy = phper_zend_new_array(zend_hash_num_elements(ht));
+    zend_string *key;
+    zend_ulong h;
+    zval *val;
+
+    ZEND_HASH_FOREACH_KEY_VAL((zend_array *)ht, h, key, val) {
+        zval item;
+        if (phper_zval_is_persistent_array(val)) {
+            ZVAL_ARR(&item, phper_zend_persistent_array_to_request(Z_ARR_P(val)));
+        } else {
+            ZVAL_COPY(&item, val);
+        }
+        if (key) {
+            zend_hash_add_new(copy, key, &item);
+        } else {
+            zend_hash_index_add_new(copy, h, &item);
+        }
+    }
+    ZEND_HASH_FOREACH_END();
+
+    return copy;
+}
+
+void phper_zend_array_destroy(zend_array *ht) {
+    if (phper_zend_array_is_persistent(ht)) {
+        phper_zend_persistent_array_free(ht);
+    } else {
+        zend_array_destroy(ht);
+    }
+}
+
+void phper_zend_array_release(zend_array *ht) {
+    if (GC_FLAGS(ht) & IS_ARRAY_IMMUTABLE) {
+        return;
+    }
+#if PHP_VERSION_ID >= 70300
+    if (GC_DELREF(ht) == 0) {
+#else
+    if (--GC_REFCOUNT(ht) == 0) {
+#endif
+        phper_zend_array_destroy(ht);
+    }
+}
+
+void phper_zend_hash_sort(HashTable *ht, int (*compare)(const void *, const void *), bool renumber) {
+#if PHP_VERSION_ID >= 80000
+    zend_hash_sort(ht, (bucket_compare_func_t) compare, renumber);
+#else
+    zend_hash_sort(ht, (compare_func_t) compare, renumber);
+#endif
+}
+
+bool phper_zval_is_persistent(const zval *zv) {
+    switch (Z_TYPE_P(zv)) {
+    case IS_STRING:
+        return ZSTR_IS_INTERNED(Z_STR_P(zv)) ||
+               (GC_FLAGS(Z_STR_P(zv)) & IS_STR_PERSISTENT) != 0;
+    case IS_ARRAY:
+        return (GC_FLAGS(Z_ARR_P(zv)) & IS_ARRAY_IMMUTABLE) != 0 ||
+               phper_zend_array_is_persistent(Z_ARR_P(zv));
+    default:
+        return !Z_REFCOUNTED_P(zv);
+    }
+}
+
 zend_array *phper_zend_array_dup(zend_array *source) {
     return zend_array_dup(source);
 }
 
 zval *phper_zend_hash_index_find(const HashTable *ht, zend_ulong h) {
-    return zend_hash_index_find(ht, h);
+    return phper_zval_defined(zend_hash_index_find(ht, h));
 }
 
 bool phper_zend_hash_index_del(HashTable *ht, zend_ulong h) {
@@ -309,12 +603,109 @@ bool phper_zend_symtable_str_del(HashTable *ht, const char *str, size_t len) {
 }
 
 zval *phper_zend_symtable_str_find(HashTable *ht, const char *str, size_t len) {
-    return zend_symtable_str_find(ht, str, len);
+    return phper_zval_defined(zend_symtable_str_find(ht, str, len));
 }
 
 bool phper_zend_symtable_str_exists(HashTable *ht, const char *str,
                                     size_t len) {
-    return zend_symtable_str_exists(ht, str, len) != 0;
+    return phper_zval_defined(zend_symtable_str_find(ht, str, len)) != NULL;
+}
+
+void phper_zval_make_array_immutable(zval *zv) {
+    zend_array *ht = Z_ARR_P(zv);
+#if PHP_VERSION_ID >= 70300
+    GC_ADD_FLAGS(ht, IS_ARRAY_IMMUTABLE);
+    GC_SET_REFCOUNT(ht, 2);
+    Z_TYPE_FLAGS_P(zv) = 0;
+#else
+    GC_FLAGS(ht) |= IS_ARRAY_IMMUTABLE;
+    GC_REFCOUNT(ht) = 2;
+    Z_TYPE_FLAGS_P(zv) = IS_TYPE_IMMUTABLE;
+#endif
+}
+
+static bool phper_zend_hash_is_packed_without_holes(const HashTable *ht) {
+#if PHP_VERSION_ID >= 70300
+    return HT_IS_PACKED(ht) && HT_IS_WITHOUT_HOLES(ht);
+#else
+    return (ht->u.flags & HASH_FLAG_PACKED) != 0 &&
+           ht->nNumUsed == ht->nNumOfElements;
+#endif
+}
+
+static zval *phper_zend_hash_packed_val(const HashTable *ht, uint32_t idx) {
+#if PHP_VERSION_ID >= 80200
+    return &ht->arPacked[idx];
+#else
+    return &ht->arData[idx].val;
+#endif
+}
+
+bool phper_zend_hash_to_longs(const HashTable *ht, zend_long *out, size_t len) {
+    zval *val;
+    size_t i = 0;
+
+    if (phper_zend_hash_is_packed_without_holes(ht)) {
+        if (ht->nNumUsed > len) {
+            return false;
+        }
+        for (; i < ht->nNumUsed; i++) {
+            val = phper_zend_hash_packed_val(ht, i);
+            ZVAL_DEREF(val);
+            if (Z_TYPE_P(val) != IS_LONG) {
+                return false;
+            }
+            out[i] = Z_LVAL_P(val);
+        }
+        return true;
+    }
+
+    ZEND_HASH_FOREACH_VAL((HashTable *)ht, val) {
+        ZVAL_DEREF(val);
+        if (i >= len || Z_TYPE_P(val) != IS_LONG) {
+            return false;
+        }
+        out[i++] = Z_LVAL_P(val);
+    } ZEND_HASH_FOREACH_END();
+    return true;
+}
+
+bool phper_zend_hash_to_doubles(const HashTable *ht, double *out, size_t len) {
+    zval *val;
+    size_t i = 0;
+
+    if (phper_zend_hash_is_packed_without_holes(ht)) {
+        if (ht->nNumUsed > len) {
+            return false;
+        }
+        for (; i < ht->nNumUsed; i++) {
+            val = phper_zend_hash_packed_val(ht, i);
+            ZVAL_DEREF(val);
+            if (Z_TYPE_P(val) == IS_DOUBLE) {
+                out[i] = Z_DVAL_P(val);
+            } else if (Z_TYPE_P(val) == IS_LONG) {
+                out[i] = (double)Z_LVAL_P(val);
+            } else {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    ZEND_HASH_FOREACH_VAL((HashTable *)ht, val) {
+        ZVAL_DEREF(val);
+        if (i >= len) {
+            return false;
+        }
+        if (Z_TYPE_P(val) == IS_DOUBLE) {
+            out[i++] = Z_DVAL_P(val);
+        } else if (Z_TYPE_P(val) == IS_LONG) {
+            out[i++] = (double)Z_LVAL_P(val);
+        } else {
+            return false;
+        }
+    } ZEND_HASH_FOREACH_END();
+    return true;
 }
 
 // ==================================================
@@ -344,6 +735,33 @@ zend_object *(**phper_get_create_object(zend_class_entry *ce))(
     return &ce->create_object;
 }
 
+zval *phper_zend_read_property_ex(zend_class_entry *scope, zend_object *object,
+                                  zend_string *name, bool silent) {
+#if PHP_VERSION_ID >= 80000
+    return zend_read_property_ex(scope, object, name, silent, NULL);
+#else
+    zval zv;
+    ZVAL_OBJ(&zv, object);
+#if PHP_VERSION_ID >= 70100
+    return zend_read_property_ex(scope, &zv, name, silent, NULL);
+#else
+    return zend_read_property(scope, &zv, ZSTR_VAL(name), ZSTR_LEN(name), silent,
+                              NULL);
+#endif
+#endif
+}
+
+void phper_zend_update_property_ex(zend_class_entry *scope, zend_object *object,
+                                   zend_string *name, zval *value) {
+#if PHP_VERSION_ID >= 80000
+    zend_update_property_ex(scope, object, name, value);
+#else
+    zval zv;
+    ZVAL_OBJ(&zv, object);
+    zend_update_property_ex(scope, &zv, name, value);
+#endif
+}
+
 bool phper_object_init_ex(zval *arg, zend_class_entry *class_type) {
     return object_init_ex(arg, class_type) == SUCCESS;
 }
@@ -356,6 +774,20 @@ uint32_t phper_zend_object_gc_refcount(const zend_object *obj) {
     return GC_REFCOUNT(obj);
 }
 
+HashTable *phper_zend_object_get_properties(zend_object *obj) {
+#if PHP_VERSION_ID >= 80000
+    return obj->handlers->get_properties(obj);
+#else
+    zval zv;
+    ZVAL_OBJ(&zv, obj);
+    return obj->handlers->get_properties(&zv);
+#endif
+}
+
+const char *phper_zend_resource_type_name(zend_resource *res) {
+    return zend_rsrc_list_get_rsrc_type(res);
+}
+
 // ==================================================
 // class apis:
 // ==================================================
@@ -379,6 +811,195 @@ zend_class_entry *phper_get_parent_class(zend_class_entry *ce) {
     return ce->parent;
 }
 
+// Find the class like `class_exists`, which tries the autoloaders if the class
+// isn't found.
+zend_class_entry *phper_zend_lookup_class(const char *name, size_t len) {
+    zend_string *s = zend_string_init(name, len, 0);
+    zend_class_entry *ce = zend_lookup_class(s);
+    zend_string_release(s);
+    return ce;
+}
+
+#if PHP_VERSION_ID >= 80100
+// The `type` is `IS_UNDEF` for the pure enum, or `IS_LONG` and `IS_STRING` for
+// the backed enum.
+zend_class_entry *
+phper_zend_register_internal_enum(const char *name, zend_uchar type,
+                                  const zend_function_entry *functions) {
+    return zend_register_internal_enum(name, type,
+                                       (zend_function_entry *)functions);
+}
+
+// The `value` is `NULL` for the case of pure enum, and the string value must
+// be interned.
+void phper_zend_enum_add_case(zend_class_entry *ce, const char *name,
+                              size_t name_len, zval *value) {
+    zend_enum_add_case(ce, phper_zend_string_init_interned(name, name_len),
+                       value);
+}
+#endif
+
+bool phper_zend_class_is_enum(const zend_class_entry *ce) {
+#if PHP_VERSION_ID >= 80100
+    return (ce->ce_flags & ZEND_ACC_ENUM) != 0;
+#else
+    return false;
+#endif
+}
+
+#define PHPER_TYPE_NULL 1
+#define PHPER_TYPE_BOOL 2
+#define PHPER_TYPE_LONG 4
+#define PHPER_TYPE_DOUBLE 8
+#define PHPER_TYPE_STRING 16
+#define PHPER_TYPE_ARRAY 32
+#define PHPER_TYPE_OBJECT 64
+#define PHPER_TYPE_MIXED 128
+#define PHPER_TYPE_VOID 256
+#define PHPER_TYPE_NEVER 512
+#define PHPER_TYPE_FALSE 1024
+#define PHPER_TYPE_TRUE 2048
+#define PHPER_TYPE_STATIC 4096
+
+#if PHP_VERSION_ID >= 80000
+// Convert the `PHPER_TYPE_*` bits to the type mask, returns false if some type
+// can't be represented by the PHP version, like the standalone `null` and
+// `false` (without class) before PHP 8.2.
+static bool phper_type_mask(uint32_t type_bits, bool has_class,
+                            uint32_t *mask) {
+    *mask = 0;
+    if (type_bits & PHPER_TYPE_NULL) *mask |= MAY_BE_NULL;
+    if (type_bits & PHPER_TYPE_BOOL) *mask |= MAY_BE_BOOL;
+    if (type_bits & PHPER_TYPE_LONG) *mask |= MAY_BE_LONG;
+    if (type_bits & PHPER_TYPE_DOUBLE) *mask |= MAY_BE_DOUBLE;
+    if (type_bits & PHPER_TYPE_STRING) *mask |= MAY_BE_STRING;
+    if (type_bits & PHPER_TYPE_ARRAY) *mask |= MAY_BE_ARRAY;
+    if (type_bits & PHPER_TYPE_OBJECT) *mask |= MAY_BE_OBJECT;
+    if (type_bits & PHPER_TYPE_MIXED) *mask |= MAY_BE_ANY;
+    if (type_bits & PHPER_TYPE_VOID) *mask |= MAY_BE_VOID;
+    if (type_bits & PHPER_TYPE_FALSE) *mask |= MAY_BE_FALSE;
+    if (type_bits & PHPER_TYPE_STATIC) *mask |= MAY_BE_STATIC;
+#if PHP_VERSION_ID >= 80100
+    if (type_bits & PHPER_TYPE_NEVER) *mask |= MAY_BE_NEVER;
+#else
+    if (type_bits & PHPER_TYPE_NEVER) return false;
+#endif
+#if PHP_VERSION_ID >= 80200
+    if (type_bits & PHPER_TYPE_TRUE) *mask |= MAY_BE_TRUE;
+#else
+    if (type_bits & PHPER_TYPE_TRUE) return false;
+    if (!has_class &&
+        (type_bits & ~(PHPER_TYPE_NULL | PHPER_TYPE_FALSE)) == 0) {
+        return false;
+    }
+#endif
+    return true;
+}
+#else
+static zend_uchar phper_type_code(uint32_t type_bits) {
+    switch (type_bits) {
+    case PHPER_TYPE_BOOL:
+        return _IS_BOOL;
+    case PHPER_TYPE_LONG:
+        return IS_LONG;
+    case PHPER_TYPE_DOUBLE:
+        return IS_DOUBLE;
+    case PHPER_TYPE_STRING:
+        return IS_STRING;
+    case PHPER_TYPE_ARRAY:
+        return IS_ARRAY;
+    case PHPER_TYPE_OBJECT:
+        return IS_OBJECT;
+#if PHP_VERSION_ID >= 70100
+    case PHPER_TYPE_VOID:
+        return IS_VOID;
+#endif
+    default:
+        return IS_UNDEF;
+    }
+}
+#endif
+
+// Declare the property with the type composed by the `PHPER_TYPE_*` bits and
+// the class names, the type which can't be represented by the PHP version is
+// ignored, and the property is declared untyped.
+void phper_zend_declare_typed_property(zend_class_entry *ce, const char *name,
+                                       size_t name_len, zval *default_value,
+                                       bool uninitialized, int access_type,
+                                       uint32_t type_bits,
+                                       const char *const *class_names,
+                                       const size_t *class_name_lens,
+                                       size_t class_count) {
+#if PHP_VERSION_ID >= 70400
+    zend_type type;
+    bool typed = true;
+
+#if PHP_VERSION_ID >= 80000
+    uint32_t mask;
+    if (!phper_type_mask(type_bits, class_count > 0, &mask)) {
+        typed = false;
+    } else if (class_count == 0) {
+        type = (zend_type)ZEND_TYPE_INIT_MASK(mask);
+    } else if (class_count == 1) {
+        zend_string *class_name =
+            phper_zend_string_init_interned(class_names[0], class_name_lens[0]);
+        type = (zend_type)ZEND_TYPE_INIT_CLASS(class_name, 0, mask);
+    } else {
+        zend_type_list *list = pemalloc(ZEND_TYPE_LIST_SIZE(class_count), 1);
+        list->num_types = class_count;
+        for (size_t i = 0; i < class_count; i++) {
+            zend_string *class_name = phper_zend_string_init_interned(
+                class_names[i], class_name_lens[i]);
+            list->types[i] = (zend_type)ZEND_TYPE_INIT_CLASS(class_name, 0, 0);
+        }
+#if PHP_VERSION_ID >= 80100
+        type = (zend_type)ZEND_TYPE_INIT_UNION(list, mask);
+#else
+        type =
+            (zend_type)ZEND_TYPE_INIT_PTR(list, _ZEND_TYPE_LIST_BIT, 0, mask);
+#endif
+    }
+#else
+    bool allow_null = (type_bits & PHPER_TYPE_NULL) != 0;
+    uint32_t other_bits = type_bits & ~PHPER_TYPE_NULL;
+    if (class_count == 1 && other_bits == 0) {
+        zend_string *class_name =
+            phper_zend_string_init_interned(class_names[0], class_name_lens[0]);
+        type = ZEND_TYPE_ENCODE_CLASS(class_name, allow_null);
+    } else if (class_count == 0 && phper_type_code(other_bits) != IS_UNDEF) {
+        type = ZEND_TYPE_ENCODE(phper_type_code(other_bits), allow_null);
+    } else {
+        typed = false;
+    }
+#endif
+
+    if (typed) {
+        if (uninitialized) {
+            ZVAL_UNDEF(default_value);
+        }
+        zend_declare_typed_property(
+            ce, phper_zend_string_init_interned(name, name_len), default_value,
+            access_type, NULL, type);
+        return;
+    }
+#endif
+
+    zend_declare_property_ex(ce,
+                             phper_zend_string_init_interned(name, name_len),
+                             default_value, access_type, NULL);
+}
+
+void phper_zend_declare_class_constant(zend_class_entry *ce, const char *name,
+                                       size_t name_len, zval *value) {
+#if PHP_VERSION_ID >= 70100
+    zend_declare_class_constant_ex(
+        ce, phper_zend_string_init_interned(name, name_len), value,
+        ZEND_ACC_PUBLIC, NULL);
+#else
+    zend_declare_class_constant(ce, name, name_len, value);
+#endif
+}
+
 // ==================================================
 // function apis:
 // ==================================================
@@ -412,6 +1033,52 @@ bool phper_call_user_function(HashTable *function_table, zval *object,
                               param_count, params) == SUCCESS;
 }
 
+bool phper_zend_fcall_info_init(zval *callable, zend_fcall_info *fci,
+                                zend_fcall_info_cache *fcc, char **error) {
+    return zend_fcall_info_init(callable, 0, fci, fcc, NULL, error) == SUCCESS;
+}
+
+bool phper_zend_fcall_info_cache_is_trampoline(
+    const zend_fcall_info_cache *fcc) {
+    return fcc->function_handler != NULL &&
+           (fcc->function_handler->common.fn_flags &
+            ZEND_ACC_CALL_VIA_TRAMPOLINE) != 0;
+}
+
+void phper_zend_release_fcall_info_cache_trampoline(zend_fcall_info_cache *fcc) {
+    zend_function *func = fcc->function_handler;
+    if (func == NULL ||
+        (func->common.fn_flags & ZEND_ACC_CALL_VIA_TRAMPOLINE) == 0) {
+        return;
+    }
+    zend_string_release(func->common.function_name);
+    if (func == &EG(trampoline)) {
+        EG(trampoline).common.function_name = NULL;
+    } else {
+        efree(func);
+    }
+    fcc->function_handler = NULL;
+}
+
+bool phper_zend_call_function(zend_fcall_info *fci,
+                              zend_fcall_info_cache *fcc, zval *retval,
+                              uint32_t param_count, zval params[]) {
+    fci->retval = retval;
+    fci->params = params;
+    fci->param_count = param_count;
+    return zend_call_function(fci, fcc) == SUCCESS;
+}
+
+int phper_zend_get_resource_handle(const char *module_name) {
+#if PHP_VERSION_ID >= 80100
+    return zend_get_resource_handle(module_name);
+#else
+    static zend_extension extension;
+    extension.name = (char *)module_name;
+    return zend_get_resource_handle(&extension);
+#endif
+}
+
 zval *phper_zend_call_var_num(zend_execute_data *execute_data, int index) {
     return ZEND_CALL_VAR_NUM(execute_data, index);
 }
@@ -424,45 +1091,1493 @@ uint32_t phper_zend_num_args(const zend_execute_data *execute_data) {
     return ZEND_NUM_ARGS();
 }
 
+// Gets the first argument and the count at once, for the hot path of calling
+// the internal functions.
+zval *phper_zend_call_args(zend_execute_data *execute_data, uint32_t *num_args) {
+    *num_args = ZEND_CALL_NUM_ARGS(execute_data);
+    return ZEND_CALL_ARG(execute_data, 1);
+}
+
 bool phper_zend_get_parameters_array_ex(uint32_t param_count,
                                         zval *argument_array) {
     return zend_get_parameters_array_ex(param_count, argument_array) != 0;
 }
 
+zend_execute_data *phper_get_current_execute_data(void) {
+    return EG(current_execute_data);
+}
+
 // ==================================================
-// memory apis:
+// zend extension apis:
 // ==================================================
 
-void *phper_emalloc(size_t size) {
-    return emalloc(size);
-}
+void phper_zend_register_extension(zend_extension *extension,
+                                   const char *module_name,
+                                   size_t module_name_len) {
+    char *lc_name = zend_str_tolower_dup(module_name, module_name_len);
+    zend_module_entry *module =
+        zend_hash_str_find_ptr(&module_registry, lc_name, module_name_len);
+    efree(lc_name);
+
+    // Transfer the library handle from the module to the zend extension, because
+    // the zend extensions are shutdown after the modules are unloaded.
+    DL_HANDLE handle = NULL;
+    if (module != NULL) {
+        handle = module->handle;
+        module->handle = NULL;
+    }
 
-void phper_efree(void *ptr) {
-    return efree(ptr);
+    zend_register_extension(extension, handle);
 }
 
 // ==================================================
-// module apis:
+// compile apis:
 // ==================================================
 
-const char *phper_get_zend_module_build_id() {
-    return ZEND_MODULE_BUILD_ID;
+const char *phper_zend_file_handle_filename(const zend_file_handle *handle) {
+#if PHP_VERSION_ID >= 80100
+    return handle->filename != NULL ? ZSTR_VAL(handle->filename) : "";
+#else
+    return handle->filename != NULL ? handle->filename : "";
+#endif
 }
 
-zend_internal_arg_info
-phper_zend_begin_arg_info_ex(bool return_reference,
-                             uintptr_t required_num_args) {
-#define static
-#define const
-    ZEND_BEGIN_ARG_INFO_EX(info, 0, return_reference, required_num_args)
-    ZEND_END_ARG_INFO()
-    return info[0];
-#undef static
-#undef const
+bool phper_zend_file_handle_source(zend_file_handle *handle, char **buf,
+                                   size_t *len) {
+    return zend_stream_fixup(handle, buf, len) == SUCCESS;
 }
 
-zend_internal_arg_info phper_zend_arg_info(bool pass_by_ref, const char *name) {
-    zend_internal_arg_info info[] = {ZEND_ARG_INFO(pass_by_ref, )};
-    info[0].name = name;
-    return info[0];
+#if PHP_VERSION_ID >= 70400
+bool phper_zend_file_handle_set_source(zend_file_handle *handle,
+                                       const char *buf, size_t len) {
+    char *old_buf;
+    size_t old_len;
+
+    // Open the stream first, so the scanner takes the replaced buffer as the
+    // already read content.
+    if (zend_stream_fixup(handle, &old_buf, &old_len) != SUCCESS) {
+        return false;
+    }
+
+    char *new_buf = emalloc(len + ZEND_MMAP_AHEAD);
+    memcpy(new_buf, buf, len);
+    memset(new_buf + len, 0, ZEND_MMAP_AHEAD);
+
+    efree(handle->buf);
+    handle->buf = new_buf;
+    handle->len = len;
+    return true;
+}
+#endif
+
+// Compile and execute the file like `include`, the file is compiled by
+// `zend_compile_file`, so the op array is cached by opcache. Unlike
+// `zend_execute_scripts`, the uncaught exception is left in `EG(exception)` for
+// the caller. Returns false if the file can't be read or compiled.
+bool phper_zend_include_file(const char *filename, zval *retval) {
+    if (access(filename, R_OK) != 0) {
+        return false;
+    }
+
+    zend_file_handle handle;
+#if PHP_VERSION_ID >= 70400
+    zend_stream_init_filename(&handle, filename);
+#else
+    memset(&handle, 0, sizeof(handle));
+    handle.type = ZEND_HANDLE_FILENAME;
+    handle.filename = filename;
+#endif
+
+    zend_op_array *op_array = zend_compile_file(&handle, ZEND_INCLUDE);
+    if (op_array != NULL && handle.opened_path != NULL) {
+        zend_hash_add_empty_element(&EG(included_files), handle.opened_path);
+    }
+    zend_destroy_file_handle(&handle);
+    if (op_array == NULL) {
+        return false;
+    }
+
+    zend_execute(op_array, retval);
+    destroy_op_array(op_array);
+    efree_size(op_array, sizeof(zend_op_array));
+    return true;
+}
+
+typedef zend_string *(*phper_compile_string_hook_t)(zend_string *source,
+                                                    const char *filename);
+
+// The hook is `NULL` after unset, then `phper_compile_string` passes through,
+// if it can't be unlinked because the other extensions chained after it.
+static phper_compile_string_hook_t phper_compile_string_hook = NULL;
+static bool phper_compile_string_chained = false;
+
+#if PHP_VERSION_ID >= 80200
+static zend_op_array *(*phper_previous_compile_string)(
+    zend_string *source_string, const char *filename,
+    zend_compile_position position) = NULL;
+
+static zend_op_array *phper_compile_string(zend_string *source_string,
+                                           const char *filename,
+                                           zend_compile_position position) {
+    if (phper_compile_string_hook == NULL) {
+        return phper_previous_compile_string(source_string, filename, position);
+    }
+    zend_string *replaced = phper_compile_string_hook(source_string, filename);
+    if (EG(exception) != NULL) {
+        return NULL;
+    }
+    zend_op_array *op_array = phper_previous_compile_string(
+        replaced != NULL ? replaced : source_string, filename, position);
+    if (replaced != NULL) {
+        zend_string_release(replaced);
+    }
+    return op_array;
+}
+#elif PHP_VERSION_ID >= 80000
+static zend_op_array *(*phper_previous_compile_string)(
+    zend_string *source_string, const char *filename) = NULL;
+
+static zend_op_array *phper_compile_string(zend_string *source_string,
+                                           const char *filename) {
+    if (phper_compile_string_hook == NULL) {
+        return phper_previous_compile_string(source_string, filename);
+    }
+    zend_string *replaced = phper_compile_string_hook(source_string, filename);
+    if (EG(exception) != NULL) {
+        return NULL;
+    }
+    zend_op_array *op_array = phper_previous_compile_string(
+        replaced != NULL ? replaced : source_string, filename);
+    if (replaced != NULL) {
+        zend_string_release(replaced);
+    }
+    return op_array;
+}
+#else
+static zend_op_array *(*phper_previous_compile_string)(zval *source_string,
+                                                       char *filename) = NULL;
+
+static zend_op_array *phper_compile_string(zval *source_string,
+                                           char *filename) {
+    if (phper_compile_string_hook == NULL ||
+        Z_TYPE_P(source_string) != IS_STRING) {
+        return phper_previous_compile_string(source_string, filename);
+    }
+    zend_string *replaced =
+        phper_compile_string_hook(Z_STR_P(source_string), filename);
+    if (EG(exception) != NULL) {
+        return NULL;
+    }
+    if (replaced == NULL) {
+        return phper_previous_compile_string(source_string, filename);
+    }
+    zval replaced_zv;
+    ZVAL_STR(&replaced_zv, replaced);
+    zend_op_array *op_array =
+        phper_previous_compile_string(&replaced_zv, filename);
+    zval_ptr_dtor(&replaced_zv);
+    return op_array;
+}
+#endif
+
+void phper_zend_set_compile_string_hook(phper_compile_string_hook_t hook) {
+    phper_compile_string_hook = hook;
+    if (hook != NULL) {
+        // Don't chain twice, otherwise it calls itself as the previous one.
+        if (!phper_compile_string_chained) {
+            phper_previous_compile_string = zend_compile_string;
+            zend_compile_string = phper_compile_string;
+            phper_compile_string_chained = true;
+        }
+    } else if (phper_compile_string_chained &&
+               zend_compile_string == phper_compile_string) {
+        // Only unlink if it's still the current one, otherwise the hook of
+        // the extension chained after it would be dropped.
+        zend_compile_string = phper_previous_compile_string;
+        phper_compile_string_chained = false;
+    }
+}
+
+// ==================================================
+// log apis:
+// ==================================================
+
+bool phper_log_err(const char *message, int syslog_level) {
+    if (!PG(log_errors)) {
+        return false;
+    }
+    php_log_err_with_severity((char *)message, syslog_level);
+    return true;
+}
+
+// ==================================================
+// thread apis:
+// ==================================================
+
+typedef struct {
+#ifndef PHP_WIN32
+    sigset_t set;
+#else
+    char unused;
+#endif
+} phper_sigset;
+
+void phper_block_signals(phper_sigset *old) {
+#ifndef PHP_WIN32
+    sigset_t set;
+    sigfillset(&set);
+    pthread_sigmask(SIG_BLOCK, &set, &old->set);
+#endif
+}
+
+void phper_restore_signals(const phper_sigset *old) {
+#ifndef PHP_WIN32
+    pthread_sigmask(SIG_SETMASK, &old->set, NULL);
+#endif
+}
+
+// ==================================================
+// timeout apis:
+// ==================================================
+
+bool phper_remaining_execution_time(double *seconds) {
+    if (EG(timeout_seconds) <= 0) {
+        return false;
+    }
+    *seconds = (double)EG(timeout_seconds);
+#if defined(ZEND_MAX_EXECUTION_TIMERS)
+    struct itimerspec its;
+    if (timer_gettime(EG(max_execution_timer_timer), &its) == 0) {
+        *seconds = its.it_value.tv_sec + its.it_value.tv_nsec / 1e9;
+    }
+#elif !defined(PHP_WIN32) && defined(HAVE_SETITIMER)
+    struct itimerval itv;
+#if defined(__CYGWIN__) || defined(__PASE__)
+    int which = ITIMER_REAL;
+#else
+    int which = ITIMER_PROF;
+#endif
+    if (getitimer(which, &itv) == 0) {
+        *seconds = itv.it_value.tv_sec + itv.it_value.tv_usec / 1e6;
+    }
+#endif
+    return true;
+}
+
+// ==================================================
+// crash handler apis:
+// ==================================================
+
+typedef void (*phper_crash_report_fn)(void *const *frames, int len, int fd);
+
+#ifndef PHP_WIN32
+static const int phper_crash_signals[] = {SIGSEGV, SIGBUS, SIGABRT, SIGILL,
+                                          SIGFPE};
+#define PHPER_CRASH_SIGNALS_LEN                                                \
+    (sizeof(phper_crash_signals) / sizeof(phper_crash_signals[0]))
+#define PHPER_CRASH_FRAMES_LEN 64
+// The `SIGSTKSZ` isn't a constant since glibc 2.34.
+#define PHPER_CRASH_STACK_SIZE (64 * 1024)
+// Wait the child resolving the report for 5 seconds at most.
+#define PHPER_CRASH_REPORT_WAITS 500
+static struct sigaction phper_previous_crash_actions[PHPER_CRASH_SIGNALS_LEN];
+static bool phper_crash_handler_installed = false;
+static char phper_crash_stack[PHPER_CRASH_STACK_SIZE];
+// Captured at installing, the handler doesn't read the engine state.
+static char phper_crash_log_path[4096];
+static phper_crash_report_fn phper_crash_report = NULL;
+static void *phper_crash_frames[PHPER_CRASH_FRAMES_LEN];
+
+// Only the async-signal-safe functions are called in the crashing process, no
+// allocation and no stdio, the numbers are formatted by hand.
+static void phper_crash_write(int fd, const char *s) {
+    size_t len = s == NULL ? 0 : strlen(s);
+    while (len > 0) {
+        ssize_t n = write(fd, s, len);
+        if (n <= 0) {
+            return;
+        }
+        s += n;
+        len -= (size_t)n;
+    }
+}
+
+static void phper_crash_write_long(int fd, long value) {
+    char buf[24];
+    char *p = buf + sizeof(buf);
+    unsigned long u = value < 0 ? -(unsigned long)value : (unsigned long)value;
+    *--p = '\0';
+    do {
+        *--p = (char)('0' + u % 10);
+        u /= 10;
+    } while (u != 0);
+    if (value < 0) {
+        *--p = '-';
+    }
+    phper_crash_write(fd, p);
+}
+
+static void phper_crash_write_address(int fd, const void *address) {
+    char buf[2 + sizeof(uintptr_t) * 2 + 1];
+    char *p = buf + sizeof(buf);
+    uintptr_t u = (uintptr_t)address;
+    *--p = '\0';
+    do {
+        *--p = "0123456789abcdef"[u % 16];
+        u /= 16;
+    } while (u != 0);
+    *--p = 'x';
+    *--p = '0';
+    phper_crash_write(fd, p);
+}
+
+static void phper_crash_write_frames(int fd) {
+    zend_execute_data *ex = EG(current_execute_data);
+    for (long depth = 0; ex != NULL && depth < 32; ex = ex->prev_execute_data) {
+        zend_function *func = ex->func;
+        if (func == NULL) {
+            continue;
+        }
+        phper_crash_write(fd, "#");
+        phper_crash_write_long(fd, depth++);
+        phper_crash_write(fd, " ");
+        if (func->common.scope != NULL) {
+            phper_crash_write(fd, ZSTR_VAL(func->common.scope->name));
+            phper_crash_write(fd, "::");
+        }
+        phper_crash_write(fd, func->common.function_name != NULL
+                                  ? ZSTR_VAL(func->common.function_name)
+                                  : "{main}");
+        if (ZEND_USER_CODE(func->type) && func->op_array.filename != NULL) {
+            phper_crash_write(fd, " at ");
+            phper_crash_write(fd, ZSTR_VAL(func->op_array.filename));
+            if (ex->opline != NULL) {
+                phper_crash_write(fd, ":");
+                phper_crash_write_long(fd, (long)ex->opline->lineno);
+            }
+        }
+        phper_crash_write(fd, "\n");
+    }
+}
+
+static void phper_crash_handler(int sig);
+
+// Whether the handler of the signal is still ours, the extension installed
+// after it may have replaced it, and chained to ours.
+static bool phper_crash_action_is_current(int sig) {
+    struct sigaction current;
+    return sigaction(sig, NULL, &current) == 0 &&
+           !(current.sa_flags & SA_SIGINFO) &&
+           current.sa_handler == phper_crash_handler;
+}
+
+// Only restore the signals whose handler is still ours, otherwise the handler
+// of the extension installed after it would be dropped.
+static void phper_crash_restore_actions(void) {
+    for (size_t i = 0; i < PHPER_CRASH_SIGNALS_LEN; i++) {
+        if (phper_crash_action_is_current(phper_crash_signals[i])) {
+            sigaction(phper_crash_signals[i], &phper_previous_crash_actions[i],
+                      NULL);
+        }
+    }
+    phper_crash_handler_installed = false;
+}
+
+// The PHP frames and the native symbols are resolved in the forked child,
+// which is free to read the engine state and allocate, because its crash or
+// deadlock (like on the lock held by the other threads) doesn't affect the
+// crashing process, and it's killed if not done in time.
+static bool phper_crash_report_in_child(int fd, int len) {
+    pid_t pid = fork();
+    if (pid < 0) {
+        return false;
+    }
+    if (pid == 0) {
+        for (size_t i = 0; i < PHPER_CRASH_SIGNALS_LEN; i++) {
+            signal(phper_crash_signals[i], SIG_DFL);
+        }
+        phper_crash_write(fd, "PHP stack trace:\n");
+        phper_crash_write_frames(fd);
+        if (phper_crash_report != NULL) {
+            phper_crash_report(phper_crash_frames, len, fd);
+        }
+        _exit(0);
+    }
+
+    for (int i = 0; i < PHPER_CRASH_REPORT_WAITS; i++) {
+        int status;
+        pid_t waited = waitpid(pid, &status, WNOHANG);
+        if (waited == pid) {
+            return WIFEXITED(status) && WEXITSTATUS(status) == 0;
+        }
+        if (waited < 0) {
+            return false;
+        }
+        struct timespec interval = {0, 10 * 1000 * 1000};
+        nanosleep(&interval, NULL);
+    }
+    kill(pid, SIGKILL);
+    waitpid(pid, NULL, 0);
+    return false;
+}
+
+static void phper_crash_handler(int sig) {
+    int fd = STDERR_FILENO;
+    if (phper_crash_log_path[0] != '\0') {
+        int log_fd =
+            open(phper_crash_log_path, O_WRONLY | O_APPEND | O_CREAT, 0644);
+        if (log_fd >= 0) {
+            fd = log_fd;
+        }
+    }
+
+    phper_crash_write(fd, "PHP Fatal error:  phper caught signal ");
+    phper_crash_write_long(fd, sig);
+    phper_crash_write(fd, ", pid ");
+    phper_crash_write_long(fd, (long)getpid());
+    phper_crash_write(fd, "\n");
+
+    int len = 0;
+#ifdef PHPER_HAVE_BACKTRACE
+    len = backtrace(phper_crash_frames, PHPER_CRASH_FRAMES_LEN);
+#endif
+    if (!phper_crash_report_in_child(fd, len)) {
+        phper_crash_write(fd, "Native stack trace (unresolved):\n");
+        for (int i = 0; i < len; i++) {
+            phper_crash_write(fd, "#");
+            phper_crash_write_long(fd, i);
+            phper_crash_write(fd, " ");
+            phper_crash_write_address(fd, phper_crash_frames[i]);
+            phper_crash_write(fd, "\n");
+        }
+    }
+
+    if (fd != STDERR_FILENO) {
+        close(fd);
+    }
+
+    // Restore the previous handlers, the signal is delivered to them (or the
+    // default action, like dumping core) after returning. If ours isn't the
+    // current one, it's chained by the handler installed after it, which takes
+    // over the signal after returning.
+    bool current = phper_crash_action_is_current(sig);
+    phper_crash_restore_actions();
+    if (current) {
+        raise(sig);
+    }
+}
+#endif
+
+bool phper_install_crash_handler(phper_crash_report_fn report) {
+#ifndef PHP_WIN32
+    if (phper_crash_handler_installed) {
+        return true;
+    }
+
+#ifdef PHPER_HAVE_BACKTRACE
+    // Load the unwinder before crashing, the first call may allocate.
+    void *frames[1];
+    backtrace(frames, 1);
+#endif
+
+    const char *error_log = PG(error_log);
+    phper_crash_log_path[0] = '\0';
+    if (error_log != NULL && strcmp(error_log, "syslog") != 0 &&
+        strlen(error_log) < sizeof(phper_crash_log_path)) {
+        strcpy(phper_crash_log_path, error_log);
+    }
+    phper_crash_report = report;
+
+    // Handle the stack overflow on the alternate stack, unless one exists,
+    // the threads spawned by phper set up their own.
+    stack_t old_stack;
+    if (sigaltstack(NULL, &old_stack) == 0 &&
+        (old_stack.ss_flags & SS_DISABLE)) {
+        stack_t stack;
+        stack.ss_sp = phper_crash_stack;
+        stack.ss_size = sizeof(phper_crash_stack);
+        stack.ss_flags = 0;
+        sigaltstack(&stack, NULL);
+    }
+
+    struct sigaction action;
+    memset(&action, 0, sizeof(action));
+    action.sa_handler = phper_crash_handler;
+    action.sa_flags = SA_ONSTACK;
+    sigemptyset(&action.sa_mask);
+    for (size_t i = 0; i < PHPER_CRASH_SIGNALS_LEN; i++) {
+        if (sigaction(phper_crash_signals[i], &action,
+                      &phper_previous_crash_actions[i]) != 0) {
+            return false;
+        }
+    }
+    phper_crash_handler_installed = true;
+    return true;
+#else
+    return false;
+#endif
+}
+
+void *phper_crash_thread_stack_new(void) {
+#ifndef PHP_WIN32
+    if (!phper_crash_handler_installed) {
+        return NULL;
+    }
+    stack_t stack;
+    if (sigaltstack(NULL, &stack) != 0 || !(stack.ss_flags & SS_DISABLE)) {
+        return NULL;
+    }
+    stack.ss_sp = malloc(PHPER_CRASH_STACK_SIZE);
+    if (stack.ss_sp == NULL) {
+        return NULL;
+    }
+    stack.ss_size = PHPER_CRASH_STACK_SIZE;
+    stack.ss_flags = 0;
+    if (sigaltstack(&stack, NULL) != 0) {
+        free(stack.ss_sp);
+        return NULL;
+    }
+    return stack.ss_sp;
+#else
+    return NULL;
+#endif
+}
+
+void phper_crash_thread_stack_free(void *sp) {
+#ifndef PHP_WIN32
+    if (sp == NULL) {
+        return;
+    }
+    stack_t stack;
+    memset(&stack, 0, sizeof(stack));
+    stack.ss_flags = SS_DISABLE;
+    sigaltstack(&stack, NULL);
+    free(sp);
+#endif
+}
+
+void phper_uninstall_crash_handler(void) {
+#ifndef PHP_WIN32
+    if (!phper_crash_handler_installed) {
+        return;
+    }
+    phper_crash_restore_actions();
+#endif
+}
+
+// ==================================================
+// sapi apis:
+// ==================================================
+
+zend_array *phper_auto_global(const char *name, size_t name_len) {
+    zend_is_auto_global_str((char *)name, name_len);
+    zval *vars = zend_hash_str_find(&EG(symbol_table), name, name_len);
+    if (vars == NULL) {
+        return NULL;
+    }
+    ZVAL_DEREF(vars);
+    if (Z_TYPE_P(vars) != IS_ARRAY) {
+        return NULL;
+    }
+    return Z_ARRVAL_P(vars);
+}
+
+bool phper_is_uploaded_file(const char *path, size_t path_len) {
+    if (SG(rfc1867_uploaded_files) == NULL) {
+        return false;
+    }
+    return zend_hash_str_exists(SG(rfc1867_uploaded_files), path, path_len);
+}
+
+bool phper_sapi_headers_sent(void) {
+    return SG(headers_sent);
+}
+
+bool phper_sapi_header(const char *line, size_t line_len, bool replace) {
+    sapi_header_line ctr = {0};
+    ctr.line = (char *)line;
+    ctr.line_len = line_len;
+    return sapi_header_op(replace ? SAPI_HEADER_REPLACE : SAPI_HEADER_ADD, &ctr) == SUCCESS;
+}
+
+// ==================================================
+// env apis:
+// ==================================================
+
+char *phper_sapi_getenv(const char *name, size_t name_len) {
+    return sapi_getenv((char *)name, name_len);
+}
+
+// ==================================================
+// memory apis:
+// ==================================================
+
+void *phper_emalloc(size_t size) {
+    return emalloc(size);
+}
+
+void *phper_erealloc(void *ptr, size_t size) {
+    return erealloc(ptr, size);
+}
+
+void phper_efree(void *ptr) {
+    return efree(ptr);
+}
+
+bool phper_is_zend_mm(void) {
+    return is_zend_mm();
+}
+
+size_t phper_zend_memory_usage(void) {
+    return zend_memory_usage(0);
+}
+
+zend_long phper_memory_limit(void) {
+    return PG(memory_limit);
+}
+
+// ==================================================
+// ini apis:
+// ==================================================
+
+void phper_ini_display(const char *value, size_t len) {
+    if (value == NULL) {
+        if (sapi_module.phpinfo_as_text) {
+            PUTS("no value");
+        } else {
+            PUTS("<i>no value</i>");
+        }
+        return;
+    }
+    if (sapi_module.phpinfo_as_text) {
+        PHPWRITE(value, len);
+    } else {
+        zend_html_puts(value, len);
+    }
+}
+
+bool phper_zend_ini_entry_reload(zend_ini_entry *entry, const char *value,
+                                 size_t len) {
+    zend_string *new_value = zend_string_init(value, len, 1);
+
+    if (entry->on_modify != NULL) {
+        if (entry->on_modify(entry, new_value, entry->mh_arg1, entry->mh_arg2,
+                             entry->mh_arg3,
+                             ZEND_INI_STAGE_STARTUP) != SUCCESS) {
+            zend_string_release(new_value);
+            return false;
+        }
+        // Keep the value overridden in the current request, the new value
+        // takes effect after the request.
+        if (entry->modified) {
+            entry->on_modify(entry, entry->value, entry->mh_arg1,
+                             entry->mh_arg2, entry->mh_arg3,
+                             ZEND_INI_STAGE_RUNTIME);
+        }
+    }
+
+    zend_string **slot = entry->modified ? &entry->orig_value : &entry->value;
+    if (*slot != NULL) {
+        zend_string_release(*slot);
+    }
+    *slot = new_value;
+    return true;
+}
+
+// ==================================================
+// random apis:
+// ==================================================
+
+bool phper_php_random_bytes(void *bytes, size_t size) {
+    return php_random_bytes(bytes, size, false) == SUCCESS;
+}
+
+bool phper_php_random_int(zend_long min, zend_long max, zend_long *result) {
+    return php_random_int(min, max, result, false) == SUCCESS;
+}
+
+// ==================================================
+// hash apis:
+// ==================================================
+
+const php_hash_ops *phper_php_hash_fetch_ops(const char *algo,
+                                             size_t algo_len) {
+#if PHP_VERSION_ID >= 80000
+    zend_string *name = zend_string_init(algo, algo_len, 0);
+    const php_hash_ops *ops = php_hash_fetch_ops(name);
+    zend_string_release(name);
+    return ops;
+#else
+    return php_hash_fetch_ops(algo, algo_len);
+#endif
+}
+
+size_t phper_php_hash_digest_size(const php_hash_ops *ops) {
+    return ops->digest_size;
+}
+
+void *phper_php_hash_new_context(const php_hash_ops *ops) {
+    void *context = pecalloc(1, ops->context_size, 1);
+#if PHP_VERSION_ID >= 80100
+    ops->hash_init(context, NULL);
+#else
+    ops->hash_init(context);
+#endif
+    return context;
+}
+
+void *phper_php_hash_copy_context(const php_hash_ops *ops, void *context) {
+    void *new_context = pecalloc(1, ops->context_size, 1);
+    ops->hash_copy(ops, context, new_context);
+    return new_context;
+}
+
+void phper_php_hash_update(const php_hash_ops *ops, void *context,
+                           const unsigned char *buf, size_t len) {
+    // The count is `unsigned int` in the old versions.
+    while (len > 0) {
+        size_t count = len > UINT_MAX ? UINT_MAX : len;
+        ops->hash_update(context, buf, count);
+        buf += count;
+        len -= count;
+    }
+}
+
+void phper_php_hash_final(const php_hash_ops *ops, void *context,
+                          unsigned char *digest) {
+    ops->hash_final(digest, context);
+}
+
+void phper_php_hash_free_context(void *context) {
+    pefree(context, 1);
+}
+
+// ==================================================
+// stream apis:
+// ==================================================
+
+// Opens through the stream wrappers with the default context, so the
+// `open_basedir`, `allow_url_fopen` and the registered wrappers are honored,
+// like `fopen()`.
+php_stream *phper_php_stream_open(const char *path, const char *mode,
+                                  bool report_errors) {
+    php_stream_context *context = php_stream_context_from_zval(NULL, 0);
+    return php_stream_open_wrapper_ex(path, mode,
+                                      report_errors ? REPORT_ERRORS : 0, NULL,
+                                      context);
+}
+
+ssize_t phper_php_stream_read(php_stream *stream, char *buf, size_t count) {
+    return (ssize_t)php_stream_read(stream, buf, count);
+}
+
+ssize_t phper_php_stream_write(php_stream *stream, const char *buf,
+                               size_t count) {
+    return (ssize_t)php_stream_write(stream, buf, count);
+}
+
+bool phper_php_stream_flush(php_stream *stream) {
+    return php_stream_flush(stream) == 0;
+}
+
+bool phper_php_stream_seek(php_stream *stream, zend_off_t offset, int whence,
+                           zend_off_t *position) {
+    if (php_stream_seek(stream, offset, whence) != 0) {
+        return false;
+    }
+    *position = php_stream_tell(stream);
+    return true;
+}
+
+void phper_php_stream_close(php_stream *stream) { php_stream_close(stream); }
+
+// ==================================================
+// path apis:
+// ==================================================
+
+// Resolves against the virtual cwd of PHP, which may differ from the cwd of
+// process, for example, in ZTS.
+zend_string *phper_expand_filepath(const char *path) {
+    char *expanded = expand_filepath(path, NULL);
+    if (expanded == NULL) {
+        return NULL;
+    }
+    zend_string *s = zend_string_init(expanded, strlen(expanded), 0);
+    efree(expanded);
+    return s;
+}
+
+zend_string *phper_vcwd_getcwd(void) {
+    char buf[MAXPATHLEN];
+    if (VCWD_GETCWD(buf, MAXPATHLEN) == NULL) {
+        return NULL;
+    }
+    return zend_string_init(buf, strlen(buf), 0);
+}
+
+zend_string *phper_vcwd_realpath(const char *path) {
+    char buf[MAXPATHLEN];
+    if (VCWD_REALPATH(path, buf) == NULL) {
+        return NULL;
+    }
+    return zend_string_init(buf, strlen(buf), 0);
+}
+
+// ==================================================
+// symbol table apis:
+// ==================================================
+
+// The symbol table of the nearest user frame, like `get_defined_vars()`, the
+// compiled variables are attached to it as the indirect zvals, null if no user
+// code is executing.
+zend_array *phper_rebuild_symbol_table(void) {
+    return zend_rebuild_symbol_table();
+}
+
+// ==================================================
+// post reader apis:
+// ==================================================
+
+typedef void (*phper_post_handler_fn)(const char *content_type, zend_string *body,
+                                      zval *post);
+
+static phper_post_handler_fn phper_post_handler_callback = NULL;
+
+static void phper_post_handler(char *content_type_dup, void *arg) {
+    zend_string *body = NULL;
+    php_stream *stream = SG(request_info).request_body;
+    if (stream != NULL) {
+        php_stream_rewind(stream);
+        body = php_stream_copy_to_mem(stream, PHP_STREAM_COPY_ALL, 0);
+    }
+    if (body == NULL) {
+        body = ZSTR_EMPTY_ALLOC();
+    }
+    phper_post_handler_callback(content_type_dup, body, (zval *)arg);
+    zend_string_release(body);
+}
+
+bool phper_register_post_reader(const char *content_type, size_t content_type_len,
+                                phper_post_handler_fn handler) {
+    sapi_post_entry entry = {(char *)content_type, (uint32_t)content_type_len,
+                             sapi_read_standard_form_data, phper_post_handler};
+    phper_post_handler_callback = handler;
+    return sapi_register_post_entry(&entry) == SUCCESS;
+}
+
+void phper_unregister_post_reader(const char *content_type, size_t content_type_len) {
+    sapi_post_entry entry = {(char *)content_type, (uint32_t)content_type_len, NULL, NULL};
+    sapi_unregister_post_entry(&entry);
+}
+
+// ==================================================
+// stream transport apis:
+// ==================================================
+
+#if PHP_VERSION_ID >= 70400
+typedef ssize_t phper_xport_result;
+#else
+typedef size_t phper_xport_result;
+#endif
+
+// The callbacks implemented by Rust, the `transport` is the one registered for
+// the protocol, and the `conn` is the connection opened by it.
+typedef struct {
+    void *(*find)(const char *proto, size_t proto_len);
+    void *(*connect)(void *transport, const char *name, size_t name_len,
+                     zend_long timeout_ms, zend_string **error_text);
+    void (*close)(void *conn);
+    zend_long (*read)(void *conn, char *buf, size_t count, bool *eof);
+    zend_long (*write)(void *conn, const char *buf, size_t count);
+    bool (*flush)(void *conn);
+} phper_xport_callbacks;
+
+typedef struct {
+    void *transport;
+    void *conn;
+} phper_xport_data;
+
+static const phper_xport_callbacks *phper_xport_cbs = NULL;
+
+static phper_xport_result phper_xport_write(php_stream *stream,
+                                            const char *buf, size_t count) {
+    phper_xport_data *data = stream->abstract;
+    zend_long n = data->conn ? phper_xport_cbs->write(data->conn, buf, count)
+                             : -1;
+#if PHP_VERSION_ID >= 70400
+    return n;
+#else
+    return n < 0 ? 0 : n;
+#endif
+}
+
+static phper_xport_result phper_xport_read(php_stream *stream, char *buf,
+                                           size_t count) {
+    phper_xport_data *data = stream->abstract;
+    bool eof = false;
+    zend_long n = data->conn
+                      ? phper_xport_cbs->read(data->conn, buf, count, &eof)
+                      : -1;
+    if (eof) {
+        stream->eof = 1;
+    }
+#if PHP_VERSION_ID >= 70400
+    return n;
+#else
+    return n < 0 ? 0 : n;
+#endif
+}
+
+static int phper_xport_close(php_stream *stream, int close_handle) {
+    phper_xport_data *data = stream->abstract;
+    if (data->conn) {
+        phper_xport_cbs->close(data->conn);
+    }
+    efree(data);
+    return 0;
+}
+
+static int phper_xport_flush(php_stream *stream) {
+    phper_xport_data *data = stream->abstract;
+    return data->conn && !phper_xport_cbs->flush(data->conn) ? -1 : 0;
+}
+
+static int phper_xport_set_option(php_stream *stream, int option, int value,
+                                  void *ptrparam) {
+    phper_xport_data *data = stream->abstract;
+    php_stream_xport_param *xparam = ptrparam;
+    zend_string *error_text = NULL;
+    zend_long timeout_ms = -1;
+
+    if (option != PHP_STREAM_OPTION_XPORT_API) {
+        return PHP_STREAM_OPTION_RETURN_NOTIMPL;
+    }
+
+    switch (xparam->op) {
+    case STREAM_XPORT_OP_CONNECT:
+    case STREAM_XPORT_OP_CONNECT_ASYNC:
+        if (xparam->inputs.timeout) {
+            timeout_ms = xparam->inputs.timeout->tv_sec * 1000 +
+                         xparam->inputs.timeout->tv_usec / 1000;
+        }
+        data->conn = phper_xport_cbs->connect(
+            data->transport, xparam->inputs.name, xparam->inputs.namelen,
+            timeout_ms, &error_text);
+        xparam->outputs.returncode = data->conn ? 0 : -1;
+        if (error_text) {
+            if (xparam->want_errortext) {
+                xparam->outputs.error_text = error_text;
+            } else {
+                zend_string_release(error_text);
+            }
+        }
+        return PHP_STREAM_OPTION_RETURN_OK;
+    default:
+        return PHP_STREAM_OPTION_RETURN_NOTIMPL;
+    }
+}
+
+static const php_stream_ops phper_xport_ops = {
+    .write = phper_xport_write,
+    .read = phper_xport_read,
+    .close = phper_xport_close,
+    .flush = phper_xport_flush,
+    .label = "phper_socket",
+    .set_option = phper_xport_set_option,
+};
+
+static php_stream *phper_xport_factory(
+    const char *proto, size_t protolen, const char *resourcename,
+    size_t resourcenamelen, const char *persistent_id, int options, int flags,
+    struct timeval *timeout, php_stream_context *context STREAMS_DC) {
+    void *transport = phper_xport_cbs->find(proto, protolen);
+    if (!transport) {
+        return NULL;
+    }
+    phper_xport_data *data = emalloc(sizeof(phper_xport_data));
+    data->transport = transport;
+    data->conn = NULL;
+    // The persistent streams aren't supported, the connections are closed at
+    // the end of request.
+    php_stream *stream = php_stream_alloc_rel(&phper_xport_ops, data, NULL, "r+");
+    if (!stream) {
+        efree(data);
+    }
+    return stream;
+}
+
+// Register the stream transport `protocol`, must be called in `MINIT`, the
+// `callbacks` are shared by all transports of the extension.
+bool phper_xport_register(const char *protocol,
+                          const phper_xport_callbacks *callbacks) {
+    phper_xport_cbs = callbacks;
+    return php_stream_xport_register(protocol, phper_xport_factory) == SUCCESS;
+}
+
+void phper_xport_unregister(const char *protocol) {
+    php_stream_xport_unregister(protocol);
+}
+
+// ==================================================
+// pdo apis:
+// ==================================================
+
+#if defined(PHPER_PDO) && PHP_VERSION_ID >= 80100
+
+// The callbacks implemented by Rust, the `conn` and `stmt` are the
+// `driver_data` of `pdo_dbh_t` and `pdo_stmt_t`, the failed callbacks keep the
+// error in them, which is fetched by `sqlstate` and `fetch_error`.
+typedef struct {
+    void *(*connect)(void *driver, pdo_dbh_t *dbh);
+    void (*close)(void *conn);
+    void *(*prepare)(void *conn, const char *sql, size_t len);
+    zend_long (*exec)(void *conn, const char *sql, size_t len);
+    zend_string *(*quote)(void *conn, const char *s, size_t len);
+    bool (*begin)(void *conn);
+    bool (*commit)(void *conn);
+    bool (*rollback)(void *conn);
+    zend_string *(*last_id)(void *conn, const char *name, size_t len);
+    void (*sqlstate)(void *conn, void *stmt, char *sqlstate);
+    void (*fetch_error)(void *conn, void *stmt, zval *info);
+    void (*stmt_dtor)(void *stmt);
+    bool (*stmt_execute)(void *stmt, const char *sql, size_t len,
+                         zend_long *column_count, zend_long *row_count);
+    int (*stmt_fetch)(void *stmt);
+    zend_string *(*stmt_column_name)(void *stmt, int colno);
+    bool (*stmt_get_col)(void *stmt, int colno, zval *result);
+} phper_pdo_callbacks;
+
+typedef struct {
+    pdo_driver_t driver;
+    void *data;
+    const phper_pdo_callbacks *callbacks;
+} phper_pdo_driver;
+
+static const phper_pdo_callbacks *phper_pdo_get_callbacks(pdo_dbh_t *dbh) {
+    return ((const phper_pdo_driver *)dbh->driver)->callbacks;
+}
+
+static void phper_pdo_dbh_error(pdo_dbh_t *dbh) {
+    phper_pdo_get_callbacks(dbh)->sqlstate(dbh->driver_data, NULL,
+                                           dbh->error_code);
+}
+
+static void phper_pdo_stmt_error(pdo_stmt_t *stmt) {
+    phper_pdo_get_callbacks(stmt->dbh)->sqlstate(
+        stmt->dbh->driver_data, stmt->driver_data, stmt->error_code);
+}
+
+static int phper_pdo_stmt_dtor(pdo_stmt_t *stmt) {
+    if (stmt->driver_data) {
+        phper_pdo_get_callbacks(stmt->dbh)->stmt_dtor(stmt->driver_data);
+        stmt->driver_data = NULL;
+    }
+    return 1;
+}
+
+static int phper_pdo_stmt_execute(pdo_stmt_t *stmt) {
+    // The placeholders are emulated by PDO, with the values quoted.
+    zend_string *sql = stmt->active_query_string ? stmt->active_query_string
+                                                 : stmt->query_string;
+    zend_long column_count = 0, row_count = 0;
+    if (!phper_pdo_get_callbacks(stmt->dbh)->stmt_execute(
+            stmt->driver_data, ZSTR_VAL(sql), ZSTR_LEN(sql), &column_count,
+            &row_count)) {
+        phper_pdo_stmt_error(stmt);
+        return 0;
+    }
+    if (!stmt->executed) {
+        php_pdo_stmt_set_column_count(stmt, (int)column_count);
+    }
+    stmt->row_count = row_count;
+    return 1;
+}
+
+static int phper_pdo_stmt_fetch(pdo_stmt_t *stmt,
+                                enum pdo_fetch_orientation ori,
+                                zend_long offset) {
+    int ret = phper_pdo_get_callbacks(stmt->dbh)->stmt_fetch(stmt->driver_data);
+    if (ret < 0) {
+        phper_pdo_stmt_error(stmt);
+        return 0;
+    }
+    return ret;
+}
+
+static int phper_pdo_stmt_describe(pdo_stmt_t *stmt, int colno) {
+    zend_string *name = phper_pdo_get_callbacks(stmt->dbh)->stmt_column_name(
+        stmt->driver_data, colno);
+    if (!name) {
+        phper_pdo_stmt_error(stmt);
+        return 0;
+    }
+    stmt->columns[colno].name = name;
+    stmt->columns[colno].maxlen = SIZE_MAX;
+    stmt->columns[colno].precision = 0;
+    return 1;
+}
+
+static int phper_pdo_stmt_get_col(pdo_stmt_t *stmt, int colno, zval *result,
+                                  enum pdo_param_type *type) {
+    if (!phper_pdo_get_callbacks(stmt->dbh)->stmt_get_col(stmt->driver_data,
+                                                          colno, result)) {
+        phper_pdo_stmt_error(stmt);
+        return 0;
+    }
+    return 1;
+}
+
+static const struct pdo_stmt_methods phper_pdo_stmt_methods = {
+    .dtor = phper_pdo_stmt_dtor,
+    .executer = phper_pdo_stmt_execute,
+    .fetcher = phper_pdo_stmt_fetch,
+    .describer = phper_pdo_stmt_describe,
+    .get_col = phper_pdo_stmt_get_col,
+};
+
+static void phper_pdo_dbh_close(pdo_dbh_t *dbh) {
+    if (dbh->driver_data) {
+        phper_pdo_get_callbacks(dbh)->close(dbh->driver_data);
+        dbh->driver_data = NULL;
+    }
+}
+
+static bool phper_pdo_dbh_prepare(pdo_dbh_t *dbh, zend_string *sql,
+                                  pdo_stmt_t *stmt, zval *driver_options) {
+    void *data = phper_pdo_get_callbacks(dbh)->prepare(
+        dbh->driver_data, ZSTR_VAL(sql), ZSTR_LEN(sql));
+    if (!data) {
+        phper_pdo_dbh_error(dbh);
+        return false;
+    }
+    stmt->driver_data = data;
+    stmt->methods = &phper_pdo_stmt_methods;
+    stmt->supports_placeholders = PDO_PLACEHOLDER_NONE;
+    return true;
+}
+
+static zend_long phper_pdo_dbh_exec(pdo_dbh_t *dbh, const zend_string *sql) {
+    zend_long ret = phper_pdo_get_callbacks(dbh)->exec(
+        dbh->driver_data, ZSTR_VAL(sql), ZSTR_LEN(sql));
+    if (ret < 0) {
+        phper_pdo_dbh_error(dbh);
+        return -1;
+    }
+    return ret;
+}
+
+static zend_string *phper_pdo_dbh_quote(pdo_dbh_t *dbh,
+                                        const zend_string *unquoted,
+                                        enum pdo_param_type paramtype) {
+    return phper_pdo_get_callbacks(dbh)->quote(
+        dbh->driver_data, ZSTR_VAL(unquoted), ZSTR_LEN(unquoted));
+}
+
+static bool phper_pdo_dbh_begin(pdo_dbh_t *dbh) {
+    if (!phper_pdo_get_callbacks(dbh)->begin(dbh->driver_data)) {
+        phper_pdo_dbh_error(dbh);
+        return false;
+    }
+    return true;
+}
+
+static bool phper_pdo_dbh_commit(pdo_dbh_t *dbh) {
+    if (!phper_pdo_get_callbacks(dbh)->commit(dbh->driver_data)) {
+        phper_pdo_dbh_error(dbh);
+        return false;
+    }
+    return true;
+}
+
+static bool phper_pdo_dbh_rollback(pdo_dbh_t *dbh) {
+    if (!phper_pdo_get_callbacks(dbh)->rollback(dbh->driver_data)) {
+        phper_pdo_dbh_error(dbh);
+        return false;
+    }
+    return true;
+}
+
+static zend_string *phper_pdo_dbh_last_id(pdo_dbh_t *dbh,
+                                          const zend_string *name) {
+    zend_string *id = phper_pdo_get_callbacks(dbh)->last_id(
+        dbh->driver_data, name ? ZSTR_VAL(name) : NULL,
+        name ? ZSTR_LEN(name) : 0);
+    if (!id) {
+        phper_pdo_dbh_error(dbh);
+    }
+    return id;
+}
+
+static void phper_pdo_dbh_fetch_error(pdo_dbh_t *dbh, pdo_stmt_t *stmt,
+                                      zval *info) {
+    phper_pdo_get_callbacks(dbh)->fetch_error(
+        dbh->driver_data, stmt ? stmt->driver_data : NULL, info);
+}
+
+static const struct pdo_dbh_methods phper_pdo_dbh_methods = {
+    .closer = phper_pdo_dbh_close,
+    .preparer = phper_pdo_dbh_prepare,
+    .doer = phper_pdo_dbh_exec,
+    .quoter = phper_pdo_dbh_quote,
+    .begin = phper_pdo_dbh_begin,
+    .commit = phper_pdo_dbh_commit,
+    .rollback = phper_pdo_dbh_rollback,
+    .last_id = phper_pdo_dbh_last_id,
+    .fetch_err = phper_pdo_dbh_fetch_error,
+};
+
+static int phper_pdo_handle_factory(pdo_dbh_t *dbh, zval *driver_options) {
+    const phper_pdo_driver *driver = (const phper_pdo_driver *)dbh->driver;
+    void *data = driver->callbacks->connect(driver->data, dbh);
+    if (!data) {
+        return 0;
+    }
+    dbh->driver_data = data;
+    dbh->methods = &phper_pdo_dbh_methods;
+    dbh->alloc_own_columns = 1;
+    return 1;
+}
+
+// Register the PDO driver named `name`, returns the handle for unregistering,
+// must be called in `MINIT`, and the `name` must be static.
+void *phper_pdo_register_driver(const char *name, size_t name_len, void *data,
+                                const phper_pdo_callbacks *callbacks) {
+    phper_pdo_driver *driver = pemalloc(sizeof(phper_pdo_driver), 1);
+    memset(driver, 0, sizeof(phper_pdo_driver));
+    driver->driver.driver_name = name;
+    driver->driver.driver_name_len = name_len;
+    driver->driver.api_version = PDO_DRIVER_API;
+    driver->driver.db_handle_factory = phper_pdo_handle_factory;
+    driver->data = data;
+    driver->callbacks = callbacks;
+    if (php_pdo_register_driver(&driver->driver) == FAILURE) {
+        pefree(driver, 1);
+        return NULL;
+    }
+    return driver;
+}
+
+void phper_pdo_unregister_driver(void *handle) {
+    phper_pdo_driver *driver = handle;
+    php_pdo_unregister_driver(&driver->driver);
+    pefree(driver, 1);
+}
+
+#endif
+
+// ==================================================
+// session apis:
+// ==================================================
+
+#if defined(PHPER_SESSION) && PHP_VERSION_ID >= 70100
+
+// The callbacks implemented by Rust, the `handler` is the data passed to
+// `phper_session_register_module`.
+typedef struct {
+    bool (*open)(void *handler, const char *save_path, const char *name);
+    bool (*close)(void *handler);
+    bool (*read)(void *handler, zend_string *key, zend_string **val);
+    bool (*write)(void *handler, zend_string *key, zend_string *val,
+                  zend_long maxlifetime);
+    bool (*destroy)(void *handler, zend_string *key);
+    bool (*gc)(void *handler, zend_long maxlifetime, zend_long *nrdels);
+    zend_string *(*create_sid)(void *handler);
+    bool (*validate_sid)(void *handler, zend_string *key);
+    bool (*update_timestamp)(void *handler, zend_string *key, zend_string *val,
+                             zend_long maxlifetime);
+} phper_session_callbacks;
+
+typedef struct {
+    ps_module module;
+    void *handler;
+    const phper_session_callbacks *callbacks;
+} phper_session_module;
+
+// The callbacks of `ps_module` don't carry the user data, so the handler is
+// recovered from the current module, which is the one calling them.
+static const phper_session_module *phper_session_current(void) {
+    return (const phper_session_module *)PS(mod);
+}
+
+#define PHPER_SESSION_CALL(name, ...)                                         \
+    phper_session_current()->callbacks->name(                                 \
+        phper_session_current()->handler, ##__VA_ARGS__)
+
+// The session extension only writes and closes the session with the module
+// data set, so it's set to the handler once opened.
+PS_OPEN_FUNC(phper) {
+    if (!PHPER_SESSION_CALL(open, save_path, session_name)) {
+        return FAILURE;
+    }
+    PS_SET_MOD_DATA(phper_session_current()->handler);
+    return SUCCESS;
+}
+
+PS_CLOSE_FUNC(phper) {
+    PS_SET_MOD_DATA(NULL);
+    return PHPER_SESSION_CALL(close) ? SUCCESS : FAILURE;
+}
+
+PS_READ_FUNC(phper) {
+    return PHPER_SESSION_CALL(read, key, val) ? SUCCESS : FAILURE;
+}
+
+PS_WRITE_FUNC(phper) {
+    return PHPER_SESSION_CALL(write, key, val, maxlifetime) ? SUCCESS
+                                                            : FAILURE;
+}
+
+PS_DESTROY_FUNC(phper) {
+    return PHPER_SESSION_CALL(destroy, key) ? SUCCESS : FAILURE;
+}
+
+PS_GC_FUNC(phper) {
+    return PHPER_SESSION_CALL(gc, maxlifetime, nrdels) ? *nrdels : -1;
+}
+
+PS_CREATE_SID_FUNC(phper) {
+    zend_string *sid = PHPER_SESSION_CALL(create_sid);
+    return sid ? sid : php_session_create_id(mod_data);
+}
+
+PS_VALIDATE_SID_FUNC(phper) {
+    return PHPER_SESSION_CALL(validate_sid, key) ? SUCCESS : FAILURE;
+}
+
+PS_UPDATE_TIMESTAMP_FUNC(phper) {
+    return PHPER_SESSION_CALL(update_timestamp, key, val, maxlifetime)
+               ? SUCCESS
+               : FAILURE;
+}
+
+// Register the session save handler named `name`, must be called in `MINIT`,
+// and the `name` must be static. The handler is kept until the process ends,
+// because the session extension has no way to unregister.
+bool phper_session_register_module(const char *name, void *handler,
+                                   const phper_session_callbacks *callbacks) {
+    phper_session_module *module = pemalloc(sizeof(phper_session_module), 1);
+    ps_module base = {PS_MOD_UPDATE_TIMESTAMP(phper)};
+    module->module = base;
+    module->module.s_name = name;
+    module->handler = handler;
+    module->callbacks = callbacks;
+    if (php_session_register_module(&module->module) != SUCCESS) {
+        pefree(module, 1);
+        return false;
+    }
+    return true;
+}
+
+#endif
+
+// ==================================================
+// closure apis:
+// ==================================================
+
+// Create the persistent internal function, which isn't added to the function
+// table, so it can't be called by name, only through the closures created by
+// `phper_zend_create_closure`.
+zend_function *
+phper_zend_new_internal_function(const char *name,
+                                 void (*handler)(INTERNAL_FUNCTION_PARAMETERS)) {
+    zend_internal_function *func = pemalloc(sizeof(zend_internal_function), 1);
+    memset(func, 0, sizeof(zend_internal_function));
+    func->type = ZEND_INTERNAL_FUNCTION;
+    func->fn_flags = ZEND_ACC_PUBLIC;
+    func->function_name = phper_zend_string_init_interned(name, strlen(name));
+    func->handler = handler;
+    return (zend_function *)func;
+}
+
+void phper_zend_free_internal_function(zend_function *func) {
+    pefree(func, 1);
+}
+
+// The closure copies the function, so the function can be freed after the
+// closures are released.
+void phper_zend_create_closure(zval *ret, zend_function *func) {
+    zend_create_closure(ret, func, NULL, NULL, NULL);
+}
+
+// ==================================================
+// module apis:
+// ==================================================
+
+const char *phper_get_zend_module_build_id() {
+    return ZEND_MODULE_BUILD_ID;
+}
+
+zend_internal_arg_info
+phper_zend_begin_arg_info_ex(bool return_reference,
+                             uintptr_t required_num_args) {
+#define static
+#define const
+    ZEND_BEGIN_ARG_INFO_EX(info, 0, return_reference, required_num_args)
+    ZEND_END_ARG_INFO()
+    return info[0];
+#undef static
+#undef const
+}
+
+zend_internal_arg_info phper_zend_arg_info(bool pass_by_ref, const char *name) {
+    zend_internal_arg_info info[] = {ZEND_ARG_INFO(pass_by_ref, )};
+    info[0].name = name;
+    return info[0];
+}
+
+// Set the type of the arg info, or the return type of the leading function
+// info, composed by the `PHPER_TYPE_*` bits and the class names joined by `|`.
+// The class name is prefixed by `?`, which is kept for the nullable class type
+// on PHP 7.2 to 7.4, where the nullability is encoded in the name.
+//
+// Returns false if the type can't be represented by the PHP version, like the
+// union type before PHP 8.0, and the arg info is left untyped.
+bool phper_zend_arg_info_set_type(zend_internal_arg_info *info,
+                                  uint32_t type_bits, const char *class_name,
+                                  size_t class_count) {
+    const char *name = class_count > 0 ? class_name + 1 : NULL;
+#if PHP_VERSION_ID >= 80000
+    uint32_t mask;
+    if (!phper_type_mask(type_bits, class_count > 0, &mask)) {
+        return false;
+    }
+#if PHP_VERSION_ID < 80100
+    // The class names joined by `|` are split since PHP 8.1.
+    if (class_count > 1) {
+        return false;
+    }
+#endif
+    // Keep the send mode and variadic flags.
+    uint32_t flags = ZEND_TYPE_FULL_MASK(info->type);
+    if (name != NULL) {
+        info->type =
+            (zend_type)ZEND_TYPE_INIT_CLASS_CONST_MASK(name, flags | mask);
+    } else {
+        info->type = (zend_type)ZEND_TYPE_INIT_MASK(flags | mask);
+    }
+    return true;
+#else
+    bool allow_null = (type_bits & PHPER_TYPE_NULL) != 0;
+    uint32_t other_bits = type_bits & ~PHPER_TYPE_NULL;
+    if (class_count == 1 && other_bits == 0) {
+#if PHP_VERSION_ID >= 70200
+        info->type = (zend_type)(allow_null ? class_name : name);
+#else
+        info->class_name = name;
+        info->type_hint = IS_OBJECT;
+        info->allow_null = allow_null;
+#endif
+        return true;
+    }
+    if (class_count == 0 && phper_type_code(other_bits) != IS_UNDEF) {
+#if PHP_VERSION_ID >= 70200
+        info->type = ZEND_TYPE_ENCODE(phper_type_code(other_bits), allow_null);
+#else
+        info->type_hint = phper_type_code(other_bits);
+        info->allow_null = allow_null;
+#endif
+        return true;
+    }
+    return false;
+#endif
 }
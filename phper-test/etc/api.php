@@ -0,0 +1,150 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+// Renders the functions, classes and constants registered by the extension,
+// named by the environment `PHPER_TEST_EXTENSION`, sorted by name, so the
+// output is deterministic.
+
+function phper_test_type($type) {
+    if ($type === null) {
+        return "";
+    }
+    if ($type instanceof ReflectionNamedType) {
+        $name = $type->getName();
+        $nullable = $type->allowsNull() && $name !== "mixed" && $name !== "null";
+        return ($nullable ? "?" : "") . $name;
+    }
+    return (string) $type;
+}
+
+function phper_test_value($value) {
+    return str_replace("\n", "", var_export($value, true));
+}
+
+function phper_test_function($function) {
+    $params = [];
+    foreach ($function->getParameters() as $param) {
+        $s = phper_test_type($param->getType());
+        $s .= ($s === "" ? "" : " ") . ($param->isPassedByReference() ? "&" : "");
+        $s .= ($param->isVariadic() ? "..." : "") . "$" . $param->getName();
+        if ($param->isOptional() && !$param->isVariadic()) {
+            $default = "?";
+            try {
+                if ($param->isDefaultValueAvailable()) {
+                    $default = phper_test_value($param->getDefaultValue());
+                }
+            } catch (Throwable $e) {
+            }
+            $s .= " = " . $default;
+        }
+        $params[] = $s;
+    }
+    $s = ($function->returnsReference() ? "&" : "") . $function->getName();
+    $s .= "(" . implode(", ", $params) . ")";
+    $return = phper_test_type($function->getReturnType());
+    return $s . ($return === "" ? "" : ": " . $return);
+}
+
+function phper_test_class($class) {
+    $lines = [];
+    if ($class->isInterface()) {
+        $kind = "interface";
+    } elseif ($class->isTrait()) {
+        $kind = "trait";
+    } else {
+        $kind = "class";
+        if ($class->isAbstract()) {
+            $kind = "abstract " . $kind;
+        }
+        if ($class->isFinal()) {
+            $kind = "final " . $kind;
+        }
+    }
+    $line = $kind . " " . $class->getName();
+    if ($class->getParentClass()) {
+        $line .= " extends " . $class->getParentClass()->getName();
+    }
+    $interfaces = $class->getInterfaceNames();
+    sort($interfaces);
+    if ($interfaces) {
+        $line .= $class->isInterface() ? " extends " : " implements ";
+        $line .= implode(", ", $interfaces);
+    }
+    $lines[] = $line;
+
+    $constants = $class->getConstants();
+    ksort($constants);
+    foreach ($constants as $name => $value) {
+        $lines[] = "    const " . $name . " = " . phper_test_value($value);
+    }
+
+    $properties = [];
+    foreach ($class->getProperties() as $property) {
+        if ($property->getDeclaringClass()->getName() !== $class->getName()) {
+            continue;
+        }
+        $modifiers = implode(" ", Reflection::getModifierNames($property->getModifiers()));
+        $type = method_exists($property, "getType") ? phper_test_type($property->getType()) : "";
+        $properties[$property->getName()] = "    " . $modifiers . ($type === "" ? "" : " " . $type)
+            . " $" . $property->getName();
+    }
+    ksort($properties);
+    $lines = array_merge($lines, array_values($properties));
+
+    $methods = [];
+    foreach ($class->getMethods() as $method) {
+        if ($method->getDeclaringClass()->getName() !== $class->getName()) {
+            continue;
+        }
+        $modifiers = implode(" ", Reflection::getModifierNames($method->getModifiers()));
+        $methods[$method->getName()] = "    " . $modifiers . " function "
+            . phper_test_function($method);
+    }
+    ksort($methods);
+    return implode("\n", array_merge($lines, array_values($methods)));
+}
+
+// Only the names starting with the environment `PHPER_TEST_PREFIX`, if any.
+function phper_test_matched($name) {
+    $prefix = (string) getenv("PHPER_TEST_PREFIX");
+    return $prefix === "" || strncasecmp($name, $prefix, strlen($prefix)) === 0;
+}
+
+$extension = new ReflectionExtension(getenv("PHPER_TEST_EXTENSION"));
+$sections = [];
+
+$constants = $extension->getConstants();
+ksort($constants);
+foreach ($constants as $name => $value) {
+    if (phper_test_matched($name)) {
+        $sections[] = "const " . $name . " = " . phper_test_value($value);
+    }
+}
+
+$functions = $extension->getFunctions();
+ksort($functions);
+foreach ($functions as $function) {
+    if (phper_test_matched($function->getName())) {
+        $sections[] = "function " . phper_test_function($function);
+    }
+}
+
+$classes = $extension->getClasses();
+ksort($classes);
+foreach ($classes as $class) {
+    if (phper_test_matched($class->getName())) {
+        $sections[] = phper_test_class($class);
+    }
+}
+
+echo implode("\n", $sections), "\n";
@@ -0,0 +1,119 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Test tools for calling the functions of extension from Rust, without
+//! writing the php scripts.
+//!
+//! The function is called in a php cli process with the extension loaded, the
+//! returned value is represented by `var_export`, and the thrown exception is
+//! caught and returned as [`Thrown`].
+//!
+//! ```no_run
+//! use phper_test::calls::{call_function, Thrown};
+//!
+//! let lib_path = "target/debug/libhello.so";
+//! assert_eq!(
+//!     call_function(lib_path, "say_hello", &["'world'"]),
+//!     Ok("NULL".to_owned())
+//! );
+//! assert_eq!(
+//!     call_function(lib_path, "say_hello", &["[]"]),
+//!     Err(Thrown {
+//!         class: "TypeError".to_owned(),
+//!         code: 0,
+//!         message: "type error: must be of type string, array given".to_owned(),
+//!     })
+//! );
+//! ```
+
+use crate::context::Context;
+use std::{io::Write, path::Path};
+use tempfile::NamedTempFile;
+
+const RESULT_MARKER: &str = "<<<phper-test-call-result>>>";
+
+/// The exception thrown by the called function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thrown {
+    /// The class name of exception, like `TypeError`.
+    pub class: String,
+    /// The code of exception.
+    pub code: i64,
+    /// The message of exception.
+    pub message: String,
+}
+
+/// Call the function of extension by name, returns the `var_export` of the
+/// returned value, or the thrown exception.
+///
+/// - `lib_path` is the path of extension lib.
+///
+/// - `args` are the php expressions of the arguments, like `"'foo'"`, `"1"` or
+///   `"[1, 2]"`.
+///
+/// # Panics
+///
+/// Panics if the php process fails, for example, the function isn't
+/// defined or the fatal error occurs.
+pub fn call_function(
+    lib_path: impl AsRef<Path>, name: &str, args: &[&str],
+) -> Result<String, Thrown> {
+    call_function_with_ini(lib_path, &[], name, args)
+}
+
+/// Call the function of extension by name with the ini settings, passed by
+/// `-d name=value`, see [`call_function`].
+pub fn call_function_with_ini(
+    lib_path: impl AsRef<Path>, ini: &[(&str, &str)], name: &str, args: &[&str],
+) -> Result<String, Thrown> {
+    let mut script = NamedTempFile::new().unwrap();
+    write!(
+        script,
+        r#"<?php
+try {{
+    $result = \{name}({args});
+    echo "\n{marker}\nreturned\n", var_export($result, true);
+}} catch (\Throwable $e) {{
+    echo "\n{marker}\nthrown\n", get_class($e), "\n", $e->getCode(), "\n", $e->getMessage();
+}}
+"#,
+        name = name,
+        args = args.join(", "),
+        marker = RESULT_MARKER,
+    )
+    .unwrap();
+
+    let context = Context::get_global();
+    let mut cmd = context.create_command_with_lib_and_ini(&lib_path, ini, script.path());
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let result = match stdout.rfind(RESULT_MARKER) {
+        Some(pos) if output.status.success() => &stdout[pos + RESULT_MARKER.len() + 1..],
+        _ => panic!(
+            "call function `{}` failed\n===== stdout ======\n{}\n===== stderr ======\n{}",
+            name,
+            stdout,
+            String::from_utf8_lossy(&output.stderr),
+        ),
+    };
+
+    if let Some(value) = result.strip_prefix("returned\n") {
+        return Ok(value.to_owned());
+    }
+
+    let thrown = result.strip_prefix("thrown\n").unwrap();
+    let mut parts = thrown.splitn(3, '\n');
+    Err(Thrown {
+        class: parts.next().unwrap_or_default().to_owned(),
+        code: parts.next().unwrap_or_default().parse().unwrap_or_default(),
+        message: parts.next().unwrap_or_default().to_owned(),
+    })
+}
@@ -50,11 +50,44 @@ pub type ScriptCondition<'a> = (&'a dyn AsRef<Path>, &'a dyn Fn(Output) -> bool)
 /// See [example logging integration test](https://github.com/phper-framework/phper/blob/master/examples/logging/tests/integration.rs).
 pub fn test_php_scripts_with_condition(
     lib_path: impl AsRef<Path>, scripts: &[ScriptCondition<'_>],
+) {
+    test_php_scripts_with_ini_and_condition(lib_path, &[], scripts);
+}
+
+/// Check your extension by executing the php script with the ini settings,
+/// passed by `-d name=value`, if the all executing return success, than the
+/// test is pass.
+///
+/// Useful for testing the extension with the zend extensions, for example,
+/// opcache:
+///
+/// ```no_run
+/// use phper_test::cli::test_php_scripts_with_ini;
+///
+/// test_php_scripts_with_ini(
+///     "target/debug/libhello.so",
+///     &[("zend_extension", "opcache"), ("opcache.enable_cli", "1")],
+///     &[&"tests/php/test.php"],
+/// );
+/// ```
+pub fn test_php_scripts_with_ini(
+    lib_path: impl AsRef<Path>, ini: &[(&str, &str)], scripts: &[&dyn AsRef<Path>],
+) {
+    let condition = |output: Output| output.status.success();
+    let scripts = scripts
+        .iter()
+        .map(|s| (*s, &condition as _))
+        .collect::<Vec<_>>();
+    test_php_scripts_with_ini_and_condition(lib_path, ini, &scripts);
+}
+
+fn test_php_scripts_with_ini_and_condition(
+    lib_path: impl AsRef<Path>, ini: &[(&str, &str)], scripts: &[ScriptCondition<'_>],
 ) {
     let context = Context::get_global();
 
     for (script, condition) in scripts {
-        let mut cmd = context.create_command_with_lib(&lib_path, script);
+        let mut cmd = context.create_command_with_lib_and_ini(&lib_path, ini, script);
 
         let output = cmd.output().unwrap();
         let path = script.as_ref().to_str().unwrap();
@@ -69,14 +69,24 @@ impl Context {
 
     pub fn create_command_with_lib(
         &self, lib_path: impl AsRef<Path>, script: impl AsRef<Path>,
+    ) -> ContextCommand {
+        self.create_command_with_lib_and_ini(lib_path, &[], script)
+    }
+
+    pub fn create_command_with_lib_and_ini(
+        &self, lib_path: impl AsRef<Path>, ini: &[(&str, &str)], script: impl AsRef<Path>,
     ) -> ContextCommand {
         let mut cmd = Command::new(&self.php_bin);
-        let args = vec![
+        let mut args = vec![
             "-n".to_owned(),
             "-d".to_owned(),
             format!("extension={}", lib_path.as_ref().display()),
-            script.as_ref().display().to_string(),
         ];
+        for (name, value) in ini {
+            args.push("-d".to_owned());
+            args.push(format!("{}={}", name, value));
+        }
+        args.push(script.as_ref().display().to_string());
         cmd.args(&args);
         ContextCommand { cmd, args }
     }
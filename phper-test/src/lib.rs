@@ -13,7 +13,10 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![doc = include_str!("../README.md")]
 
+pub mod calls;
 pub mod cli;
 mod context;
 pub mod fpm;
+pub mod matrix;
+pub mod snapshots;
 pub mod utils;
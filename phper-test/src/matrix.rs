@@ -0,0 +1,156 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Test tools for running the tests against multiple PHP versions.
+//!
+//! The extension is built against the headers of one PHP version, so it can't
+//! be loaded by other versions. Instead, `cargo build` and `cargo test` are run
+//! once for each version, with the environment variable `PHP_CONFIG` pointing
+//! to the matching `php-config`, which rebuilds the extension.
+
+use crate::utils;
+use std::{
+    env,
+    ffi::OsStr,
+    fmt::{self, Display},
+    path::Path,
+    process::Command,
+};
+
+/// The environment variable of comma separated PHP binaries, like
+/// `php7.4,php8.2`.
+pub const PHP_BINARIES_ENV: &str = "PHP_BINARIES";
+
+/// The installed PHP, with its `php-config`.
+#[derive(Debug, Clone)]
+pub struct PhpInstallation {
+    /// Path of the php binary.
+    pub php_bin: String,
+    /// Path of the `php-config` of the php binary.
+    pub php_config: String,
+    /// Value of `PHP_VERSION`.
+    pub version: String,
+}
+
+impl PhpInstallation {
+    /// Detect the installation by the php binary, the `php-config` is found
+    /// next to it with the same suffix, like `php-config7.4` for `php7.4`.
+    pub fn from_php_bin(php_bin: impl Into<String>) -> Self {
+        let php_bin = php_bin.into();
+        let php_config = find_php_config(&php_bin);
+
+        let version = utils::execute_command(&[php_bin.as_str(), "-r", "echo PHP_VERSION;"]);
+        let config_version = utils::execute_command(&[php_config.as_str(), "--version"]);
+        assert_eq!(
+            version, config_version,
+            "the version of `{}` mismatches the version of `{}`",
+            php_bin, php_config
+        );
+
+        Self {
+            php_bin,
+            php_config,
+            version,
+        }
+    }
+}
+
+impl Display for PhpInstallation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PHP {} ({})", self.version, self.php_bin)
+    }
+}
+
+fn find_php_config(php_bin: &str) -> String {
+    let path = Path::new(php_bin);
+    let suffix = path
+        .file_name()
+        .and_then(OsStr::to_str)
+        .and_then(|name| name.strip_prefix("php"))
+        .unwrap_or_default();
+    path.with_file_name(format!("php-config{}", suffix))
+        .display()
+        .to_string()
+}
+
+/// Discover the installations listed in the environment variable
+/// [`PHP_BINARIES_ENV`], empty if it isn't set.
+pub fn discover() -> Vec<PhpInstallation> {
+    env::var(PHP_BINARIES_ENV)
+        .map(|binaries| {
+            binaries
+                .split(',')
+                .map(str::trim)
+                .filter(|php_bin| !php_bin.is_empty())
+                .map(PhpInstallation::from_php_bin)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The test result of one PHP version.
+#[derive(Debug, Clone)]
+pub struct MatrixResult {
+    /// The tested installation.
+    pub installation: PhpInstallation,
+    /// Whether `cargo build` and `cargo test` are both success.
+    pub success: bool,
+}
+
+/// Run `cargo build` and `cargo test` for each installation in order, and
+/// report the per-version results.
+///
+/// - `cargo_args` are passed to both `cargo build` and `cargo test`, like
+///   `--release`.
+///
+/// - `test_args` are passed to the test binaries, like `--nocapture`.
+pub fn run_cargo_test<S: AsRef<OsStr>>(
+    installations: &[PhpInstallation], cargo_args: &[S], test_args: &[S],
+) -> Vec<MatrixResult> {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+
+    let results = installations
+        .iter()
+        .map(|installation| {
+            eprintln!("===== test with {} =====", installation);
+
+            let run = |subcommand: &str, extra_args: &[S]| {
+                let mut command = Command::new(&cargo);
+                command
+                    .arg(subcommand)
+                    .args(cargo_args)
+                    .env("PHP_CONFIG", &installation.php_config);
+                if !extra_args.is_empty() {
+                    command.arg("--").args(extra_args);
+                }
+                command
+                    .status()
+                    .unwrap_or_else(|_| panic!("Execute command {:?} failed", command))
+                    .success()
+            };
+
+            MatrixResult {
+                installation: installation.clone(),
+                success: run("build", &[]) && run("test", test_args),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    eprintln!("===== matrix results =====");
+    for result in &results {
+        eprintln!(
+            "{}: {}",
+            result.installation,
+            if result.success { "ok" } else { "FAILED" }
+        );
+    }
+
+    results
+}
@@ -0,0 +1,130 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Test tools for snapshotting the PHP api surface of extension.
+//!
+//! The registered constants, functions and classes (with the signatures and
+//! the modifiers) are rendered by reflection into a deterministic text, sorted
+//! by name, and compared with the golden file committed with the tests, so
+//! the accidental breaking changes show up in the code review.
+//!
+//! ```no_run
+//! use phper_test::snapshots::assert_api_snapshot;
+//!
+//! assert_api_snapshot("target/debug/libhello.so", "hello", "tests/api.txt");
+//! ```
+
+use crate::context::Context;
+use std::{env, fs, io::Write, path::Path};
+use tempfile::NamedTempFile;
+
+/// The environment to overwrite the mismatched golden files.
+pub const UPDATE_ENV: &str = "PHPER_UPDATE_SNAPSHOTS";
+
+/// Render the api surface of the extension named `extension_name`.
+///
+/// # Panics
+///
+/// Panics if the php process fails, for example, the extension isn't loaded.
+pub fn render_api(lib_path: impl AsRef<Path>, extension_name: &str) -> String {
+    render_api_with_prefix(lib_path, extension_name, "")
+}
+
+/// Render the api surface of the extension named `extension_name`, only the
+/// constants, functions and classes whose names start with `prefix`
+/// case-insensitively, like the namespace `Foo\`.
+///
+/// # Panics
+///
+/// Panics if the php process fails, for example, the extension isn't loaded.
+pub fn render_api_with_prefix(
+    lib_path: impl AsRef<Path>, extension_name: &str, prefix: &str,
+) -> String {
+    let mut script = NamedTempFile::new().unwrap();
+    script.write_all(include_bytes!("../etc/api.php")).unwrap();
+
+    let context = Context::get_global();
+    let mut cmd = context.create_command_with_lib(&lib_path, script.path());
+    cmd.env("PHPER_TEST_EXTENSION", extension_name);
+    cmd.env("PHPER_TEST_PREFIX", prefix);
+    let output = cmd.output().unwrap();
+    if !output.status.success() {
+        panic!(
+            "render api of `{}` failed\n===== stdout ======\n{}\n===== stderr ======\n{}",
+            extension_name,
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Check the api surface of the extension against the golden file `path`.
+///
+/// The test fails if the golden file doesn't exist, or is mismatched with the
+/// first different line. The golden file is created or overwritten when the
+/// environment `PHPER_UPDATE_SNAPSHOTS` is set, then the change can be
+/// reviewed by the diff.
+pub fn assert_api_snapshot(
+    lib_path: impl AsRef<Path>, extension_name: &str, path: impl AsRef<Path>,
+) {
+    assert_api_snapshot_with_prefix(lib_path, extension_name, "", path)
+}
+
+/// Check the api surface of the extension against the golden file `path`, like
+/// [`assert_api_snapshot`], but only the names starting with `prefix`, see
+/// [`render_api_with_prefix`].
+pub fn assert_api_snapshot_with_prefix(
+    lib_path: impl AsRef<Path>, extension_name: &str, prefix: &str, path: impl AsRef<Path>,
+) {
+    let path = path.as_ref();
+    let actual = render_api_with_prefix(lib_path, extension_name, prefix);
+
+    if env::var_os(UPDATE_ENV).is_some() {
+        if fs::read_to_string(path).ok().as_deref() != Some(&actual) {
+            fs::write(path, &actual).unwrap();
+        }
+        return;
+    }
+
+    let expected = match fs::read_to_string(path) {
+        Ok(expected) => expected,
+        Err(e) => panic!(
+            "api snapshot `{}` can't be read: {}\nset `{}` to create it",
+            path.display(),
+            e,
+            UPDATE_ENV,
+        ),
+    };
+    if expected == actual {
+        return;
+    }
+
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut line = 1;
+    loop {
+        let (e, a) = (expected_lines.next(), actual_lines.next());
+        if e != a {
+            panic!(
+                "api snapshot `{}` mismatched at line {}\n- {}\n+ {}\nset `{}` to update it",
+                path.display(),
+                line,
+                e.unwrap_or("<eof>"),
+                a.unwrap_or("<eof>"),
+                UPDATE_ENV,
+            );
+        }
+        if e.is_none() {
+            panic!("api snapshot `{}` mismatched", path.display());
+        }
+        line += 1;
+    }
+}
@@ -9,15 +9,11 @@
 // See the Mulan PSL v2 for more details.
 
 use phper_sys::*;
+use std::env;
 
 fn main() {
-    phper_build::register_configures();
-
-    #[cfg(target_os = "macos")]
-    {
-        println!("cargo:rustc-link-arg=-undefined");
-        println!("cargo:rustc-link-arg=dynamic_lookup");
-    }
+    phper_build::register_all();
+    register_optional_apis();
 
     assert_eq!(
         USING_ZTS, 0,
@@ -25,3 +21,15 @@ fn main() {
          `--enable-zts`)."
     );
 }
+
+/// The apis of the optional extensions are only compiled on the supported PHP
+/// versions, and left out on the others, like the bindings of `phper-sys`.
+fn register_optional_apis() {
+    let version = (PHP_MAJOR_VERSION, PHP_MINOR_VERSION);
+    if env::var_os("CARGO_FEATURE_PDO").is_some() && version >= (8, 1) {
+        println!("cargo:rustc-cfg=phper_pdo");
+    }
+    if env::var_os("CARGO_FEATURE_SESSION").is_some() && version >= (7, 1) {
+        println!("cargo:rustc-cfg=phper_session");
+    }
+}
@@ -10,14 +10,25 @@
 
 //! Apis relate to [zend_array].
 
-use crate::{alloc::ToRefOwned, strings::ZStr, sys::*, values::ZVal};
+use crate::{
+    alloc::ToRefOwned,
+    functions::CachedCallable,
+    strings::ZStr,
+    sys::*,
+    values::{PersistentZVal, ZVal},
+};
 use derive_more::From;
 use std::{
+    any::Any,
     borrow::Borrow,
+    cell::Cell,
+    cmp::Ordering,
     fmt::{self, Debug},
     marker::PhantomData,
     mem::ManuallyDrop,
     ops::{Deref, DerefMut},
+    os::raw::{c_int, c_void},
+    panic::{self, AssertUnwindSafe},
     ptr::null_mut,
 };
 
@@ -49,6 +60,24 @@ pub enum InsertKey<'a> {
     ZStr(&'a ZStr),
 }
 
+impl<'a> From<IterKey<'a>> for Key<'a> {
+    fn from(k: IterKey<'a>) -> Self {
+        match k {
+            IterKey::Index(i) => Key::Index(i),
+            IterKey::ZStr(s) => Key::ZStr(s),
+        }
+    }
+}
+
+impl<'a> From<IterKey<'a>> for InsertKey<'a> {
+    fn from(k: IterKey<'a>) -> Self {
+        match k {
+            IterKey::Index(i) => InsertKey::Index(i),
+            IterKey::ZStr(s) => InsertKey::ZStr(s),
+        }
+    }
+}
+
 impl<'a> From<Key<'a>> for InsertKey<'a> {
     fn from(k: Key<'a>) -> Self {
         match k {
@@ -139,16 +168,33 @@ impl ZArr {
         unsafe { zend_array_count(self.as_mut_ptr()).try_into().unwrap() }
     }
 
+    /// Returns `true` if the array is allocated persistently, outlives the
+    /// request.
+    #[inline]
+    pub fn is_persistent(&self) -> bool {
+        unsafe { phper_zend_array_is_persistent(self.as_ptr()) }
+    }
+
     /// Add or update item by key.
     ///
     /// Notice that phper prefer to use [`Symtables`](https://www.phpinternalsbook.com/php5/hashtables/array_api.html#symtables) api `zend_symtable_*`,
     /// so `insert(42)` and `insert("42")` should be considered the same.
+    ///
+    /// # Panics
+    ///
+    /// In debug build, panic if the array is persistent but the value is
+    /// allocated per request.
     #[allow(clippy::useless_conversion)]
     pub fn insert<'a>(&mut self, key: impl Into<InsertKey<'a>>, value: impl Into<ZVal>) {
         let key = key.into();
         let mut value = ManuallyDrop::new(value.into());
         let val = value.as_mut_ptr();
 
+        debug_assert!(
+            !self.is_persistent() || unsafe { phper_zval_is_persistent(val) },
+            "can't insert the value allocated per request into persistent array"
+        );
+
         unsafe {
             match key {
                 InsertKey::NextIndex => {
@@ -314,6 +360,39 @@ impl ZArr {
         }
     }
 
+    /// Converts the values to `Vec<i64>` in a tight loop, without wrapping
+    /// each value into [`ZVal`], the packed array (list) without holes takes
+    /// the fastest path. The keys are discarded.
+    ///
+    /// Returns `None` if any value isn't int.
+    pub fn to_vec_i64(&self) -> Option<Vec<i64>> {
+        let len = self.inner.nNumOfElements as usize;
+        let mut vec = Vec::<i64>::with_capacity(len);
+        unsafe {
+            if !phper_zend_hash_to_longs(self.as_ptr(), vec.as_mut_ptr().cast(), len) {
+                return None;
+            }
+            vec.set_len(len);
+        }
+        Some(vec)
+    }
+
+    /// Converts the values to `Vec<f64>` in a tight loop, like
+    /// [`ZArr::to_vec_i64`], the int values are converted to float.
+    ///
+    /// Returns `None` if any value isn't float or int.
+    pub fn to_vec_f64(&self) -> Option<Vec<f64>> {
+        let len = self.inner.nNumOfElements as usize;
+        let mut vec = Vec::<f64>::with_capacity(len);
+        unsafe {
+            if !phper_zend_hash_to_doubles(self.as_ptr(), vec.as_mut_ptr(), len) {
+                return None;
+            }
+            vec.set_len(len);
+        }
+        Some(vec)
+    }
+
     /// Provides a forward iterator.
     #[inline]
     pub fn iter(&self) -> Iter<'_> {
@@ -325,6 +404,147 @@ impl ZArr {
     pub fn iter_mut(&mut self) -> IterMut<'_> {
         IterMut::new(self)
     }
+
+    /// Sorts the array with a comparator function, like `usort` in PHP, the
+    /// keys are discarded and the items are renumbered from 0.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{arrays::ZArray, values::ZVal};
+    ///
+    /// let mut arr = ZArray::new();
+    /// for i in [3, 1, 2] {
+    ///     arr.insert((), ZVal::from(i));
+    /// }
+    /// arr.sort_by(|a, b| a.as_long().cmp(&b.as_long()));
+    /// ```
+    pub fn sort_by(&mut self, compare: impl FnMut(&ZVal, &ZVal) -> Ordering) {
+        self.inner_sort_by(compare, true);
+    }
+
+    /// Sorts the array with a comparator function, like `uasort` in PHP, the
+    /// key association is maintained.
+    pub fn sort_by_preserve_keys(&mut self, compare: impl FnMut(&ZVal, &ZVal) -> Ordering) {
+        self.inner_sort_by(compare, false);
+    }
+
+    /// Sorts the array with a PHP callback comparator, like `usort` in PHP.
+    ///
+    /// The callback is called with two items and should return an integer
+    /// less than, equal to, or greater than zero.
+    ///
+    /// # Errors
+    ///
+    /// Return the error of the first failed callback call, the remaining
+    /// comparisons are skipped and the order of items is unspecified.
+    pub fn sort_by_callable(&mut self, callable: &mut CachedCallable) -> crate::Result<()> {
+        let mut error = None;
+        self.inner_sort_by(
+            |a, b| {
+                if error.is_some() {
+                    return Ordering::Equal;
+                }
+                match callable.call([a.shallow_clone(), b.shallow_clone()]) {
+                    Ok(mut ret) => {
+                        let ret = unsafe { phper_zval_get_long(ret.as_mut_ptr()) };
+                        ret.cmp(&0)
+                    }
+                    Err(e) => {
+                        error = Some(e);
+                        Ordering::Equal
+                    }
+                }
+            },
+            true,
+        );
+        match error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    fn inner_sort_by(&mut self, mut compare: impl FnMut(&ZVal, &ZVal) -> Ordering, renumber: bool) {
+        let mut context = SortContext {
+            compare: &mut compare,
+            panic: None,
+        };
+        let prev =
+            SORT_CONTEXT.with(|c| c.replace(&mut context as *mut SortContext as *mut c_void));
+        unsafe {
+            phper_zend_hash_sort(self.as_mut_ptr(), Some(sort_compare), renumber);
+        }
+        SORT_CONTEXT.with(|c| c.set(prev));
+
+        if let Some(payload) = context.panic {
+            panic::resume_unwind(payload);
+        }
+    }
+
+    /// Creates a new array by calling the function on every item, the keys
+    /// are kept.
+    pub fn map(&self, mut f: impl FnMut(&ZVal) -> ZVal) -> ZArray {
+        let mut arr = ZArray::with_capacity(self.inner.nNumOfElements as usize);
+        for (key, val) in self.iter() {
+            arr.insert(key, f(val));
+        }
+        arr
+    }
+
+    /// Creates a new array with the items that the predicate returns `true`,
+    /// the keys are kept, like `array_filter` in PHP.
+    pub fn filter(&self, mut predicate: impl FnMut(&ZVal) -> bool) -> ZArray {
+        let mut arr = ZArray::new();
+        for (key, val) in self.iter() {
+            if predicate(val) {
+                arr.insert(key, val.shallow_clone());
+            }
+        }
+        arr
+    }
+
+    /// Merges the items of other array into this array, like `array_merge`
+    /// in PHP, the items with string keys overwrite the existing items, and
+    /// the items with index keys are appended.
+    pub fn merge(&mut self, other: &ZArr) {
+        for (key, val) in other.iter() {
+            match key {
+                IterKey::Index(_) => self.insert(InsertKey::NextIndex, val.shallow_clone()),
+                IterKey::ZStr(s) => self.insert(s, val.shallow_clone()),
+            }
+        }
+    }
+}
+
+type SortCompare<'a> = dyn FnMut(&ZVal, &ZVal) -> Ordering + 'a;
+
+struct SortContext<'a> {
+    compare: &'a mut SortCompare<'a>,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+thread_local! {
+    /// The `zend_hash_sort` compare function doesn't take user data, so the
+    /// context of the current sorting is put here.
+    static SORT_CONTEXT: Cell<*mut c_void> = Cell::new(null_mut());
+}
+
+unsafe extern "C" fn sort_compare(a: *const c_void, b: *const c_void) -> c_int {
+    let context = &mut *(SORT_CONTEXT.with(Cell::get) as *mut SortContext);
+    if context.panic.is_some() {
+        return 0;
+    }
+
+    let a = ZVal::from_ptr(&(*(a as *const Bucket)).val);
+    let b = ZVal::from_ptr(&(*(b as *const Bucket)).val);
+
+    match panic::catch_unwind(AssertUnwindSafe(|| (context.compare)(a, b))) {
+        Ok(ordering) => ordering as c_int,
+        Err(payload) => {
+            context.panic = Some(payload);
+            0
+        }
+    }
 }
 
 impl Debug for ZArr {
@@ -366,6 +586,10 @@ pub struct ZArray {
 
 impl ZArray {
     /// Creates an empty `ZArray`.
+    ///
+    /// The array is allocated per request, and released at the end of
+    /// request, so it is only valid in the request (between `RINIT` and
+    /// `RSHUTDOWN`), use [`PersistentZArray`] in `MINIT` instead.
     #[inline]
     pub fn new() -> Self {
         Self::with_capacity(0)
@@ -382,6 +606,15 @@ impl ZArray {
         }
     }
 
+    /// Creates an empty persistent `ZArray` with at least the specified
+    /// capacity, the inserted values must be persistent too.
+    pub(crate) fn with_capacity_persistent(n: usize) -> Self {
+        unsafe {
+            let ptr = phper_zend_new_persistent_array(n.try_into().unwrap());
+            Self::from_raw(ptr)
+        }
+    }
+
     /// Create owned object From raw pointer, usually used in pairs with
     /// `into_raw`.
     ///
@@ -447,11 +680,80 @@ impl Clone for ZArray {
 impl Drop for ZArray {
     fn drop(&mut self) {
         unsafe {
-            zend_array_destroy(self.as_mut_ptr());
+            phper_zend_array_release(self.as_mut_ptr());
         }
     }
 }
 
+/// The persistent [`ZArray`], allocated by `malloc` and outlives the request,
+/// so it can be created in `MINIT` and held across requests.
+///
+/// Unlike [`ZArray`], only the [`PersistentZVal`], which is the scalar or the
+/// persistent string and array, can be inserted, so the value allocated per
+/// request is rejected at compile time.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{arrays::PersistentZArray, strings::PersistentZString};
+///
+/// let mut arr = PersistentZArray::new();
+/// arr.insert("foo", 1i64);
+/// arr.insert("bar", PersistentZString::new("baz"));
+/// ```
+pub struct PersistentZArray {
+    inner: ZArray,
+}
+
+impl PersistentZArray {
+    /// Creates an empty persistent array.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates an empty persistent array with at least the specified
+    /// capacity.
+    #[inline]
+    pub fn with_capacity(n: usize) -> Self {
+        Self {
+            inner: ZArray::with_capacity_persistent(n),
+        }
+    }
+
+    /// Add or update item by key, like [`ZArr::insert`].
+    #[inline]
+    pub fn insert<'a>(&mut self, key: impl Into<InsertKey<'a>>, value: impl Into<PersistentZVal>) {
+        self.inner.insert(key, value.into().into_inner());
+    }
+
+    /// Consumes the `PersistentZArray`, returning the wrapped [`ZArray`].
+    #[inline]
+    pub fn into_inner(self) -> ZArray {
+        self.inner
+    }
+}
+
+impl Debug for PersistentZArray {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        common_fmt(self, f, "PersistentZArray")
+    }
+}
+
+impl Default for PersistentZArray {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for PersistentZArray {
+    type Target = ZArr;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
 /// Iterator key for [`ZArr::iter`] and [`ZArr::iter_mut`].
 #[derive(Debug, Clone, PartialEq, From)]
 pub enum IterKey<'a> {
@@ -487,11 +789,21 @@ impl<'a> Iterator for RawIter<'a> {
     type Item = (IterKey<'a>, *mut zval);
 
     fn next(&mut self) -> Option<Self::Item> {
-        unsafe {
-            if self.finished {
-                return None;
+        while !self.finished {
+            if let Some(item) = self.next_slot() {
+                return Some(item);
             }
+        }
+        None
+    }
+}
 
+impl<'a> RawIter<'a> {
+    /// Reads the current slot and moves forward, `None` if the slot is
+    /// undefined (for example, an unset variable of the symbol table), then
+    /// it should be skipped.
+    fn next_slot(&mut self) -> Option<(IterKey<'a>, *mut zval)> {
+        unsafe {
             let mut str_index: *mut zend_string = null_mut();
             let mut num_index: zend_ulong = 0;
 
@@ -518,12 +830,17 @@ impl<'a> Iterator for RawIter<'a> {
                 self.finished = true;
                 return None;
             }
+            let val = phper_zval_defined(val);
 
             if zend_hash_move_forward_ex(self.arr, &mut self.pos) == ZEND_RESULT_CODE_FAILURE {
                 self.finished = true;
             }
 
-            Some((iter_key, val))
+            if val.is_null() {
+                None
+            } else {
+                Some((iter_key, val))
+            }
         }
     }
 }
@@ -0,0 +1,158 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to class autoloading, backed by `spl_autoload_register`.
+
+use crate::{
+    classes::ClassEntry,
+    errors::throw,
+    functions::call,
+    modules::global_module,
+    sources::SourceEntity,
+    sys::*,
+    values::{ExecuteData, ZVal},
+};
+use std::collections::HashMap;
+
+enum Loader {
+    Handler(Box<dyn Fn(&str) -> bool>),
+    /// The sources keyed by the lowercase class names, because the class names
+    /// are case-insensitive.
+    Sources {
+        prefix: String,
+        sources: HashMap<String, SourceEntity>,
+    },
+}
+
+/// Builder for registering class autoloader.
+pub(crate) struct AutoloadEntity {
+    loader: Loader,
+}
+
+impl AutoloadEntity {
+    pub(crate) fn new(handler: impl Fn(&str) -> bool + 'static) -> Self {
+        Self {
+            loader: Loader::Handler(Box::new(handler)),
+        }
+    }
+
+    pub(crate) fn new_with_prefix(prefix: String, sources: HashMap<String, SourceEntity>) -> Self {
+        Self {
+            loader: Loader::Sources { prefix, sources },
+        }
+    }
+
+    /// The embedded sources to be written in `MINIT`.
+    pub(crate) fn sources_mut(&mut self) -> impl Iterator<Item = &mut SourceEntity> {
+        let sources = match &mut self.loader {
+            Loader::Handler(_) => None,
+            Loader::Sources { sources, .. } => Some(sources.values_mut()),
+        };
+        sources.into_iter().flatten()
+    }
+
+    pub(crate) fn load(&self, class_name: &str) -> crate::Result<bool> {
+        match &self.loader {
+            Loader::Handler(handler) => Ok(handler(class_name)),
+            Loader::Sources { prefix, sources } => {
+                let class_name = class_name.trim_start_matches('\\');
+                let has_prefix = class_name
+                    .get(..prefix.len())
+                    .map(|s| s.eq_ignore_ascii_case(prefix))
+                    .unwrap_or_default();
+                if !has_prefix {
+                    return Ok(false);
+                }
+                match sources.get(&class_name.to_ascii_lowercase()) {
+                    Some(source) => {
+                        source.execute()?;
+                        Ok(true)
+                    }
+                    None => Ok(false),
+                }
+            }
+        }
+    }
+}
+
+/// The hidden autoloader created in `MINIT`, which isn't added to the function
+/// table, so the userland can't call it by name, only the closure registered to
+/// `spl_autoload_register` reaches it.
+pub(crate) struct AutoloadFunction(*mut zend_function);
+
+impl AutoloadFunction {
+    pub(crate) fn new() -> Self {
+        unsafe {
+            Self(phper_zend_new_internal_function(
+                "{closure}\0".as_ptr().cast(),
+                Some(autoload_handler),
+            ))
+        }
+    }
+
+    /// Register the closure to `spl_autoload_register`, should be called in
+    /// every request, because the autoload stack is cleared in `RSHUTDOWN`.
+    pub(crate) fn register(&self) -> crate::Result<()> {
+        let mut closure = ZVal::default();
+        unsafe {
+            phper_zend_create_closure(closure.as_mut_ptr(), self.0);
+        }
+        call("spl_autoload_register", [closure])?;
+        Ok(())
+    }
+}
+
+impl Drop for AutoloadFunction {
+    fn drop(&mut self) {
+        unsafe {
+            phper_zend_free_internal_function(self.0);
+        }
+    }
+}
+
+/// The handler of hidden autoloader.
+unsafe extern "C" fn autoload_handler(execute_data: *mut zend_execute_data, _: *mut zval) {
+    let execute_data = ExecuteData::from_mut_ptr(execute_data);
+    if execute_data.num_args() == 0 {
+        return;
+    }
+    if let Err(e) = load(execute_data.get_parameter(0)) {
+        throw(e);
+    }
+}
+
+/// Try the autoloaders of module in order, stop when one of them returns
+/// `true`.
+///
+/// The class of module which isn't registered because the parent isn't found
+/// after startup, triggers the loading of the parent, and the reason is thrown,
+/// because the parent declared in the request can't be extended by the internal
+/// class.
+fn load(class_name: &ZVal) -> crate::Result<()> {
+    let class_name = class_name.expect_z_str()?.to_str()?;
+    let module = unsafe { global_module() };
+    if let Some(parent_name) = module.deferred_parent_name(class_name) {
+        let reason = if ClassEntry::lookup(parent_name).is_some() {
+            "is declared after startup, which can't be extended by the internal class"
+        } else {
+            "isn't found"
+        };
+        return Err(crate::Error::boxed(format!(
+            "class {} isn't registered, because the parent class {} {}",
+            class_name, parent_name, reason
+        )));
+    }
+    for autoloader in module.autoload_entities() {
+        if autoloader.load(class_name)? {
+            break;
+        }
+    }
+    Ok(())
+}
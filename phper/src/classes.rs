@@ -12,27 +12,35 @@
 
 use crate::{
     arrays::ZArr,
+    constants::ConstantValue,
+    contexts::ModuleContext,
     errors::{ClassNotFoundError, InitializeObjectError, Throwable},
-    functions::{Function, FunctionEntry, Method, MethodEntity},
+    functions::{
+        is_runtime_definition_key, Argument, Callable, Function, FunctionEntry, Method,
+        MethodEntity, MethodWithReturn, ObjectMethod, ReturnValue,
+    },
     modules::global_module,
-    objects::{StateObj, StateObject, ZObject},
-    strings::ZStr,
+    objects::{ObjectId, StateObj, StateObject, ZObj, ZObject},
+    strings::{ZStr, ZString},
     sys::*,
-    types::Scalar,
+    types::{Scalar, TypeHint},
     utils::ensure_end_with_zero,
     values::ZVal,
 };
 use std::{
     any::Any,
-    ffi::{c_void, CString},
+    cmp,
+    ffi::{c_void, CStr, CString},
     fmt::Debug,
     marker::PhantomData,
     mem::{replace, size_of, zeroed, ManuallyDrop},
     os::raw::c_int,
-    ptr::null_mut,
+    ptr::{self, null_mut},
     rc::Rc,
-    slice,
-    sync::atomic::{AtomicPtr, Ordering},
+    sync::{
+        atomic::{AtomicPtr, Ordering},
+        Arc,
+    },
 };
 
 /// Predefined interface `Iterator`.
@@ -47,6 +55,40 @@ pub fn array_access_class<'a>() -> &'a ClassEntry {
     unsafe { ClassEntry::from_ptr(zend_ce_arrayaccess) }
 }
 
+/// Get the classes defined in `EG(class_table)`, including the internal classes
+/// and the user classes declared so far, the class aliases are yielded again.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::classes::{defined_classes, ClassType};
+///
+/// let interfaces = defined_classes()
+///     .filter(|ce| ce.class_type() == ClassType::Interface)
+///     .count();
+/// ```
+pub fn defined_classes<'a>() -> impl Iterator<Item = &'a ClassEntry> {
+    unsafe {
+        ZArr::from_ptr(eg!(class_table))
+            .iter()
+            .filter(|(key, _)| !is_runtime_definition_key(key))
+            .map(|(_, val)| ClassEntry::from_ptr(phper_z_ptr_p(val.as_ptr()).cast()))
+    }
+}
+
+/// The kind of [`ClassEntry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassType {
+    /// Normal class, include abstract class.
+    Class,
+    /// Interface.
+    Interface,
+    /// Trait.
+    Trait,
+    /// Enum, since PHP 8.1.
+    Enum,
+}
+
 /// Wrapper of [zend_class_entry].
 #[repr(transparent)]
 pub struct ClassEntry {
@@ -134,6 +176,15 @@ impl ClassEntry {
         }
     }
 
+    /// Find the class by name like [`ClassEntry::from_globals`], and try the
+    /// autoloaders if it isn't found.
+    pub(crate) fn lookup(class_name: &str) -> Option<&'static Self> {
+        unsafe {
+            let ptr = phper_zend_lookup_class(class_name.as_ptr().cast(), class_name.len());
+            ClassEntry::try_from_ptr(ptr)
+        }
+    }
+
     /// Create the object from class and call `__construct` with arguments.
     ///
     /// If the `__construct` is private, or protected and the called scope isn't
@@ -168,6 +219,43 @@ impl ClassEntry {
         unsafe { ZStr::from_ptr(self.inner.name) }
     }
 
+    /// Get the kind of class.
+    pub fn class_type(&self) -> ClassType {
+        let flags = self.inner.ce_flags;
+        if unsafe { phper_zend_class_is_enum(self.as_ptr()) } {
+            ClassType::Enum
+        } else if flags & ZEND_ACC_INTERFACE != 0 {
+            ClassType::Interface
+        } else if flags & ZEND_ACC_TRAIT != 0 {
+            ClassType::Trait
+        } else {
+            ClassType::Class
+        }
+    }
+
+    /// Detect if the class is defined by extension.
+    #[inline]
+    pub fn is_internal(&self) -> bool {
+        self.inner.type_ as u32 == ZEND_INTERNAL_CLASS
+    }
+
+    /// Get the file name where the class is declared, `None` for internal
+    /// class.
+    pub fn filename(&self) -> Option<&ZStr> {
+        if self.is_internal() {
+            return None;
+        }
+        unsafe { ZStr::try_from_ptr(self.inner.info.user.filename) }
+    }
+
+    /// Get the line where the class starts, `None` for internal class.
+    pub fn line_start(&self) -> Option<u32> {
+        if self.is_internal() {
+            return None;
+        }
+        Some(unsafe { self.inner.info.user.line_start })
+    }
+
     /// Detect if the method is exists in class.
     pub fn has_method(&self, method_name: &str) -> bool {
         unsafe {
@@ -364,6 +452,10 @@ pub(crate) type StateConstructor = dyn Fn() -> *mut dyn Any;
 
 pub(crate) type StateCloner = dyn Fn(*const dyn Any) -> *mut dyn Any;
 
+pub(crate) type StateComparator = dyn Fn(*const dyn Any, *const dyn Any) -> Option<cmp::Ordering>;
+
+pub(crate) type DestroyHook = dyn Fn(ObjectId);
+
 /// Builder for registering class.
 ///
 /// `<T>` means the type of holding state.
@@ -374,11 +466,18 @@ pub struct ClassEntity<T: 'static> {
     class_name: CString,
     state_constructor: Rc<StateConstructor>,
     method_entities: Vec<MethodEntity>,
+    function_entries: Vec<zend_function_entry>,
     property_entities: Vec<PropertyEntity>,
-    parent: Option<Box<dyn Fn() -> &'static ClassEntry>>,
+    constants: Vec<(String, ConstantValue)>,
+    parent: Option<Parent>,
     interfaces: Vec<Box<dyn Fn() -> &'static ClassEntry>>,
     bind_class: Option<&'static StaticStateClass<T>>,
     state_cloner: Option<Rc<StateCloner>>,
+    state_comparator: Option<Rc<StateComparator>>,
+    destroy_hooks: Vec<Rc<DestroyHook>>,
+    has_construct: bool,
+    flags: u32,
+    ce: *mut zend_class_entry,
     _p: PhantomData<(*mut (), T)>,
 }
 
@@ -389,6 +488,84 @@ impl ClassEntity<()> {
     }
 }
 
+impl<T: 'static> ClassEntity<Option<T>> {
+    /// Add `__construct` method to class, which builds the state from the
+    /// arguments, the state is `None` until the constructor returns `Ok`.
+    ///
+    /// If the constructor returns `Err` (thrown as exception), or isn't called
+    /// at all, the state stays `None`, and
+    /// [`StateObj::as_initialized_state`] returns the error, so the object
+    /// can't be used by accident.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{
+    ///     classes::{ClassEntity, Visibility},
+    ///     functions::Argument,
+    /// };
+    ///
+    /// struct Client {
+    ///     endpoint: String,
+    /// }
+    ///
+    /// let mut class = ClassEntity::<Option<Client>>::new_with_default_state_constructor("Client");
+    /// class
+    ///     .add_state_construct(Visibility::Public, |arguments| {
+    ///         let endpoint = arguments[0].expect_z_str()?.to_str()?.to_owned();
+    ///         Ok::<_, phper::Error>(Client { endpoint })
+    ///     })
+    ///     .argument(Argument::by_val("endpoint"));
+    /// class.add_method("endpoint", Visibility::Public, |this, _| {
+    ///     let client = this.as_initialized_state()?;
+    ///     Ok::<_, phper::Error>(client.endpoint.clone())
+    /// });
+    /// ```
+    pub fn add_state_construct<F, E>(&mut self, vis: Visibility, handler: F) -> &mut MethodEntity
+    where
+        F: Fn(&mut [ZVal]) -> Result<T, E> + 'static,
+        E: Throwable + 'static,
+    {
+        self.add_magic_construct(vis, move |this, arguments| {
+            let state = handler(arguments)?;
+            *this.as_mut_state() = Some(state);
+            Ok(())
+        })
+    }
+}
+
+impl<T: Clone + 'static> ClassEntity<T> {
+    /// Make the object cloneable, the state of the cloned object is cloned by
+    /// [`Clone::clone`], shortcut of `state_cloner(Clone::clone)`.
+    pub fn clone_state(&mut self) {
+        self.state_cloner(Clone::clone);
+    }
+}
+
+impl<T: PartialEq + 'static> ClassEntity<T> {
+    /// Compare the states by [`PartialEq`], so `==` and `!=` work, but the
+    /// objects with unequal states are uncomparable for `<` and `>`.
+    pub fn compare_state_by_eq(&mut self) {
+        self.state_comparator(|a, b| (a == b).then_some(cmp::Ordering::Equal));
+    }
+}
+
+impl<T: Ord + 'static> ClassEntity<T> {
+    /// Compare the states by [`Ord`], so all the comparison operators work.
+    pub fn compare_state_by_ord(&mut self) {
+        self.state_comparator(|a, b| Some(a.cmp(b)));
+    }
+}
+
+impl<T: 'static> ClassEntity<Arc<T>> {
+    /// Make the object cloneable, the cloned object shares the same state with
+    /// the original object, so the modification through either one is visible
+    /// to both.
+    pub fn share_state(&mut self) {
+        self.state_cloner(Arc::clone);
+    }
+}
+
 impl<T: Default + 'static> ClassEntity<T> {
     /// Construct a new `ClassEntity` with class name and default state
     /// constructor.
@@ -411,11 +588,18 @@ impl<T: 'static> ClassEntity<T> {
                 Box::into_raw(boxed)
             }),
             method_entities: Vec::new(),
+            function_entries: Vec::new(),
             property_entities: Vec::new(),
+            constants: Vec::new(),
             parent: None,
             interfaces: Vec::new(),
             bind_class: None,
             state_cloner: None,
+            state_comparator: None,
+            destroy_hooks: Vec::new(),
+            has_construct: false,
+            flags: 0,
+            ce: null_mut(),
             _p: PhantomData,
         }
     }
@@ -437,6 +621,24 @@ impl<T: 'static> ClassEntity<T> {
         self.method_entities.last_mut().unwrap()
     }
 
+    /// Add member method to class, the return value is constructed in place by
+    /// the [`ReturnValue`] builder, see
+    /// [`Module::add_function_with_return`](crate::modules::Module::add_function_with_return).
+    pub fn add_method_with_return<F, E>(
+        &mut self, name: impl Into<String>, vis: Visibility, handler: F,
+    ) -> &mut MethodEntity
+    where
+        F: Fn(&mut StateObj<T>, &mut [ZVal], &mut ReturnValue<'_>) -> Result<(), E> + 'static,
+        E: Throwable + 'static,
+    {
+        self.method_entities.push(MethodEntity::new(
+            name,
+            Some(Rc::new(MethodWithReturn::new(handler))),
+            vis,
+        ));
+        self.method_entities.last_mut().unwrap()
+    }
+
     /// Add static method to class, with visibility and method handler.
     pub fn add_static_method<F, Z, E>(
         &mut self, name: impl Into<String>, vis: Visibility, handler: F,
@@ -462,6 +664,152 @@ impl<T: 'static> ClassEntity<T> {
         self.method_entities.last_mut().unwrap()
     }
 
+    /// Add the methods, properties or constants in `f` only if `condition`
+    /// holds, see [`Module::when`](crate::modules::Module::when).
+    pub fn when(&mut self, condition: bool, f: impl FnOnce(&mut Self)) -> &mut Self {
+        if condition {
+            f(self);
+        }
+        self
+    }
+
+    /// Add `__construct` method to class, the arguments can be declared by the
+    /// returned [`MethodEntity`].
+    ///
+    /// The object is marked as initialized only after the handler returns
+    /// `Ok`, use [`StateObj::try_as_state`] in other methods to reject the
+    /// object which isn't constructed.
+    pub fn add_magic_construct<F, E>(&mut self, vis: Visibility, handler: F) -> &mut MethodEntity
+    where
+        F: Fn(&mut StateObj<T>, &mut [ZVal]) -> Result<(), E> + 'static,
+        E: Throwable + 'static,
+    {
+        self.has_construct = true;
+        self.add_method("__construct", vis, move |this, arguments| {
+            handler(this, arguments)?;
+            this.set_initialized(true);
+            Ok::<_, E>(())
+        })
+    }
+
+    /// Add `__destruct` method to class, which is public and has no argument.
+    pub fn add_magic_destruct<F, E>(&mut self, handler: F)
+    where
+        F: Fn(&mut StateObj<T>) -> Result<(), E> + 'static,
+        E: Throwable + 'static,
+    {
+        self.add_method("__destruct", Visibility::Public, move |this, _| {
+            handler(this)
+        });
+    }
+
+    /// Add `__toString` method to class, which is public and has no argument,
+    /// the return type is declared as `string` since PHP 8.0, and the class
+    /// implements `Stringable` automatically.
+    pub fn add_magic_to_string<F, S, E>(&mut self, handler: F)
+    where
+        F: Fn(&mut StateObj<T>) -> Result<S, E> + 'static,
+        S: AsRef<[u8]> + 'static,
+        E: Throwable + 'static,
+    {
+        let _entity = self.add_method("__toString", Visibility::Public, move |this, _| {
+            handler(this).map(ZString::new)
+        });
+        #[cfg(phper_major_version = "8")]
+        _entity.set_return_type(TypeHint::String);
+    }
+
+    /// Add `__get` method to class, which is public and receives the property
+    /// name, called when reading the inaccessible property.
+    pub fn add_magic_get<F, Z, E>(&mut self, handler: F)
+    where
+        F: Fn(&mut StateObj<T>, &ZStr) -> Result<Z, E> + 'static,
+        Z: Into<ZVal> + 'static,
+        E: Throwable + 'static,
+    {
+        self.add_method("__get", Visibility::Public, move |this, arguments| {
+            let name = arguments[0].expect_z_str()?;
+            handler(this, name).map_err(crate::Error::throw)
+        })
+        .argument(Argument::by_val("name"));
+    }
+
+    /// Add `__set` method to class, which is public and receives the property
+    /// name and value, called when writing the inaccessible property.
+    pub fn add_magic_set<F, E>(&mut self, handler: F)
+    where
+        F: Fn(&mut StateObj<T>, &ZStr, &mut ZVal) -> Result<(), E> + 'static,
+        E: Throwable + 'static,
+    {
+        self.add_method("__set", Visibility::Public, move |this, arguments| {
+            let (name, value) = arguments.split_at_mut(1);
+            let name = name[0].expect_z_str()?;
+            handler(this, name, &mut value[0]).map_err(crate::Error::throw)
+        })
+        .arguments([Argument::by_val("name"), Argument::by_val("value")]);
+    }
+
+    /// Add `__call` method to class, which is public and receives the method
+    /// name and arguments, called when invoking the inaccessible method.
+    pub fn add_magic_call<F, Z, E>(&mut self, handler: F)
+    where
+        F: Fn(&mut StateObj<T>, &ZStr, &mut ZArr) -> Result<Z, E> + 'static,
+        Z: Into<ZVal> + 'static,
+        E: Throwable + 'static,
+    {
+        self.add_method("__call", Visibility::Public, move |this, arguments| {
+            let (name, args) = arguments.split_at_mut(1);
+            let name = name[0].expect_z_str()?;
+            let args = args[0].expect_mut_z_arr()?;
+            handler(this, name, args).map_err(crate::Error::throw)
+        })
+        .arguments([Argument::by_val("name"), Argument::by_val("arguments")]);
+    }
+
+    /// Add `__callStatic` method to class, which is public static and receives
+    /// the method name and arguments, called when invoking the inaccessible
+    /// static method.
+    pub fn add_magic_call_static<F, Z, E>(&mut self, handler: F)
+    where
+        F: Fn(&ZStr, &mut ZArr) -> Result<Z, E> + 'static,
+        Z: Into<ZVal> + 'static,
+        E: Throwable + 'static,
+    {
+        self.add_static_method("__callStatic", Visibility::Public, move |arguments| {
+            let (name, args) = arguments.split_at_mut(1);
+            let name = name[0].expect_z_str()?;
+            let args = args[0].expect_mut_z_arr()?;
+            handler(name, args).map_err(crate::Error::throw)
+        })
+        .arguments([Argument::by_val("name"), Argument::by_val("arguments")]);
+    }
+
+    /// Add `__invoke` method to class, which is public, called when the object
+    /// is called as a function, the arguments can be declared by the returned
+    /// [`MethodEntity`].
+    pub fn add_magic_invoke<F, Z, E>(&mut self, handler: F) -> &mut MethodEntity
+    where
+        F: Fn(&mut StateObj<T>, &mut [ZVal]) -> Result<Z, E> + 'static,
+        Z: Into<ZVal> + 'static,
+        E: Throwable + 'static,
+    {
+        self.add_method("__invoke", Visibility::Public, handler)
+    }
+
+    /// Add `__clone` method to class, which has no argument, called on the
+    /// cloned object, after the state is cloned by
+    /// [`ClassEntity::state_cloner`].
+    ///
+    /// The private or protected `__clone` prevents the object from being
+    /// cloned outside.
+    pub fn add_magic_clone<F, E>(&mut self, vis: Visibility, handler: F)
+    where
+        F: Fn(&mut StateObj<T>) -> Result<(), E> + 'static,
+        E: Throwable + 'static,
+    {
+        self.add_method("__clone", vis, move |this, _| handler(this));
+    }
+
     /// Declare property.
     ///
     /// The argument `value` should be `Copy` because 'zend_declare_property'
@@ -469,9 +817,10 @@ impl<T: 'static> ClassEntity<T> {
     /// "Internal zvals cannot be refcounted".
     pub fn add_property(
         &mut self, name: impl Into<String>, visibility: Visibility, value: impl Into<Scalar>,
-    ) {
+    ) -> &mut PropertyEntity {
         self.property_entities
             .push(PropertyEntity::new(name, visibility, value));
+        self.property_entities.last_mut().unwrap()
     }
 
     /// Declare static property.
@@ -481,10 +830,11 @@ impl<T: 'static> ClassEntity<T> {
     /// "Internal zvals cannot be refcounted".
     pub fn add_static_property(
         &mut self, name: impl Into<String>, visibility: Visibility, value: impl Into<Scalar>,
-    ) {
+    ) -> &mut PropertyEntity {
         let mut entity = PropertyEntity::new(name, visibility, value);
         entity.set_vis_static();
         self.property_entities.push(entity);
+        self.property_entities.last_mut().unwrap()
     }
 
     /// Register class to `extends` the parent class.
@@ -502,7 +852,47 @@ impl<T: 'static> ClassEntity<T> {
     /// class.extends(|| ClassEntry::from_globals("Exception").unwrap());
     /// ```
     pub fn extends(&mut self, parent: impl Fn() -> &'static ClassEntry + 'static) {
-        self.parent = Some(Box::new(parent));
+        self.parent = Some(Parent::Entry(Box::new(parent)));
+    }
+
+    /// Register class to `extends` the parent class by name, the parent class
+    /// can be registered by this module (no matter the order of adding), or
+    /// by the other extension.
+    ///
+    /// The classes are registered in `MINIT`, if the parent class isn't
+    /// registered by then, the class is deferred and retried after all the
+    /// extensions are started (since PHP 7.4), so the class of the extension
+    /// started after this module can be extended without declaring the
+    /// dependency by
+    /// [`Module::add_dependency`](crate::modules::Module::add_dependency).
+    ///
+    /// If the parent class still isn't found after startup, a warning is
+    /// emitted, and the class is kept unregistered, because the internal class
+    /// can't extend the classes declared in the request, like the userland
+    /// classes. Loading it by the autoloader loads the parent, and throws the
+    /// reason.
+    ///
+    /// Like [`ClassEntity::extends`], the objects are created by `phper`, so
+    /// the parent class with the custom object storage (like `PDO` or
+    /// `ArrayObject`) can't be extended.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{
+    ///     classes::ClassEntity,
+    ///     modules::{DependencyType, Module},
+    /// };
+    ///
+    /// let mut module = Module::new("my_ext", "0.1.0", "");
+    /// module.add_dependency("json", DependencyType::Required);
+    ///
+    /// let mut class = ClassEntity::new("MyExt\\JsonException");
+    /// class.extends_by_name("JsonException");
+    /// module.add_class(class);
+    /// ```
+    pub fn extends_by_name(&mut self, parent_name: impl Into<String>) {
+        self.parent = Some(Parent::Name(parent_name.into()));
     }
 
     /// Register class to `implements` the interface, due to the class can
@@ -577,14 +967,122 @@ impl<T: 'static> ClassEntity<T> {
         }));
     }
 
+    /// Register the state compare method, so the comparison operators (`==`,
+    /// `<`, `>`, `<=>` and so on) of two objects of this class (or the
+    /// subclasses) compare the states, rather than the properties.
+    ///
+    /// Return `None` means the states are uncomparable, so `==`, `<` and `>`
+    /// all evaluate to `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use phper::classes::ClassEntity;
+    ///
+    /// fn make_money_class() -> ClassEntity<i64> {
+    ///     let mut class = ClassEntity::new_with_state_constructor("Money", || 0);
+    ///     class.state_comparator(|a, b| a.partial_cmp(b));
+    ///     class
+    /// }
+    /// ```
+    pub fn state_comparator(
+        &mut self, compare_fn: impl Fn(&T, &T) -> Option<cmp::Ordering> + 'static,
+    ) {
+        self.state_comparator = Some(Rc::new(move |a, b| {
+            let (a, b) = unsafe {
+                (
+                    a.as_ref()
+                        .unwrap()
+                        .downcast_ref::<T>()
+                        .expect("cast Any to T failed"),
+                    b.as_ref()
+                        .unwrap()
+                        .downcast_ref::<T>()
+                        .expect("cast Any to T failed"),
+                )
+            };
+            compare_fn(a, b)
+        }));
+    }
+
+    /// Declare the public class constant, the value can be the scalar or the
+    /// array, see [`ConstantValue`].
+    pub fn add_constant(&mut self, name: impl Into<String>, value: impl Into<ConstantValue>) {
+        self.constants.push((name.into(), value.into()));
+    }
+
+    /// Forbid cloning the object, which is the default behavior, so it only
+    /// takes effect to revoke the previous registered state cloner.
+    ///
+    /// Cloning the object will throw the Error: `Trying to clone an
+    /// uncloneable object of class Foo`.
+    pub fn deny_clone(&mut self) {
+        self.state_cloner = None;
+    }
+
+    /// Mark the class as `final`, which can't be extended.
+    pub fn mark_final(&mut self) {
+        self.flags |= ZEND_ACC_FINAL;
+    }
+
+    /// Mark the class as `abstract`, which can't be instantiated.
+    pub fn mark_abstract(&mut self) {
+        self.flags |= ZEND_ACC_EXPLICIT_ABSTRACT_CLASS;
+    }
+
+    /// Mark the class as `readonly`, since PHP 8.2, all the instance
+    /// properties are readonly, and the dynamic properties are forbidden.
+    ///
+    /// See [`PropertyEntity::mark_readonly`].
+    #[cfg(phper_php_version_at_least = "8.2")]
+    pub fn mark_readonly(&mut self) {
+        self.flags |= ZEND_ACC_READONLY_CLASS | ZEND_ACC_NO_DYNAMIC_PROPERTIES;
+    }
+
+    /// Add the hook called when the object of this class (or the subclass) is
+    /// destroyed, after the `__destruct` is called.
+    ///
+    /// Useful for cleaning the per object data keyed by [`ObjectId`], because
+    /// the id will be reused by the new object.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use phper::{classes::ClassEntity, objects::ObjectId};
+    /// use std::{cell::RefCell, collections::HashMap};
+    ///
+    /// thread_local! {
+    ///     static METADATA: RefCell<HashMap<ObjectId, String>> = Default::default();
+    /// }
+    ///
+    /// fn make_foo_class() -> ClassEntity<()> {
+    ///     let mut class = ClassEntity::new("Foo");
+    ///     class.on_destroy(|id| {
+    ///         METADATA.with(|m| m.borrow_mut().remove(&id));
+    ///     });
+    ///     class
+    /// }
+    /// ```
+    pub fn on_destroy(&mut self, hook: impl Fn(ObjectId) + 'static) {
+        self.destroy_hooks.push(Rc::new(hook));
+    }
+
+    /// The name of parent class if it's declared by name but not registered
+    /// yet.
+    pub(crate) fn unresolved_parent_name(&self) -> Option<&str> {
+        match &self.parent {
+            Some(Parent::Name(name)) if ClassEntry::from_globals(name).is_err() => Some(name),
+            _ => None,
+        }
+    }
+
     #[allow(clippy::useless_conversion)]
-    pub(crate) unsafe fn init(&self) -> *mut zend_class_entry {
-        let parent: *mut zend_class_entry = self
-            .parent
-            .as_ref()
-            .map(|parent| parent())
-            .map(|entry| entry.as_ptr() as *mut _)
-            .unwrap_or(null_mut());
+    pub(crate) unsafe fn init(&mut self, _ctx: &ModuleContext<'_>) -> *mut zend_class_entry {
+        let parent: *mut zend_class_entry = match &self.parent {
+            Some(Parent::Entry(parent)) => parent().as_ptr() as *mut _,
+            Some(Parent::Name(name)) => ClassEntry::from_globals(name).unwrap().as_ptr() as *mut _,
+            None => null_mut(),
+        };
 
         let class_ce = phper_init_class_entry_ex(
             self.class_name.as_ptr().cast(),
@@ -594,6 +1092,9 @@ impl<T: 'static> ClassEntity<T> {
             parent.cast(),
         );
 
+        self.ce = class_ce;
+        (*class_ce).ce_flags |= self.flags;
+
         if let Some(bind_class) = self.bind_class {
             bind_class.bind(class_ce);
         }
@@ -614,43 +1115,56 @@ impl<T: 'static> ClassEntity<T> {
         }
     }
 
-    unsafe fn function_entries(&self) -> *const zend_function_entry {
+    pub(crate) unsafe fn declare_constants(&self, ce: *mut zend_class_entry) {
+        for (name, value) in &self.constants {
+            let mut value = ManuallyDrop::new(value.to_persistent_z_val());
+            phper_zend_declare_class_constant(
+                ce,
+                name.as_ptr().cast(),
+                name.len(),
+                value.as_mut_ptr(),
+            );
+        }
+    }
+
+    unsafe fn function_entries(&mut self) -> *const zend_function_entry {
         let mut methods = self
             .method_entities
-            .iter()
+            .iter_mut()
             .map(|method| FunctionEntry::from_method_entity(method))
             .collect::<Vec<_>>();
 
         methods.push(zeroed::<zend_function_entry>());
 
-        // Store the state constructor pointer to zend_class_entry.
-        methods.push(self.take_state_constructor_into_function_entry());
-
-        // Store the state cloner pointer to zend_class_entry.
-        methods.push(self.take_state_cloner_into_function_entry());
+        self.function_entries = methods;
+        self.function_entries.as_ptr()
+    }
 
-        Box::into_raw(methods.into_boxed_slice()).cast()
+    #[inline]
+    pub(crate) fn class_name(&self) -> &CStr {
+        &self.class_name
     }
 
-    unsafe fn take_state_constructor_into_function_entry(&self) -> zend_function_entry {
-        let mut entry = zeroed::<zend_function_entry>();
-        let ptr = &mut entry as *mut _ as *mut *const StateConstructor;
-        let state_constructor = Rc::into_raw(self.state_constructor.clone());
-        ptr.write(state_constructor);
-        entry
+    /// The names of all the methods, include the abstract methods.
+    pub(crate) fn method_names(&self) -> impl Iterator<Item = &CStr> {
+        self.method_entities.iter().map(MethodEntity::name)
     }
 
-    unsafe fn take_state_cloner_into_function_entry(&self) -> zend_function_entry {
-        let mut entry = zeroed::<zend_function_entry>();
-        let ptr = &mut entry as *mut _ as *mut *const StateCloner;
-        if let Some(state_cloner) = &self.state_cloner {
-            let state_constructor = Rc::into_raw(state_cloner.clone());
-            ptr.write(state_constructor);
-        }
-        entry
+    /// The methods which have handler, exclude the abstract methods.
+    pub(crate) fn method_handlers(&self) -> impl Iterator<Item = (&CStr, &Rc<dyn Callable>)> {
+        self.method_entities
+            .iter()
+            .filter_map(|method| method.handler().map(|handler| (method.name(), handler)))
     }
 }
 
+/// The parent class, declared by the closure returning the `ClassEntry`, or
+/// by the class name.
+enum Parent {
+    Entry(Box<dyn Fn() -> &'static ClassEntry>),
+    Name(String),
+}
+
 unsafe extern "C" fn class_init_handler(
     class_ce: *mut zend_class_entry, argument: *mut c_void,
 ) -> *mut zend_class_entry {
@@ -666,6 +1180,7 @@ unsafe extern "C" fn class_init_handler(
 pub struct InterfaceEntity {
     interface_name: CString,
     method_entities: Vec<MethodEntity>,
+    function_entries: Vec<zend_function_entry>,
     extends: Vec<Box<dyn Fn() -> &'static ClassEntry>>,
     bind_interface: Option<&'static StaticInterface>,
 }
@@ -676,6 +1191,7 @@ impl InterfaceEntity {
         Self {
             interface_name: ensure_end_with_zero(interface_name.into()),
             method_entities: Vec::new(),
+            function_entries: Vec::new(),
             extends: Vec::new(),
             bind_interface: None,
         }
@@ -719,7 +1235,7 @@ impl InterfaceEntity {
     }
 
     #[allow(clippy::useless_conversion)]
-    pub(crate) unsafe fn init(&self) -> *mut zend_class_entry {
+    pub(crate) unsafe fn init(&mut self, _ctx: &ModuleContext<'_>) -> *mut zend_class_entry {
         let class_ce = phper_init_class_entry_ex(
             self.interface_name.as_ptr().cast(),
             self.interface_name.as_bytes().len().try_into().unwrap(),
@@ -740,16 +1256,27 @@ impl InterfaceEntity {
         class_ce
     }
 
-    unsafe fn function_entries(&self) -> *const zend_function_entry {
+    unsafe fn function_entries(&mut self) -> *const zend_function_entry {
         let mut methods = self
             .method_entities
-            .iter()
+            .iter_mut()
             .map(|method| FunctionEntry::from_method_entity(method))
             .collect::<Vec<_>>();
 
         methods.push(zeroed::<zend_function_entry>());
 
-        Box::into_raw(methods.into_boxed_slice()).cast()
+        self.function_entries = methods;
+        self.function_entries.as_ptr()
+    }
+
+    #[inline]
+    pub(crate) fn interface_name(&self) -> &CStr {
+        &self.interface_name
+    }
+
+    /// The names of all the methods, include the abstract methods.
+    pub(crate) fn method_names(&self) -> impl Iterator<Item = &CStr> {
+        self.method_entities.iter().map(MethodEntity::name)
     }
 }
 
@@ -759,11 +1286,140 @@ unsafe extern "C" fn interface_init_handler(
     zend_register_internal_interface(class_ce)
 }
 
+/// Builder for registering trait.
+///
+/// The methods are implemented in Rust, and copied into the userland classes
+/// using the trait, so the `$this` is the object of the using class, without
+/// the state.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{
+///     classes::{TraitEntity, Visibility},
+///     modules::Module,
+/// };
+/// use std::convert::Infallible;
+///
+/// let mut module = Module::new("my_ext", "0.1.0", "");
+///
+/// let mut tr = TraitEntity::new("MyExt\\Instrumented");
+/// tr.add_abstract_method("spanName", Visibility::Protected);
+/// tr.add_method("trace", Visibility::Public, |this, _| {
+///     let name = this.call("spanName", [])?;
+///     Ok::<_, phper::Error>(name)
+/// });
+/// module.add_trait(tr);
+/// ```
+pub struct TraitEntity {
+    trait_name: CString,
+    method_entities: Vec<MethodEntity>,
+    function_entries: Vec<zend_function_entry>,
+}
+
+impl TraitEntity {
+    /// Construct a new `TraitEntity` with trait name.
+    pub fn new(trait_name: impl Into<String>) -> Self {
+        Self {
+            trait_name: ensure_end_with_zero(trait_name.into()),
+            method_entities: Vec::new(),
+            function_entries: Vec::new(),
+        }
+    }
+
+    /// Add member method to trait, with visibility and method handler.
+    pub fn add_method<F, Z, E>(
+        &mut self, name: impl Into<String>, vis: Visibility, handler: F,
+    ) -> &mut MethodEntity
+    where
+        F: Fn(&mut ZObj, &mut [ZVal]) -> Result<Z, E> + 'static,
+        Z: Into<ZVal> + 'static,
+        E: Throwable + 'static,
+    {
+        self.method_entities.push(MethodEntity::new(
+            name,
+            Some(Rc::new(ObjectMethod::new(handler))),
+            vis,
+        ));
+        self.method_entities.last_mut().unwrap()
+    }
+
+    /// Add static method to trait, with visibility and method handler.
+    pub fn add_static_method<F, Z, E>(
+        &mut self, name: impl Into<String>, vis: Visibility, handler: F,
+    ) -> &mut MethodEntity
+    where
+        F: Fn(&mut [ZVal]) -> Result<Z, E> + 'static,
+        Z: Into<ZVal> + 'static,
+        E: Throwable + 'static,
+    {
+        let mut entity = MethodEntity::new(name, Some(Rc::new(Function::new(handler))), vis);
+        entity.set_vis_static();
+        self.method_entities.push(entity);
+        self.method_entities.last_mut().unwrap()
+    }
+
+    /// Add abstract method to trait, which must be implemented by the using
+    /// class.
+    pub fn add_abstract_method(
+        &mut self, name: impl Into<String>, vis: Visibility,
+    ) -> &mut MethodEntity {
+        let mut entity = MethodEntity::new(name, None, vis);
+        entity.set_vis_abstract();
+        self.method_entities.push(entity);
+        self.method_entities.last_mut().unwrap()
+    }
+
+    #[allow(clippy::useless_conversion)]
+    pub(crate) unsafe fn init(&mut self, _ctx: &ModuleContext<'_>) -> *mut zend_class_entry {
+        let class_ce = phper_init_class_entry_ex(
+            self.trait_name.as_ptr().cast(),
+            self.trait_name.as_bytes().len().try_into().unwrap(),
+            self.function_entries(),
+            Some(class_init_handler),
+            null_mut(),
+        );
+        (*class_ce).ce_flags |= ZEND_ACC_TRAIT;
+        class_ce
+    }
+
+    unsafe fn function_entries(&mut self) -> *const zend_function_entry {
+        let mut methods = self
+            .method_entities
+            .iter_mut()
+            .map(|method| FunctionEntry::from_method_entity(method))
+            .collect::<Vec<_>>();
+
+        methods.push(zeroed::<zend_function_entry>());
+
+        self.function_entries = methods;
+        self.function_entries.as_ptr()
+    }
+
+    #[inline]
+    pub(crate) fn trait_name(&self) -> &CStr {
+        &self.trait_name
+    }
+
+    /// The names of all the methods, include the abstract methods.
+    pub(crate) fn method_names(&self) -> impl Iterator<Item = &CStr> {
+        self.method_entities.iter().map(MethodEntity::name)
+    }
+
+    /// The methods which have handler, exclude the abstract methods.
+    pub(crate) fn method_handlers(&self) -> impl Iterator<Item = (&CStr, &Rc<dyn Callable>)> {
+        self.method_entities
+            .iter()
+            .filter_map(|method| method.handler().map(|handler| (method.name(), handler)))
+    }
+}
+
 /// Builder for declare class property.
-struct PropertyEntity {
+pub struct PropertyEntity {
     name: String,
     visibility: RawVisibility,
     value: Scalar,
+    type_hint: Option<TypeHint>,
 }
 
 impl PropertyEntity {
@@ -772,6 +1428,7 @@ impl PropertyEntity {
             name: name.into(),
             visibility: visibility as RawVisibility,
             value: value.into(),
+            type_hint: None,
         }
     }
 
@@ -781,11 +1438,83 @@ impl PropertyEntity {
         self
     }
 
+    /// Declare the type of property, so assigning the value of wrong type
+    /// throws `TypeError`, since PHP 7.4.
+    ///
+    /// If the value is `null` but the type isn't nullable, the property is
+    /// uninitialized, like `public int $foo;` in PHP.
+    ///
+    /// The type can't be represented by the PHP version (for example, the
+    /// union type before PHP 8.0) is ignored, the property is declared untyped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{
+    ///     classes::{ClassEntity, Visibility},
+    ///     types::TypeHint,
+    /// };
+    ///
+    /// let mut class = ClassEntity::new("Foo");
+    /// class
+    ///     .add_property("id", Visibility::Public, 0)
+    ///     .set_type(TypeHint::Long);
+    /// class
+    ///     .add_property("name", Visibility::Public, ())
+    ///     .set_type(TypeHint::String.nullable());
+    /// ```
+    pub fn set_type(&mut self, type_hint: TypeHint) -> &mut Self {
+        self.type_hint = Some(type_hint);
+        self
+    }
+
+    /// Mark the property as `readonly`, since PHP 8.1, which can only be
+    /// initialized once from inside the class, like by
+    /// [`ZObj::set_property`] in the constructor.
+    ///
+    /// The property is uninitialized, the default value is ignored, and it is
+    /// typed as `mixed` if the type isn't declared.
+    #[cfg(phper_php_version_at_least = "8.1")]
+    pub fn mark_readonly(&mut self) -> &mut Self {
+        self.visibility |= ZEND_ACC_READONLY;
+        self
+    }
+
+    /// The access type, the instance properties of readonly class are
+    /// readonly.
+    fn access_type(&self, _ce: *mut zend_class_entry) -> RawVisibility {
+        #[cfg(phper_php_version_at_least = "8.2")]
+        if unsafe { (*_ce).ce_flags } & ZEND_ACC_READONLY_CLASS != 0
+            && self.visibility & ZEND_ACC_STATIC == 0
+        {
+            return self.visibility | ZEND_ACC_READONLY;
+        }
+        self.visibility
+    }
+
     #[allow(clippy::useless_conversion)]
     pub(crate) fn declare(&self, ce: *mut zend_class_entry) {
+        let access_type = self.access_type(ce);
+
+        #[cfg(phper_php_version_at_least = "8.1")]
+        if access_type & ZEND_ACC_READONLY != 0 {
+            let type_hint = self.type_hint.clone().unwrap_or(TypeHint::Mixed);
+            unsafe {
+                self.declare_typed(ce, &type_hint, access_type);
+            }
+            return;
+        }
+
+        if let Some(type_hint) = &self.type_hint {
+            unsafe {
+                self.declare_typed(ce, type_hint, access_type);
+            }
+            return;
+        }
+
         let name = self.name.as_ptr().cast();
         let name_length = self.name.len().try_into().unwrap();
-        let access_type = self.visibility as i32;
+        let access_type = access_type as i32;
 
         unsafe {
             match &self.value {
@@ -826,6 +1555,50 @@ impl PropertyEntity {
             }
         }
     }
+
+    unsafe fn declare_typed(
+        &self, ce: *mut zend_class_entry, type_hint: &TypeHint, access_type: RawVisibility,
+    ) {
+        let mut bits = 0;
+        let mut class_names = Vec::new();
+        type_hint.flatten(&mut bits, &mut class_names);
+        let class_name_ptrs = class_names
+            .iter()
+            .map(|name| name.as_ptr().cast())
+            .collect::<Vec<_>>();
+        let class_name_lens = class_names.iter().map(String::len).collect::<Vec<_>>();
+
+        // The readonly property can't have the default value.
+        #[cfg(phper_php_version_at_least = "8.1")]
+        let readonly = access_type & ZEND_ACC_READONLY != 0;
+        #[cfg(not(phper_php_version_at_least = "8.1"))]
+        let readonly = false;
+        let uninitialized =
+            readonly || (matches!(self.value, Scalar::Null) && !type_hint.allows_null());
+        // The default value is owned by the class, so the string should be
+        // interned, like `zend_declare_property_stringl` does.
+        let mut value = ManuallyDrop::new(match &self.value {
+            Scalar::Null => ZVal::from(()),
+            Scalar::Bool(b) => ZVal::from(*b),
+            Scalar::I64(i) => ZVal::from(*i),
+            Scalar::F64(f) => ZVal::from(*f),
+            Scalar::String(s) => ZVal::from(ZString::interned(s)),
+            Scalar::Bytes(b) => ZVal::from(ZString::interned(b)),
+        });
+
+        phper_zend_declare_typed_property(
+            ce,
+            self.name.as_ptr().cast(),
+            self.name.len(),
+            value.as_mut_ptr(),
+            uninitialized,
+            access_type as i32,
+            bits,
+            class_name_ptrs.as_ptr(),
+            class_name_lens.as_ptr(),
+            class_names.len(),
+        );
+    }
 }
 
 /// Visibility of class properties and methods.
@@ -848,30 +1621,13 @@ pub(crate) type RawVisibility = u32;
 
 #[allow(clippy::useless_conversion)]
 unsafe extern "C" fn create_object(ce: *mut zend_class_entry) -> *mut zend_object {
-    // Get real ce which hold state_constructor.
-    let real_ce = find_real_ce(ce).unwrap();
+    // Get the class entity registered by phper, which hold state constructor.
+    let entity = find_class_entity(ce).unwrap();
 
     // Alloc more memory size to store state data.
     let state_object = phper_zend_object_alloc(size_of::<StateObj<()>>().try_into().unwrap(), ce);
     let state_object = StateObj::<()>::from_mut_ptr(state_object);
 
-    // Find the hack elements hidden behind null builtin_function.
-    let mut func_ptr = (*real_ce).info.internal.builtin_functions;
-    while !(*func_ptr).fname.is_null() {
-        func_ptr = func_ptr.offset(1);
-    }
-
-    // Get state constructor.
-    func_ptr = func_ptr.offset(1);
-    let state_constructor = func_ptr as *mut *const StateConstructor;
-    let state_constructor = state_constructor.read().as_ref().unwrap();
-
-    // Get state cloner.
-    func_ptr = func_ptr.offset(1);
-    let has_state_cloner =
-        slice::from_raw_parts(func_ptr as *const u8, size_of::<*const StateCloner>())
-            != [0u8; size_of::<*const StateCloner>()];
-
     // Common initialize process.
     let object = state_object.as_mut_object().as_mut_ptr();
     zend_object_std_init(object, ce);
@@ -882,13 +1638,30 @@ unsafe extern "C" fn create_object(ce: *mut zend_class_entry) -> *mut zend_objec
     let mut handlers = Box::new(std_object_handlers);
     handlers.offset = StateObj::<()>::offset() as c_int;
     handlers.free_obj = Some(free_object);
-    handlers.clone_obj = has_state_cloner.then_some(clone_object);
+    handlers.clone_obj = entity.state_cloner.is_some().then_some(clone_object);
+    if entity.state_comparator.is_some() {
+        #[cfg(phper_major_version = "8")]
+        {
+            handlers.compare = Some(compare_objects);
+        }
+        #[cfg(phper_major_version = "7")]
+        {
+            handlers.compare_objects = Some(compare_objects);
+        }
+    }
+    if !entity.destroy_hooks.is_empty() {
+        handlers.dtor_obj = Some(destroy_object);
+    }
     (*object).handlers = Box::into_raw(handlers);
 
     // Call the state constructor and store the state.
-    let data = (state_constructor)();
+    let data = (entity.state_constructor)();
     *state_object.as_mut_any_state() = data;
 
+    // The object with constructor isn't initialized until the constructor
+    // returns successfully.
+    state_object.set_initialized(!entity.has_construct);
+
     object
 }
 
@@ -906,24 +1679,14 @@ unsafe extern "C" fn clone_object(object: *mut zval) -> *mut zend_object {
 #[allow(clippy::useless_conversion)]
 unsafe fn clone_object_common(object: *mut zend_object) -> *mut zend_object {
     let ce = (*object).ce;
-    let real_ce = find_real_ce(ce).unwrap();
+    let entity = find_class_entity(ce).unwrap();
+    let state_cloner = entity.state_cloner.as_ref().unwrap();
 
     // Alloc more memory size to store state data.
     let new_state_object =
         phper_zend_object_alloc(size_of::<StateObj<()>>().try_into().unwrap(), ce);
     let new_state_object = StateObj::<()>::from_mut_ptr(new_state_object);
 
-    // Find the hack elements hidden behind null builtin_function.
-    let mut func_ptr = (*real_ce).info.internal.builtin_functions;
-    while !(*func_ptr).fname.is_null() {
-        func_ptr = func_ptr.offset(1);
-    }
-
-    // Get state cloner.
-    func_ptr = func_ptr.offset(2);
-    let state_cloner = func_ptr as *mut *const StateCloner;
-    let state_cloner = state_cloner.read().as_ref().unwrap();
-
     // Initialize and clone members
     let new_object = new_state_object.as_mut_object().as_mut_ptr();
     zend_object_std_init(new_object, ce);
@@ -937,10 +1700,55 @@ unsafe fn clone_object_common(object: *mut zend_object) -> *mut zend_object {
     let state_object = StateObj::<()>::from_mut_object_ptr(object);
     let data = (state_cloner)(*state_object.as_mut_any_state());
     *new_state_object.as_mut_any_state() = data;
+    new_state_object.set_initialized(state_object.is_initialized());
 
     new_object
 }
 
+unsafe extern "C" fn compare_objects(o1: *mut zval, o2: *mut zval) -> c_int {
+    let objects = ZVal::from_ptr(o1)
+        .as_z_obj()
+        .zip(ZVal::from_ptr(o2).as_z_obj());
+
+    if let Some((obj1, obj2)) = objects {
+        let entity1 = find_class_entity(obj1.get_class().as_ptr() as *mut _);
+        let entity2 = find_class_entity(obj2.get_class().as_ptr() as *mut _);
+
+        // Only the objects share the same class entity have the same state type.
+        let state_comparator = match (entity1, entity2) {
+            (Some(entity1), Some(entity2)) if ptr::eq(entity1, entity2) => {
+                entity1.state_comparator.as_ref()
+            }
+            _ => None,
+        };
+
+        if let Some(state_comparator) = state_comparator {
+            let state1 = StateObj::<()>::from_object_ptr(obj1.as_ptr());
+            let state2 = StateObj::<()>::from_object_ptr(obj2.as_ptr());
+            return match state_comparator(state1.any_state_ptr(), state2.any_state_ptr()) {
+                Some(cmp::Ordering::Less) => -1,
+                Some(cmp::Ordering::Equal) => 0,
+                // Uncomparable is treated as greater, same as `ZEND_UNCOMPARABLE`.
+                Some(cmp::Ordering::Greater) | None => 1,
+            };
+        }
+    }
+
+    zend_std_compare_objects(o1, o2)
+}
+
+unsafe extern "C" fn destroy_object(object: *mut zend_object) {
+    // Original destructor call, which calls `__destruct`.
+    zend_objects_destroy_object(object);
+
+    let id = ZObj::from_ptr(object).id();
+    if let Some(entity) = find_class_entity((*object).ce) {
+        for hook in &entity.destroy_hooks {
+            hook(id);
+        }
+    }
+}
+
 unsafe extern "C" fn free_object(object: *mut zend_object) {
     let state_object = StateObj::<()>::from_mut_object_ptr(object);
 
@@ -951,15 +1759,14 @@ unsafe extern "C" fn free_object(object: *mut zend_object) {
     zend_object_std_dtor(object);
 }
 
-/// Find the class that registered by phper.
-unsafe fn find_real_ce(mut ce: *mut zend_class_entry) -> Option<*mut zend_class_entry> {
+/// Find the class entity registered by phper, from the class of object, which
+/// may be the user class inherits it.
+unsafe fn find_class_entity<'a>(mut ce: *mut zend_class_entry) -> Option<&'a ClassEntity<()>> {
     let class_entities = global_module().class_entities();
 
     while !ce.is_null() {
-        for entity in class_entities {
-            if ClassEntry::from_ptr(ce).get_name().to_c_str() == Ok(&entity.class_name) {
-                return Some(ce);
-            }
+        if let Some(entity) = class_entities.iter().find(|entity| entity.ce == ce) {
+            return Some(entity);
         }
         ce = phper_get_parent_class(ce);
     }
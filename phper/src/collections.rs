@@ -0,0 +1,438 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to the optional built-in collection classes, registered on
+//! demand by [`register`]:
+//!
+//! - `Phper\Collection\Vector`, backed by Rust [`Vec`], a list of values with
+//!   the continuous int offsets.
+//! - `Phper\Collection\Map`, backed by [`IndexMap`], an ordered map with int or
+//!   string keys.
+//!
+//! Both classes implement `ArrayAccess`, `Countable` and `IteratorAggregate`,
+//! and can be cloned, useful as the efficient return type of the extension.
+//!
+//! Only one loaded extension should register the classes, because the class
+//! names are global.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use phper::{collections, modules::Module};
+//!
+//! fn register(module: &mut Module) {
+//!     collections::register(module);
+//!
+//!     module.add_function("my_primes", |_| collections::new_vector([2i64, 3, 5, 7]));
+//! }
+//! ```
+
+use crate::{
+    arrays::{InsertKey, IterKey, ZArray},
+    classes::{array_access_class, ClassEntity, ClassEntry, StaticStateClass, Visibility},
+    functions::Argument,
+    modules::Module,
+    objects::ZObject,
+    values::ZVal,
+};
+use indexmap::IndexMap;
+use std::convert::Infallible;
+
+/// The class name of the vector collection.
+pub const VECTOR_CLASS_NAME: &str = "Phper\\Collection\\Vector";
+
+/// The class name of the map collection.
+pub const MAP_CLASS_NAME: &str = "Phper\\Collection\\Map";
+
+/// The state of `Phper\Collection\Vector`.
+pub type VectorState = Vec<ZVal>;
+
+/// The state of `Phper\Collection\Map`.
+pub type MapState = IndexMap<CollectionKey, ZVal>;
+
+static VECTOR_CLASS: StaticStateClass<VectorState> = StaticStateClass::null();
+
+static MAP_CLASS: StaticStateClass<MapState> = StaticStateClass::null();
+
+/// Key of `Phper\Collection\Map`, normalized like the PHP array key, so the
+/// decimal integer string key `"1"` is the same as the int key `1`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CollectionKey {
+    /// Int key.
+    Index(i64),
+    /// String key, which isn't decimal integer.
+    Str(Vec<u8>),
+}
+
+impl CollectionKey {
+    /// Converts from the int or string `ZVal`, otherwise returns
+    /// [`crate::Error::Type`].
+    pub fn from_z_val(val: &ZVal) -> crate::Result<Self> {
+        if let Some(i) = val.as_long() {
+            return Ok(Self::Index(i));
+        }
+        if let Some(bytes) = val.as_bytes() {
+            return Ok(bytes.into());
+        }
+        Err(crate::Error::type_error("Illegal offset type"))
+    }
+
+    fn to_z_val(&self) -> ZVal {
+        match self {
+            Self::Index(i) => (*i).into(),
+            Self::Str(s) => s.as_slice().into(),
+        }
+    }
+
+    fn insert_key(&self) -> InsertKey<'_> {
+        match self {
+            Self::Index(i) => InsertKey::Index(*i as u64),
+            Self::Str(s) => InsertKey::Bytes(s),
+        }
+    }
+}
+
+impl From<i64> for CollectionKey {
+    fn from(i: i64) -> Self {
+        Self::Index(i)
+    }
+}
+
+impl From<&[u8]> for CollectionKey {
+    fn from(bytes: &[u8]) -> Self {
+        match parse_index(bytes) {
+            Some(i) => Self::Index(i),
+            None => Self::Str(bytes.to_owned()),
+        }
+    }
+}
+
+impl From<&str> for CollectionKey {
+    fn from(s: &str) -> Self {
+        s.as_bytes().into()
+    }
+}
+
+impl From<IterKey<'_>> for CollectionKey {
+    fn from(key: IterKey<'_>) -> Self {
+        match key {
+            IterKey::Index(i) => Self::Index(i as i64),
+            IterKey::ZStr(s) => s.to_bytes().into(),
+        }
+    }
+}
+
+/// Parse the canonical decimal integer, same as `ZEND_HANDLE_NUMERIC_STR`.
+fn parse_index(bytes: &[u8]) -> Option<i64> {
+    let digits = bytes.strip_prefix(b"-").unwrap_or(bytes);
+    let canonical = match digits {
+        [] => false,
+        [b'0'] => digits.len() == bytes.len(),
+        [b'0', ..] => false,
+        _ => digits.iter().all(u8::is_ascii_digit),
+    };
+    if !canonical {
+        return None;
+    }
+    std::str::from_utf8(bytes).ok()?.parse().ok()
+}
+
+/// Register the collection classes `Phper\Collection\Vector` and
+/// `Phper\Collection\Map` into the module.
+pub fn register(module: &mut Module) {
+    module.add_class(make_vector_class());
+    module.add_class(make_map_class());
+}
+
+/// Create the `Phper\Collection\Vector` object with the values.
+///
+/// # Panics
+///
+/// Panics if the classes aren't registered by [`register`].
+pub fn new_vector<I>(values: I) -> crate::Result<ZObject>
+where
+    I: IntoIterator,
+    I::Item: Into<ZVal>,
+{
+    let mut object = VECTOR_CLASS.init_object()?;
+    *object.as_mut_state() = values.into_iter().map(Into::into).collect();
+    Ok(object.into_z_object())
+}
+
+/// Create the `Phper\Collection\Map` object with the entries.
+///
+/// # Panics
+///
+/// Panics if the classes aren't registered by [`register`].
+pub fn new_map<I, K, V>(entries: I) -> crate::Result<ZObject>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<CollectionKey>,
+    V: Into<ZVal>,
+{
+    let mut object = MAP_CLASS.init_object()?;
+    *object.as_mut_state() = entries
+        .into_iter()
+        .map(|(key, value)| (key.into(), value.into()))
+        .collect();
+    Ok(object.into_z_object())
+}
+
+fn implements_common<T: 'static>(class: &mut ClassEntity<T>) {
+    class.mark_final();
+    class.implements(array_access_class);
+    class.implements(|| ClassEntry::from_globals("Countable").unwrap());
+    class.implements(|| ClassEntry::from_globals("IteratorAggregate").unwrap());
+}
+
+fn new_array_iterator(arr: ZArray) -> crate::Result<ZObject> {
+    ClassEntry::from_globals("ArrayIterator")?.new_object([ZVal::from(arr)])
+}
+
+fn vector_to_array(values: &VectorState) -> ZArray {
+    let mut arr = ZArray::new();
+    for value in values {
+        arr.insert(InsertKey::NextIndex, value.clone());
+    }
+    arr
+}
+
+fn map_to_array(entries: &MapState) -> ZArray {
+    let mut arr = ZArray::new();
+    for (key, value) in entries {
+        arr.insert(key.insert_key(), value.clone());
+    }
+    arr
+}
+
+fn vector_offset(values: &VectorState, offset: &ZVal) -> crate::Result<usize> {
+    let offset = match CollectionKey::from_z_val(offset)? {
+        CollectionKey::Index(i) => usize::try_from(i).ok(),
+        CollectionKey::Str(_) => None,
+    };
+    offset.filter(|&i| i < values.len()).ok_or_else(|| {
+        crate::Error::custom(
+            "OutOfRangeException",
+            "Offset is out of range of the vector",
+        )
+    })
+}
+
+fn map_key_not_found() -> crate::Error {
+    crate::Error::custom("OutOfBoundsException", "Key isn't found in the map")
+}
+
+fn make_vector_class() -> ClassEntity<VectorState> {
+    let mut class =
+        ClassEntity::<VectorState>::new_with_default_state_constructor(VECTOR_CLASS_NAME);
+    class.bind(&VECTOR_CLASS);
+    class.clone_state();
+    implements_common(&mut class);
+
+    class
+        .add_method("__construct", Visibility::Public, |this, arguments| {
+            if let Some(values) = arguments.get(0) {
+                let values = values.expect_z_arr()?;
+                *this.as_mut_state() = values.iter().map(|(_, value)| value.clone()).collect();
+            }
+            Ok::<_, crate::Error>(())
+        })
+        .argument(Argument::by_val_optional("values"));
+
+    class
+        .add_method("push", Visibility::Public, |this, arguments| {
+            this.as_mut_state().push(arguments[0].clone());
+            Ok::<_, Infallible>(())
+        })
+        .argument(Argument::by_val("value"));
+
+    class.add_method("pop", Visibility::Public, |this, _| {
+        Ok::<_, Infallible>(this.as_mut_state().pop())
+    });
+
+    class
+        .add_method("get", Visibility::Public, |this, arguments| {
+            let values = this.as_state();
+            let offset = vector_offset(values, &arguments[0])?;
+            Ok::<_, crate::Error>(values[offset].clone())
+        })
+        .argument(Argument::by_val("offset"));
+
+    class
+        .add_method("set", Visibility::Public, |this, arguments| {
+            let values = this.as_mut_state();
+            let offset = vector_offset(values, &arguments[0])?;
+            values[offset] = arguments[1].clone();
+            Ok::<_, crate::Error>(())
+        })
+        .arguments([Argument::by_val("offset"), Argument::by_val("value")]);
+
+    class.add_method("count", Visibility::Public, |this, _| {
+        Ok::<_, Infallible>(this.as_state().len() as i64)
+    });
+
+    class.add_method("toArray", Visibility::Public, |this, _| {
+        Ok::<_, Infallible>(vector_to_array(this.as_state()))
+    });
+
+    class.add_method("getIterator", Visibility::Public, |this, _| {
+        new_array_iterator(vector_to_array(this.as_state()))
+    });
+
+    class
+        .add_method("offsetExists", Visibility::Public, |this, arguments| {
+            Ok::<_, Infallible>(vector_offset(this.as_state(), &arguments[0]).is_ok())
+        })
+        .argument(Argument::by_val("offset"));
+
+    class
+        .add_method("offsetGet", Visibility::Public, |this, arguments| {
+            let values = this.as_state();
+            let offset = vector_offset(values, &arguments[0])?;
+            Ok::<_, crate::Error>(values[offset].clone())
+        })
+        .argument(Argument::by_val("offset"));
+
+    class
+        .add_method("offsetSet", Visibility::Public, |this, arguments| {
+            let values = this.as_mut_state();
+            if arguments[0].get_type_info().is_null() {
+                values.push(arguments[1].clone());
+            } else {
+                let offset = vector_offset(values, &arguments[0])?;
+                values[offset] = arguments[1].clone();
+            }
+            Ok::<_, crate::Error>(())
+        })
+        .arguments([Argument::by_val("offset"), Argument::by_val("value")]);
+
+    class
+        .add_method("offsetUnset", Visibility::Public, |this, arguments| {
+            let values = this.as_mut_state();
+            if let Ok(offset) = vector_offset(values, &arguments[0]) {
+                values.remove(offset);
+            }
+            Ok::<_, Infallible>(())
+        })
+        .argument(Argument::by_val("offset"));
+
+    class
+}
+
+fn make_map_class() -> ClassEntity<MapState> {
+    let mut class = ClassEntity::<MapState>::new_with_default_state_constructor(MAP_CLASS_NAME);
+    class.bind(&MAP_CLASS);
+    class.clone_state();
+    implements_common(&mut class);
+
+    class
+        .add_method("__construct", Visibility::Public, |this, arguments| {
+            if let Some(entries) = arguments.get(0) {
+                let entries = entries.expect_z_arr()?;
+                *this.as_mut_state() = entries
+                    .iter()
+                    .map(|(key, value)| (key.into(), value.clone()))
+                    .collect();
+            }
+            Ok::<_, crate::Error>(())
+        })
+        .argument(Argument::by_val_optional("entries"));
+
+    class
+        .add_method("get", Visibility::Public, |this, arguments| {
+            let key = CollectionKey::from_z_val(&arguments[0])?;
+            let value = this.as_state().get(&key).ok_or_else(map_key_not_found)?;
+            Ok::<_, crate::Error>(value.clone())
+        })
+        .argument(Argument::by_val("key"));
+
+    class
+        .add_method("set", Visibility::Public, |this, arguments| {
+            let key = CollectionKey::from_z_val(&arguments[0])?;
+            this.as_mut_state().insert(key, arguments[1].clone());
+            Ok::<_, crate::Error>(())
+        })
+        .arguments([Argument::by_val("key"), Argument::by_val("value")]);
+
+    class
+        .add_method("has", Visibility::Public, |this, arguments| {
+            let key = CollectionKey::from_z_val(&arguments[0])?;
+            Ok::<_, crate::Error>(this.as_state().contains_key(&key))
+        })
+        .argument(Argument::by_val("key"));
+
+    class
+        .add_method("remove", Visibility::Public, |this, arguments| {
+            let key = CollectionKey::from_z_val(&arguments[0])?;
+            Ok::<_, crate::Error>(this.as_mut_state().shift_remove(&key))
+        })
+        .argument(Argument::by_val("key"));
+
+    class.add_method("keys", Visibility::Public, |this, _| {
+        let mut arr = ZArray::new();
+        for key in this.as_state().keys() {
+            arr.insert(InsertKey::NextIndex, key.to_z_val());
+        }
+        Ok::<_, Infallible>(arr)
+    });
+
+    class.add_method("values", Visibility::Public, |this, _| {
+        let mut arr = ZArray::new();
+        for value in this.as_state().values() {
+            arr.insert(InsertKey::NextIndex, value.clone());
+        }
+        Ok::<_, Infallible>(arr)
+    });
+
+    class.add_method("count", Visibility::Public, |this, _| {
+        Ok::<_, Infallible>(this.as_state().len() as i64)
+    });
+
+    class.add_method("toArray", Visibility::Public, |this, _| {
+        Ok::<_, Infallible>(map_to_array(this.as_state()))
+    });
+
+    class.add_method("getIterator", Visibility::Public, |this, _| {
+        new_array_iterator(map_to_array(this.as_state()))
+    });
+
+    class
+        .add_method("offsetExists", Visibility::Public, |this, arguments| {
+            let key = CollectionKey::from_z_val(&arguments[0])?;
+            Ok::<_, crate::Error>(this.as_state().contains_key(&key))
+        })
+        .argument(Argument::by_val("offset"));
+
+    class
+        .add_method("offsetGet", Visibility::Public, |this, arguments| {
+            let key = CollectionKey::from_z_val(&arguments[0])?;
+            let value = this.as_state().get(&key).ok_or_else(map_key_not_found)?;
+            Ok::<_, crate::Error>(value.clone())
+        })
+        .argument(Argument::by_val("offset"));
+
+    class
+        .add_method("offsetSet", Visibility::Public, |this, arguments| {
+            let key = CollectionKey::from_z_val(&arguments[0])?;
+            this.as_mut_state().insert(key, arguments[1].clone());
+            Ok::<_, crate::Error>(())
+        })
+        .arguments([Argument::by_val("offset"), Argument::by_val("value")]);
+
+    class
+        .add_method("offsetUnset", Visibility::Public, |this, arguments| {
+            let key = CollectionKey::from_z_val(&arguments[0])?;
+            this.as_mut_state().shift_remove(&key);
+            Ok::<_, crate::Error>(())
+        })
+        .argument(Argument::by_val("offset"));
+
+    class
+}
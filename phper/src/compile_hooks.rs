@@ -0,0 +1,193 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to hooking [zend_compile_file] and [zend_compile_string], for
+//! observing or rewriting the source before compilation.
+
+use crate::{
+    contexts::ModuleContext,
+    errors::throw,
+    modules::global_module,
+    strings::{ZStr, ZString},
+    sys::*,
+};
+use std::{
+    ffi::CStr,
+    os::raw::{c_char, c_int},
+    ptr::null_mut,
+    slice::from_raw_parts,
+};
+
+pub(crate) type CompileFileHook = dyn Fn(&mut ZFileHandle) -> crate::Result<()>;
+
+pub(crate) type CompileStringHook = dyn Fn(&ZStr, &CStr) -> crate::Result<Option<ZString>>;
+
+/// Wrapper of [zend_file_handle], the file going to be compiled.
+#[repr(transparent)]
+pub struct ZFileHandle {
+    inner: zend_file_handle,
+}
+
+impl ZFileHandle {
+    /// Wraps a raw pointer.
+    ///
+    /// # Safety
+    ///
+    /// Create from raw pointer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if pointer is null.
+    pub unsafe fn from_mut_ptr<'a>(ptr: *mut zend_file_handle) -> &'a mut ZFileHandle {
+        let ptr = ptr as *mut Self;
+        ptr.as_mut().expect("ptr shouldn't be null")
+    }
+
+    /// Returns a raw pointer wrapped.
+    pub const fn as_ptr(&self) -> *const zend_file_handle {
+        &self.inner
+    }
+
+    /// Returns a raw pointer wrapped.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut zend_file_handle {
+        &mut self.inner
+    }
+
+    /// Get the file name, as passed to `include` or `require`.
+    pub fn filename(&self) -> &CStr {
+        unsafe { CStr::from_ptr(phper_zend_file_handle_filename(self.as_ptr())) }
+    }
+
+    /// Read the whole source of file, `None` if the file can't be opened.
+    pub fn source(&mut self) -> Option<&[u8]> {
+        unsafe {
+            let mut buf: *mut c_char = null_mut();
+            let mut len = 0;
+            if !phper_zend_file_handle_source(self.as_mut_ptr(), &mut buf, &mut len) {
+                return None;
+            }
+            if len == 0 {
+                return Some(&[]);
+            }
+            Some(from_raw_parts(buf as *const u8, len))
+        }
+    }
+
+    /// Replace the source going to be compiled, return `false` if the file
+    /// can't be opened.
+    #[cfg(not(all(
+        phper_major_version = "7",
+        any(
+            phper_minor_version = "0",
+            phper_minor_version = "1",
+            phper_minor_version = "2",
+            phper_minor_version = "3",
+        )
+    )))]
+    pub fn set_source(&mut self, source: impl AsRef<[u8]>) -> bool {
+        let source = source.as_ref();
+        unsafe {
+            phper_zend_file_handle_set_source(
+                self.as_mut_ptr(),
+                source.as_ptr().cast(),
+                source.len(),
+            )
+        }
+    }
+}
+
+static mut PREVIOUS_COMPILE_FILE: Option<
+    unsafe extern "C" fn(*mut zend_file_handle, c_int) -> *mut zend_op_array,
+> = None;
+
+/// `compile_file` is chained in `zend_compile_file`, until it's unlinked.
+static mut COMPILE_FILE_CHAINED: bool = false;
+
+/// `compile_file` runs the hooks, otherwise passes through, if it can't be
+/// unlinked because the other extensions chained after it.
+static mut COMPILE_FILE_ENABLED: bool = false;
+
+/// Chain the hooks in `MINIT`, before the hooks of the extensions loaded
+/// previously.
+pub(crate) unsafe fn register(
+    _ctx: &ModuleContext<'_>, has_compile_file_hooks: bool, has_compile_string_hooks: bool,
+) {
+    if has_compile_file_hooks {
+        // Don't chain twice, otherwise it calls itself as the previous one.
+        if !COMPILE_FILE_CHAINED {
+            PREVIOUS_COMPILE_FILE = zend_compile_file;
+            zend_compile_file = Some(compile_file);
+            COMPILE_FILE_CHAINED = true;
+        }
+        COMPILE_FILE_ENABLED = true;
+    }
+    if has_compile_string_hooks {
+        phper_zend_set_compile_string_hook(Some(compile_string));
+    }
+}
+
+/// Restore the previous hooks in `MSHUTDOWN`, because the library is going to
+/// be unloaded.
+///
+/// Only unlink the hook if it's still the current one, otherwise the hook of
+/// the extension chained after it would be dropped.
+pub(crate) unsafe fn unregister() {
+    COMPILE_FILE_ENABLED = false;
+    if COMPILE_FILE_CHAINED && zend_compile_file.map(|f| f as usize) == Some(compile_file as usize)
+    {
+        zend_compile_file = PREVIOUS_COMPILE_FILE.take();
+        COMPILE_FILE_CHAINED = false;
+    }
+    phper_zend_set_compile_string_hook(None);
+}
+
+unsafe extern "C" fn compile_file(
+    file_handle: *mut zend_file_handle, r#type: c_int,
+) -> *mut zend_op_array {
+    if COMPILE_FILE_ENABLED {
+        let handle = ZFileHandle::from_mut_ptr(file_handle);
+        for hook in global_module().compile_file_hooks() {
+            if let Err(e) = hook(handle) {
+                throw(e);
+                return null_mut();
+            }
+        }
+    }
+
+    match PREVIOUS_COMPILE_FILE {
+        Some(previous) => previous(file_handle, r#type),
+        None => null_mut(),
+    }
+}
+
+unsafe extern "C" fn compile_string(
+    source: *mut zend_string, filename: *const c_char,
+) -> *mut zend_string {
+    let filename = CStr::from_ptr(filename);
+    let mut replaced: Option<ZString> = None;
+
+    for hook in global_module().compile_string_hooks() {
+        let current = match &replaced {
+            Some(replaced) => &**replaced,
+            None => ZStr::from_ptr(source),
+        };
+        match hook(current, filename) {
+            Ok(Some(source)) => replaced = Some(source),
+            Ok(None) => {}
+            Err(e) => {
+                throw(e);
+                return null_mut();
+            }
+        }
+    }
+
+    replaced.map(ZString::into_raw).unwrap_or_else(null_mut)
+}
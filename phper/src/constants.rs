@@ -10,9 +10,50 @@
 
 //! Apis relate to [zend_constant](crate::sys::zend_constant).
 
-use crate::{sys::*, types::Scalar};
+use crate::{
+    arrays::{InsertKey, ZArr, ZArray},
+    strings::{ZStr, ZString},
+    sys::*,
+    types::Scalar,
+    values::ZVal,
+};
+use derive_more::From;
 use std::ffi::{c_char, c_int};
 
+/// Get the value of the global constant by name, `None` if the constant isn't
+/// defined.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::constants;
+///
+/// let version = constants::get("PHP_VERSION").unwrap();
+/// let _ = version.as_z_str();
+/// ```
+pub fn get<'a>(name: impl AsRef<str>) -> Option<&'a ZVal> {
+    let name = name.as_ref();
+    unsafe {
+        let ptr = zend_get_constant_str(name.as_ptr().cast(), name.len());
+        ZVal::try_from_ptr(ptr)
+    }
+}
+
+/// Get the constants defined in `EG(zend_constants)`, including the ones
+/// defined by the extensions and the userland, yields the name and value.
+pub fn defined_constants<'a>() -> impl Iterator<Item = (&'a ZStr, &'a ZVal)> {
+    unsafe {
+        ZArr::from_ptr(eg!(zend_constants)).iter().map(|(_, val)| {
+            let constant: *const zend_constant = phper_z_ptr_p(val.as_ptr()).cast();
+            (
+                ZStr::from_ptr((*constant).name),
+                ZVal::from_ptr(&(*constant).value),
+            )
+        })
+    }
+}
+
+/// Builder for registering constant.
 pub(crate) struct Constant {
     name: String,
     value: Scalar,
@@ -26,6 +67,11 @@ impl Constant {
         }
     }
 
+    #[inline]
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
     pub(crate) fn register(&self, module_number: c_int) {
         let name_ptr = self.name.as_ptr() as *const c_char;
         let name_len = self.name.len();
@@ -79,3 +125,132 @@ impl Constant {
         }
     }
 }
+
+/// Value of the class constant, the scalar or the array, which is built as the
+/// persistent and immutable value when the class is registered.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{classes::ClassEntity, constants::ConstantValue};
+///
+/// let mut class = ClassEntity::new("Http");
+/// class.add_constant("METHODS", ConstantValue::list(["GET", "POST"]));
+/// class.add_constant(
+///     "PORTS",
+///     ConstantValue::map([("http", 80i64), ("https", 443)]),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, From)]
+pub enum ConstantValue {
+    /// Scalar value.
+    Scalar(Scalar),
+    /// List array, like `[1, 2, 3]`.
+    List(Vec<ConstantValue>),
+    /// Array with string keys, like `["a" => 1, "b" => 2]`, the order is kept.
+    Map(Vec<(String, ConstantValue)>),
+}
+
+impl ConstantValue {
+    /// Construct the list array.
+    pub fn list<I>(values: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<ConstantValue>,
+    {
+        Self::List(values.into_iter().map(Into::into).collect())
+    }
+
+    /// Construct the array with string keys.
+    pub fn map<I, K, V>(entries: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<ConstantValue>,
+    {
+        Self::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        )
+    }
+
+    /// Build the persistent value, the strings are interned and the arrays are
+    /// immutable, so it can be shared by all requests.
+    pub(crate) fn to_persistent_z_val(&self) -> ZVal {
+        match self {
+            Self::Scalar(Scalar::Null) => ZVal::from(()),
+            Self::Scalar(Scalar::Bool(b)) => ZVal::from(*b),
+            Self::Scalar(Scalar::I64(i)) => ZVal::from(*i),
+            Self::Scalar(Scalar::F64(f)) => ZVal::from(*f),
+            Self::Scalar(Scalar::String(s)) => interned_z_val(s.as_bytes()),
+            Self::Scalar(Scalar::Bytes(b)) => interned_z_val(b),
+            Self::List(values) => {
+                let mut arr = ZArray::with_capacity_persistent(values.len());
+                for value in values {
+                    arr.insert(InsertKey::NextIndex, value.to_persistent_z_val());
+                }
+                immutable_z_val(arr)
+            }
+            Self::Map(entries) => {
+                let mut arr = ZArray::with_capacity_persistent(entries.len());
+                for (key, value) in entries {
+                    arr.insert(InsertKey::Str(key), value.to_persistent_z_val());
+                }
+                immutable_z_val(arr)
+            }
+        }
+    }
+}
+
+impl From<()> for ConstantValue {
+    fn from(_: ()) -> Self {
+        Self::Scalar(Scalar::Null)
+    }
+}
+
+impl From<bool> for ConstantValue {
+    fn from(b: bool) -> Self {
+        Self::Scalar(Scalar::Bool(b))
+    }
+}
+
+impl From<i64> for ConstantValue {
+    fn from(i: i64) -> Self {
+        Self::Scalar(Scalar::I64(i))
+    }
+}
+
+impl From<f64> for ConstantValue {
+    fn from(f: f64) -> Self {
+        Self::Scalar(Scalar::F64(f))
+    }
+}
+
+impl From<String> for ConstantValue {
+    fn from(s: String) -> Self {
+        Self::Scalar(Scalar::String(s))
+    }
+}
+
+impl From<&str> for ConstantValue {
+    fn from(s: &str) -> Self {
+        Self::Scalar(s.into())
+    }
+}
+
+fn interned_z_val(s: &[u8]) -> ZVal {
+    unsafe {
+        let s = phper_zend_string_init_interned(s.as_ptr().cast(), s.len());
+        ZVal::from(ZString::from_raw(s))
+    }
+}
+
+fn immutable_z_val(arr: ZArray) -> ZVal {
+    let mut val = ZVal::from(arr);
+    unsafe {
+        phper_zval_make_array_immutable(val.as_mut_ptr());
+    }
+    val
+}
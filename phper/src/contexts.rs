@@ -0,0 +1,127 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to the phases of the PHP lifecycle, as the marker types.
+//!
+//! Some apis are only legal in the certain phase, like registering the classes
+//! only in `MINIT`, or allocating by the Zend memory manager only during the
+//! request. The apis requiring the [`ModuleContext`] or [`RequestContext`]
+//! argument can't be called outside of the phase, because the markers can only
+//! be borrowed in the closure of [`ModuleContext::with`] and
+//! [`RequestContext::with`], and they are neither [`Send`] nor [`Sync`], so
+//! they can't leave the PHP thread.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use phper::contexts::RequestContext;
+//!
+//! use phper::alloc::EVec;
+//!
+//! fn start_background_task() {
+//!     RequestContext::with(|ctx| {
+//!         let mut buf = EVec::with_capacity_in(ctx, 1024);
+//!         buf.push(0u8);
+//!
+//!         let token = ctx.token();
+//!         std::thread::spawn(move || {
+//!             while !token.is_cancelled() {
+//!                 // Do something here.
+//!             }
+//!         });
+//!     })
+//!     .expect("must be called during the request");
+//! }
+//! ```
+
+use crate::{
+    alloc::InRequest,
+    requests::{self, RequestToken},
+};
+use std::{cell::Cell, marker::PhantomData};
+
+thread_local! {
+    static IN_MODULE_INIT: Cell<bool> = Cell::new(false);
+}
+
+/// The marker of `MINIT`, the phase to register the classes, functions,
+/// constants, ini entries and the other global entities.
+pub struct ModuleContext<'a> {
+    _p: PhantomData<(&'a (), *mut ())>,
+}
+
+impl ModuleContext<'_> {
+    /// Constructs the marker, only by the framework running `MINIT`.
+    pub(crate) unsafe fn new() -> Self {
+        Self { _p: PhantomData }
+    }
+
+    /// Whether the current thread is running `MINIT`.
+    pub fn is_active() -> bool {
+        IN_MODULE_INIT.with(Cell::get)
+    }
+
+    /// Calls `f` with the marker if the current thread is running `MINIT`,
+    /// returns `None` otherwise.
+    pub fn with<R>(f: impl FnOnce(&ModuleContext<'_>) -> R) -> Option<R> {
+        if Self::is_active() {
+            Some(f(&ModuleContext { _p: PhantomData }))
+        } else {
+            None
+        }
+    }
+}
+
+/// The marker of the request, between `RINIT` and `RSHUTDOWN`, the phase to
+/// allocate by the Zend memory manager, and to touch the PHP values.
+pub struct RequestContext<'a> {
+    _p: PhantomData<(&'a (), *mut ())>,
+}
+
+impl RequestContext<'_> {
+    /// Constructs the marker, only by the framework running the request.
+    pub(crate) unsafe fn new() -> Self {
+        Self { _p: PhantomData }
+    }
+
+    /// Whether the current thread is running the request.
+    pub fn is_active() -> bool {
+        requests::in_request()
+    }
+
+    /// Calls `f` with the marker if the current thread is running the request,
+    /// returns `None` otherwise.
+    pub fn with<R>(f: impl FnOnce(&RequestContext<'_>) -> R) -> Option<R> {
+        if Self::is_active() {
+            Some(f(&RequestContext { _p: PhantomData }))
+        } else {
+            None
+        }
+    }
+
+    /// Gets the cancellation token of the request, which is never the
+    /// cancelled one returned by [`RequestToken::current`] outside of the
+    /// request.
+    pub fn token(&self) -> RequestToken {
+        RequestToken::current()
+    }
+}
+
+unsafe impl InRequest for RequestContext<'_> {}
+
+/// Marks the current thread running `MINIT`.
+pub(crate) fn enter_module_init() {
+    IN_MODULE_INIT.with(|flag| flag.set(true));
+}
+
+/// Marks the current thread leaving `MINIT`.
+pub(crate) fn leave_module_init() {
+    IN_MODULE_INIT.with(|flag| flag.set(false));
+}
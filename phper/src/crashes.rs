@@ -0,0 +1,103 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Internal crash handler, see
+//! [`Module::enable_crash_handler`](crate::modules::Module::enable_crash_handler).
+
+use crate::sys::*;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    mem::ManuallyDrop,
+    os::raw::{c_int, c_void},
+    slice,
+};
+
+pub(crate) fn install() -> bool {
+    unsafe { phper_install_crash_handler(Some(report)) }
+}
+
+pub(crate) fn uninstall() {
+    unsafe {
+        phper_uninstall_crash_handler();
+    }
+}
+
+/// Resolves the native frames captured by the crash handler into the
+/// demangled Rust symbols with the file and line.
+///
+/// Only called in the child forked by the crash handler, which has the only
+/// thread, so the symbols are resolved without the lock.
+#[cfg(unix)]
+unsafe extern "C" fn report(frames: *const *mut c_void, len: c_int, fd: c_int) {
+    use std::os::unix::io::FromRawFd;
+
+    let frames = match usize::try_from(len) {
+        Ok(len) if !frames.is_null() => slice::from_raw_parts(frames, len),
+        _ => &[],
+    };
+    // The fd is closed by the crash handler.
+    let file = ManuallyDrop::new(File::from_raw_fd(fd));
+    let _ = write_frames(&mut BufWriter::new(&*file), frames);
+}
+
+#[cfg(not(unix))]
+unsafe extern "C" fn report(_frames: *const *mut c_void, _len: c_int, _fd: c_int) {}
+
+unsafe fn write_frames(w: &mut impl Write, frames: &[*mut c_void]) -> io::Result<()> {
+    writeln!(w, "Native stack trace:")?;
+    for (i, &ip) in frames.iter().enumerate() {
+        let mut resolved = false;
+        let mut result = Ok(());
+        backtrace::resolve_unsynchronized(ip, |symbol| {
+            if !resolved {
+                resolved = true;
+                result = write_symbol(w, i, ip, symbol);
+            }
+        });
+        result?;
+        if !resolved {
+            writeln!(w, "#{} {:?}", i, ip)?;
+        }
+    }
+    w.flush()
+}
+
+fn write_symbol(
+    w: &mut impl Write, i: usize, ip: *mut c_void, symbol: &backtrace::Symbol,
+) -> io::Result<()> {
+    match symbol.name() {
+        Some(name) => writeln!(w, "#{} {:?} {:#}", i, ip, name)?,
+        None => writeln!(w, "#{} {:?} <unknown>", i, ip)?,
+    }
+    if let (Some(file), Some(line)) = (symbol.filename(), symbol.lineno()) {
+        writeln!(w, "    at {}:{}", file.display(), line)?;
+    }
+    Ok(())
+}
+
+/// The alternate signal stack of the thread spawned by phper, so the stack
+/// overflow of the thread is caught by the crash handler, freed when dropped
+/// at the end of the thread.
+pub(crate) struct SignalStack(*mut c_void);
+
+impl SignalStack {
+    pub(crate) fn new() -> Self {
+        unsafe { Self(phper_crash_thread_stack_new()) }
+    }
+}
+
+impl Drop for SignalStack {
+    fn drop(&mut self) {
+        unsafe {
+            phper_crash_thread_stack_free(self.0);
+        }
+    }
+}
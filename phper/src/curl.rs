@@ -0,0 +1,89 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to the handles of `ext/curl`, for reusing the connections or
+//! inspecting the options of the userland handles, for example, in the tracing
+//! extensions which instrument the HTTP clients.
+//!
+//! The handle is the `CurlHandle` object since PHP 8.0, or the `curl` resource
+//! before. The underlying `CURL *` easy handle is owned by the userland handle,
+//! pass it to the libcurl bindings (like `curl-sys`) without freeing it.
+
+use crate::values::ZVal;
+use std::{ffi::c_void, ptr::NonNull};
+
+/// Detect if the value is the handle of `ext/curl`, which isn't closed.
+pub fn is_curl_handle(val: &ZVal) -> bool {
+    easy_ptr(val).is_some()
+}
+
+/// Get the underlying `CURL *` easy handle of the userland `CurlHandle` (or
+/// the `curl` resource before PHP 8.0), otherwise returns the `TypeError`.
+///
+/// The handle is valid as long as the userland handle is alive, and isn't
+/// closed by `curl_close()` before PHP 8.0.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{curl, values::ZVal};
+/// use std::ffi::c_void;
+///
+/// fn handle(arguments: &mut [ZVal]) -> phper::Result<()> {
+///     let easy = curl::easy_handle(&arguments[0])?;
+///     let _cp: *mut c_void = easy.as_ptr();
+///     Ok(())
+/// }
+/// ```
+pub fn easy_handle(val: &ZVal) -> crate::Result<NonNull<c_void>> {
+    easy_ptr(val).ok_or_else(|| {
+        crate::Error::type_error(format!(
+            "must be of type CurlHandle, {} given",
+            val.get_type_info()
+        ))
+    })
+}
+
+/// The `php_curl` struct begins with the `CURL *cp` field in all versions.
+#[cfg(phper_major_version = "8")]
+fn easy_ptr(val: &ZVal) -> Option<NonNull<c_void>> {
+    let obj = val.as_z_obj()?;
+    if obj.get_class().get_name().to_bytes() != b"CurlHandle" {
+        return None;
+    }
+    unsafe {
+        // The `php_curl` is located by the offset of embedded `zend_object`.
+        let obj = obj.as_ptr();
+        let offset = (*(*obj).handlers).offset as usize;
+        let php_curl = obj.cast::<u8>().sub(offset).cast::<*mut c_void>();
+        NonNull::new(*php_curl)
+    }
+}
+
+/// The `php_curl` struct begins with the `CURL *cp` field in all versions.
+#[cfg(phper_major_version = "7")]
+fn easy_ptr(val: &ZVal) -> Option<NonNull<c_void>> {
+    use crate::sys::*;
+    use std::ffi::CStr;
+
+    let res = val.as_z_res()?;
+    unsafe {
+        // The type name is null if the resource is closed.
+        let type_name = zend_rsrc_list_get_rsrc_type(res.as_ptr() as *mut _);
+        if type_name.is_null() || CStr::from_ptr(type_name).to_bytes() != b"curl" {
+            return None;
+        }
+        let php_curl = (*res.as_ptr()).ptr.cast::<*mut c_void>();
+        if php_curl.is_null() {
+            return None;
+        }
+        NonNull::new(*php_curl)
+    }
+}
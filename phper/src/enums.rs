@@ -0,0 +1,235 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to registering the enums, only available on PHP 8.1 or later.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use phper::{
+//!     classes::Visibility,
+//!     enums::{EnumEntity, EnumType},
+//!     modules::Module,
+//! };
+//!
+//! fn register(module: &mut Module) {
+//!     let mut suit = EnumEntity::new("Suit", EnumType::String);
+//!     suit.add_case("Hearts", "H");
+//!     suit.add_case("Spades", "S");
+//!     suit.add_method("label", Visibility::Public, |this, _| {
+//!         let name = this
+//!             .get_property("name")
+//!             .expect_z_str()?
+//!             .to_str()?
+//!             .to_owned();
+//!         Ok::<_, phper::Error>(name.to_lowercase())
+//!     });
+//!     module.add_enum(suit);
+//! }
+//! ```
+
+use crate::{
+    classes::Visibility,
+    contexts::ModuleContext,
+    errors::Throwable,
+    functions::{Callable, Function, FunctionEntry, MethodEntity, ObjectMethod},
+    objects::ZObj,
+    strings::ZString,
+    sys::*,
+    types::Scalar,
+    utils::ensure_end_with_zero,
+    values::ZVal,
+};
+use std::{
+    collections::HashSet,
+    ffi::{CStr, CString},
+    mem::{zeroed, ManuallyDrop},
+    ptr::null_mut,
+    rc::Rc,
+};
+
+/// The type of enum, pure or backed by `int` or `string`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnumType {
+    /// Pure enum, like `enum Suit`, the cases have no value.
+    Pure,
+    /// Backed enum, like `enum Suit: int`.
+    Int,
+    /// Backed enum, like `enum Suit: string`.
+    String,
+}
+
+impl EnumType {
+    fn raw(self) -> u32 {
+        match self {
+            Self::Pure => IS_UNDEF,
+            Self::Int => IS_LONG,
+            Self::String => IS_STRING,
+        }
+    }
+
+    fn matches(self, value: &Scalar) -> bool {
+        matches!(
+            (self, value),
+            (Self::Pure, Scalar::Null)
+                | (Self::Int, Scalar::I64(_))
+                | (Self::String, Scalar::String(_) | Scalar::Bytes(_))
+        )
+    }
+}
+
+/// Builder for registering enum, the cases are objects created by the engine,
+/// with the `name` property (and the `value` property of backed enum), and the
+/// methods `cases()`, `from()` and `tryFrom()` are provided by the engine.
+pub struct EnumEntity {
+    enum_name: CString,
+    enum_type: EnumType,
+    cases: Vec<(CString, Scalar)>,
+    method_entities: Vec<MethodEntity>,
+    function_entries: Vec<zend_function_entry>,
+}
+
+impl EnumEntity {
+    /// Construct a new `EnumEntity` with enum name and type.
+    pub fn new(enum_name: impl Into<String>, enum_type: EnumType) -> Self {
+        Self {
+            enum_name: ensure_end_with_zero(enum_name),
+            enum_type,
+            cases: Vec::new(),
+            method_entities: Vec::new(),
+            function_entries: Vec::new(),
+        }
+    }
+
+    /// Add the case, the value is `()` for pure enum, integer for `int`
+    /// backed enum, and string for `string` backed enum, the mismatched value
+    /// fails the module startup.
+    pub fn add_case(&mut self, name: impl Into<String>, value: impl Into<Scalar>) {
+        self.cases.push((ensure_end_with_zero(name), value.into()));
+    }
+
+    /// Add member method to enum, with visibility and method handler, the
+    /// `this` is the case object.
+    pub fn add_method<F, Z, E>(
+        &mut self, name: impl Into<String>, vis: Visibility, handler: F,
+    ) -> &mut MethodEntity
+    where
+        F: Fn(&mut ZObj, &mut [ZVal]) -> Result<Z, E> + 'static,
+        Z: Into<ZVal> + 'static,
+        E: Throwable + 'static,
+    {
+        self.method_entities.push(MethodEntity::new(
+            name,
+            Some(Rc::new(ObjectMethod::new(handler))),
+            vis,
+        ));
+        self.method_entities.last_mut().unwrap()
+    }
+
+    /// Add static method to enum, with visibility and method handler.
+    pub fn add_static_method<F, Z, E>(
+        &mut self, name: impl Into<String>, vis: Visibility, handler: F,
+    ) -> &mut MethodEntity
+    where
+        F: Fn(&mut [ZVal]) -> Result<Z, E> + 'static,
+        Z: Into<ZVal> + 'static,
+        E: Throwable + 'static,
+    {
+        let mut entity = MethodEntity::new(name, Some(Rc::new(Function::new(handler))), vis);
+        entity.set_vis_static();
+        self.method_entities.push(entity);
+        self.method_entities.last_mut().unwrap()
+    }
+
+    pub(crate) unsafe fn init(&mut self, _ctx: &ModuleContext<'_>) -> *mut zend_class_entry {
+        let ce = phper_zend_register_internal_enum(
+            self.enum_name.as_ptr(),
+            self.enum_type.raw() as zend_uchar,
+            self.function_entries(),
+        );
+
+        for (name, value) in &self.cases {
+            // The string value is owned by the enum, so it should be interned.
+            let mut value = ManuallyDrop::new(match value {
+                Scalar::I64(i) => ZVal::from(*i),
+                Scalar::String(s) => ZVal::from(ZString::interned(s)),
+                Scalar::Bytes(b) => ZVal::from(ZString::interned(b)),
+                _ => ZVal::from(()),
+            });
+            let value = match self.enum_type {
+                EnumType::Pure => null_mut(),
+                _ => value.as_mut_ptr(),
+            };
+            phper_zend_enum_add_case(ce, name.as_ptr(), name.as_bytes().len(), value);
+        }
+
+        ce
+    }
+
+    unsafe fn function_entries(&mut self) -> *const zend_function_entry {
+        let mut methods = self
+            .method_entities
+            .iter_mut()
+            .map(|method| FunctionEntry::from_method_entity(method))
+            .collect::<Vec<_>>();
+
+        methods.push(zeroed::<zend_function_entry>());
+
+        self.function_entries = methods;
+        self.function_entries.as_ptr()
+    }
+
+    #[inline]
+    pub(crate) fn enum_name(&self) -> &CStr {
+        &self.enum_name
+    }
+
+    /// The names of all the methods.
+    pub(crate) fn method_names(&self) -> impl Iterator<Item = &CStr> {
+        self.method_entities.iter().map(MethodEntity::name)
+    }
+
+    /// The methods with handler, all the enum methods have handler.
+    pub(crate) fn method_handlers(&self) -> impl Iterator<Item = (&CStr, &Rc<dyn Callable>)> {
+        self.method_entities
+            .iter()
+            .filter_map(|method| method.handler().map(|handler| (method.name(), handler)))
+    }
+
+    /// Check the cases, the names should be distinct, and the values should
+    /// match the enum type and be distinct.
+    pub(crate) fn check_cases(&self) -> Vec<String> {
+        let enum_name = self.enum_name.to_string_lossy();
+        let mut errors = Vec::new();
+        let mut names = HashSet::new();
+        let mut values = HashSet::new();
+        for (name, value) in &self.cases {
+            let name = name.to_string_lossy();
+            if !names.insert(name.clone()) {
+                errors.push(format!(
+                    "enum case {}::{} is registered repeatedly",
+                    enum_name, name
+                ));
+            }
+            if !self.enum_type.matches(value) {
+                errors.push(format!(
+                    "enum case {}::{} has the value {:?} mismatched with {:?} enum",
+                    enum_name, name, value, self.enum_type
+                ));
+            } else if self.enum_type != EnumType::Pure && !values.insert(format!("{:?}", value)) {
+                errors.push(format!(
+                    "enum case {}::{} has the duplicated value {:?}",
+                    enum_name, name, value
+                ));
+            }
+        }
+        errors
+    }
+}
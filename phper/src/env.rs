@@ -0,0 +1,93 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to the environment variables, consistent with the userland
+//! `getenv()` and `putenv()`.
+//!
+//! The SAPIs like php-fpm pass the environment variables in the request (the
+//! FastCGI params), which aren't in the process environment, so they are
+//! missed by [`std::env::var`], use [`var`] instead.
+
+use crate::{functions::call, sys::*, values::ZVal};
+use std::ffi::{CStr, CString};
+
+/// Gets the environment variable provided by the SAPI, like the FastCGI params
+/// of php-fpm, without looking up the process environment.
+///
+/// The invalid UTF-8 sequences are replaced with `U+FFFD`.
+pub fn sapi_var(name: &str) -> Option<String> {
+    let name = CString::new(name).ok()?;
+    unsafe {
+        let value = phper_sapi_getenv(name.as_ptr(), name.as_bytes().len());
+        if value.is_null() {
+            return None;
+        }
+        let s = CStr::from_ptr(value).to_string_lossy().into_owned();
+        phper_efree(value.cast());
+        Some(s)
+    }
+}
+
+/// Gets the environment variable like `getenv()`, provided by the SAPI first,
+/// then the process environment.
+///
+/// The invalid UTF-8 sequences are replaced with `U+FFFD`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::env;
+///
+/// fn region() -> String {
+///     env::var("AWS_REGION").unwrap_or_else(|| "us-east-1".to_owned())
+/// }
+/// ```
+pub fn var(name: &str) -> Option<String> {
+    sapi_var(name)
+        .or_else(|| std::env::var_os(name).map(|value| value.to_string_lossy().into_owned()))
+}
+
+/// Sets the environment variable like `putenv()`, the original value is
+/// restored at the end of request.
+///
+/// # Errors
+///
+/// Return the error if the name is empty or contains `=`.
+pub fn set_var(name: &str, value: &str) -> crate::Result<()> {
+    put(name, format!("{}={}", name, value))
+}
+
+/// Removes the environment variable like `putenv()` with only the name, the
+/// original value is restored at the end of request.
+///
+/// # Errors
+///
+/// Return the error if the name is empty or contains `=`.
+pub fn remove_var(name: &str) -> crate::Result<()> {
+    put(name, name.to_owned())
+}
+
+fn put(name: &str, setting: String) -> crate::Result<()> {
+    if name.is_empty() || name.contains('=') {
+        return Err(crate::Error::argument(format!(
+            "Invalid environment variable name \"{}\"",
+            name
+        )));
+    }
+    let ret = call("putenv", [ZVal::from(setting)])?;
+    if ret.as_bool() == Some(true) {
+        Ok(())
+    } else {
+        Err(crate::Error::runtime(format!(
+            "Failed to set environment variable \"{}\"",
+            name
+        )))
+    }
+}
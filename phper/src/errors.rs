@@ -10,7 +10,13 @@
 
 //! The errors for crate and php.
 
-use crate::{classes::ClassEntry, objects::ZObject, sys::*, types::TypeInfo, values::ZVal};
+use crate::{
+    classes::ClassEntry,
+    objects::{ZObj, ZObject},
+    sys::*,
+    types::TypeInfo,
+    values::ZVal,
+};
 use derive_more::Constructor;
 use phper_alloc::ToRefOwned;
 use std::{
@@ -210,6 +216,62 @@ pub enum Error {
     /// Failed when the object isn't implement PHP `Throwable`.
     #[error(transparent)]
     NotImplementThrowable(#[from] NotImplementThrowableError),
+
+    /// Failed when the value isn't a valid callable.
+    #[error(transparent)]
+    NotCallable(#[from] NotCallableError),
+
+    /// Failed to change the ini entry.
+    #[error(transparent)]
+    IniSet(#[from] IniSetError),
+
+    /// Failed to encode or decode JSON.
+    #[error(transparent)]
+    Json(#[from] JsonError),
+
+    /// Failed to unserialize the data.
+    #[error(transparent)]
+    Unserialize(#[from] UnserializeError),
+
+    /// Failed to gather the random data.
+    #[error(transparent)]
+    Random(#[from] RandomError),
+
+    /// The hash algorithm isn't supported.
+    #[error(transparent)]
+    HashAlgo(#[from] HashAlgoError),
+
+    /// The flags contain unknown bits.
+    #[error(transparent)]
+    UnknownFlags(#[from] UnknownFlagsError),
+
+    /// The value is rejected by the filter.
+    #[error(transparent)]
+    Filter(#[from] FilterError),
+
+    /// The request is shutting down.
+    #[error(transparent)]
+    Cancelled(#[from] CancelledError),
+
+    /// The object state isn't initialized by the constructor.
+    #[error(transparent)]
+    NotInitialized(#[from] NotInitializedError),
+
+    /// Throw as PHP `TypeError`.
+    #[error(transparent)]
+    Type(#[from] TypeError),
+
+    /// Throw as PHP `InvalidArgumentException`.
+    #[error(transparent)]
+    Argument(#[from] ArgumentError),
+
+    /// Throw as PHP `RuntimeException`.
+    #[error(transparent)]
+    Runtime(#[from] RuntimeError),
+
+    /// Throw as the custom PHP exception class.
+    #[error(transparent)]
+    Custom(#[from] CustomError),
 }
 
 impl Error {
@@ -218,6 +280,54 @@ impl Error {
         Self::Boxed(e.into())
     }
 
+    /// Create the error thrown as PHP `TypeError`.
+    pub fn type_error(message: impl Into<String>) -> Self {
+        Self::Type(TypeError::new(message.into()))
+    }
+
+    /// Create the error thrown as PHP `InvalidArgumentException`.
+    pub fn argument(message: impl Into<String>) -> Self {
+        Self::Argument(ArgumentError::new(message.into()))
+    }
+
+    /// Create the error thrown as PHP `RuntimeException`.
+    pub fn runtime(message: impl Into<String>) -> Self {
+        Self::Runtime(RuntimeError::new(message.into()))
+    }
+
+    /// Create the error thrown as the PHP exception class named `class_name`,
+    /// falling back to `Exception` if the class isn't found when thrown.
+    pub fn custom(class_name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Custom(CustomError::new(class_name.into(), message.into()))
+    }
+
+    /// Transfer into exception object internally with the previous exception
+    /// attached, and wrap it into `Throw`, preserving the causal chain, for
+    /// example, the exception captured from a failed PHP callback.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{functions::call, values::ZVal};
+    ///
+    /// fn run(callback: ZVal) -> phper::Result<ZVal> {
+    ///     call(callback, []).map_err(|e| match e {
+    ///         phper::Error::Throw(previous) => {
+    ///             phper::Error::runtime("callback failed").with_previous(previous)
+    ///         }
+    ///         e => e,
+    ///     })
+    /// }
+    /// ```
+    pub fn with_previous(self, previous: ThrowObject) -> Self {
+        let mut obj = match self {
+            Self::Throw(obj) => obj,
+            e => ThrowObject::from_throwable(e),
+        };
+        obj.set_previous(previous);
+        Self::Throw(obj)
+    }
+
     /// Transfer [Throwable] item to exception object internally, and wrap it
     /// into `Throw`.
     pub fn throw(t: impl Throwable) -> Self {
@@ -226,6 +336,13 @@ impl Error {
     }
 }
 
+#[cfg(feature = "anyhow")]
+impl From<anyhow::Error> for Error {
+    fn from(e: anyhow::Error) -> Self {
+        Self::Boxed(e.into())
+    }
+}
+
 impl Throwable for Error {
     #[inline]
     fn get_class(&self) -> &ClassEntry {
@@ -240,6 +357,20 @@ impl Throwable for Error {
             Error::InitializeObject(e) => Throwable::get_class(e),
             Error::ExpectType(e) => Throwable::get_class(e),
             Error::NotImplementThrowable(e) => Throwable::get_class(e),
+            Error::NotCallable(e) => Throwable::get_class(e),
+            Error::IniSet(e) => Throwable::get_class(e),
+            Error::Json(e) => Throwable::get_class(e),
+            Error::Unserialize(e) => Throwable::get_class(e),
+            Error::Random(e) => Throwable::get_class(e),
+            Error::HashAlgo(e) => Throwable::get_class(e),
+            Error::UnknownFlags(e) => Throwable::get_class(e),
+            Error::Filter(e) => Throwable::get_class(e),
+            Error::Cancelled(e) => Throwable::get_class(e),
+            Error::NotInitialized(e) => Throwable::get_class(e),
+            Error::Type(e) => Throwable::get_class(e),
+            Error::Argument(e) => Throwable::get_class(e),
+            Error::Runtime(e) => Throwable::get_class(e),
+            Error::Custom(e) => Throwable::get_class(e),
         }
     }
 
@@ -256,6 +387,20 @@ impl Throwable for Error {
             Error::InitializeObject(e) => Throwable::get_code(e),
             Error::ExpectType(e) => Throwable::get_code(e),
             Error::NotImplementThrowable(e) => Throwable::get_code(e),
+            Error::NotCallable(e) => Throwable::get_code(e),
+            Error::IniSet(e) => Throwable::get_code(e),
+            Error::Json(e) => Throwable::get_code(e),
+            Error::Unserialize(e) => Throwable::get_code(e),
+            Error::Random(e) => Throwable::get_code(e),
+            Error::HashAlgo(e) => Throwable::get_code(e),
+            Error::UnknownFlags(e) => Throwable::get_code(e),
+            Error::Filter(e) => Throwable::get_code(e),
+            Error::Cancelled(e) => Throwable::get_code(e),
+            Error::NotInitialized(e) => Throwable::get_code(e),
+            Error::Type(e) => Throwable::get_code(e),
+            Error::Argument(e) => Throwable::get_code(e),
+            Error::Runtime(e) => Throwable::get_code(e),
+            Error::Custom(e) => Throwable::get_code(e),
         }
     }
 
@@ -271,6 +416,20 @@ impl Throwable for Error {
             Error::InitializeObject(e) => Throwable::get_message(e),
             Error::ExpectType(e) => Throwable::get_message(e),
             Error::NotImplementThrowable(e) => Throwable::get_message(e),
+            Error::NotCallable(e) => Throwable::get_message(e),
+            Error::IniSet(e) => Throwable::get_message(e),
+            Error::Json(e) => Throwable::get_message(e),
+            Error::Unserialize(e) => Throwable::get_message(e),
+            Error::Random(e) => Throwable::get_message(e),
+            Error::HashAlgo(e) => Throwable::get_message(e),
+            Error::UnknownFlags(e) => Throwable::get_message(e),
+            Error::Filter(e) => Throwable::get_message(e),
+            Error::Cancelled(e) => Throwable::get_message(e),
+            Error::NotInitialized(e) => Throwable::get_message(e),
+            Error::Type(e) => Throwable::get_message(e),
+            Error::Argument(e) => Throwable::get_message(e),
+            Error::Runtime(e) => Throwable::get_message(e),
+            Error::Custom(e) => Throwable::get_message(e),
         }
     }
 
@@ -286,11 +445,50 @@ impl Throwable for Error {
             Error::InitializeObject(e) => Throwable::to_object(e),
             Error::ExpectType(e) => Throwable::to_object(e),
             Error::NotImplementThrowable(e) => Throwable::to_object(e),
+            Error::NotCallable(e) => Throwable::to_object(e),
+            Error::IniSet(e) => Throwable::to_object(e),
+            Error::Json(e) => Throwable::to_object(e),
+            Error::Unserialize(e) => Throwable::to_object(e),
+            Error::Random(e) => Throwable::to_object(e),
+            Error::HashAlgo(e) => Throwable::to_object(e),
+            Error::UnknownFlags(e) => Throwable::to_object(e),
+            Error::Filter(e) => Throwable::to_object(e),
+            Error::Cancelled(e) => Throwable::to_object(e),
+            Error::NotInitialized(e) => Throwable::to_object(e),
+            Error::Type(e) => Throwable::to_object(e),
+            Error::Argument(e) => Throwable::to_object(e),
+            Error::Runtime(e) => Throwable::to_object(e),
+            Error::Custom(e) => Throwable::to_object(e),
         }
     }
 }
 
 /// Wrapper of Throwable object.
+///
+/// The exception thrown by the PHP code called from Rust, such as
+/// [`call`](crate::functions::call), [`ZObj::call`] and
+/// [`ClassEntry::new_object`], is taken out of the executor and returned as
+/// [`Error::Throw`], so it can be inspected, cleared by dropping, or rethrown
+/// by returning it from the handler.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{classes::ClassEntry, functions::call, values::ZVal};
+///
+/// fn call_with_retry(callback: ZVal, mut attempts: usize) -> phper::Result<ZVal> {
+///     let retryable = ClassEntry::from_globals("RuntimeException")?;
+///     loop {
+///         attempts -= 1;
+///         match call(callback.clone(), []) {
+///             Err(phper::Error::Throw(e)) if attempts > 0 && e.is_instance_of(retryable) => {
+///                 phper::warning!("retry after {}: {}", e.class_name(), e.message());
+///             }
+///             result => return result,
+///         }
+///     }
+/// }
+/// ```
 #[derive(Debug)]
 pub struct ThrowObject(ZObject);
 
@@ -343,14 +541,34 @@ impl ThrowObject {
         self.0
     }
 
-    fn inner_get_code(&self) -> i64 {
+    /// Gets the wrapped object.
+    #[inline]
+    pub fn as_object(&self) -> &ZObj {
+        &self.0
+    }
+
+    /// Gets the class name of the exception.
+    pub fn class_name(&self) -> &str {
+        self.0.get_class().get_name().to_str().unwrap_or_default()
+    }
+
+    /// Whether the exception is instance of the class, useful to decide which
+    /// exceptions to catch.
+    #[inline]
+    pub fn is_instance_of(&self, class: &ClassEntry) -> bool {
+        self.0.get_class().is_instance_of(class)
+    }
+
+    /// Gets the exception code, same as `Throwable::getCode()`.
+    pub fn code(&self) -> i64 {
         self.0
             .get_property("code")
             .as_long()
             .expect("code isn't long")
     }
 
-    fn inner_get_message(&self) -> String {
+    /// Gets the exception message, same as `Throwable::getMessage()`.
+    pub fn message(&self) -> String {
         self.0
             .get_property("message")
             .as_z_str()
@@ -359,11 +577,62 @@ impl ThrowObject {
             .map(ToOwned::to_owned)
             .unwrap_or_default()
     }
+
+    /// Gets the file name where the exception was created, same as
+    /// `Throwable::getFile()`.
+    pub fn file(&self) -> String {
+        self.call_getter("getFile")
+            .as_z_str()
+            .and_then(|file| file.to_str().ok().map(ToOwned::to_owned))
+            .unwrap_or_default()
+    }
+
+    /// Gets the line where the exception was created, same as
+    /// `Throwable::getLine()`.
+    pub fn line(&self) -> i64 {
+        self.call_getter("getLine").as_long().unwrap_or_default()
+    }
+
+    /// Gets the stack trace as string, same as
+    /// `Throwable::getTraceAsString()`.
+    pub fn trace_as_string(&self) -> String {
+        self.call_getter("getTraceAsString")
+            .as_z_str()
+            .and_then(|trace| trace.to_str().ok().map(ToOwned::to_owned))
+            .unwrap_or_default()
+    }
+
+    /// Gets the previous exception, same as `Throwable::getPrevious()`.
+    pub fn previous(&self) -> Option<ThrowObject> {
+        let previous = self.call_getter("getPrevious");
+        let previous = previous.as_z_obj()?.to_ref_owned();
+        ThrowObject::new(previous).ok()
+    }
+
+    /// Attach the previous exception, which is appended to the end of the
+    /// chain of the previous exceptions, same as the engine does for the
+    /// exception thrown in `finally`.
+    pub fn set_previous(&mut self, previous: ThrowObject) {
+        if self.0.as_ptr() == previous.0.as_ptr() {
+            return;
+        }
+        unsafe {
+            zend_exception_set_previous(self.0.as_mut_ptr(), previous.0.into_raw());
+        }
+    }
+
+    fn call_getter(&self, method_name: &str) -> ZVal {
+        // The getters of `Throwable` are final and never throw.
+        self.0
+            .to_ref_owned()
+            .call(method_name, [])
+            .unwrap_or_default()
+    }
 }
 
 impl Display for ThrowObject {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        Display::fmt(&self.inner_get_message(), f)
+        Display::fmt(&self.message(), f)
     }
 }
 
@@ -377,12 +646,12 @@ impl Throwable for ThrowObject {
 
     #[inline]
     fn get_code(&self) -> Option<i64> {
-        Some(self.inner_get_code())
+        Some(self.code())
     }
 
     #[inline]
     fn get_message(&self) -> Option<String> {
-        Some(self.inner_get_message())
+        Some(self.message())
     }
 
     #[inline]
@@ -456,6 +725,81 @@ impl Throwable for InitializeObjectError {
     }
 }
 
+/// Failed when the object state isn't initialized, because the constructor
+/// isn't called or throws.
+#[derive(Debug, thiserror::Error, Constructor)]
+#[error("Object of class {class_name} is not initialized")]
+pub struct NotInitializedError {
+    class_name: String,
+}
+
+impl Throwable for NotInitializedError {
+    fn get_class(&self) -> &ClassEntry {
+        error_class()
+    }
+}
+
+/// Error thrown as PHP `TypeError`, see [`Error::type_error`].
+#[derive(Debug, thiserror::Error, Constructor)]
+#[error("{message}")]
+pub struct TypeError {
+    message: String,
+}
+
+impl Throwable for TypeError {
+    fn get_class(&self) -> &ClassEntry {
+        type_error_class()
+    }
+}
+
+/// Error thrown as PHP `InvalidArgumentException`, see [`Error::argument`].
+#[derive(Debug, thiserror::Error, Constructor)]
+#[error("{message}")]
+pub struct ArgumentError {
+    message: String,
+}
+
+impl Throwable for ArgumentError {
+    fn get_class(&self) -> &ClassEntry {
+        ClassEntry::from_globals("InvalidArgumentException").unwrap_or_else(|_| exception_class())
+    }
+}
+
+/// Error thrown as PHP `RuntimeException`, see [`Error::runtime`].
+#[derive(Debug, thiserror::Error, Constructor)]
+#[error("{message}")]
+pub struct RuntimeError {
+    message: String,
+}
+
+impl Throwable for RuntimeError {
+    fn get_class(&self) -> &ClassEntry {
+        ClassEntry::from_globals("RuntimeException").unwrap_or_else(|_| exception_class())
+    }
+}
+
+/// Error thrown as the custom PHP exception class, see [`Error::custom`].
+#[derive(Debug, thiserror::Error, Constructor)]
+#[error("{message}")]
+pub struct CustomError {
+    class_name: String,
+    message: String,
+}
+
+impl CustomError {
+    /// Get the name of the PHP exception class.
+    #[inline]
+    pub fn class_name(&self) -> &str {
+        &self.class_name
+    }
+}
+
+impl Throwable for CustomError {
+    fn get_class(&self) -> &ClassEntry {
+        ClassEntry::from_globals(&self.class_name).unwrap_or_else(|_| exception_class())
+    }
+}
+
 /// Failed when the object isn't implement PHP `Throwable`.
 #[derive(Debug, thiserror::Error)]
 #[error("Cannot throw objects that do not implement Throwable")]
@@ -467,6 +811,179 @@ impl Throwable for NotImplementThrowableError {
     }
 }
 
+/// Failed when the value isn't a valid callable.
+#[derive(Debug, thiserror::Error, Constructor)]
+#[error("{message}")]
+pub struct NotCallableError {
+    message: String,
+}
+
+impl Throwable for NotCallableError {
+    fn get_class(&self) -> &ClassEntry {
+        type_error_class()
+    }
+}
+
+/// Failed to change the ini entry, because the entry doesn't exist, isn't
+/// modifiable in the stage, or the value is rejected.
+#[derive(Debug, thiserror::Error, Constructor)]
+#[error("Failed to set ini entry {name}")]
+pub struct IniSetError {
+    name: String,
+}
+
+impl Throwable for IniSetError {
+    fn get_class(&self) -> &ClassEntry {
+        exception_class()
+    }
+}
+
+/// Failed to encode or decode JSON, the code and message are the same as
+/// `json_last_error()` and `json_last_error_msg()`.
+#[derive(Debug, thiserror::Error, Constructor)]
+#[error("{message}")]
+pub struct JsonError {
+    code: i64,
+    message: String,
+}
+
+impl JsonError {
+    /// Get the error code, same as the `JSON_ERROR_*` constants.
+    #[inline]
+    pub fn code(&self) -> i64 {
+        self.code
+    }
+}
+
+impl Throwable for JsonError {
+    fn get_class(&self) -> &ClassEntry {
+        ClassEntry::from_globals("JsonException").unwrap_or_else(|_| exception_class())
+    }
+
+    fn get_code(&self) -> Option<i64> {
+        Some(self.code)
+    }
+}
+
+/// Failed to unserialize the data, because the data is malformed.
+#[derive(Debug, thiserror::Error)]
+#[error("Failed to unserialize the data")]
+pub struct UnserializeError;
+
+impl Throwable for UnserializeError {
+    fn get_class(&self) -> &ClassEntry {
+        exception_class()
+    }
+}
+
+/// Failed to gather the random data, because the source of randomness can't
+/// be used.
+#[derive(Debug, thiserror::Error)]
+#[error("Cannot gather sufficient random data")]
+pub struct RandomError;
+
+impl Throwable for RandomError {
+    fn get_class(&self) -> &ClassEntry {
+        ClassEntry::from_globals("Random\\RandomException").unwrap_or_else(|_| exception_class())
+    }
+}
+
+/// Failed when the hash algorithm isn't supported.
+#[derive(Debug, thiserror::Error, Constructor)]
+#[error("Unknown hashing algorithm: {algo}")]
+pub struct HashAlgoError {
+    algo: String,
+}
+
+impl Throwable for HashAlgoError {
+    fn get_class(&self) -> &ClassEntry {
+        ClassEntry::from_globals("ValueError").unwrap_or_else(|_| exception_class())
+    }
+}
+
+/// Failed when the flags contain unknown bits.
+#[derive(Debug, thiserror::Error, Constructor)]
+#[error("Unknown flags: {bits:#x}")]
+pub struct UnknownFlagsError {
+    bits: i64,
+}
+
+impl UnknownFlagsError {
+    /// Gets the unknown bits.
+    #[inline]
+    pub fn bits(&self) -> i64 {
+        self.bits
+    }
+}
+
+impl Throwable for UnknownFlagsError {
+    fn get_class(&self) -> &ClassEntry {
+        ClassEntry::from_globals("ValueError").unwrap_or_else(|_| exception_class())
+    }
+}
+
+/// Failed when the value is rejected by the validate filter of `ext/filter`.
+#[derive(Debug, thiserror::Error, Constructor)]
+#[error("Value is not valid for filter \"{name}\"")]
+pub struct FilterError {
+    filter: i64,
+    name: String,
+}
+
+impl FilterError {
+    /// Gets the filter id, same as the `FILTER_*` constants.
+    #[inline]
+    pub fn filter(&self) -> i64 {
+        self.filter
+    }
+
+    /// Gets the filter name, like `validate_email`.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Throwable for FilterError {
+    fn get_class(&self) -> &ClassEntry {
+        ClassEntry::from_globals("ValueError").unwrap_or_else(|_| exception_class())
+    }
+}
+
+/// Failed when the native work is aborted, because the request which started
+/// it is shutting down, see [`RequestToken`](crate::requests::RequestToken).
+#[derive(Debug, thiserror::Error)]
+#[error("The request is cancelled")]
+pub struct CancelledError;
+
+impl Throwable for CancelledError {
+    fn get_class(&self) -> &ClassEntry {
+        exception_class()
+    }
+}
+
+/// Error thrown as PHP `Error`, when the Rust allocations counted by
+/// [`EAllocator`](crate::alloc::EAllocator) exceed the `memory_limit`.
+#[derive(Debug, thiserror::Error, Constructor)]
+#[error("Allowed memory size of {limit} bytes exhausted by the Rust allocations")]
+pub struct MemoryLimitError {
+    limit: usize,
+}
+
+impl Throwable for MemoryLimitError {
+    fn get_class(&self) -> &ClassEntry {
+        error_class()
+    }
+}
+
+/// The error returned by the ini `on_modify` callback, when the new value is
+/// invalid.
+#[derive(Debug, thiserror::Error, Constructor)]
+#[error("{message}")]
+pub struct IniError {
+    message: String,
+}
+
 /// Guarder for preventing the thrown exception from being overwritten.
 ///
 /// Normally, you don't need to use `ExceptionGuard`, unless before you call the
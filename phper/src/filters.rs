@@ -0,0 +1,195 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to validating and sanitizing values by `ext/filter`,
+//! consistent with the userland `filter_var()`.
+
+use crate::{arrays::ZArray, errors::FilterError, functions::call, values::ZVal};
+
+/// `FILTER_FLAG_NONE`.
+pub const FLAG_NONE: i64 = 0;
+/// `FILTER_REQUIRE_SCALAR`.
+pub const REQUIRE_SCALAR: i64 = 1 << 25;
+/// `FILTER_REQUIRE_ARRAY`.
+pub const REQUIRE_ARRAY: i64 = 1 << 24;
+/// `FILTER_FORCE_ARRAY`.
+pub const FORCE_ARRAY: i64 = 1 << 26;
+/// `FILTER_NULL_ON_FAILURE`.
+pub const NULL_ON_FAILURE: i64 = 1 << 27;
+
+/// `FILTER_VALIDATE_INT`.
+pub const VALIDATE_INT: i64 = 0x0101;
+/// `FILTER_VALIDATE_BOOL`.
+pub const VALIDATE_BOOL: i64 = 0x0102;
+/// `FILTER_VALIDATE_FLOAT`.
+pub const VALIDATE_FLOAT: i64 = 0x0103;
+/// `FILTER_VALIDATE_REGEXP`.
+pub const VALIDATE_REGEXP: i64 = 0x0110;
+/// `FILTER_VALIDATE_URL`.
+pub const VALIDATE_URL: i64 = 0x0111;
+/// `FILTER_VALIDATE_EMAIL`.
+pub const VALIDATE_EMAIL: i64 = 0x0112;
+/// `FILTER_VALIDATE_IP`.
+pub const VALIDATE_IP: i64 = 0x0113;
+/// `FILTER_VALIDATE_MAC`.
+pub const VALIDATE_MAC: i64 = 0x0114;
+/// `FILTER_VALIDATE_DOMAIN`.
+pub const VALIDATE_DOMAIN: i64 = 0x0115;
+
+/// `FILTER_UNSAFE_RAW`, also `FILTER_DEFAULT`.
+pub const UNSAFE_RAW: i64 = 0x0204;
+/// `FILTER_SANITIZE_ENCODED`.
+pub const SANITIZE_ENCODED: i64 = 0x0202;
+/// `FILTER_SANITIZE_SPECIAL_CHARS`.
+pub const SANITIZE_SPECIAL_CHARS: i64 = 0x0203;
+/// `FILTER_SANITIZE_EMAIL`.
+pub const SANITIZE_EMAIL: i64 = 0x0205;
+/// `FILTER_SANITIZE_URL`.
+pub const SANITIZE_URL: i64 = 0x0206;
+/// `FILTER_SANITIZE_NUMBER_INT`.
+pub const SANITIZE_NUMBER_INT: i64 = 0x0207;
+/// `FILTER_SANITIZE_NUMBER_FLOAT`.
+pub const SANITIZE_NUMBER_FLOAT: i64 = 0x0208;
+/// `FILTER_SANITIZE_FULL_SPECIAL_CHARS`.
+pub const SANITIZE_FULL_SPECIAL_CHARS: i64 = 0x020a;
+/// `FILTER_SANITIZE_ADD_SLASHES`, since PHP 7.3.
+pub const SANITIZE_ADD_SLASHES: i64 = 0x020b;
+
+/// `FILTER_FLAG_ALLOW_OCTAL`.
+pub const FLAG_ALLOW_OCTAL: i64 = 0x0001;
+/// `FILTER_FLAG_ALLOW_HEX`.
+pub const FLAG_ALLOW_HEX: i64 = 0x0002;
+/// `FILTER_FLAG_STRIP_LOW`.
+pub const FLAG_STRIP_LOW: i64 = 0x0004;
+/// `FILTER_FLAG_STRIP_HIGH`.
+pub const FLAG_STRIP_HIGH: i64 = 0x0008;
+/// `FILTER_FLAG_ENCODE_LOW`.
+pub const FLAG_ENCODE_LOW: i64 = 0x0010;
+/// `FILTER_FLAG_ENCODE_HIGH`.
+pub const FLAG_ENCODE_HIGH: i64 = 0x0020;
+/// `FILTER_FLAG_ENCODE_AMP`.
+pub const FLAG_ENCODE_AMP: i64 = 0x0040;
+/// `FILTER_FLAG_NO_ENCODE_QUOTES`.
+pub const FLAG_NO_ENCODE_QUOTES: i64 = 0x0080;
+/// `FILTER_FLAG_EMPTY_STRING_NULL`.
+pub const FLAG_EMPTY_STRING_NULL: i64 = 0x0100;
+/// `FILTER_FLAG_STRIP_BACKTICK`.
+pub const FLAG_STRIP_BACKTICK: i64 = 0x0200;
+/// `FILTER_FLAG_ALLOW_FRACTION`.
+pub const FLAG_ALLOW_FRACTION: i64 = 0x1000;
+/// `FILTER_FLAG_ALLOW_THOUSAND`.
+pub const FLAG_ALLOW_THOUSAND: i64 = 0x2000;
+/// `FILTER_FLAG_ALLOW_SCIENTIFIC`.
+pub const FLAG_ALLOW_SCIENTIFIC: i64 = 0x4000;
+/// `FILTER_FLAG_PATH_REQUIRED`.
+pub const FLAG_PATH_REQUIRED: i64 = 0x040000;
+/// `FILTER_FLAG_QUERY_REQUIRED`.
+pub const FLAG_QUERY_REQUIRED: i64 = 0x080000;
+/// `FILTER_FLAG_IPV4`.
+pub const FLAG_IPV4: i64 = 0x100000;
+/// `FILTER_FLAG_IPV6`.
+pub const FLAG_IPV6: i64 = 0x200000;
+/// `FILTER_FLAG_NO_RES_RANGE`.
+pub const FLAG_NO_RES_RANGE: i64 = 0x400000;
+/// `FILTER_FLAG_NO_PRIV_RANGE`.
+pub const FLAG_NO_PRIV_RANGE: i64 = 0x800000;
+/// `FILTER_FLAG_HOSTNAME`.
+pub const FLAG_HOSTNAME: i64 = 0x100000;
+/// `FILTER_FLAG_EMAIL_UNICODE`.
+pub const FLAG_EMAIL_UNICODE: i64 = 0x100000;
+
+/// Detect if `ext/filter` is loaded, which is enabled by default, but can be
+/// disabled by `--disable-filter`.
+pub fn is_loaded() -> bool {
+    crate::modules::is_loaded("filter")
+}
+
+/// Apply the filter to the value, like `filter_var()`, returns the filtered
+/// value, or `false` if failed.
+///
+/// The `options` are the filter specific options, like `min_range` of
+/// [`VALIDATE_INT`].
+pub fn apply(
+    value: &ZVal, filter: i64, flags: i64, options: Option<ZArray>,
+) -> crate::Result<ZVal> {
+    let mut arr = ZArray::new();
+    arr.insert("flags", ZVal::from(flags));
+    if let Some(options) = options {
+        arr.insert("options", ZVal::from(options));
+    }
+    call(
+        "filter_var",
+        [value.clone(), ZVal::from(filter), ZVal::from(arr)],
+    )
+}
+
+/// Validate the value by the validate filter, like `VALIDATE_*`, returns the
+/// converted value, or `None` if invalid.
+///
+/// Unlike `filter_var()`, the invalid value is distinguished from the valid
+/// `false` of [`VALIDATE_BOOL`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{filters, values::ZVal};
+///
+/// fn example() -> phper::Result<()> {
+///     let value = ZVal::from("yes");
+///     let valid = filters::validate(&value, filters::VALIDATE_BOOL, filters::FLAG_NONE, None)?;
+///     assert_eq!(valid.and_then(|v| v.as_bool()), Some(true));
+///     Ok(())
+/// }
+/// ```
+pub fn validate(
+    value: &ZVal, filter: i64, flags: i64, options: Option<ZArray>,
+) -> crate::Result<Option<ZVal>> {
+    let ret = apply(value, filter, flags | NULL_ON_FAILURE, options)?;
+    Ok(if ret.get_type_info().is_null() {
+        None
+    } else {
+        Some(ret)
+    })
+}
+
+/// Same as [`validate`], but the invalid value is an error, so it can be
+/// rejected by `?`, thrown as `ValueError`.
+///
+/// # Errors
+///
+/// Return [`FilterError`] if the value is invalid.
+pub fn require(
+    value: &ZVal, filter: i64, flags: i64, options: Option<ZArray>,
+) -> crate::Result<ZVal> {
+    match validate(value, filter, flags, options)? {
+        Some(value) => Ok(value),
+        None => Err(FilterError::new(filter, name(filter).unwrap_or_default()).into()),
+    }
+}
+
+/// Sanitize the value by the sanitize filter, like `SANITIZE_*`.
+pub fn sanitize(value: &ZVal, filter: i64, flags: i64) -> crate::Result<ZVal> {
+    apply(value, filter, flags, None)
+}
+
+/// Get the filter name by id, like `validate_email`, the reverse of
+/// `filter_id()`.
+pub fn name(filter: i64) -> Option<String> {
+    let list = call("filter_list", []).ok()?;
+    list.as_z_arr()?.iter().find_map(|(_, name)| {
+        let name = name.as_z_str()?;
+        let id = call("filter_id", [ZVal::from(name.to_bytes())]).ok()?;
+        if id.as_long() == Some(filter) {
+            name.to_str().ok().map(ToOwned::to_owned)
+        } else {
+            None
+        }
+    })
+}
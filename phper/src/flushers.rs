@@ -0,0 +1,267 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to sending the data in background, like the metrics and traces.
+
+use crate::{crashes::SignalStack, utils::SignalsBlocked};
+use std::{
+    mem::{forget, take},
+    panic::{catch_unwind, AssertUnwindSafe},
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// The batched background sender, the items pushed in the request are buffered
+/// in a bounded queue, and flushed by a worker thread, periodically, when the
+/// queue is half full, or at the end of request.
+///
+/// Register it by [`Module::add_background_flusher`] to be woken up in
+/// `RSHUTDOWN` and drained in `MSHUTDOWN`.
+///
+/// It is designed to be safe with the signal handling and fork model of PHP:
+///
+/// - The worker thread is spawned with all the signals blocked, so the signals
+///   like the timer of `max_execution_time` are always delivered to the PHP
+///   thread.
+/// - The worker thread is spawned lazily in the first push of the process, if
+///   the process is forked (like the php-fpm workers), a new worker thread is
+///   spawned in the child process, and the items inherited from the parent are
+///   discarded, rather than touching the locks may be held by the thread which
+///   doesn't exist in the child.
+/// - The [`push`](BackgroundFlusher::push) never blocks on the sending, the
+///   items are dropped and counted when the queue is full.
+///
+/// [`Module::add_background_flusher`]: crate::modules::Module::add_background_flusher
+///
+/// # Examples
+///
+/// ```no_run
+/// use once_cell::sync::Lazy;
+/// use phper::{flushers::BackgroundFlusher, modules::Module, php_get_module};
+/// use std::time::Duration;
+///
+/// static SPANS: Lazy<BackgroundFlusher<String>> = Lazy::new(|| {
+///     BackgroundFlusher::new(1024, Duration::from_secs(1), |spans| {
+///         // Send the spans to the collector here.
+///         let _ = spans;
+///     })
+/// });
+///
+/// #[php_get_module]
+/// pub fn get_module() -> Module {
+///     let mut module = Module::new(
+///         env!("CARGO_PKG_NAME"),
+///         env!("CARGO_PKG_VERSION"),
+///         env!("CARGO_PKG_AUTHORS"),
+///     );
+///
+///     module.add_background_flusher(&SPANS);
+///     module.on_request_shutdown(|| {
+///         SPANS.push("request".to_owned());
+///     });
+///
+///     module
+/// }
+/// ```
+pub struct BackgroundFlusher<T: Send + 'static> {
+    inner: Arc<Inner<T>>,
+}
+
+struct Inner<T> {
+    capacity: usize,
+    interval: Duration,
+    flush: Arc<dyn Fn(Vec<T>) + Send + Sync>,
+    worker: Mutex<Option<Worker<T>>>,
+    dropped: AtomicUsize,
+}
+
+struct Worker<T> {
+    pid: u32,
+    shared: Arc<Shared<T>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+struct Shared<T> {
+    queue: Mutex<Queue<T>>,
+    condvar: Condvar,
+}
+
+struct Queue<T> {
+    items: Vec<T>,
+    closed: bool,
+}
+
+impl<T: Send + 'static> BackgroundFlusher<T> {
+    /// Construct with the capacity of queue, the interval of flushing, and the
+    /// function to send the batch of items, which is called in the worker
+    /// thread.
+    pub fn new(
+        capacity: usize, interval: Duration, flush: impl Fn(Vec<T>) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                capacity,
+                interval,
+                flush: Arc::new(flush),
+                worker: Mutex::new(None),
+                dropped: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Push the item to the queue, return `false` if the queue is full or the
+    /// flusher is shutdown, the item is dropped.
+    pub fn push(&self, item: T) -> bool {
+        let mut worker = self.inner.worker.lock().unwrap();
+        let pid = process::id();
+        if worker.as_ref().map(|worker| worker.pid) != Some(pid) {
+            if let Some(inherited) = worker.take() {
+                forget(inherited);
+            }
+            *worker = Some(Worker::spawn(
+                pid,
+                self.inner.interval,
+                self.inner.flush.clone(),
+            ));
+        }
+        let shared = &worker.as_ref().unwrap().shared;
+
+        let mut queue = shared.queue.lock().unwrap();
+        if queue.closed || queue.items.len() >= self.inner.capacity {
+            self.inner.dropped.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        queue.items.push(item);
+        if queue.items.len() * 2 >= self.inner.capacity {
+            shared.condvar.notify_one();
+        }
+        true
+    }
+
+    /// Wake up the worker thread to flush the queue now, without waiting.
+    pub fn flush(&self) {
+        if let Some(worker) = self.current_worker().as_ref() {
+            worker.shared.condvar.notify_one();
+        }
+    }
+
+    /// Flush the remaining items and stop the worker thread, the following
+    /// pushes in the process are rejected.
+    pub fn shutdown(&self) {
+        let worker = self.current_worker().take();
+        if let Some(mut worker) = worker {
+            worker.shared.queue.lock().unwrap().closed = true;
+            worker.shared.condvar.notify_one();
+            if let Some(thread) = worker.thread.take() {
+                let _ = thread.join();
+            }
+            // Keep the closed queue to reject the following pushes.
+            *self.inner.worker.lock().unwrap() = Some(worker);
+        }
+    }
+
+    /// The count of items dropped because the queue is full.
+    #[inline]
+    pub fn dropped(&self) -> usize {
+        self.inner.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Lock the worker of the current process, the inherited one is discarded.
+    fn current_worker(&self) -> std::sync::MutexGuard<'_, Option<Worker<T>>> {
+        let mut worker = self.inner.worker.lock().unwrap();
+        if worker.as_ref().map(|worker| worker.pid) != Some(process::id()) {
+            if let Some(inherited) = worker.take() {
+                forget(inherited);
+            }
+        }
+        worker
+    }
+}
+
+impl<T: Send + 'static> Clone for BackgroundFlusher<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Send + 'static> Worker<T> {
+    fn spawn(pid: u32, interval: Duration, flush: Arc<dyn Fn(Vec<T>) + Send + Sync>) -> Self {
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue {
+                items: Vec::new(),
+                closed: false,
+            }),
+            condvar: Condvar::new(),
+        });
+        let thread = {
+            let shared = shared.clone();
+            let _blocked = SignalsBlocked::new();
+            thread::Builder::new()
+                .name("phper-flusher".to_owned())
+                .spawn(move || {
+                    let _stack = SignalStack::new();
+                    run(shared, interval, flush)
+                })
+                .ok()
+        };
+        Self {
+            pid,
+            shared,
+            thread,
+        }
+    }
+}
+
+fn run<T>(shared: Arc<Shared<T>>, interval: Duration, flush: Arc<dyn Fn(Vec<T>) + Send + Sync>) {
+    loop {
+        let (items, closed) = {
+            let mut queue = shared.queue.lock().unwrap();
+            if queue.items.is_empty() && !queue.closed {
+                queue = shared.condvar.wait_timeout(queue, interval).unwrap().0;
+            }
+            (take(&mut queue.items), queue.closed)
+        };
+
+        if !items.is_empty() {
+            // Keep the worker thread alive even if the sending panics.
+            let _ = catch_unwind(AssertUnwindSafe(|| flush(items)));
+        }
+
+        if closed {
+            break;
+        }
+    }
+}
+
+/// Type erased [`BackgroundFlusher`] for module.
+pub(crate) trait Flush {
+    fn flush(&self);
+
+    fn shutdown(&self);
+}
+
+impl<T: Send + 'static> Flush for BackgroundFlusher<T> {
+    #[inline]
+    fn flush(&self) {
+        BackgroundFlusher::flush(self)
+    }
+
+    #[inline]
+    fn shutdown(&self) {
+        BackgroundFlusher::shutdown(self)
+    }
+}
@@ -13,21 +13,31 @@
 //! TODO Add lambda.
 
 use crate::{
+    alloc::EAllocator,
+    arrays::{IterKey, ZArr, ZArray},
     classes::{ClassEntry, RawVisibility, Visibility},
-    errors::{throw, ArgumentCountError, ExceptionGuard, ThrowObject, Throwable},
+    errors::{
+        throw, ArgumentCountError, ExceptionGuard, MemoryLimitError, NotCallableError, ThrowObject,
+        Throwable,
+    },
+    modules::global_module,
     objects::{StateObj, ZObj, ZObject},
-    strings::{ZStr, ZString},
+    strings::{ZStr, ZString, ZStringBuilder},
     sys::*,
+    types::TypeHint,
     utils::ensure_end_with_zero,
     values::{ExecuteData, ZVal},
 };
 use phper_alloc::ToRefOwned;
 use std::{
-    ffi::{CStr, CString},
+    ffi::{c_void, CStr, CString},
+    fmt, io,
     marker::PhantomData,
-    mem::{transmute, zeroed},
+    mem::{take, zeroed},
+    os::raw::c_char,
     ptr::{self, null_mut},
     rc::Rc,
+    slice,
 };
 
 pub(crate) trait Callable {
@@ -50,15 +60,46 @@ where
 {
     fn call(&self, _: &mut ExecuteData, arguments: &mut [ZVal], return_value: &mut ZVal) {
         match (self.0)(arguments) {
-            Ok(z) => {
-                *return_value = z.into();
-            }
-            Err(e) => {
-                unsafe {
-                    throw(e);
-                }
-                *return_value = ().into();
+            Ok(z) => unsafe {
+                write_return_value(return_value, z.into());
+            },
+            Err(e) => unsafe {
+                throw(e);
+                write_return_value(return_value, ().into());
+            },
+        }
+    }
+}
+
+/// Moves the value returned by the handler into the return slot, which is
+/// initialized to `null` by the engine before calling the handler, so there is
+/// nothing to release, and the returned scalar is just copied in place.
+#[inline]
+unsafe fn write_return_value(return_value: &mut ZVal, value: ZVal) {
+    debug_assert!(!phper_z_refcounted_p(return_value.as_mut_ptr()));
+    ptr::write(return_value, value);
+}
+
+pub(crate) struct FunctionWithReturn<F, E>(F, PhantomData<E>);
+
+impl<F, E> FunctionWithReturn<F, E> {
+    pub(crate) fn new(f: F) -> Self {
+        Self(f, PhantomData)
+    }
+}
+
+impl<F, E> Callable for FunctionWithReturn<F, E>
+where
+    F: Fn(&mut [ZVal], &mut ReturnValue<'_>) -> Result<(), E>,
+    E: Throwable,
+{
+    fn call(&self, _: &mut ExecuteData, arguments: &mut [ZVal], return_value: &mut ZVal) {
+        if let Err(e) = (self.0)(arguments, &mut ReturnValue(return_value)) {
+            unsafe {
+                throw(e);
             }
+            // The value may be set before failing, so it's released by assigning.
+            *return_value = ().into();
         }
     }
 }
@@ -80,17 +121,184 @@ where
     fn call(
         &self, execute_data: &mut ExecuteData, arguments: &mut [ZVal], return_value: &mut ZVal,
     ) {
+        // The `$this` is taken from the execute data at call time, so the handler
+        // is shared by the subclasses of declaring class.
         let this = unsafe { execute_data.get_this_mut().unwrap().as_mut_state_obj() };
         match (self.0)(this, arguments) {
-            Ok(z) => {
-                *return_value = z.into();
-            }
-            Err(e) => {
-                unsafe {
-                    throw(e);
-                }
-                *return_value = ().into();
+            Ok(z) => unsafe {
+                write_return_value(return_value, z.into());
+            },
+            Err(e) => unsafe {
+                throw(e);
+                write_return_value(return_value, ().into());
+            },
+        }
+    }
+}
+
+pub(crate) struct MethodWithReturn<F, E, T>(F, PhantomData<(E, T)>);
+
+impl<F, E, T> MethodWithReturn<F, E, T> {
+    pub(crate) fn new(f: F) -> Self {
+        Self(f, PhantomData)
+    }
+}
+
+impl<F, E, T: 'static> Callable for MethodWithReturn<F, E, T>
+where
+    F: Fn(&mut StateObj<T>, &mut [ZVal], &mut ReturnValue<'_>) -> Result<(), E>,
+    E: Throwable,
+{
+    fn call(
+        &self, execute_data: &mut ExecuteData, arguments: &mut [ZVal], return_value: &mut ZVal,
+    ) {
+        let this = unsafe { execute_data.get_this_mut().unwrap().as_mut_state_obj() };
+        if let Err(e) = (self.0)(this, arguments, &mut ReturnValue(return_value)) {
+            unsafe {
+                throw(e);
             }
+            // The value may be set before failing, so it's released by assigning.
+            *return_value = ().into();
+        }
+    }
+}
+
+/// The return value of the handler registered by
+/// [`Module::add_function_with_return`] and
+/// [`ClassEntity::add_method_with_return`], constructed in place, so the large
+/// array or string is built without the intermediate Rust value.
+///
+/// The return value is `null` if not set.
+///
+/// [`Module::add_function_with_return`]: crate::modules::Module::add_function_with_return
+/// [`ClassEntity::add_method_with_return`]: crate::classes::ClassEntity::add_method_with_return
+pub struct ReturnValue<'a>(&'a mut ZVal);
+
+impl ReturnValue<'_> {
+    /// Set the return value.
+    #[inline]
+    pub fn set(&mut self, value: impl Into<ZVal>) {
+        *self.0 = value.into();
+    }
+
+    /// Get the return value.
+    #[inline]
+    pub fn as_mut_z_val(&mut self) -> &mut ZVal {
+        self.0
+    }
+
+    /// Set the return value to the empty array if it isn't array, and get the
+    /// array to insert the elements.
+    #[inline]
+    pub fn as_array(&mut self) -> &mut ZArr {
+        self.as_array_with_capacity(0)
+    }
+
+    /// Same as [`ReturnValue::as_array`], but the array is allocated with the
+    /// capacity of `n` elements.
+    pub fn as_array_with_capacity(&mut self, n: usize) -> &mut ZArr {
+        if !self.0.get_type_info().is_array() {
+            *self.0 = ZArray::with_capacity(n).into();
+        }
+        self.0.as_mut_z_arr().unwrap()
+    }
+
+    /// Get the writer of string, the return value is set to the written
+    /// string when the writer is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{modules::Module, values::ZVal};
+    /// use std::io::Write;
+    ///
+    /// fn register(module: &mut Module) {
+    ///     module.add_function_with_return("repeat_csv", |arguments: &mut [ZVal], ret| {
+    ///         let n = arguments[0].expect_long()?;
+    ///         let mut writer = ret.as_string_writer();
+    ///         for i in 0..n {
+    ///             writeln!(writer, "{},row{}", i, i)?;
+    ///         }
+    ///         Ok::<_, phper::Error>(())
+    ///     });
+    /// }
+    /// ```
+    #[inline]
+    pub fn as_string_writer(&mut self) -> ReturnStringWriter<'_> {
+        ReturnStringWriter {
+            val: self.0,
+            builder: ZStringBuilder::new(),
+        }
+    }
+}
+
+/// The string writer created by [`ReturnValue::as_string_writer`].
+pub struct ReturnStringWriter<'a> {
+    val: &'a mut ZVal,
+    builder: ZStringBuilder,
+}
+
+impl ReturnStringWriter<'_> {
+    /// Append the bytes.
+    #[inline]
+    pub fn append(&mut self, s: impl AsRef<[u8]>) {
+        self.builder.append(s);
+    }
+}
+
+impl io::Write for ReturnStringWriter<'_> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.builder.write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl fmt::Write for ReturnStringWriter<'_> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.builder.write_str(s)
+    }
+}
+
+impl Drop for ReturnStringWriter<'_> {
+    fn drop(&mut self) {
+        *self.val = take(&mut self.builder).finish().into();
+    }
+}
+
+pub(crate) struct ObjectMethod<F, Z, E>(F, PhantomData<(Z, E)>);
+
+impl<F, Z, E> ObjectMethod<F, Z, E> {
+    pub(crate) fn new(f: F) -> Self {
+        Self(f, PhantomData)
+    }
+}
+
+impl<F, Z, E> Callable for ObjectMethod<F, Z, E>
+where
+    F: Fn(&mut ZObj, &mut [ZVal]) -> Result<Z, E>,
+    Z: Into<ZVal>,
+    E: Throwable,
+{
+    fn call(
+        &self, execute_data: &mut ExecuteData, arguments: &mut [ZVal], return_value: &mut ZVal,
+    ) {
+        // The object may be any class using the trait, so it isn't taken as
+        // the state object.
+        let this = execute_data.get_this_mut().unwrap();
+        match (self.0)(this, arguments) {
+            Ok(z) => unsafe {
+                write_return_value(return_value, z.into());
+            },
+            Err(e) => unsafe {
+                throw(e);
+                write_return_value(return_value, ().into());
+            },
         }
     }
 }
@@ -103,70 +311,102 @@ pub struct FunctionEntry {
 }
 
 impl FunctionEntry {
-    pub(crate) unsafe fn from_function_entity(entity: &FunctionEntity) -> zend_function_entry {
+    pub(crate) unsafe fn from_function_entity(entity: &mut FunctionEntity) -> zend_function_entry {
         Self::entry(
             &entity.name,
             &entity.arguments,
-            Some(entity.handler.clone()),
+            &mut entity.arg_infos,
+            true,
             None,
+            entity.return_type.as_ref(),
+            entity.return_reference,
         )
     }
 
-    pub(crate) unsafe fn from_method_entity(entity: &MethodEntity) -> zend_function_entry {
+    pub(crate) unsafe fn from_method_entity(entity: &mut MethodEntity) -> zend_function_entry {
         Self::entry(
             &entity.name,
             &entity.arguments,
-            entity.handler.clone(),
+            &mut entity.arg_infos,
+            entity.handler.is_some(),
             Some(entity.visibility),
+            entity.return_type.as_ref(),
+            entity.return_reference,
         )
     }
 
-    /// Will leak memory
+    /// The arg infos are built into the buffer owned by the entity, so the
+    /// entity must outlive the registered function.
     unsafe fn entry(
-        name: &CStr, arguments: &[Argument], handler: Option<Rc<dyn Callable>>,
-        visibility: Option<RawVisibility>,
+        name: &CStr, arguments: &[Argument], arg_infos: &mut ArgInfos, has_handler: bool,
+        visibility: Option<RawVisibility>, return_type: Option<&TypeHint>, return_reference: bool,
     ) -> zend_function_entry {
-        let mut infos = Vec::new();
+        arg_infos.infos.clear();
+        arg_infos.type_names.clear();
 
         let require_arg_count = arguments.iter().filter(|arg| arg.required).count();
-        infos.push(phper_zend_begin_arg_info_ex(false, require_arg_count));
-
-        for arg in arguments {
-            infos.push(phper_zend_arg_info(
-                arg.pass_by_ref,
-                arg.name.as_ptr().cast(),
-            ));
+        let mut info = phper_zend_begin_arg_info_ex(return_reference, require_arg_count);
+        if let Some(return_type) = return_type {
+            arg_infos.set_type(&mut info, return_type);
         }
+        arg_infos.infos.push(info);
 
-        infos.push(zeroed::<zend_internal_arg_info>());
-
-        let raw_handler = handler.as_ref().map(|_| invoke as _);
-
-        if let Some(handler) = handler {
-            let translator = CallableTranslator {
-                callable: Rc::into_raw(handler),
-            };
-            let last_arg_info: zend_internal_arg_info = translator.internal_arg_info;
-            infos.push(last_arg_info);
+        for arg in arguments {
+            let mut info = phper_zend_arg_info(arg.pass_by_ref, arg.name.as_ptr().cast());
+            if let Some(type_hint) = &arg.type_hint {
+                arg_infos.set_type(&mut info, type_hint);
+            }
+            arg_infos.infos.push(info);
         }
 
         let flags = visibility.unwrap_or(Visibility::default() as u32);
+        let handler: unsafe extern "C" fn(*mut zend_execute_data, *mut zval) =
+            if arguments.is_empty() {
+                invoke_without_arguments
+            } else {
+                invoke
+            };
 
         zend_function_entry {
             fname: name.as_ptr().cast(),
-            handler: raw_handler,
-            arg_info: Box::into_raw(infos.into_boxed_slice()).cast(),
+            handler: has_handler.then_some(handler),
+            arg_info: arg_infos.infos.as_ptr(),
             num_args: arguments.len() as u32,
             flags,
         }
     }
 }
 
+/// The arg infos of function, and the class names referenced by the types,
+/// which must live as long as the arg infos.
+#[derive(Default)]
+struct ArgInfos {
+    infos: Vec<zend_internal_arg_info>,
+    type_names: Vec<CString>,
+}
+
+impl ArgInfos {
+    /// The type can't be represented by the PHP version is ignored.
+    unsafe fn set_type(&mut self, info: &mut zend_internal_arg_info, type_hint: &TypeHint) {
+        let mut bits = 0;
+        let mut class_names = Vec::new();
+        type_hint.flatten(&mut bits, &mut class_names);
+        // The `?` prefix is the nullable class encoding of PHP 7.2 to 7.4, see
+        // `phper_zend_arg_info_set_type`.
+        let type_name = ensure_end_with_zero(format!("?{}", class_names.join("|")));
+        phper_zend_arg_info_set_type(info, bits, type_name.as_ptr(), class_names.len());
+        self.type_names.push(type_name);
+    }
+}
+
 /// Builder for registering php function.
 pub struct FunctionEntity {
     name: CString,
     handler: Rc<dyn Callable>,
     arguments: Vec<Argument>,
+    arg_infos: ArgInfos,
+    return_type: Option<TypeHint>,
+    return_reference: bool,
 }
 
 impl FunctionEntity {
@@ -176,9 +416,22 @@ impl FunctionEntity {
             name: ensure_end_with_zero(name),
             handler,
             arguments: Default::default(),
+            arg_infos: Default::default(),
+            return_type: None,
+            return_reference: false,
         }
     }
 
+    #[inline]
+    pub(crate) fn name(&self) -> &CStr {
+        &self.name
+    }
+
+    #[inline]
+    pub(crate) fn handler(&self) -> &Rc<dyn Callable> {
+        &self.handler
+    }
+
     /// Add single function argument info.
     #[inline]
     pub fn argument(&mut self, argument: Argument) -> &mut Self {
@@ -192,6 +445,39 @@ impl FunctionEntity {
         self.arguments.extend(arguments);
         self
     }
+
+    /// Declare the return type of function, shown by reflection, like
+    /// `function foo(): ?int`.
+    ///
+    /// The returned value isn't checked by the engine except the debug build of
+    /// PHP, which asserts that the value matches the declared type.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{modules::Module, types::TypeHint, values::ZVal};
+    ///
+    /// let mut module = Module::new("foo", "0.1.0", "");
+    /// module
+    ///     .add_function("answer", |_: &mut [ZVal]| Ok::<_, phper::Error>(42))
+    ///     .set_return_type(TypeHint::Long);
+    /// ```
+    #[inline]
+    pub fn set_return_type(&mut self, return_type: TypeHint) -> &mut Self {
+        self.return_type = Some(return_type);
+        self
+    }
+
+    /// Declare the function returns by reference, like `function &foo()`.
+    ///
+    /// The returned value is converted to reference if it isn't, use
+    /// [`ZVal::make_ref`] and [`ZVal::shallow_clone`] to return the reference
+    /// of the existing value.
+    #[inline]
+    pub fn return_by_ref(&mut self) -> &mut Self {
+        self.return_reference = true;
+        self
+    }
 }
 
 /// Builder for registering class method.
@@ -199,7 +485,10 @@ pub struct MethodEntity {
     name: CString,
     handler: Option<Rc<dyn Callable>>,
     arguments: Vec<Argument>,
+    arg_infos: ArgInfos,
     visibility: RawVisibility,
+    return_type: Option<TypeHint>,
+    return_reference: bool,
 }
 
 impl MethodEntity {
@@ -212,9 +501,22 @@ impl MethodEntity {
             handler,
             visibility: visibility as RawVisibility,
             arguments: Default::default(),
+            arg_infos: Default::default(),
+            return_type: None,
+            return_reference: false,
         }
     }
 
+    #[inline]
+    pub(crate) fn name(&self) -> &CStr {
+        &self.name
+    }
+
+    #[inline]
+    pub(crate) fn handler(&self) -> Option<&Rc<dyn Callable>> {
+        self.handler.as_ref()
+    }
+
     #[inline]
     pub(crate) fn set_vis_static(&mut self) -> &mut Self {
         self.visibility |= ZEND_ACC_STATIC;
@@ -227,6 +529,15 @@ impl MethodEntity {
         self
     }
 
+    /// Declare the return type of method.
+    ///
+    /// See [`FunctionEntity::set_return_type`].
+    #[inline]
+    pub fn set_return_type(&mut self, return_type: TypeHint) -> &mut Self {
+        self.return_type = Some(return_type);
+        self
+    }
+
     /// Add single method argument info.
     #[inline]
     pub fn argument(&mut self, argument: Argument) -> &mut Self {
@@ -240,6 +551,15 @@ impl MethodEntity {
         self.arguments.extend(arguments);
         self
     }
+
+    /// Declare the method returns by reference, like `function &foo()`.
+    ///
+    /// See [`FunctionEntity::return_by_ref`].
+    #[inline]
+    pub fn return_by_ref(&mut self) -> &mut Self {
+        self.return_reference = true;
+        self
+    }
 }
 
 /// Function or method argument info.
@@ -247,6 +567,7 @@ pub struct Argument {
     name: CString,
     pass_by_ref: bool,
     required: bool,
+    type_hint: Option<TypeHint>,
 }
 
 impl Argument {
@@ -257,6 +578,7 @@ impl Argument {
             name,
             pass_by_ref: false,
             required: true,
+            type_hint: None,
         }
     }
 
@@ -267,6 +589,7 @@ impl Argument {
             name,
             pass_by_ref: true,
             required: true,
+            type_hint: None,
         }
     }
 
@@ -277,6 +600,7 @@ impl Argument {
             name,
             pass_by_ref: false,
             required: false,
+            type_hint: None,
         }
     }
 
@@ -287,8 +611,68 @@ impl Argument {
             name,
             pass_by_ref: true,
             required: false,
+            type_hint: None,
         }
     }
+
+    /// Declare the type of argument, shown by reflection, like `int $a`.
+    ///
+    /// PHP 7 checks the passed value before calling the handler, and throws
+    /// `TypeError` if mismatched. Since PHP 8.0, the arguments of internal
+    /// functions aren't checked by the engine, the handler should still check
+    /// the value by the `expect_*` methods, and the debug build of PHP asserts
+    /// that the mismatched value is rejected.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{functions::Argument, types::TypeHint};
+    ///
+    /// let argument = Argument::by_val("name").with_type(TypeHint::String.nullable());
+    /// ```
+    pub fn with_type(mut self, type_hint: TypeHint) -> Self {
+        self.type_hint = Some(type_hint);
+        self
+    }
+}
+
+/// Get the functions defined in `EG(function_table)`, including the internal
+/// functions and the user functions declared so far.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::functions::{defined_functions, FunctionType};
+///
+/// let user_functions = defined_functions()
+///     .filter(|f| f.function_type() == FunctionType::User)
+///     .count();
+/// ```
+pub fn defined_functions<'a>() -> impl Iterator<Item = &'a ZFunc> {
+    unsafe {
+        ZArr::from_ptr(eg!(function_table))
+            .iter()
+            .filter(|(key, _)| !is_runtime_definition_key(key))
+            .map(|(_, val)| ZFunc::from_ptr(phper_z_ptr_p(val.as_ptr()).cast()))
+    }
+}
+
+/// The runtime definition keys, which start with `\0`, are the placeholders of
+/// the conditionally declared functions and classes.
+pub(crate) fn is_runtime_definition_key(key: &IterKey<'_>) -> bool {
+    match key {
+        IterKey::ZStr(key) => key.to_bytes().first() == Some(&0),
+        IterKey::Index(_) => false,
+    }
+}
+
+/// The type of [`ZFunc`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunctionType {
+    /// Function defined by extension.
+    Internal,
+    /// Function defined by PHP script.
+    User,
 }
 
 /// Wrapper of [`zend_function`].
@@ -312,6 +696,20 @@ impl ZFunc {
         ptr.as_mut().expect("ptr shouldn't be null")
     }
 
+    /// Wraps a raw pointer.
+    ///
+    /// # Safety
+    ///
+    /// Create from raw pointer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if pointer is null.
+    pub unsafe fn from_ptr<'a>(ptr: *const zend_function) -> &'a ZFunc {
+        let ptr = ptr as *const Self;
+        ptr.as_ref().expect("ptr shouldn't be null")
+    }
+
     /// Returns a raw pointer wrapped.
     pub const fn as_ptr(&self) -> *const zend_function {
         &self.inner
@@ -339,6 +737,59 @@ impl ZFunc {
         }
     }
 
+    /// Detect if the function returns by reference, like `function &foo()`.
+    pub fn returns_reference(&self) -> bool {
+        unsafe { self.inner.common.fn_flags & ZEND_ACC_RETURN_REFERENCE != 0 }
+    }
+
+    /// Detect if the function is declared in the `declare(strict_types=1)`
+    /// file.
+    pub fn uses_strict_types(&self) -> bool {
+        unsafe { self.inner.common.fn_flags & ZEND_ACC_STRICT_TYPES != 0 }
+    }
+
+    /// Get the type of function.
+    pub fn function_type(&self) -> FunctionType {
+        if unsafe { self.inner.type_ } as u32 == ZEND_INTERNAL_FUNCTION {
+            FunctionType::Internal
+        } else {
+            FunctionType::User
+        }
+    }
+
+    /// Get the compiled op array, `None` for internal function.
+    pub fn op_array(&self) -> Option<&ZOpArray> {
+        match self.function_type() {
+            FunctionType::Internal => None,
+            FunctionType::User => unsafe { Some(ZOpArray::from_ptr(&self.inner.op_array)) },
+        }
+    }
+
+    /// Get the file name where the function is declared, `None` for internal
+    /// function.
+    pub fn filename(&self) -> Option<&ZStr> {
+        self.op_array()?.filename()
+    }
+
+    /// Get the module registering the internal function, null for user
+    /// function.
+    pub(crate) fn module_ptr(&self) -> *const zend_module_entry {
+        match self.function_type() {
+            FunctionType::Internal => unsafe { self.inner.internal_function.module },
+            FunctionType::User => ptr::null(),
+        }
+    }
+
+    /// Get the `reserved` slot of internal function.
+    pub(crate) unsafe fn get_reserved(&self, handle: usize) -> *mut c_void {
+        self.inner.internal_function.reserved[handle]
+    }
+
+    /// Set the `reserved` slot of internal function.
+    pub(crate) unsafe fn set_reserved(&mut self, handle: usize, ptr: *mut c_void) {
+        self.inner.internal_function.reserved[handle] = ptr;
+    }
+
     /// Get the function related class if exists.
     pub fn get_class(&self) -> Option<&ClassEntry> {
         unsafe {
@@ -432,30 +883,91 @@ impl ZFunc {
     }
 }
 
-/// Just for type transmutation.
-pub(crate) union CallableTranslator {
-    pub(crate) callable: *const dyn Callable,
-    pub(crate) internal_arg_info: zend_internal_arg_info,
-    pub(crate) arg_info: zend_arg_info,
+/// Wrapper of [`zend_op_array`], the compiled user function or script.
+#[repr(transparent)]
+pub struct ZOpArray {
+    inner: zend_op_array,
+}
+
+impl ZOpArray {
+    /// Wraps a raw pointer.
+    ///
+    /// # Safety
+    ///
+    /// Create from raw pointer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if pointer is null.
+    pub unsafe fn from_ptr<'a>(ptr: *const zend_op_array) -> &'a ZOpArray {
+        let ptr = ptr as *const Self;
+        ptr.as_ref().expect("ptr shouldn't be null")
+    }
+
+    /// Wraps a raw pointer.
+    ///
+    /// # Safety
+    ///
+    /// Create from raw pointer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if pointer is null.
+    pub unsafe fn from_mut_ptr<'a>(ptr: *mut zend_op_array) -> &'a mut ZOpArray {
+        let ptr = ptr as *mut Self;
+        ptr.as_mut().expect("ptr shouldn't be null")
+    }
+
+    /// Returns a raw pointer wrapped.
+    pub const fn as_ptr(&self) -> *const zend_op_array {
+        &self.inner
+    }
+
+    /// Returns a raw pointer wrapped.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut zend_op_array {
+        &mut self.inner
+    }
+
+    /// Get the function name, `None` for the top level code of script.
+    pub fn function_name(&self) -> Option<&ZStr> {
+        unsafe { ZStr::try_from_ptr(self.inner.function_name) }
+    }
+
+    /// Get the file name where the function or script is defined.
+    pub fn filename(&self) -> Option<&ZStr> {
+        unsafe { ZStr::try_from_ptr(self.inner.filename) }
+    }
+
+    /// Get the line where the function starts.
+    #[inline]
+    pub fn line_start(&self) -> u32 {
+        self.inner.line_start
+    }
+
+    /// Get the line where the function ends.
+    #[inline]
+    pub fn line_end(&self) -> u32 {
+        self.inner.line_end
+    }
+
+    /// Get the compiled opcodes.
+    pub fn opcodes(&self) -> &[zend_op] {
+        if self.inner.opcodes.is_null() {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(self.inner.opcodes, self.inner.last as usize) }
+    }
 }
 
-/// The entry for all registered PHP functions.
+/// The entry for the registered PHP functions declared with arguments.
 unsafe extern "C" fn invoke(execute_data: *mut zend_execute_data, return_value: *mut zval) {
     let execute_data = ExecuteData::from_mut_ptr(execute_data);
     let return_value = ZVal::from_mut_ptr(return_value);
 
-    let num_args = execute_data.common_num_args();
-    let arg_info = execute_data.common_arg_info();
-
-    let last_arg_info = arg_info.offset((num_args + 1) as isize);
-    let translator = CallableTranslator {
-        arg_info: *last_arg_info,
-    };
-    let handler = translator.callable;
-    let handler = handler.as_ref().expect("handler is null");
-
-    // Check arguments count.
-    let num_args = execute_data.num_args();
+    // Check arguments count, the slots are got with the count at once.
+    let arguments = execute_data.get_parameters_slice();
+    let num_args = arguments.len();
     let required_num_args = execute_data.common_required_num_args();
     if num_args < required_num_args {
         let func_name = execute_data.func().get_function_or_method_name();
@@ -466,14 +978,61 @@ unsafe extern "C" fn invoke(execute_data: *mut zend_execute_data, return_value:
             Err(e) => e.into(),
         };
         throw(err);
-        *return_value = ().into();
+        write_return_value(return_value, ().into());
         return;
     }
 
-    let mut arguments = execute_data.get_parameters_array();
-    let arguments = arguments.as_mut_slice();
+    call_handler(execute_data, arguments, return_value);
+}
+
+/// The fast entry for the registered PHP functions declared without arguments,
+/// like `myext_version()`, nothing is required so the arguments count check is
+/// skipped. The arguments slice is empty without touching the call frame,
+/// unless the undeclared arguments are passed.
+unsafe extern "C" fn invoke_without_arguments(
+    execute_data: *mut zend_execute_data, return_value: *mut zval,
+) {
+    let execute_data = ExecuteData::from_mut_ptr(execute_data);
+    let return_value = ZVal::from_mut_ptr(return_value);
 
-    handler.call(execute_data, transmute(arguments), return_value);
+    let arguments: &mut [ZVal] = if execute_data.num_args() == 0 {
+        &mut []
+    } else {
+        execute_data.get_parameters_slice()
+    };
+    call_handler(execute_data, arguments, return_value);
+}
+
+#[inline]
+unsafe fn call_handler(
+    execute_data: &mut ExecuteData, arguments: &mut [ZVal], return_value: &mut ZVal,
+) {
+    let module = global_module();
+    let index = module
+        .get_handler_index(execute_data.func())
+        .expect("handler is not registered");
+    let handler = module
+        .get_handler(index)
+        .expect("handler is not registered");
+
+    match module.stats() {
+        Some(stats) => stats.record(index, || {
+            handler.call(execute_data, arguments, return_value)
+        }),
+        None => handler.call(execute_data, arguments, return_value),
+    }
+
+    // The Rust allocations counted by `EAllocator` can't bail out when they
+    // exceed the `memory_limit`.
+    if let Some(limit) = EAllocator::take_exceeded() {
+        throw(MemoryLimitError::new(limit));
+    }
+
+    // The engine requires the return value of the function returning by
+    // reference to be a reference.
+    if execute_data.func().returns_reference() {
+        return_value.make_ref();
+    }
 }
 
 /// Call user function by name.
@@ -497,6 +1056,114 @@ pub fn call(callable: impl Into<ZVal>, arguments: impl AsMut<[ZVal]>) -> crate::
     call_internal(&mut func, None, arguments)
 }
 
+/// The callable resolved once into [`zend_fcall_info_cache`], and reused in
+/// the repeated invocation, avoiding resolving the callable in every call.
+///
+/// Useful for the map/filter like apis, which call the same PHP callback many
+/// times.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{functions::CachedCallable, values::ZVal};
+///
+/// fn map(callback: ZVal, items: &[i64]) -> phper::Result<Vec<ZVal>> {
+///     let mut callback = CachedCallable::new(callback)?;
+///     items
+///         .iter()
+///         .map(|item| callback.call([ZVal::from(*item)]))
+///         .collect()
+/// }
+/// ```
+pub struct CachedCallable {
+    callable: ZVal,
+    fci: zend_fcall_info,
+    fcc: zend_fcall_info_cache,
+    trampoline: bool,
+}
+
+impl CachedCallable {
+    /// Resolve the callable (string or array or closure or invokable object).
+    ///
+    /// # Errors
+    ///
+    /// Return [`NotCallableError`] when the value is not callable.
+    pub fn new(callable: impl Into<ZVal>) -> crate::Result<Self> {
+        let mut callable = callable.into();
+        let (fci, mut fcc) = Self::resolve(&mut callable)?;
+
+        // The trampoline function (like `__call`) is released after called, so
+        // can't be cached.
+        let trampoline = unsafe { phper_zend_fcall_info_cache_is_trampoline(&fcc) };
+        if trampoline {
+            unsafe {
+                phper_zend_release_fcall_info_cache_trampoline(&mut fcc);
+            }
+        }
+
+        Ok(Self {
+            callable,
+            fci,
+            fcc,
+            trampoline,
+        })
+    }
+
+    fn resolve(callable: &mut ZVal) -> crate::Result<(zend_fcall_info, zend_fcall_info_cache)> {
+        unsafe {
+            let mut fci = zeroed::<zend_fcall_info>();
+            let mut fcc = zeroed::<zend_fcall_info_cache>();
+            let mut error: *mut c_char = null_mut();
+
+            let ok =
+                phper_zend_fcall_info_init(callable.as_mut_ptr(), &mut fci, &mut fcc, &mut error);
+
+            let message = (!error.is_null()).then(|| {
+                let message = CStr::from_ptr(error).to_string_lossy().into_owned();
+                phper_efree(error.cast());
+                message
+            });
+
+            if ok {
+                Ok((fci, fcc))
+            } else {
+                let message = message.unwrap_or_else(|| "value is not a valid callback".to_owned());
+                Err(NotCallableError::new(message).into())
+            }
+        }
+    }
+
+    /// Gets the callable value.
+    #[inline]
+    pub fn callable(&self) -> &ZVal {
+        &self.callable
+    }
+
+    /// Call the cached callable with arguments.
+    pub fn call(&mut self, mut arguments: impl AsMut<[ZVal]>) -> crate::Result<ZVal> {
+        let arguments = arguments.as_mut();
+
+        if self.trampoline {
+            let (fci, fcc) = Self::resolve(&mut self.callable)?;
+            self.fci = fci;
+            self.fcc = fcc;
+        }
+
+        let fci = &mut self.fci;
+        let fcc = &mut self.fcc;
+
+        call_raw_common(|ret| unsafe {
+            phper_zend_call_function(
+                fci,
+                fcc,
+                ret.as_mut_ptr(),
+                arguments.len() as u32,
+                arguments.as_mut_ptr().cast(),
+            );
+        })
+    }
+}
+
 pub(crate) fn call_internal(
     func: &mut ZVal, mut object: Option<&mut ZObj>, mut arguments: impl AsMut<[ZVal]>,
 ) -> crate::Result<ZVal> {
@@ -0,0 +1,120 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to the streaming hash contexts of `ext/hash`, the same
+//! algorithms as the userland `hash_init()`, `hash_update()` and
+//! `hash_final()`.
+
+use crate::{errors::HashAlgoError, sys::*};
+use std::{ffi::c_void, io};
+
+/// The streaming hash context, owns the state of the algorithm.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::hashes::HashContext;
+///
+/// fn example() -> phper::Result<()> {
+///     let mut context = HashContext::new("sha256")?;
+///     context.update("foo");
+///     context.update("bar");
+///     let digest = context.finalize();
+///     assert_eq!(digest.len(), 32);
+///     Ok(())
+/// }
+/// ```
+pub struct HashContext {
+    ops: *const php_hash_ops,
+    context: *mut c_void,
+}
+
+impl HashContext {
+    /// Create the context of the algorithm, the name is case-insensitive.
+    ///
+    /// # Errors
+    ///
+    /// Return [`HashAlgoError`] if the algorithm isn't supported.
+    pub fn new(algo: &str) -> crate::Result<Self> {
+        unsafe {
+            let ops = phper_php_hash_fetch_ops(algo.as_ptr().cast(), algo.len());
+            if ops.is_null() {
+                return Err(HashAlgoError::new(algo.to_owned()).into());
+            }
+            Ok(Self {
+                ops,
+                context: phper_php_hash_new_context(ops),
+            })
+        }
+    }
+
+    /// Get the size of the digest in bytes.
+    pub fn digest_size(&self) -> usize {
+        unsafe { phper_php_hash_digest_size(self.ops) }
+    }
+
+    /// Feed the data into the context.
+    pub fn update(&mut self, data: impl AsRef<[u8]>) {
+        let data = data.as_ref();
+        unsafe {
+            phper_php_hash_update(self.ops, self.context, data.as_ptr(), data.len());
+        }
+    }
+
+    /// Consume the context and get the raw digest.
+    pub fn finalize(self) -> Vec<u8> {
+        let mut digest = vec![0; self.digest_size()];
+        unsafe {
+            phper_php_hash_final(self.ops, self.context, digest.as_mut_ptr());
+        }
+        digest
+    }
+}
+
+impl Clone for HashContext {
+    fn clone(&self) -> Self {
+        unsafe {
+            Self {
+                ops: self.ops,
+                context: phper_php_hash_copy_context(self.ops, self.context),
+            }
+        }
+    }
+}
+
+impl io::Write for HashContext {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for HashContext {
+    fn drop(&mut self) {
+        unsafe {
+            phper_php_hash_free_context(self.context);
+        }
+    }
+}
+
+/// Calculate the raw digest of the data, like `hash()` with `binary` set.
+///
+/// # Errors
+///
+/// Return [`HashAlgoError`] if the algorithm isn't supported.
+pub fn hash(algo: &str, data: impl AsRef<[u8]>) -> crate::Result<Vec<u8>> {
+    let mut context = HashContext::new(algo)?;
+    context.update(data);
+    Ok(context.finalize())
+}
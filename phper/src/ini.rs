@@ -10,12 +10,17 @@
 
 //! Apis relate to [zend_ini_entry_def].
 
-use crate::{c_str, sys::*};
+use crate::{
+    c_str,
+    errors::{IniError, IniSetError},
+    strings::{ZStr, ZString},
+    sys::*,
+};
 use std::{
-    ffi::{c_int, CStr},
+    ffi::{c_int, c_void, CStr},
     mem::zeroed,
     os::raw::c_char,
-    ptr::null_mut,
+    ptr::{null, null_mut},
     str,
 };
 
@@ -34,9 +39,200 @@ pub fn ini_get<T: FromIniValue>(name: &str) -> T {
     T::from_ini_value(name)
 }
 
-/// Configuration changeable policy.
+/// Get the current value of the configuration, registered by any extension or
+/// core, like `ini_get()` in PHP.
+///
+/// The value is effective in the current request, which may be overridden by
+/// the per directory configuration (like `.user.ini` or `.htaccess`) or
+/// `ini_set()`.
+///
+/// Return `None` if the configuration doesn't exist.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::ini;
+///
+/// let _memory_limit = ini::get_str("memory_limit");
+/// ```
+pub fn get_str(name: &str) -> Option<String> {
+    find_entry(name).map(|entry| zend_string_to_string(entry.value))
+}
+
+/// Get the original value of the configuration, before overridden by the per
+/// directory configuration or `ini_set()` in the current request, like the
+/// `global_value` of `ini_get_all()` in PHP.
+///
+/// Return `None` if the configuration doesn't exist.
+pub fn get_original_str(name: &str) -> Option<String> {
+    find_entry(name).map(|entry| {
+        if entry.modified != 0 {
+            zend_string_to_string(entry.orig_value)
+        } else {
+            zend_string_to_string(entry.value)
+        }
+    })
+}
+
+/// Returns `true` if the configuration is overridden in the current request,
+/// by the per directory configuration or `ini_set()`.
+pub fn is_modified(name: &str) -> bool {
+    find_entry(name).map_or(false, |entry| entry.modified != 0)
+}
+
+fn find_entry<'a>(name: &str) -> Option<&'a zend_ini_entry> {
+    unsafe {
+        let entry =
+            phper_zend_hash_str_find_ptr(eg!(ini_directives), name.as_ptr().cast(), name.len())
+                as *const zend_ini_entry;
+        entry.as_ref()
+    }
+}
+
+fn zend_string_to_string(s: *const zend_string) -> String {
+    match unsafe { ZStr::try_from_ptr(s) } {
+        Some(s) => String::from_utf8_lossy(s.to_bytes()).into_owned(),
+        None => String::new(),
+    }
+}
+
+/// Change the value of the configuration in the stage, like `ini_set()` in
+/// PHP when the stage is [`Stage::Runtime`].
+///
+/// # Errors
+///
+/// Return [`IniSetError`] if the configuration doesn't exist, isn't
+/// modifiable in the stage, or the value is rejected.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::ini::{self, Stage};
+///
+/// fn example() -> phper::Result<()> {
+///     ini::set("precision", "10", Stage::Runtime)?;
+///     Ok(())
+/// }
+/// ```
+#[allow(clippy::useless_conversion)]
+pub fn set(name: &str, value: &str, stage: Stage) -> crate::Result<()> {
+    let mut name_z = ZString::new(name);
+    let result = unsafe {
+        zend_alter_ini_entry_chars(
+            name_z.as_mut_ptr(),
+            value.as_ptr().cast(),
+            value.len(),
+            stage.modify_type() as c_int,
+            stage as c_int,
+        )
+    };
+    if result == ZEND_RESULT_CODE_SUCCESS {
+        Ok(())
+    } else {
+        Err(IniSetError::new(name.to_owned()).into())
+    }
+}
+
+/// Restore the configuration to the original value, like `ini_restore()` in
+/// PHP when the stage is [`Stage::Runtime`].
+pub fn restore(name: &str, stage: Stage) {
+    let mut name_z = ZString::new(name);
+    unsafe {
+        zend_restore_ini_entry(name_z.as_mut_ptr(), stage as c_int);
+    }
+}
+
+/// Reload the configurations process wide, from the source other than
+/// `php.ini`, like a configuration center or an admin function call, so the
+/// long-lived workers needn't restart for config tweaks.
+///
+/// The `on_modify` callbacks of the entries are called, to parse the new values
+/// into the module globals, and the values become the original values of the
+/// following requests. If the configuration is overridden by `ini_set()` in
+/// the current request, the new value takes effect after the request.
+///
+/// The reloading is atomic, if any value is rejected, the values changed before
+/// are rolled back.
+///
+/// Should be called in the request, a signal handler can set a flag and reload
+/// in the next `RINIT`.
+///
+/// # Errors
+///
+/// Return [`IniSetError`] if the configuration doesn't exist, or the value is
+/// rejected.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::ini;
+///
+/// fn example() -> phper::Result<()> {
+///     ini::reload([("foo.level", "3"), ("foo.endpoint", "127.0.0.1:8080")])?;
+///     Ok(())
+/// }
+/// ```
+pub fn reload<'a>(values: impl IntoIterator<Item = (&'a str, &'a str)>) -> crate::Result<()> {
+    let mut reloaded = Vec::new();
+
+    for (name, value) in values {
+        let result = get_original_str(name).filter(|_| reload_entry(name, value));
+        match result {
+            Some(original) => reloaded.push((name, original)),
+            None => {
+                for (name, original) in reloaded.into_iter().rev() {
+                    reload_entry(name, &original);
+                }
+                return Err(IniSetError::new(name.to_owned()).into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn reload_entry(name: &str, value: &str) -> bool {
+    unsafe {
+        let entry =
+            phper_zend_hash_str_find_ptr(eg!(ini_directives), name.as_ptr().cast(), name.len())
+                as *mut zend_ini_entry;
+        !entry.is_null() && phper_zend_ini_entry_reload(entry, value.as_ptr().cast(), value.len())
+    }
+}
+
+/// The stage of changing configuration.
 #[repr(u32)]
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Stage {
+    /// Module startup, the `MINIT` stage.
+    Startup = ZEND_INI_STAGE_STARTUP,
+    /// Module shutdown, the `MSHUTDOWN` stage.
+    Shutdown = ZEND_INI_STAGE_SHUTDOWN,
+    /// Request startup, the `RINIT` stage.
+    Activate = ZEND_INI_STAGE_ACTIVATE,
+    /// Request shutdown, the `RSHUTDOWN` stage.
+    Deactivate = ZEND_INI_STAGE_DEACTIVATE,
+    /// Running the user scripts, like `ini_set()`.
+    Runtime = ZEND_INI_STAGE_RUNTIME,
+    /// Applying the per directory configuration, like `.htaccess`.
+    Htaccess = ZEND_INI_STAGE_HTACCESS,
+}
+
+impl Stage {
+    /// The modification type (permission level) of changing in the stage,
+    /// same as what PHP uses.
+    fn modify_type(self) -> u32 {
+        match self {
+            Stage::Runtime => PHP_INI_USER,
+            Stage::Htaccess => PHP_INI_PERDIR,
+            _ => PHP_INI_SYSTEM,
+        }
+    }
+}
+
+/// Configuration changeable policy, which stages the entry can be changed in.
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Policy {
     /// Entry can be set anywhere.
     All = PHP_INI_ALL,
@@ -47,6 +243,32 @@ pub enum Policy {
     Perdir = PHP_INI_PERDIR,
     /// Entry can be set in `php.ini` or `httpd.conf`.
     System = PHP_INI_SYSTEM,
+    /// Entry can be set in user scripts or per directory, but not in
+    /// `php.ini`.
+    UserPerdir = PHP_INI_USER | PHP_INI_PERDIR,
+    /// Entry can be set in user scripts or `php.ini`, but not per directory.
+    UserSystem = PHP_INI_USER | PHP_INI_SYSTEM,
+    /// Entry can be set in `php.ini` or per directory, but not in user scripts,
+    /// the common policy of the entries affect the whole request.
+    PerdirSystem = PHP_INI_PERDIR | PHP_INI_SYSTEM,
+}
+
+impl Policy {
+    /// Returns `true` if the entry with this policy can be changed in the
+    /// stage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use phper::ini::{Policy, Stage};
+    ///
+    /// assert!(Policy::PerdirSystem.is_changeable_in(Stage::Htaccess));
+    /// assert!(!Policy::PerdirSystem.is_changeable_in(Stage::Runtime));
+    /// ```
+    #[inline]
+    pub fn is_changeable_in(self, stage: Stage) -> bool {
+        self as u32 & stage.modify_type() != 0
+    }
 }
 
 /// The Type which can transform to an ini value.
@@ -141,10 +363,76 @@ impl FromIniValue for Option<&CStr> {
     }
 }
 
-pub(crate) struct IniEntity {
+impl FromIniValue for String {
+    fn from_ini_value(name: &str) -> Self {
+        <Option<&CStr>>::from_ini_value(name)
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+/// The configuration struct, the fields are mapped to the ini entries,
+/// registered by
+/// [`Module::add_ini_config`](crate::modules::Module::add_ini_config).
+///
+/// Usually implemented by `#[derive(IniConfig)]`, the entry names are
+/// `{prefix}.{field_name}`, and the default values are the fields of
+/// [`Default::default`], the types of fields should implement both
+/// [`IntoIniValue`] and [`FromIniValue`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{
+///     ini::{IniConfig, Policy},
+///     modules::Module,
+/// };
+///
+/// #[derive(phper::IniConfig)]
+/// #[ini(prefix = "foo")]
+/// struct FooConfig {
+///     enable: bool,
+///     #[ini(rename = "max_size")]
+///     size: i64,
+///     endpoint: String,
+/// }
+///
+/// impl Default for FooConfig {
+///     fn default() -> Self {
+///         Self {
+///             enable: true,
+///             size: 1024,
+///             endpoint: "localhost:9000".to_owned(),
+///         }
+///     }
+/// }
+///
+/// let mut module = Module::new("foo", "0.1.0", "");
+/// // Registers `foo.enable`, `foo.max_size` and `foo.endpoint`.
+/// module.add_ini_config::<FooConfig>(Policy::All);
+/// module.on_request_init(|| {
+///     let _config = FooConfig::load();
+/// });
+/// ```
+pub trait IniConfig: Sized {
+    /// The names and the default values of ini entries.
+    fn ini_entries() -> Vec<(&'static str, String)>;
+
+    /// Load the current values of ini entries.
+    fn load() -> Self;
+}
+
+pub(crate) type OnModify = dyn Fn(&str) -> Result<(), IniError>;
+
+pub(crate) type Displayer = dyn Fn(&str) -> String;
+
+/// Builder for registering ini entry.
+pub struct IniEntity {
     name: String,
     default_value: String,
     policy: Policy,
+    on_modify: Option<Box<OnModify>>,
+    displayer: Option<Box<Displayer>>,
 }
 
 impl IniEntity {
@@ -155,12 +443,132 @@ impl IniEntity {
             name: name.into(),
             default_value: default_value.into_ini_value(),
             policy,
+            on_modify: None,
+            displayer: None,
         }
     }
 
+    /// Set the callback called when the value of entry is changed, to
+    /// validate and parse the new value, the change is rejected if the
+    /// callback returns error.
+    ///
+    /// The callback is called in all stages, including the startup with the
+    /// value in `php.ini` (or the default value), and the deactivation of
+    /// request with the original value, to restore the value changed by
+    /// `ini_set()`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{errors::IniError, ini::Policy, modules::Module};
+    /// use std::cell::Cell;
+    ///
+    /// thread_local! {
+    ///     static LEVEL: Cell<i64> = Cell::new(0);
+    /// }
+    ///
+    /// let mut module = Module::new("foo", "0.1.0", "");
+    /// module
+    ///     .add_ini("foo.level", 1i64, Policy::All)
+    ///     .on_modify(|value| {
+    ///         let level = value
+    ///             .parse::<i64>()
+    ///             .map_err(|e| IniError::new(e.to_string()))?;
+    ///         LEVEL.with(|l| l.set(level));
+    ///         Ok(())
+    ///     });
+    /// ```
+    pub fn on_modify(&mut self, f: impl Fn(&str) -> Result<(), IniError> + 'static) -> &mut Self {
+        self.on_modify = Some(Box::new(f));
+        self
+    }
+
+    /// Set the callback to format the value displayed in `phpinfo()`, for
+    /// example, masking the secrets, or showing the human readable sizes.
+    ///
+    /// The formatted value will be escaped in html mode.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{ini::Policy, modules::Module};
+    ///
+    /// let mut module = Module::new("foo", "0.1.0", "");
+    /// module
+    ///     .add_ini("foo.password", "".to_owned(), Policy::System)
+    ///     .displayer(|_| "****".to_owned());
+    /// ```
+    pub fn displayer(&mut self, f: impl Fn(&str) -> String + 'static) -> &mut Self {
+        self.displayer = Some(Box::new(f));
+        self
+    }
+
     #[inline]
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The entity address is passed as the `mh_arg1`, so the entity shouldn't
+    /// be moved after registered.
     pub(crate) fn entry(&self) -> zend_ini_entry_def {
-        create_ini_entry_ex(&self.name, &self.default_value, self.policy as u32)
+        let mut entry = create_ini_entry_ex(&self.name, &self.default_value, self.policy as u32);
+        if self.on_modify.is_some() {
+            entry.on_modify = Some(on_modify);
+        }
+        if self.displayer.is_some() {
+            entry.displayer = Some(displayer);
+        }
+        if self.on_modify.is_some() || self.displayer.is_some() {
+            entry.mh_arg1 = self as *const Self as *mut c_void;
+        }
+        entry
+    }
+}
+
+unsafe extern "C" fn on_modify(
+    _entry: *mut zend_ini_entry, new_value: *mut zend_string, mh_arg1: *mut c_void,
+    _mh_arg2: *mut c_void, _mh_arg3: *mut c_void, _stage: c_int,
+) -> c_int {
+    let entity = &*(mh_arg1 as *const IniEntity);
+    let Some(f) = &entity.on_modify else {
+        return ZEND_RESULT_CODE_SUCCESS;
+    };
+
+    let result = match ZStr::try_from_ptr(new_value).map(ZStr::to_str) {
+        Some(Ok(value)) => f(value),
+        Some(Err(e)) => Err(IniError::new(e.to_string())),
+        None => f(""),
+    };
+
+    match result {
+        Ok(()) => ZEND_RESULT_CODE_SUCCESS,
+        Err(e) => {
+            crate::warning!("Invalid value for ini entry {}: {}", entity.name, e);
+            ZEND_RESULT_CODE_FAILURE
+        }
+    }
+}
+
+unsafe extern "C" fn displayer(entry: *mut zend_ini_entry, type_: c_int) {
+    let entity = &*((*entry).mh_arg1 as *const IniEntity);
+
+    let value = if type_ == ZEND_INI_DISPLAY_ORIG as c_int && (*entry).modified != 0 {
+        (*entry).orig_value
+    } else {
+        (*entry).value
+    };
+
+    match (ZStr::try_from_ptr(value), &entity.displayer) {
+        (Some(value), Some(f)) => {
+            let display = f(&String::from_utf8_lossy(value.to_bytes()));
+            phper_ini_display(display.as_ptr().cast(), display.len());
+        }
+        (Some(value), None) => {
+            phper_ini_display(value.as_c_str_ptr(), value.len());
+        }
+        (None, _) => {
+            phper_ini_display(null(), 0);
+        }
     }
 }
 
@@ -0,0 +1,163 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to streaming values to PHP lazily by the Rust [Iterator], so
+//! the large result sets (database rows, log lines) don't need to be
+//! materialized into one array.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use phper::{
+//!     classes::StaticStateClass,
+//!     iterators::{make_iterator_class, new_iterator_object, IteratorState},
+//!     modules::Module,
+//! };
+//!
+//! static LINES_CLASS: StaticStateClass<IteratorState> = StaticStateClass::null();
+//!
+//! fn register(module: &mut Module) {
+//!     let mut class = make_iterator_class("MyLines");
+//!     class.bind(&LINES_CLASS);
+//!     module.add_class(class);
+//!
+//!     module.add_function("my_lines", |_| {
+//!         new_iterator_object(&LINES_CLASS, (1..=3).map(|i| format!("line {}", i)))
+//!     });
+//! }
+//! ```
+
+use crate::{
+    classes::{iterator_class, ClassEntity, StaticStateClass, Visibility},
+    objects::ZObject,
+    values::ZVal,
+};
+use std::{convert::Infallible, fmt, fmt::Debug};
+
+/// The state of the object produces values lazily from the Rust iterator, the
+/// keys are `0, 1, 2...`, like the PHP `Generator` yielding values without
+/// keys.
+#[derive(Default)]
+pub struct IteratorState {
+    iter: Option<Box<dyn Iterator<Item = ZVal>>>,
+    current: Option<ZVal>,
+    key: i64,
+    started: bool,
+}
+
+impl IteratorState {
+    /// Construct from the Rust iterator, which isn't advanced until the
+    /// object is traversed.
+    pub fn new<I>(iter: I) -> Self
+    where
+        I: IntoIterator + 'static,
+        I::Item: Into<ZVal>,
+    {
+        Self {
+            iter: Some(Box::new(iter.into_iter().map(Into::into))),
+            ..Default::default()
+        }
+    }
+
+    fn ensure_started(&mut self) {
+        if !self.started {
+            self.started = true;
+            self.fetch();
+        }
+    }
+
+    fn fetch(&mut self) {
+        self.current = self.iter.as_mut().and_then(|iter| iter.next());
+        if self.current.is_none() {
+            // Release the exhausted iterator as soon as possible.
+            self.iter = None;
+        }
+    }
+
+    fn next(&mut self) {
+        self.ensure_started();
+        if self.current.is_some() {
+            self.fetch();
+            self.key += 1;
+        }
+    }
+}
+
+impl Debug for IteratorState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IteratorState")
+            .field("current", &self.current)
+            .field("key", &self.key)
+            .field("started", &self.started)
+            .finish()
+    }
+}
+
+/// Make the final class entity implements `Iterator`, whose objects produce
+/// values from [`IteratorState`].
+///
+/// Like the PHP `Generator`, the object can be traversed only once, rewinding
+/// it after the first value is consumed throws `Exception`.
+pub fn make_iterator_class(class_name: impl Into<String>) -> ClassEntity<IteratorState> {
+    let mut class = ClassEntity::<IteratorState>::new_with_default_state_constructor(class_name);
+    class.mark_final();
+    class.implements(iterator_class);
+
+    class.add_method("current", Visibility::Public, |this, _| {
+        let state = this.as_mut_state();
+        state.ensure_started();
+        Ok::<_, Infallible>(state.current.clone())
+    });
+
+    class.add_method("key", Visibility::Public, |this, _| {
+        let state = this.as_mut_state();
+        state.ensure_started();
+        Ok::<_, Infallible>(state.current.as_ref().map(|_| state.key))
+    });
+
+    class.add_method("next", Visibility::Public, |this, _| {
+        this.as_mut_state().next();
+        Ok::<_, Infallible>(())
+    });
+
+    class.add_method("valid", Visibility::Public, |this, _| {
+        let state = this.as_mut_state();
+        state.ensure_started();
+        Ok::<_, Infallible>(state.current.is_some())
+    });
+
+    class.add_method("rewind", Visibility::Public, |this, _| {
+        let state = this.as_mut_state();
+        if state.key > 0 {
+            return Err(crate::Error::custom(
+                "Exception",
+                "Cannot rewind a generator that was already run",
+            ));
+        }
+        state.ensure_started();
+        Ok(())
+    });
+
+    class
+}
+
+/// Create the object of the class made by [`make_iterator_class`], producing
+/// values from the Rust iterator lazily.
+pub fn new_iterator_object<I>(
+    class: &'static StaticStateClass<IteratorState>, iter: I,
+) -> crate::Result<ZObject>
+where
+    I: IntoIterator + 'static,
+    I::Item: Into<ZVal>,
+{
+    let mut object = class.init_object()?;
+    *object.as_mut_state() = IteratorState::new(iter);
+    Ok(object.into_z_object())
+}
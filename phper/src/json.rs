@@ -0,0 +1,136 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to encoding and decoding JSON by `ext/json`, consistent with
+//! the userland `json_encode()` and `json_decode()`.
+
+use crate::{errors::JsonError, functions::call, strings::ZString, values::ZVal};
+
+/// `JSON_HEX_TAG`.
+pub const HEX_TAG: i64 = 1 << 0;
+/// `JSON_HEX_AMP`.
+pub const HEX_AMP: i64 = 1 << 1;
+/// `JSON_HEX_APOS`.
+pub const HEX_APOS: i64 = 1 << 2;
+/// `JSON_HEX_QUOT`.
+pub const HEX_QUOT: i64 = 1 << 3;
+/// `JSON_FORCE_OBJECT`.
+pub const FORCE_OBJECT: i64 = 1 << 4;
+/// `JSON_NUMERIC_CHECK`.
+pub const NUMERIC_CHECK: i64 = 1 << 5;
+/// `JSON_UNESCAPED_SLASHES`.
+pub const UNESCAPED_SLASHES: i64 = 1 << 6;
+/// `JSON_PRETTY_PRINT`.
+pub const PRETTY_PRINT: i64 = 1 << 7;
+/// `JSON_UNESCAPED_UNICODE`.
+pub const UNESCAPED_UNICODE: i64 = 1 << 8;
+/// `JSON_PARTIAL_OUTPUT_ON_ERROR`.
+pub const PARTIAL_OUTPUT_ON_ERROR: i64 = 1 << 9;
+/// `JSON_PRESERVE_ZERO_FRACTION`.
+pub const PRESERVE_ZERO_FRACTION: i64 = 1 << 10;
+/// `JSON_UNESCAPED_LINE_TERMINATORS`, since PHP 7.1.
+pub const UNESCAPED_LINE_TERMINATORS: i64 = 1 << 11;
+/// `JSON_OBJECT_AS_ARRAY`.
+pub const OBJECT_AS_ARRAY: i64 = 1 << 0;
+/// `JSON_BIGINT_AS_STRING`.
+pub const BIGINT_AS_STRING: i64 = 1 << 1;
+/// `JSON_INVALID_UTF8_IGNORE`, since PHP 7.2.
+pub const INVALID_UTF8_IGNORE: i64 = 1 << 20;
+/// `JSON_INVALID_UTF8_SUBSTITUTE`, since PHP 7.2.
+pub const INVALID_UTF8_SUBSTITUTE: i64 = 1 << 21;
+/// `JSON_THROW_ON_ERROR`, since PHP 7.3, the `JsonException` thrown is
+/// returned as [`Error::Throw`](crate::Error::Throw).
+pub const THROW_ON_ERROR: i64 = 1 << 22;
+
+/// The default nesting depth, same as `json_encode()` and `json_decode()`.
+pub const DEFAULT_DEPTH: i64 = 512;
+
+/// Detect if `ext/json` is loaded, which is always loaded since PHP 8.0.
+pub fn is_loaded() -> bool {
+    crate::modules::is_loaded("json")
+}
+
+/// Encode the value to JSON, like `json_encode()`.
+///
+/// # Errors
+///
+/// Return [`JsonError`] if failed, or the `JsonException` if the
+/// [`THROW_ON_ERROR`] flag is set.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{arrays::ZArray, json, values::ZVal};
+///
+/// fn example() -> phper::Result<()> {
+///     let mut arr = ZArray::new();
+///     arr.insert("foo", ZVal::from("bar"));
+///     let s = json::encode(
+///         &ZVal::from(arr),
+///         json::UNESCAPED_SLASHES,
+///         json::DEFAULT_DEPTH,
+///     )?;
+///     assert_eq!(s.to_str()?, r#"{"foo":"bar"}"#);
+///     Ok(())
+/// }
+/// ```
+pub fn encode(value: &ZVal, flags: i64, depth: i64) -> crate::Result<ZString> {
+    let ret = call(
+        "json_encode",
+        [value.clone(), ZVal::from(flags), ZVal::from(depth)],
+    )?;
+    match ret.as_z_str() {
+        Some(s) => Ok(s.to_owned()),
+        None => Err(last_error().into()),
+    }
+}
+
+/// Decode the JSON to value, like `json_decode()`, the objects are decoded as
+/// arrays if the [`OBJECT_AS_ARRAY`] flag is set.
+///
+/// # Errors
+///
+/// Return [`JsonError`] if failed, or the `JsonException` if the
+/// [`THROW_ON_ERROR`] flag is set.
+pub fn decode(json: impl AsRef<[u8]>, flags: i64, depth: i64) -> crate::Result<ZVal> {
+    let ret = call(
+        "json_decode",
+        [
+            ZVal::from(json.as_ref()),
+            ZVal::from(()),
+            ZVal::from(depth),
+            ZVal::from(flags),
+        ],
+    )?;
+    if flags & THROW_ON_ERROR == 0 {
+        let error = last_error();
+        if error.code() != 0 {
+            return Err(error.into());
+        }
+    }
+    Ok(ret)
+}
+
+fn last_error() -> JsonError {
+    let code = call("json_last_error", [])
+        .ok()
+        .and_then(|code| code.as_long())
+        .unwrap_or_default();
+    let message = call("json_last_error_msg", [])
+        .ok()
+        .and_then(|message| {
+            message
+                .as_z_str()
+                .map(|s| s.to_str().map(ToOwned::to_owned))
+        })
+        .and_then(Result::ok)
+        .unwrap_or_default();
+    JsonError::new(code, message)
+}
@@ -16,20 +16,68 @@
 mod macros;
 
 pub mod arrays;
+pub(crate) mod autoload;
 pub mod classes;
-pub(crate) mod constants;
+pub mod collections;
+pub mod compile_hooks;
+pub mod constants;
+pub mod contexts;
+pub(crate) mod crashes;
+pub mod curl;
+#[cfg(phper_php_version_at_least = "8.1")]
+pub mod enums;
+pub mod env;
 pub mod errors;
+pub mod filters;
+pub mod flushers;
 pub mod functions;
+pub mod hashes;
 pub mod ini;
+pub mod iterators;
+pub mod json;
+#[cfg(feature = "log")]
+pub mod loggers;
+pub mod metrics;
 pub mod modules;
 pub mod objects;
+pub mod opcodes;
 pub mod output;
+pub mod paths;
+#[cfg(phper_pdo)]
+pub mod pdo;
+pub mod pools;
+pub mod post_readers;
+pub mod processes;
+pub mod random;
 pub mod references;
+pub mod requests;
 pub mod resources;
+pub mod responses;
+pub mod serialize;
+#[cfg(phper_session)]
+pub mod sessions;
+pub(crate) mod sources;
+pub mod stats;
+pub mod streams;
 pub mod strings;
+pub mod symbols;
+pub mod timers;
+pub mod transports;
 pub mod types;
 mod utils;
 pub mod values;
+#[cfg(not(all(
+    phper_major_version = "7",
+    any(
+        phper_minor_version = "0",
+        phper_minor_version = "1",
+        phper_minor_version = "2",
+        phper_minor_version = "3",
+    )
+)))]
+pub mod weak;
+pub mod workers;
+pub mod zend_extensions;
 
 pub use crate::errors::{ok, Error, Result};
 pub use phper_alloc as alloc;
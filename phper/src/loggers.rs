@@ -0,0 +1,119 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to writing the records of [`log`] crate to the PHP error log,
+//! need the `log` feature.
+//!
+//! The records are written where the `error_log` ini points to, a file,
+//! `syslog`, or the log of SAPI (like the php-fpm error log) if not set, only
+//! when the `log_errors` ini is enabled.
+//!
+//! The records of `tracing` can be bridged by its `log` feature, when no
+//! subscriber is set.
+
+use crate::sys::*;
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use once_cell::sync::OnceCell;
+use std::{
+    ffi::CString,
+    io::{self, Write},
+    os::raw::c_int,
+    thread::{self, ThreadId},
+};
+
+static LOGGER: PhpLogger = PhpLogger;
+
+static PHP_THREAD: OnceCell<ThreadId> = OnceCell::new();
+
+/// Set the PHP error log as the backend of [`log`], with the max level, should
+/// be called in `MINIT` or [`php_get_module`](crate::php_get_module).
+///
+/// # Examples
+///
+/// ```no_run
+/// use log::LevelFilter;
+/// use phper::{loggers, modules::Module, php_get_module};
+///
+/// #[php_get_module]
+/// pub fn get_module() -> Module {
+///     loggers::init(LevelFilter::Warn).unwrap();
+///
+///     Module::new(
+///         env!("CARGO_PKG_NAME"),
+///         env!("CARGO_PKG_VERSION"),
+///         env!("CARGO_PKG_AUTHORS"),
+///     )
+/// }
+/// ```
+pub fn init(level: LevelFilter) -> Result<(), SetLoggerError> {
+    let _ = PHP_THREAD.set(thread::current().id());
+    log::set_logger(&LOGGER)?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+/// The [`Log`] implementation writing to the PHP error log.
+///
+/// The PHP apis aren't thread safe, the records logged by the threads other
+/// than the PHP thread (like the background workers) are written to stderr.
+pub struct PhpLogger;
+
+impl Log for PhpLogger {
+    #[inline]
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let message = format!(
+            "PHP {} [{}]:  {}",
+            level_name(record.level()),
+            record.target(),
+            record.args()
+        );
+
+        if PHP_THREAD.get() != Some(&thread::current().id()) {
+            let _ = writeln!(io::stderr(), "{}", message);
+            return;
+        }
+
+        let message = CString::new(message.replace('\0', "\\0")).unwrap();
+        unsafe {
+            phper_log_err(message.as_ptr(), syslog_level(record.level()));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Same as the error types of PHP.
+fn level_name(level: Level) -> &'static str {
+    match level {
+        Level::Error => "Error",
+        Level::Warn => "Warning",
+        Level::Info => "Notice",
+        Level::Debug | Level::Trace => "Debug",
+    }
+}
+
+/// The severities of syslog, `LOG_ERR`, `LOG_WARNING`, `LOG_NOTICE` and
+/// `LOG_DEBUG`, same as the mapping of PHP errors.
+fn syslog_level(level: Level) -> c_int {
+    match level {
+        Level::Error => 3,
+        Level::Warn => 4,
+        Level::Info => 5,
+        Level::Debug | Level::Trace => 7,
+    }
+}
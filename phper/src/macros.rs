@@ -78,6 +78,110 @@ macro_rules! deprecated {
     })
 }
 
+/// Equivalent to the php `ZEND_ASSERT`, checked when PHP is built with
+/// `--enable-debug`, or the feature `debug` is enabled.
+macro_rules! zend_assert {
+    ($($arg:tt)*) => {
+        if cfg!(any(phper_debug, feature = "debug")) {
+            assert!($($arg)*);
+        }
+    };
+}
+
+/// Defines the typed bit flags, which implement [`Flags`](crate::types::Flags).
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{flags, types::Flags};
+///
+/// flags! {
+///     /// Open options.
+///     pub struct OpenFlags {
+///         const READ = 1;
+///         const WRITE = 2;
+///         const CREATE = 4;
+///     }
+/// }
+///
+/// let flags = OpenFlags::READ | OpenFlags::CREATE;
+/// assert!(flags.contains(OpenFlags::CREATE));
+/// assert_eq!(OpenFlags::from_bits(8), None);
+/// ```
+#[macro_export]
+macro_rules! flags {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$inner:meta])*
+                const $flag:ident = $value:expr;
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+        $vis struct $name(i64);
+
+        #[allow(dead_code)]
+        impl $name {
+            $(
+                $(#[$inner])*
+                pub const $flag: Self = Self($value);
+            )*
+
+            /// Creates the flags without any bit.
+            pub const fn empty() -> Self {
+                Self(0)
+            }
+
+            /// Returns `true` if no bit is set.
+            pub const fn is_empty(self) -> bool {
+                self.0 == 0
+            }
+
+            /// Returns `true` if all the bits of `other` are set.
+            pub const fn contains(self, other: Self) -> bool {
+                self.0 & other.0 == other.0
+            }
+        }
+
+        impl $crate::types::Flags for $name {
+            const NAMES: &'static [(&'static str, i64)] = &[$((stringify!($flag), $value)),*];
+
+            fn from_bits_retain(bits: i64) -> Self {
+                Self(bits)
+            }
+
+            fn bits(self) -> i64 {
+                self.0
+            }
+        }
+
+        impl ::std::ops::BitOr for $name {
+            type Output = Self;
+
+            fn bitor(self, rhs: Self) -> Self {
+                Self(self.0 | rhs.0)
+            }
+        }
+
+        impl ::std::ops::BitOrAssign for $name {
+            fn bitor_assign(&mut self, rhs: Self) {
+                self.0 |= rhs.0;
+            }
+        }
+
+        impl ::std::ops::BitAnd for $name {
+            type Output = Self;
+
+            fn bitand(self, rhs: Self) -> Self {
+                Self(self.0 & rhs.0)
+            }
+        }
+    };
+}
+
 /// Equivalent to the php `CG`.
 #[macro_export]
 macro_rules! cg {
@@ -0,0 +1,254 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to the metrics of extension, for the self-telemetry.
+//!
+//! The counters, gauges and histograms are registered by
+//! [`Module::add_counter`](crate::modules::Module::add_counter),
+//! [`Module::add_gauge`](crate::modules::Module::add_gauge) and
+//! [`Module::add_histogram`](crate::modules::Module::add_histogram), the
+//! returned handles are cheap to clone, and moved into the handlers.
+//!
+//! The values are atomics shared in the process, so the updating is lock
+//! free, and safe for both the NTS and ZTS builds. Each worker process of
+//! php-fpm has its own values.
+//!
+//! The metrics are shown in the `phpinfo()` section of module, and returned
+//! by the PHP function `{module_name}_phper_metrics`, which is registered by
+//! [`Module::enable_metrics_function`](crate::modules::Module::enable_metrics_function).
+
+use crate::{arrays::ZArray, values::ZVal};
+use std::sync::{
+    atomic::{AtomicI64, AtomicU64, Ordering},
+    Arc,
+};
+
+/// The monotonic counter, like the count of requests.
+#[derive(Debug, Clone, Default)]
+pub struct Counter(Arc<AtomicU64>);
+
+impl Counter {
+    /// Increases the counter by one.
+    #[inline]
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    /// Increases the counter by `n`.
+    #[inline]
+    pub fn add(&self, n: u64) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Gets the current value.
+    #[inline]
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The value that goes up and down, like the count of open connections.
+#[derive(Debug, Clone, Default)]
+pub struct Gauge(Arc<AtomicI64>);
+
+impl Gauge {
+    /// Sets the value.
+    #[inline]
+    pub fn set(&self, value: i64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    /// Increases the value by one.
+    #[inline]
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    /// Decreases the value by one.
+    #[inline]
+    pub fn dec(&self) {
+        self.add(-1);
+    }
+
+    /// Adds `n` to the value, `n` can be negative.
+    #[inline]
+    pub fn add(&self, n: i64) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Gets the current value.
+    #[inline]
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug)]
+struct HistogramInner {
+    bounds: Vec<f64>,
+    /// The counts of each bucket, not cumulative, the last one is `+Inf`.
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    /// The bits of `f64`.
+    sum: AtomicU64,
+}
+
+/// The distribution of observed values, like the latencies, counted in the
+/// buckets by the upper bounds.
+#[derive(Debug, Clone)]
+pub struct Histogram(Arc<HistogramInner>);
+
+impl Histogram {
+    fn new(bounds: impl IntoIterator<Item = f64>) -> Self {
+        let mut bounds = bounds
+            .into_iter()
+            .filter(|bound| bound.is_finite())
+            .collect::<Vec<_>>();
+        bounds.sort_by(f64::total_cmp);
+        bounds.dedup();
+        let buckets = (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect();
+        Self(Arc::new(HistogramInner {
+            bounds,
+            buckets,
+            count: AtomicU64::new(0),
+            sum: AtomicU64::new(0f64.to_bits()),
+        }))
+    }
+
+    /// Records the value.
+    pub fn observe(&self, value: f64) {
+        let inner = &*self.0;
+        let index = inner
+            .bounds
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(inner.bounds.len());
+        inner.buckets[index].fetch_add(1, Ordering::Relaxed);
+        inner.count.fetch_add(1, Ordering::Relaxed);
+        let _ = inner
+            .sum
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |sum| {
+                Some((f64::from_bits(sum) + value).to_bits())
+            });
+    }
+
+    /// Gets the count of observed values.
+    #[inline]
+    pub fn count(&self) -> u64 {
+        self.0.count.load(Ordering::Relaxed)
+    }
+
+    /// Gets the sum of observed values.
+    #[inline]
+    pub fn sum(&self) -> f64 {
+        f64::from_bits(self.0.sum.load(Ordering::Relaxed))
+    }
+
+    /// Gets the cumulative counts of buckets, as `(upper bound, count)`, the
+    /// last upper bound is `f64::INFINITY`.
+    pub fn buckets(&self) -> Vec<(f64, u64)> {
+        let inner = &*self.0;
+        let mut cumulative = 0;
+        inner
+            .bounds
+            .iter()
+            .copied()
+            .chain([f64::INFINITY])
+            .zip(&inner.buckets)
+            .map(|(bound, count)| {
+                cumulative += count.load(Ordering::Relaxed);
+                (bound, cumulative)
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+enum Metric {
+    Counter(Counter),
+    Gauge(Gauge),
+    Histogram(Histogram),
+}
+
+/// The registered metric of module.
+pub(crate) struct MetricEntity {
+    name: String,
+    help: String,
+    metric: Metric,
+}
+
+impl MetricEntity {
+    pub(crate) fn counter(name: String, help: String) -> (Self, Counter) {
+        let counter = Counter::default();
+        let metric = Metric::Counter(counter.clone());
+        (Self { name, help, metric }, counter)
+    }
+
+    pub(crate) fn gauge(name: String, help: String) -> (Self, Gauge) {
+        let gauge = Gauge::default();
+        let metric = Metric::Gauge(gauge.clone());
+        (Self { name, help, metric }, gauge)
+    }
+
+    pub(crate) fn histogram(
+        name: String, help: String, bounds: impl IntoIterator<Item = f64>,
+    ) -> (Self, Histogram) {
+        let histogram = Histogram::new(bounds);
+        let metric = Metric::Histogram(histogram.clone());
+        (Self { name, help, metric }, histogram)
+    }
+
+    #[inline]
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub(crate) fn help(&self) -> &str {
+        &self.help
+    }
+
+    /// The value shown in `phpinfo()`.
+    pub(crate) fn display_value(&self) -> String {
+        match &self.metric {
+            Metric::Counter(counter) => counter.get().to_string(),
+            Metric::Gauge(gauge) => gauge.get().to_string(),
+            Metric::Histogram(histogram) => {
+                format!("count={} sum={}", histogram.count(), histogram.sum())
+            }
+        }
+    }
+
+    /// The value returned by `{module_name}_phper_metrics`, the histogram is
+    /// an array like `["count" => 2, "sum" => 0.3, "buckets" => ["0.1" => 1,
+    /// "+Inf" => 2]]`.
+    pub(crate) fn to_z_val(&self) -> ZVal {
+        match &self.metric {
+            Metric::Counter(counter) => ZVal::from(counter.get() as i64),
+            Metric::Gauge(gauge) => ZVal::from(gauge.get()),
+            Metric::Histogram(histogram) => {
+                let mut buckets = ZArray::new();
+                for (bound, count) in histogram.buckets() {
+                    let key = if bound.is_finite() {
+                        bound.to_string()
+                    } else {
+                        "+Inf".to_owned()
+                    };
+                    buckets.insert(&*key, ZVal::from(count as i64));
+                }
+                let mut arr = ZArray::new();
+                arr.insert("count", ZVal::from(histogram.count() as i64));
+                arr.insert("sum", ZVal::from(histogram.sum()));
+                arr.insert("buckets", ZVal::from(buckets));
+                ZVal::from(arr)
+            }
+        }
+    }
+}
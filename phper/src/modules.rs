@@ -10,25 +10,57 @@
 
 //! Apis relate to [zend_module_entry].
 
+#[cfg(phper_php_version_at_least = "8.1")]
+use crate::enums::EnumEntity;
+#[cfg(phper_pdo)]
+use crate::pdo::{PdoDriver, PdoDriverEntity};
+#[cfg(phper_session)]
+use crate::sessions::{SessionHandler, SessionHandlerEntity};
 use crate::{
+    alloc::EAllocator,
+    arrays::{ZArr, ZArray},
+    autoload::{AutoloadEntity, AutoloadFunction},
     c_str_ptr,
-    classes::{ClassEntity, InterfaceEntity},
+    classes::{ClassEntity, ClassEntry, InterfaceEntity, TraitEntity},
+    compile_hooks::{self, CompileFileHook, CompileStringHook, ZFileHandle},
     constants::Constant,
+    contexts::{self, ModuleContext, RequestContext},
+    crashes,
     errors::Throwable,
-    functions::{Function, FunctionEntity, FunctionEntry},
+    flushers::{BackgroundFlusher, Flush},
+    functions::{
+        Callable, Function, FunctionEntity, FunctionEntry, FunctionWithReturn, ReturnValue, ZFunc,
+    },
     ini,
+    metrics::{Counter, Gauge, Histogram, MetricEntity},
+    opcodes::{Opcode, OpcodeHandlerEntity, UserOpcode},
+    pools::{Pool, PoolEntity},
+    post_readers::PostReaderEntity,
+    requests,
+    sources::{SourceDir, SourceEntity},
+    stats::{self, StatsRecorder},
+    strings::{ZStr, ZString},
     sys::*,
+    timers::{self, RequestTimings},
+    transports::{StreamTransport, StreamTransportEntity},
     types::Scalar,
-    utils::ensure_end_with_zero,
-    values::ZVal,
+    utils::{ensure_end_with_zero, is_valid_name},
+    values::{ExecuteData, ZVal},
+    workers::WorkerPool,
+    zend_extensions::ZendExtensionEntity,
 };
 use std::{
-    collections::HashMap,
-    ffi::CString,
+    cmp,
+    collections::{HashMap, HashSet},
+    ffi::{c_void, CStr, CString},
+    fmt::{self, Debug},
+    hash::Hash,
     mem::{size_of, take, transmute, zeroed},
     os::raw::{c_int, c_uchar, c_uint, c_ushort},
+    process,
     ptr::{null, null_mut},
     rc::Rc,
+    sync::atomic::{AtomicU32, Ordering},
 };
 
 /// Global pointer hold the Module builder.
@@ -37,53 +69,372 @@ static mut GLOBAL_MODULE: *mut Module = null_mut();
 
 static mut GLOBAL_MODULE_ENTRY: *mut zend_module_entry = null_mut();
 
+/// The pid of process which has run the process init hook, `0` means the hook
+/// hasn't been run in this library.
+static PROCESS_INIT_PID: AtomicU32 = AtomicU32::new(0);
+
+/// The pid of process which has run `MINIT` or the last `RINIT`, for detecting
+/// the forks.
+static LAST_PID: AtomicU32 = AtomicU32::new(0);
+
+/// The post startup callback chained before this module, called before
+/// retrying the deferred classes.
+#[cfg(phper_php_version_at_least = "7.4")]
+static mut PREVIOUS_POST_STARTUP: Option<unsafe extern "C" fn() -> c_int> = None;
+
 #[inline]
 pub(crate) unsafe fn global_module<'a>() -> &'a Module {
     GLOBAL_MODULE.as_ref().unwrap()
 }
 
-unsafe extern "C" fn module_startup(_type: c_int, module_number: c_int) -> c_int {
+#[inline]
+pub(crate) unsafe fn global_module_mut<'a>() -> &'a mut Module {
+    GLOBAL_MODULE.as_mut().unwrap()
+}
+
+unsafe extern "C" fn module_startup(module_type: c_int, module_number: c_int) -> c_int {
+    let module = GLOBAL_MODULE.as_mut().unwrap();
+    module.module_number = module_number;
+    LAST_PID.store(process::id(), Ordering::SeqCst);
+    contexts::enter_module_init();
+    let result = module_startup_inner(&ModuleContext::new(), module, module_number);
+    contexts::leave_module_init();
+
+    if !module.deferred_classes.is_empty() {
+        // The extensions started after this module may register the parents,
+        // retry after all of them are started, unless loaded by `dl()`.
+        #[cfg(phper_php_version_at_least = "7.4")]
+        if module_type == MODULE_PERSISTENT as c_int {
+            PREVIOUS_POST_STARTUP = zend_post_startup_cb;
+            zend_post_startup_cb = Some(post_startup);
+            return result;
+        }
+        let _ = module_type;
+        module.warn_deferred_classes();
+    }
+
+    result
+}
+
+#[cfg(phper_php_version_at_least = "7.4")]
+unsafe extern "C" fn post_startup() -> c_int {
+    if let Some(f) = PREVIOUS_POST_STARTUP {
+        if f() != ZEND_RESULT_CODE_SUCCESS {
+            return ZEND_RESULT_CODE_FAILURE;
+        }
+    }
+
     let module = GLOBAL_MODULE.as_mut().unwrap();
+    contexts::enter_module_init();
+    let pending = take(&mut module.deferred_classes);
+    module.deferred_classes = register_classes(&ModuleContext::new(), module, pending);
+    contexts::leave_module_init();
+    module.warn_deferred_classes();
+
+    ZEND_RESULT_CODE_SUCCESS
+}
+
+unsafe fn module_startup_inner(
+    ctx: &ModuleContext<'_>, module: &mut Module, module_number: c_int,
+) -> c_int {
+    // Fail rather than crashing or overriding the existing classes silently.
+    let mut errors = take(&mut module.startup_errors);
+    errors.extend(module.check_registered_classes());
+    if !errors.is_empty() {
+        for error in errors {
+            crate::warning!("{}: {}", module.name.to_string_lossy(), error);
+        }
+        return ZEND_RESULT_CODE_FAILURE;
+    }
 
     ini::register(&module.ini_entities, module_number);
 
+    if module.crash_handler_enabled && !crashes::install() {
+        crate::warning!(
+            "{}: install the crash handler failed",
+            module.name.to_string_lossy()
+        );
+    }
+
     for constant in &module.constants {
         constant.register(module_number);
     }
 
-    for class_entity in &module.class_entities {
-        let ce = class_entity.init();
-        class_entity.declare_properties(ce);
+    // The functions are registered before `MINIT`, and the classes are
+    // registered here, bind the handlers to them once registered.
+    let handle = phper_zend_get_resource_handle(module.name.as_ptr());
+    module.resource_handle = handle;
+
+    for f in &module.function_entities {
+        bind_handler(
+            &module.handler_indexes,
+            handle,
+            cg!(function_table),
+            f.name(),
+            &f.name().to_string_lossy(),
+        );
+    }
+
+    // The classes whose parent declared by name isn't registered yet are
+    // deferred, until the parent (maybe also registered by this module, or the
+    // extension started later) is registered.
+    let pending = (0..module.class_entities.len()).collect();
+    module.deferred_classes = register_classes(ctx, module, pending);
+
+    for interface_entity in &mut module.interface_entities {
+        interface_entity.init(ctx);
+    }
+
+    for trait_entity in &mut module.trait_entities {
+        let ce = trait_entity.init(ctx);
+
+        for (method_name, _) in trait_entity.method_handlers() {
+            bind_handler(
+                &module.handler_indexes,
+                handle,
+                &mut (*ce).function_table,
+                method_name,
+                &method_key(trait_entity.trait_name(), method_name),
+            );
+        }
+    }
+
+    #[cfg(phper_php_version_at_least = "8.1")]
+    for enum_entity in &mut module.enum_entities {
+        let ce = enum_entity.init(ctx);
+
+        for (method_name, _) in enum_entity.method_handlers() {
+            bind_handler(
+                &module.handler_indexes,
+                handle,
+                &mut (*ce).function_table,
+                method_name,
+                &method_key(enum_entity.enum_name(), method_name),
+            );
+        }
+    }
+
+    if let Some(zend_extension) = &module.zend_extension {
+        zend_extension.register(ctx, &module.name);
+    }
+
+    for opcode_handler in &mut module.opcode_handler_entities {
+        opcode_handler.register(ctx);
+    }
+
+    for transport in &module.stream_transport_entities {
+        if !transport.register(ctx) {
+            crate::warning!(
+                "{}: stream transport {} isn't registered",
+                module.name.to_string_lossy(),
+                transport.name().to_string_lossy()
+            );
+        }
+    }
+
+    for post_reader in &module.post_reader_entities {
+        if !post_reader.register(ctx) {
+            crate::warning!(
+                "{}: post reader of {} isn't registered",
+                module.name.to_string_lossy(),
+                post_reader.content_type().to_string_lossy()
+            );
+        }
     }
 
-    for interface_entity in &module.interface_entities {
-        interface_entity.init();
+    #[cfg(phper_pdo)]
+    for pdo_driver in &mut module.pdo_driver_entities {
+        if !pdo_driver.register(ctx) {
+            crate::warning!(
+                "{}: PDO driver {} isn't registered, maybe the name is taken",
+                module.name.to_string_lossy(),
+                pdo_driver.name().to_string_lossy()
+            );
+        }
+    }
+
+    #[cfg(phper_session)]
+    for session_handler in &mut module.session_handler_entities {
+        if !session_handler.register(ctx) {
+            crate::warning!(
+                "{}: session save handler {} isn't registered, maybe too many handlers",
+                module.name.to_string_lossy(),
+                session_handler.name().to_string_lossy()
+            );
+        }
+    }
+
+    compile_hooks::register(
+        ctx,
+        !module.compile_file_hooks.is_empty(),
+        !module.compile_string_hooks.is_empty(),
+    );
+
+    if !module.autoload_entities.is_empty() || !module.deferred_classes.is_empty() {
+        module.autoload_function = Some(AutoloadFunction::new());
+    }
+
+    let sources = module.source_entities.iter_mut().chain(
+        module
+            .autoload_entities
+            .iter_mut()
+            .flat_map(AutoloadEntity::sources_mut),
+    );
+    let mut sources = sources.peekable();
+    if sources.peek().is_some() {
+        match SourceDir::write_sources(&module.name, sources) {
+            Ok(dir) => module.source_dir = Some(dir),
+            Err(e) => crate::warning!(
+                "{}: write embedded sources failed, evaluate them per request: {}",
+                module.name.to_string_lossy(),
+                e
+            ),
+        }
     }
 
     if let Some(f) = take(&mut module.module_init) {
-        f();
+        f(ctx);
     }
 
     ZEND_RESULT_CODE_SUCCESS
 }
 
+/// Register the classes whose parent is resolved, repeatedly, because the
+/// parent may be one of them. Returns the classes still deferred.
+unsafe fn register_classes(
+    ctx: &ModuleContext<'_>, module: &mut Module, mut pending: Vec<usize>,
+) -> Vec<usize> {
+    loop {
+        let ready;
+        (ready, pending) = take(&mut pending)
+            .into_iter()
+            .partition(|&i| module.class_entities[i].unresolved_parent_name().is_none());
+        if ready.is_empty() {
+            return pending;
+        }
+        for i in ready {
+            let class_entity = &mut module.class_entities[i];
+            let ce = class_entity.init(ctx);
+            class_entity.declare_properties(ce);
+            class_entity.declare_constants(ce);
+
+            for (method_name, _) in class_entity.method_handlers() {
+                bind_handler(
+                    &module.handler_indexes,
+                    module.resource_handle,
+                    &mut (*ce).function_table,
+                    method_name,
+                    &method_key(class_entity.class_name(), method_name),
+                );
+            }
+        }
+    }
+}
+
 unsafe extern "C" fn module_shutdown(_type: c_int, module_number: c_int) -> c_int {
     let module = GLOBAL_MODULE.as_mut().unwrap();
+    debug_assert_eq!(module.module_number, module_number);
 
     ini::unregister(module_number);
 
+    if module.crash_handler_enabled {
+        crashes::uninstall();
+    }
+
     if let Some(f) = take(&mut module.module_shutdown) {
         f();
     }
 
+    for flusher in take(&mut module.flushers) {
+        flusher.shutdown();
+    }
+
+    for pool in take(&mut module.worker_pools) {
+        pool.shutdown();
+    }
+
+    for pool in take(&mut module.pool_entities) {
+        pool.shutdown();
+    }
+
+    // Only the process which has run the init hook tears down, the forked
+    // processes inherit the state but not the ownership.
+    if PROCESS_INIT_PID.swap(0, Ordering::SeqCst) == process::id() {
+        if let Some(f) = take(&mut module.process_shutdown) {
+            f();
+        }
+    }
+
+    for opcode_handler in module.opcode_handler_entities.iter().rev() {
+        opcode_handler.unregister();
+    }
+
+    for transport in &module.stream_transport_entities {
+        transport.unregister();
+    }
+
+    for post_reader in &module.post_reader_entities {
+        post_reader.unregister();
+    }
+
+    #[cfg(phper_pdo)]
+    for pdo_driver in &mut module.pdo_driver_entities {
+        pdo_driver.unregister();
+    }
+
+    compile_hooks::unregister();
+
+    if let Some(source_dir) = take(&mut module.source_dir) {
+        source_dir.remove();
+    }
+
+    module.autoload_function = None;
+
+    // The function entries and arg infos are kept as long as the module, don't
+    // release them here, the engine unregisters the functions of the module
+    // loaded by `dl()` by the entries after `MSHUTDOWN`, and destroys the
+    // function table after all modules are shut down.
+
     ZEND_RESULT_CODE_SUCCESS
 }
 
 unsafe extern "C" fn request_startup(_type: c_int, _module_number: c_int) -> c_int {
     let module = GLOBAL_MODULE.as_ref().unwrap();
 
+    EAllocator::enter_request();
+    #[cfg(feature = "debug")]
+    crate::alloc::debug::reset();
+    requests::enter_request();
+    timers::enter_request();
+
+    let pid = process::id();
+    let last_pid = LAST_PID.swap(pid, Ordering::SeqCst);
+    if last_pid != pid {
+        if let Some(f) = &module.fork {
+            f(last_pid);
+        }
+    }
+
+    if let Some(f) = &module.process_init {
+        if PROCESS_INIT_PID.swap(pid, Ordering::SeqCst) != pid {
+            f();
+        }
+    }
+
+    if let Some(autoload_function) = &module.autoload_function {
+        if let Err(e) = autoload_function.register() {
+            crate::warning!("register autoloader failed: {}", e);
+        }
+    }
+
+    for source in &module.source_entities {
+        if let Err(e) = source.execute() {
+            crate::warning!("evaluate embedded source failed: {}", e);
+        }
+    }
+
     if let Some(f) = &module.request_init {
-        f();
+        f(&RequestContext::new());
     }
 
     ZEND_RESULT_CODE_SUCCESS
@@ -92,13 +443,62 @@ unsafe extern "C" fn request_startup(_type: c_int, _module_number: c_int) -> c_i
 unsafe extern "C" fn request_shutdown(_type: c_int, _module_number: c_int) -> c_int {
     let module = GLOBAL_MODULE.as_ref().unwrap();
 
+    requests::leave_request();
+
+    // The request is still running until all modules are shut down, although
+    // its token has been cancelled.
     if let Some(f) = &module.request_shutdown {
+        f(&RequestContext::new());
+    }
+
+    if let Some(timings) = timers::leave_request() {
+        if let Some(f) = &module.request_timings {
+            f(&timings);
+        }
+    }
+
+    for flusher in &module.flushers {
+        flusher.flush();
+    }
+
+    for pool in &module.pool_entities {
+        pool.release_request();
+    }
+
+    #[cfg(feature = "debug")]
+    report_leaks(module);
+
+    EAllocator::leave_request();
+
+    ZEND_RESULT_CODE_SUCCESS
+}
+
+unsafe extern "C" fn post_deactivate() -> c_int {
+    let module = GLOBAL_MODULE.as_ref().unwrap();
+
+    if let Some(f) = &module.post_deactivate {
         f();
     }
 
     ZEND_RESULT_CODE_SUCCESS
 }
 
+/// Report the allocations of `phper-alloc` which aren't freed at the end of
+/// request, they will be reclaimed by the Zend memory manager, or leaked when
+/// running with `USE_ZEND_ALLOC=0`.
+#[cfg(feature = "debug")]
+fn report_leaks(module: &Module) {
+    let stats = crate::alloc::debug::live_allocations();
+    if !stats.is_empty() {
+        crate::warning!(
+            "{}: {} allocations ({} bytes) are leaked in the request",
+            module.name.to_string_lossy(),
+            stats.count,
+            stats.bytes
+        );
+    }
+}
+
 unsafe extern "C" fn module_info(zend_module: *mut zend_module_entry) {
     let module = GLOBAL_MODULE.as_ref().unwrap();
 
@@ -114,25 +514,355 @@ unsafe extern "C" fn module_info(zend_module: *mut zend_module_entry) {
     }
     php_info_print_table_end();
 
+    if let Some(stats) = &module.stats {
+        php_info_print_table_start();
+        php_info_print_table_header(
+            3,
+            c_str_ptr!("Function"),
+            c_str_ptr!("Calls"),
+            c_str_ptr!("Time (ms)"),
+        );
+        for stat in stats.snapshot() {
+            let name = ensure_end_with_zero(stat.name());
+            let calls = ensure_end_with_zero(stat.calls().to_string());
+            let time = ensure_end_with_zero(format!("{:.3}", stat.time().as_secs_f64() * 1000.));
+            php_info_print_table_row(3, name.as_ptr(), calls.as_ptr(), time.as_ptr());
+        }
+        php_info_print_table_end();
+    }
+
+    if !module.metric_entities.is_empty() {
+        php_info_print_table_start();
+        php_info_print_table_header(
+            3,
+            c_str_ptr!("Metric"),
+            c_str_ptr!("Value"),
+            c_str_ptr!("Description"),
+        );
+        for metric in &module.metric_entities {
+            let name = ensure_end_with_zero(metric.name());
+            let value = ensure_end_with_zero(metric.display_value());
+            let help = ensure_end_with_zero(metric.help());
+            php_info_print_table_row(3, name.as_ptr(), value.as_ptr(), help.as_ptr());
+        }
+        php_info_print_table_end();
+    }
+
     display_ini_entries(zend_module);
 }
 
+/// The version of PHP, compared with the `(major, minor)` or `(major, minor,
+/// release)` tuples.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::modules::php_version;
+///
+/// if php_version() >= (8, 1) {
+///     // Use the enums.
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PhpVersion {
+    major: u32,
+    minor: u32,
+    release: u32,
+}
+
+impl PhpVersion {
+    /// Construct the version.
+    pub const fn new(major: u32, minor: u32, release: u32) -> Self {
+        Self {
+            major,
+            minor,
+            release,
+        }
+    }
+
+    /// Construct from the `PHP_VERSION_ID`, like `80102`.
+    pub const fn from_id(id: u32) -> Self {
+        Self::new(id / 10000, id / 100 % 100, id % 100)
+    }
+
+    /// The version which `phper` is built with.
+    pub const fn built() -> Self {
+        Self::new(PHP_MAJOR_VERSION, PHP_MINOR_VERSION, PHP_RELEASE_VERSION)
+    }
+
+    /// Get the major version.
+    #[inline]
+    pub fn major(&self) -> u32 {
+        self.major
+    }
+
+    /// Get the minor version.
+    #[inline]
+    pub fn minor(&self) -> u32 {
+        self.minor
+    }
+
+    /// Get the release version.
+    #[inline]
+    pub fn release(&self) -> u32 {
+        self.release
+    }
+
+    /// Get the `PHP_VERSION_ID`.
+    #[inline]
+    pub fn id(&self) -> u32 {
+        self.major * 10000 + self.minor * 100 + self.release
+    }
+}
+
+impl fmt::Display for PhpVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.release)
+    }
+}
+
+impl PartialEq<(u32, u32)> for PhpVersion {
+    fn eq(&self, other: &(u32, u32)) -> bool {
+        (self.major, self.minor) == *other
+    }
+}
+
+impl PartialOrd<(u32, u32)> for PhpVersion {
+    fn partial_cmp(&self, other: &(u32, u32)) -> Option<cmp::Ordering> {
+        Some((self.major, self.minor).cmp(other))
+    }
+}
+
+impl PartialEq<(u32, u32, u32)> for PhpVersion {
+    fn eq(&self, other: &(u32, u32, u32)) -> bool {
+        (self.major, self.minor, self.release) == *other
+    }
+}
+
+impl PartialOrd<(u32, u32, u32)> for PhpVersion {
+    fn partial_cmp(&self, other: &(u32, u32, u32)) -> Option<cmp::Ordering> {
+        Some((self.major, self.minor, self.release).cmp(other))
+    }
+}
+
+/// Get the version of the running PHP, from the `PHP_VERSION_ID` constant, or
+/// the version which `phper` is built with if the constant isn't registered
+/// yet.
+pub fn php_version() -> PhpVersion {
+    crate::constants::get("PHP_VERSION_ID")
+        .and_then(ZVal::as_long)
+        .map(|id| PhpVersion::from_id(id as u32))
+        .unwrap_or_else(PhpVersion::built)
+}
+
+/// Detect if the extension is loaded, the name is the extension name, like
+/// `curl`, case-insensitive.
+pub fn is_loaded(name: impl AsRef<str>) -> bool {
+    get_loaded(name).is_some()
+}
+
+/// Get the loaded extension by name, like `curl`, case-insensitive.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{functions::call, modules::get_loaded, values::ZVal};
+///
+/// fn curl_version() -> phper::Result<Option<ZVal>> {
+///     match get_loaded("curl") {
+///         Some(curl) if curl.get_function("curl_version").is_some() => {
+///             Ok(Some(call("curl_version", [])?))
+///         }
+///         _ => Ok(None),
+///     }
+/// }
+/// ```
+pub fn get_loaded<'a>(name: impl AsRef<str>) -> Option<&'a ModuleEntry> {
+    let name = name.as_ref().to_ascii_lowercase();
+    unsafe {
+        let ptr = phper_zend_hash_str_find_ptr(&module_registry, name.as_ptr().cast(), name.len());
+        (!ptr.is_null()).then(|| ModuleEntry::from_ptr(ptr.cast()))
+    }
+}
+
+/// Get the loaded extensions, including the ones built in PHP, like `core` and
+/// `standard`.
+pub fn loaded_modules<'a>() -> impl Iterator<Item = &'a ModuleEntry> {
+    unsafe {
+        ZArr::from_ptr(&module_registry)
+            .iter()
+            .map(|(_, val)| ModuleEntry::from_ptr(phper_z_ptr_p(val.as_ptr()).cast()))
+    }
+}
+
+/// Wrapper of [`zend_module_entry`] of the loaded extension.
+#[repr(transparent)]
+pub struct ModuleEntry {
+    inner: zend_module_entry,
+}
+
+impl ModuleEntry {
+    /// Wraps a raw pointer.
+    ///
+    /// # Safety
+    ///
+    /// Create from raw pointer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if pointer is null.
+    pub unsafe fn from_ptr<'a>(ptr: *const zend_module_entry) -> &'a Self {
+        (ptr as *const Self)
+            .as_ref()
+            .expect("ptr shouldn't be null")
+    }
+
+    /// Returns a raw pointer wrapped.
+    pub const fn as_ptr(&self) -> *const zend_module_entry {
+        &self.inner
+    }
+
+    /// Get the extension name.
+    pub fn name(&self) -> &CStr {
+        unsafe { CStr::from_ptr(self.inner.name) }
+    }
+
+    /// Get the extension version, `None` if isn't declared.
+    pub fn version(&self) -> Option<&CStr> {
+        unsafe {
+            self.inner
+                .version
+                .as_ref()
+                .map(|version| CStr::from_ptr(version))
+        }
+    }
+
+    /// Get the module number.
+    #[inline]
+    pub fn module_number(&self) -> i32 {
+        self.inner.module_number as i32
+    }
+
+    /// Whether `MINIT` of the extension has been run.
+    #[inline]
+    pub fn is_started(&self) -> bool {
+        self.inner.module_started != 0
+    }
+
+    /// Get the functions registered by the extension.
+    pub fn functions(&self) -> impl Iterator<Item = &ZFunc> {
+        unsafe {
+            ZArr::from_ptr(cg!(function_table))
+                .iter()
+                .map(|(_, val)| ZFunc::from_ptr(phper_z_ptr_p(val.as_ptr()).cast()))
+                .filter(move |f| f.module_ptr() == self.as_ptr())
+        }
+    }
+
+    /// Get the function registered by the extension by name, case-insensitive,
+    /// `None` if the function isn't found or registered by the other one.
+    pub fn get_function(&self, name: impl AsRef<str>) -> Option<&ZFunc> {
+        let name = name.as_ref().to_ascii_lowercase();
+        unsafe {
+            let ptr =
+                phper_zend_hash_str_find_ptr(cg!(function_table), name.as_ptr().cast(), name.len());
+            if ptr.is_null() {
+                return None;
+            }
+            let f = ZFunc::from_ptr(ptr.cast());
+            (f.module_ptr() == self.as_ptr()).then_some(f)
+        }
+    }
+}
+
+impl Debug for ModuleEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ModuleEntry")
+            .field("name", &self.name())
+            .field("version", &self.version())
+            .finish()
+    }
+}
+
+/// The type of the dependency on the other extension.
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DependencyType {
+    /// The extension must be loaded before this module.
+    Required = MODULE_DEP_REQUIRED,
+    /// The extension can't be loaded together with this module.
+    Conflicts = MODULE_DEP_CONFLICTS,
+    /// If the extension is loaded, it's started before this module.
+    Optional = MODULE_DEP_OPTIONAL,
+}
+
 /// Builder for registering PHP Module.
+///
+/// One crate (cdylib) can only define one module, because the module states
+/// are held by the statics of `phper`, see
+/// [`php_get_module`](crate::php_get_module). The extensions built by `phper`
+/// can be loaded together, the states are separated in their own libraries,
+/// and the slots of functions are allocated by the module name.
+///
+/// The functions, classes and constants are registered in `MINIT` with the
+/// persistent and interned names, as the internal entities of PHP, so they are
+/// shared by opcache and preloading without being copied.
 #[allow(clippy::type_complexity)]
 pub struct Module {
     name: CString,
     version: CString,
     author: CString,
-    module_init: Option<Box<dyn FnOnce()>>,
+    module_init: Option<Box<dyn FnOnce(&ModuleContext<'_>)>>,
     module_shutdown: Option<Box<dyn FnOnce()>>,
-    request_init: Option<Box<dyn Fn()>>,
-    request_shutdown: Option<Box<dyn Fn()>>,
+    request_init: Option<Box<dyn Fn(&RequestContext<'_>)>>,
+    request_shutdown: Option<Box<dyn Fn(&RequestContext<'_>)>>,
+    post_deactivate: Option<Box<dyn Fn()>>,
+    request_timings: Option<Box<dyn Fn(&RequestTimings)>>,
+    process_init: Option<Box<dyn Fn()>>,
+    process_shutdown: Option<Box<dyn FnOnce()>>,
+    fork: Option<Box<dyn Fn(u32)>>,
+    flushers: Vec<Box<dyn Flush>>,
     function_entities: Vec<FunctionEntity>,
+    function_entries: Vec<zend_function_entry>,
+    handlers: Vec<Rc<dyn Callable>>,
+    handler_indexes: HashMap<String, usize>,
+    resource_handle: c_int,
+    module_number: c_int,
     class_entities: Vec<ClassEntity<()>>,
+    deferred_classes: Vec<usize>,
     interface_entities: Vec<InterfaceEntity>,
+    trait_entities: Vec<TraitEntity>,
+    #[cfg(phper_php_version_at_least = "8.1")]
+    enum_entities: Vec<EnumEntity>,
     constants: Vec<Constant>,
     ini_entities: Vec<ini::IniEntity>,
+    autoload_entities: Vec<AutoloadEntity>,
+    autoload_function: Option<AutoloadFunction>,
+    source_entities: Vec<SourceEntity>,
+    source_dir: Option<SourceDir>,
     infos: HashMap<CString, CString>,
+    debug_globals: Option<Box<dyn Fn() -> String>>,
+    zend_extension: Option<ZendExtensionEntity>,
+    opcode_handler_entities: Vec<OpcodeHandlerEntity>,
+    stream_transport_entities: Vec<StreamTransportEntity>,
+    post_reader_entities: Vec<PostReaderEntity>,
+    #[cfg(phper_pdo)]
+    pdo_driver_entities: Vec<PdoDriverEntity>,
+    #[cfg(phper_session)]
+    session_handler_entities: Vec<SessionHandlerEntity>,
+    compile_file_hooks: Vec<Box<CompileFileHook>>,
+    compile_string_hooks: Vec<Box<CompileStringHook>>,
+    dependencies: Vec<(CString, DependencyType)>,
+    dependency_entries: Vec<zend_module_dep>,
+    stats_enabled: bool,
+    metrics_function_enabled: bool,
+    stats: Option<StatsRecorder>,
+    metric_entities: Vec<MetricEntity>,
+    pool_entities: Vec<Box<dyn PoolEntity>>,
+    worker_pools: Vec<WorkerPool>,
+    crash_handler_enabled: bool,
+    symbol_tables_enabled: bool,
+    startup_errors: Vec<String>,
 }
 
 impl Module {
@@ -148,17 +878,63 @@ impl Module {
             module_shutdown: None,
             request_init: None,
             request_shutdown: None,
+            post_deactivate: None,
+            request_timings: None,
+            process_init: None,
+            process_shutdown: None,
+            fork: None,
+            flushers: Default::default(),
             function_entities: vec![],
+            function_entries: Default::default(),
+            handlers: Default::default(),
+            handler_indexes: Default::default(),
+            resource_handle: -1,
+            module_number: -1,
             class_entities: Default::default(),
+            deferred_classes: Default::default(),
             interface_entities: Default::default(),
+            trait_entities: Default::default(),
+            #[cfg(phper_php_version_at_least = "8.1")]
+            enum_entities: Default::default(),
             constants: Default::default(),
             ini_entities: Default::default(),
+            autoload_entities: Default::default(),
+            autoload_function: None,
+            source_entities: Default::default(),
+            source_dir: None,
             infos: Default::default(),
+            debug_globals: None,
+            zend_extension: None,
+            opcode_handler_entities: Default::default(),
+            stream_transport_entities: Default::default(),
+            post_reader_entities: Default::default(),
+            #[cfg(phper_pdo)]
+            pdo_driver_entities: Default::default(),
+            #[cfg(phper_session)]
+            session_handler_entities: Default::default(),
+            compile_file_hooks: Default::default(),
+            compile_string_hooks: Default::default(),
+            dependencies: Default::default(),
+            dependency_entries: Default::default(),
+            stats_enabled: false,
+            metrics_function_enabled: false,
+            stats: None,
+            metric_entities: Default::default(),
+            pool_entities: Default::default(),
+            worker_pools: Default::default(),
+            crash_handler_enabled: false,
+            symbol_tables_enabled: false,
+            startup_errors: Default::default(),
         }
     }
 
     /// Register `MINIT` hook.
     pub fn on_module_init(&mut self, func: impl FnOnce() + 'static) {
+        self.module_init = Some(Box::new(move |_: &ModuleContext<'_>| func()));
+    }
+
+    /// Register `MINIT` hook, with the marker of `MINIT`.
+    pub fn on_module_init_with_context(&mut self, func: impl FnOnce(&ModuleContext<'_>) + 'static) {
         self.module_init = Some(Box::new(func));
     }
 
@@ -169,14 +945,259 @@ impl Module {
 
     /// Register `RINIT` hook.
     pub fn on_request_init(&mut self, func: impl Fn() + 'static) {
+        self.request_init = Some(Box::new(move |_: &RequestContext<'_>| func()));
+    }
+
+    /// Register `RINIT` hook, with the marker of the request.
+    pub fn on_request_init_with_context(&mut self, func: impl Fn(&RequestContext<'_>) + 'static) {
         self.request_init = Some(Box::new(func));
     }
 
     /// Register `RSHUTDOWN` hook.
     pub fn on_request_shutdown(&mut self, func: impl Fn() + 'static) {
+        self.request_shutdown = Some(Box::new(move |_: &RequestContext<'_>| func()));
+    }
+
+    /// Register `RSHUTDOWN` hook, with the marker of the request.
+    pub fn on_request_shutdown_with_context(
+        &mut self, func: impl Fn(&RequestContext<'_>) + 'static,
+    ) {
         self.request_shutdown = Some(Box::new(func));
     }
 
+    /// Register the `ZEND_MODULE_POST_ZEND_DEACTIVATE` hook, run late in
+    /// `php_request_shutdown`, after the `RSHUTDOWN` of all modules.
+    ///
+    /// All user code, including the shutdown functions and the destructors,
+    /// has finished and the output has been sent, so it is the place to flush
+    /// the buffers of extension, like the traces or the metrics.
+    ///
+    /// The executor has been deactivated, PHP functions can't be called and
+    /// the values of the request are released, so the hook should work on its
+    /// own Rust states only.
+    pub fn on_post_deactivate(&mut self, func: impl Fn() + 'static) {
+        self.post_deactivate = Some(Box::new(func));
+    }
+
+    /// Register the hook receiving the [`RequestTimings`] of each request,
+    /// the duration from `RINIT` and the [spans](timers::span) recorded in it.
+    ///
+    /// It's run in `RSHUTDOWN` after the [`on_request_shutdown`] hook, the
+    /// spans still open are closed at that time. The [background flushers]
+    /// are woken up after it, so the timings can be queued to be flushed with
+    /// the other data of request.
+    ///
+    /// [`on_request_shutdown`]: Self::on_request_shutdown
+    /// [background flushers]: Self::add_background_flusher
+    pub fn on_request_timings(&mut self, func: impl Fn(&RequestTimings) + 'static) {
+        self.request_timings = Some(Box::new(func));
+    }
+
+    /// Register the hook run once in each process, for the expensive global
+    /// setup, like loading models or TLS roots.
+    ///
+    /// Unlike `MINIT`, which is run in the master process of php-fpm or Apache
+    /// before forking the workers, the hook is run lazily in the first `RINIT`
+    /// of the process, so the state isn't shared between the forked processes.
+    /// The pid is checked, if a process forks after the hook is run (like
+    /// `pcntl_fork()`), the hook is run again in the child process, and the
+    /// inherited state should be replaced rather than used.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{modules::Module, php_get_module};
+    /// use std::sync::Mutex;
+    ///
+    /// static MODEL: Mutex<Option<Vec<u8>>> = Mutex::new(None);
+    ///
+    /// #[php_get_module]
+    /// pub fn get_module() -> Module {
+    ///     let mut module = Module::new(
+    ///         env!("CARGO_PKG_NAME"),
+    ///         env!("CARGO_PKG_VERSION"),
+    ///         env!("CARGO_PKG_AUTHORS"),
+    ///     );
+    ///
+    ///     module.on_process_init(|| {
+    ///         *MODEL.lock().unwrap() = std::fs::read("/path/to/model").ok();
+    ///     });
+    ///     module.on_process_shutdown(|| {
+    ///         MODEL.lock().unwrap().take();
+    ///     });
+    ///
+    ///     module
+    /// }
+    /// ```
+    pub fn on_process_init(&mut self, func: impl Fn() + 'static) {
+        self.process_init = Some(Box::new(func));
+    }
+
+    /// Register the hook to tear down the state set up by
+    /// [`on_process_init`](Module::on_process_init), run in `MSHUTDOWN` of the
+    /// process which has run the init hook, before the library is unloaded.
+    ///
+    /// The hook isn't run in the processes exiting without `MSHUTDOWN`, like
+    /// the children of Apache prefork.
+    pub fn on_process_shutdown(&mut self, func: impl FnOnce() + 'static) {
+        self.process_shutdown = Some(Box::new(func));
+    }
+
+    /// Register the hook run in the first `RINIT` after the process is forked,
+    /// like the php-fpm workers forked by the master, the argument is the pid
+    /// of the parent process.
+    ///
+    /// The sockets, epoll fds and background threads created in the parent
+    /// process are shared or lost in the child process, reinitialize them in
+    /// the hook instead of using the broken ones.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{modules::Module, php_get_module};
+    ///
+    /// #[php_get_module]
+    /// pub fn get_module() -> Module {
+    ///     let mut module = Module::new(
+    ///         env!("CARGO_PKG_NAME"),
+    ///         env!("CARGO_PKG_VERSION"),
+    ///         env!("CARGO_PKG_AUTHORS"),
+    ///     );
+    ///
+    ///     module.on_fork(|_parent_pid| {
+    ///         // Reconnect the collector, restart the reporter thread here.
+    ///     });
+    ///
+    ///     module
+    /// }
+    /// ```
+    pub fn on_fork(&mut self, func: impl Fn(u32) + 'static) {
+        self.fork = Some(Box::new(func));
+    }
+
+    /// Register the [`BackgroundFlusher`], which is woken up to flush in
+    /// `RSHUTDOWN`, and drained in `MSHUTDOWN`.
+    pub fn add_background_flusher<T: Send + 'static>(&mut self, flusher: &BackgroundFlusher<T>) {
+        self.flushers.push(Box::new(flusher.clone()));
+    }
+
+    /// Register the module as a zend extension too, for the engine level hooks,
+    /// see [`ZendExtensionEntity`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{modules::Module, php_get_module};
+    ///
+    /// #[php_get_module]
+    /// pub fn get_module() -> Module {
+    ///     let mut module = Module::new(
+    ///         env!("CARGO_PKG_NAME"),
+    ///         env!("CARGO_PKG_VERSION"),
+    ///         env!("CARGO_PKG_AUTHORS"),
+    ///     );
+    ///
+    ///     module.zend_extension().on_op_array_handler(|op_array| {
+    ///         // Collect the compiled functions.
+    ///         let _ = (op_array.filename(), op_array.function_name());
+    ///     });
+    ///
+    ///     module
+    /// }
+    /// ```
+    pub fn zend_extension(&mut self) -> &mut ZendExtensionEntity {
+        let (name, version, author) = (&self.name, &self.version, &self.author);
+        self.zend_extension
+            .get_or_insert_with(|| ZendExtensionEntity::new(name, version, author))
+    }
+
+    /// Override the handler of opcode, by `zend_set_user_opcode_handler`, the
+    /// handler registered before is restored in `MSHUTDOWN`.
+    ///
+    /// Return [`UserOpcode::Dispatch`] to let the original handler execute the
+    /// opline, and the returned error will be thrown as exception.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{
+    ///     modules::Module,
+    ///     opcodes::{Opcode, UserOpcode},
+    ///     php_get_module,
+    /// };
+    ///
+    /// #[php_get_module]
+    /// pub fn get_module() -> Module {
+    ///     let mut module = Module::new(
+    ///         env!("CARGO_PKG_NAME"),
+    ///         env!("CARGO_PKG_VERSION"),
+    ///         env!("CARGO_PKG_AUTHORS"),
+    ///     );
+    ///
+    ///     module.add_opcode_handler(Opcode::IncludeOrEval, |_execute_data| {
+    ///         // Audit the included files here.
+    ///         Ok(UserOpcode::Dispatch)
+    ///     });
+    ///
+    ///     module
+    /// }
+    /// ```
+    pub fn add_opcode_handler(
+        &mut self, opcode: Opcode,
+        handler: impl Fn(&mut ExecuteData) -> crate::Result<UserOpcode> + 'static,
+    ) {
+        self.opcode_handler_entities
+            .push(OpcodeHandlerEntity::new(opcode, handler));
+    }
+
+    /// Hook `zend_compile_file`, for observing or rewriting the source of the
+    /// included files before compilation, the hooks of the extensions loaded
+    /// previously are called after.
+    ///
+    /// The source can be replaced by [`ZFileHandle::set_source`], which isn't
+    /// available before PHP 7.4. The returned error will be thrown as
+    /// exception, and the file won't be compiled.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{modules::Module, php_get_module};
+    ///
+    /// #[php_get_module]
+    /// pub fn get_module() -> Module {
+    ///     let mut module = Module::new(
+    ///         env!("CARGO_PKG_NAME"),
+    ///         env!("CARGO_PKG_VERSION"),
+    ///         env!("CARGO_PKG_AUTHORS"),
+    ///     );
+    ///
+    ///     module.on_compile_file(|file| {
+    ///         // Collect the coverage of included files here.
+    ///         let _ = (file.filename(), file.source());
+    ///         Ok(())
+    ///     });
+    ///
+    ///     module
+    /// }
+    /// ```
+    pub fn on_compile_file(
+        &mut self, hook: impl Fn(&mut ZFileHandle) -> crate::Result<()> + 'static,
+    ) {
+        self.compile_file_hooks.push(Box::new(hook));
+    }
+
+    /// Hook `zend_compile_string`, for observing or rewriting the source of
+    /// `eval` before compilation, the arguments are the source and the
+    /// description of filename, return `Some` to replace the source.
+    ///
+    /// The returned error will be thrown as exception, and the source won't be
+    /// compiled.
+    pub fn on_compile_string(
+        &mut self, hook: impl Fn(&ZStr, &CStr) -> crate::Result<Option<ZString>> + 'static,
+    ) {
+        self.compile_string_hooks.push(Box::new(hook));
+    }
+
     /// Register function to module.
     pub fn add_function<F, Z, E>(
         &mut self, name: impl Into<String>, handler: F,
@@ -191,28 +1212,399 @@ impl Module {
         self.function_entities.last_mut().unwrap()
     }
 
+    /// Register function to module, the return value is constructed in place
+    /// by the [`ReturnValue`] builder, rather than converted from the returned
+    /// Rust value.
+    pub fn add_function_with_return<F, E>(
+        &mut self, name: impl Into<String>, handler: F,
+    ) -> &mut FunctionEntity
+    where
+        F: Fn(&mut [ZVal], &mut ReturnValue<'_>) -> Result<(), E> + 'static,
+        E: Throwable + 'static,
+    {
+        self.function_entities.push(FunctionEntity::new(
+            name,
+            Rc::new(FunctionWithReturn::new(handler)),
+        ));
+        self.function_entities.last_mut().unwrap()
+    }
+
+    /// Enable counting the calls and the cumulative time of every registered
+    /// function and method in the process.
+    ///
+    /// The statistics are shown in the `phpinfo()` section of module, and
+    /// returned by the PHP function `{module_name}_phper_stats`, as an array
+    /// like `["foo" => ["calls" => 2, "time" => 0.001]]`, the time is in
+    /// seconds. Read them in Rust by [`stats::function_stats`].
+    pub fn enable_stats(&mut self) {
+        self.stats_enabled = true;
+    }
+
+    /// Install the handler of the crash signals (`SIGSEGV`, `SIGBUS`,
+    /// `SIGABRT`, `SIGILL` and `SIGFPE`) in `MINIT`, which writes the PHP
+    /// stack trace (function, file and line) and the native stack trace with
+    /// the demangled Rust symbols to the `error_log` file (or stderr) before
+    /// crashing, the path of `error_log` is captured in `MINIT`.
+    ///
+    /// The crashing process only calls the async-signal-safe functions, the
+    /// stack traces are resolved in a forked child, which is killed if not
+    /// done in 5 seconds, then only the raw addresses are written. Then the
+    /// signal is re-raised to the previous handler, or the default action like
+    /// dumping core.
+    ///
+    /// The previous handlers are restored in `MSHUTDOWN`, only for the signals
+    /// whose handler is still ours, the handlers installed by the other
+    /// extensions after it are kept.
+    ///
+    /// The stack overflow is caught on the alternate signal stack, of the
+    /// thread loading the module and the threads spawned by phper.
+    ///
+    /// Not supported on Windows.
+    pub fn enable_crash_handler(&mut self) {
+        self.crash_handler_enabled = true;
+    }
+
+    /// Enable reading the symbol tables of the calling scope by
+    /// [`calling_scope`](crate::symbols::calling_scope) and
+    /// [`local_variables`](crate::symbols::local_variables), for
+    /// example, to snapshot the local variables in the error reports.
+    ///
+    /// Reading the symbol table of a function makes the rest of the calling
+    /// function slower, like calling `get_defined_vars()`, so it's opt-in.
+    pub fn enable_symbol_tables(&mut self) {
+        self.symbol_tables_enabled = true;
+    }
+
+    /// Register the monotonic counter, see [`metrics`](crate::metrics).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{modules::Module, values::ZVal};
+    ///
+    /// let mut module = Module::new("foo", "0.1.0", "");
+    /// let calls = module.add_counter("foo_calls", "The calls of foo_run().");
+    /// module.add_function("foo_run", move |_: &mut [ZVal]| {
+    ///     calls.inc();
+    ///     phper::ok(())
+    /// });
+    /// ```
+    pub fn add_counter(&mut self, name: impl Into<String>, help: impl Into<String>) -> Counter {
+        let (entity, counter) = MetricEntity::counter(name.into(), help.into());
+        self.metric_entities.push(entity);
+        counter
+    }
+
+    /// Register the gauge, see [`metrics`](crate::metrics).
+    pub fn add_gauge(&mut self, name: impl Into<String>, help: impl Into<String>) -> Gauge {
+        let (entity, gauge) = MetricEntity::gauge(name.into(), help.into());
+        self.metric_entities.push(entity);
+        gauge
+    }
+
+    /// Register the histogram with the upper bounds of buckets, the bucket
+    /// `+Inf` is added implicitly, see [`metrics`](crate::metrics).
+    pub fn add_histogram(
+        &mut self, name: impl Into<String>, help: impl Into<String>,
+        bounds: impl IntoIterator<Item = f64>,
+    ) -> Histogram {
+        let (entity, histogram) = MetricEntity::histogram(name.into(), help.into(), bounds);
+        self.metric_entities.push(entity);
+        histogram
+    }
+
+    /// Register the function `{module_name}_phper_metrics()`, which returns the
+    /// values of the registered metrics as an array, see
+    /// [`metrics`](crate::metrics).
+    ///
+    /// Not registered by default, because the hidden function is callable by
+    /// any script, the metrics are always shown in `phpinfo()`.
+    pub fn enable_metrics_function(&mut self) {
+        self.metrics_function_enabled = true;
+    }
+
+    /// Register the pool of objects keyed by the configuration, keeping at
+    /// most `max_idle` idle objects for each key, see [`pools`](crate::pools).
+    ///
+    /// The objects checked out by [`Pool::with_request`] are returned in
+    /// `RSHUTDOWN`, and all the objects are dropped in `MSHUTDOWN`.
+    pub fn add_pool<K, T>(&mut self, max_idle: usize) -> Pool<K, T>
+    where
+        K: Eq + Hash + Clone + 'static,
+        T: 'static,
+    {
+        let pool = Pool::new(max_idle);
+        self.pool_entities.push(Box::new(pool.clone()));
+        pool
+    }
+
+    /// Register the pool of at most `threads` worker threads, for the
+    /// CPU-bound work of handlers, see [`workers`](crate::workers).
+    ///
+    /// The worker threads are spawned in the first call of the process, and
+    /// stopped in `MSHUTDOWN`.
+    pub fn add_worker_pool(&mut self, threads: usize) -> WorkerPool {
+        let pool = WorkerPool::new(threads);
+        self.worker_pools.push(pool.clone());
+        pool
+    }
+
     /// Register class to module.
     pub fn add_class<T>(&mut self, class: ClassEntity<T>) {
         self.class_entities.push(unsafe { transmute(class) });
     }
 
+    /// Register the entities in `f` only if `condition` holds, for enabling
+    /// the richer apis on the newer PHP or with the optional extensions, while
+    /// still loading on the others.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{
+    ///     modules::{is_loaded, php_version, Module},
+    ///     values::ZVal,
+    /// };
+    ///
+    /// fn register(module: &mut Module) {
+    ///     module.when(php_version() >= (8, 1), |module| {
+    ///         module.add_function("myext_fibers", |_: &mut [ZVal]| phper::ok(true));
+    ///     });
+    ///     module.when(is_loaded("curl"), |module| {
+    ///         module.add_function("myext_curl_info", |_: &mut [ZVal]| phper::ok(()));
+    ///     });
+    /// }
+    /// ```
+    pub fn when(&mut self, condition: bool, f: impl FnOnce(&mut Self)) -> &mut Self {
+        if condition {
+            f(self);
+        }
+        self
+    }
+
     /// Register interface to module.
     pub fn add_interface(&mut self, interface: InterfaceEntity) {
         self.interface_entities.push(interface);
     }
 
+    /// Register trait to module.
+    pub fn add_trait(&mut self, tr: TraitEntity) {
+        self.trait_entities.push(tr);
+    }
+
+    /// Register enum to module, since PHP 8.1.
+    #[cfg(phper_php_version_at_least = "8.1")]
+    pub fn add_enum(&mut self, entity: EnumEntity) {
+        self.enum_entities.push(entity);
+    }
+
+    /// Declare the dependency on the other extension, the dependencies are
+    /// started before this module, so their classes and functions can be used
+    /// in `MINIT`, for example, by
+    /// [`ClassEntity::extends_by_name`](crate::classes::ClassEntity::extends_by_name).
+    ///
+    /// The name is the extension name, like `pdo`, case-insensitive.
+    pub fn add_dependency(&mut self, name: impl Into<String>, dependency_type: DependencyType) {
+        self.dependencies
+            .push((ensure_end_with_zero(name), dependency_type));
+    }
+
+    /// Register the stream transport, so `stream_socket_client("{name}://...")`
+    /// is served by `transport`, see [`crate::transports`].
+    pub fn add_stream_transport(
+        &mut self, name: impl Into<String>, transport: impl StreamTransport + 'static,
+    ) {
+        self.stream_transport_entities
+            .push(StreamTransportEntity::new(name, transport));
+    }
+
+    /// Register the reader of POST body with the content type, like
+    /// `application/msgpack`, which parses the body into `$_POST`, see
+    /// [`crate::post_readers`].
+    ///
+    /// The content type is case-insensitive, without the parameters like
+    /// `charset`, the reader receives the full content type, the body, and the
+    /// `$_POST` array to fill. The error returned is reported as a warning.
+    pub fn add_post_reader(
+        &mut self, content_type: impl Into<String>,
+        reader: impl Fn(&str, &[u8], &mut ZArr) -> crate::Result<()> + 'static,
+    ) {
+        self.post_reader_entities
+            .push(PostReaderEntity::new(content_type, reader));
+    }
+
+    /// Register the PDO driver, so `new PDO("{name}:...")` is served by
+    /// `driver`, the dependency on `pdo` is declared.
+    ///
+    /// Require the `pdo` feature and PHP 8.1 or later, see [`crate::pdo`].
+    #[cfg(phper_pdo)]
+    pub fn add_pdo_driver(&mut self, name: impl Into<String>, driver: impl PdoDriver + 'static) {
+        if self.pdo_driver_entities.is_empty() {
+            self.add_dependency("pdo", DependencyType::Required);
+        }
+        self.pdo_driver_entities
+            .push(PdoDriverEntity::new(name, driver));
+    }
+
+    /// Register the session save handler, so `session.save_handler={name}` is
+    /// served by `handler`, the dependency on `session` is declared.
+    ///
+    /// Require the `session` feature and PHP 7.1 or later, see
+    /// [`crate::sessions`].
+    #[cfg(phper_session)]
+    pub fn add_session_handler(
+        &mut self, name: impl Into<String>, handler: impl SessionHandler + 'static,
+    ) {
+        if self.session_handler_entities.is_empty() {
+            self.add_dependency("session", DependencyType::Required);
+        }
+        self.session_handler_entities
+            .push(SessionHandlerEntity::new(name, handler));
+    }
+
     /// Register constant to module.
     pub fn add_constant(&mut self, name: impl Into<String>, value: impl Into<Scalar>) {
         self.constants.push(Constant::new(name, value));
     }
 
+    /// Register the group of related int constants, as both the class
+    /// constants of `class` and the global constants named `{prefix}{name}`,
+    /// which is the standard pattern for option flags.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{classes::ClassEntity, modules::Module};
+    ///
+    /// fn register(module: &mut Module) {
+    ///     let mut class = ClassEntity::new("MyExt\\Mode");
+    ///     // Register `MyExt\Mode::FAST` and `MYEXT_MODE_FAST`, etc.
+    ///     module.add_constant_group(&mut class, "MYEXT_MODE_", [("FAST", 1), ("SAFE", 2)]);
+    ///     module.add_class(class);
+    /// }
+    /// ```
+    pub fn add_constant_group<T, N>(
+        &mut self, class: &mut ClassEntity<T>, prefix: impl AsRef<str>,
+        constants: impl IntoIterator<Item = (N, i64)>,
+    ) where
+        N: Into<String>,
+    {
+        for (name, value) in constants {
+            let name = name.into();
+            self.add_constant(format!("{}{}", prefix.as_ref(), name), value);
+            class.add_constant(name, value);
+        }
+    }
+
     /// Register ini configuration to module.
     pub fn add_ini(
         &mut self, name: impl Into<String>, default_value: impl ini::IntoIniValue,
         policy: ini::Policy,
-    ) {
+    ) -> &mut ini::IniEntity {
         self.ini_entities
             .push(ini::IniEntity::new(name, default_value, policy));
+        self.ini_entities.last_mut().unwrap()
+    }
+
+    /// Register the ini entries of the configuration struct, with the same
+    /// policy, see [`ini::IniConfig`].
+    pub fn add_ini_config<T: ini::IniConfig>(&mut self, policy: ini::Policy) {
+        for (name, default_value) in T::ini_entries() {
+            self.ini_entities
+                .push(ini::IniEntity::new(name, default_value, policy));
+        }
+    }
+
+    /// Register class autoloader, which receives the class name and returns
+    /// `true` if the class is loaded.
+    ///
+    /// The autoloaders are registered to `spl_autoload_register` in `RINIT`,
+    /// and be called in the order of registration.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::modules::Module;
+    ///
+    /// let mut module = Module::new("foo", "0.1.0", "");
+    /// module.add_autoloader(|class_name| class_name == "Foo\\Bar");
+    /// ```
+    pub fn add_autoloader(&mut self, autoloader: impl Fn(&str) -> bool + 'static) {
+        self.autoload_entities.push(AutoloadEntity::new(autoloader));
+    }
+
+    /// Register class autoloader for the namespace prefix, which maps the
+    /// full class names under the prefix to embedded PHP source code.
+    ///
+    /// The sources are written with the ones of [`Module::add_source`] in
+    /// `MINIT`, and included when the class is autoloaded, so they are
+    /// compiled by `zend_compile_file` and the op arrays are cached by opcache
+    /// across the requests. If the directory can't be written, the source code
+    /// is evaluated instead.
+    ///
+    /// # Panics
+    ///
+    /// Panic if the class name isn't under the prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::modules::Module;
+    ///
+    /// let mut module = Module::new("foo", "0.1.0", "");
+    /// module.add_autoload_prefix("Foo\\", [("Foo\\Bar", "<?php namespace Foo; class Bar {}")]);
+    /// ```
+    pub fn add_autoload_prefix<N, C>(
+        &mut self, prefix: impl Into<String>, sources: impl IntoIterator<Item = (N, C)>,
+    ) where
+        N: Into<String>,
+        C: AsRef<[u8]> + 'static,
+    {
+        let prefix = prefix.into();
+        let sources = sources
+            .into_iter()
+            .map(|(class_name, code)| {
+                let class_name = class_name.into();
+                assert!(
+                    class_name.starts_with(&prefix),
+                    "class {} isn't under the prefix {}",
+                    class_name,
+                    prefix
+                );
+                (
+                    class_name.to_ascii_lowercase(),
+                    SourceEntity::new(class_name, code),
+                )
+            })
+            .collect();
+        self.autoload_entities
+            .push(AutoloadEntity::new_with_prefix(prefix, sources));
+    }
+
+    /// Register embedded PHP source code, usually bundled by `include_bytes!`
+    /// or `include_str!`, to declare the companion userland classes and
+    /// functions.
+    ///
+    /// The source code is written into a private directory under the temp
+    /// directory in `MINIT`, and executed like `include` in `RINIT` of every
+    /// request, in the order of registration, because the userland classes and
+    /// functions are released at the end of request. The files are compiled by
+    /// `zend_compile_file`, so the compiled scripts are cached by opcache like
+    /// the normal scripts, and `__FILE__` is the written file. If the directory
+    /// can't be written, the source code is evaluated in every request instead.
+    /// When the sources only declare classes, prefer
+    /// [`Module::add_autoload_prefix`] to load them on demand.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::modules::Module;
+    ///
+    /// let mut module = Module::new("foo", "0.1.0", "");
+    /// module.add_source("foo.php", "<?php function foo() { return 1; }");
+    /// ```
+    pub fn add_source(&mut self, name: impl Into<String>, code: impl AsRef<[u8]> + 'static) {
+        self.source_entities.push(SourceEntity::new(name, code));
     }
 
     /// Register info item.
@@ -226,10 +1618,45 @@ impl Module {
         self.infos.insert(key, value);
     }
 
+    /// Register the function `{module_name}_debug_dump()`, which dumps the
+    /// runtime states of the module as an array, includes the current ini
+    /// values, the globals formatted by [`Debug`], and the registered
+    /// functions, classes, interfaces and constants.
+    ///
+    /// Useful for the operational debugging of the deployed extension.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::modules::Module;
+    /// use std::cell::Cell;
+    ///
+    /// thread_local! {
+    ///     static COUNTER: Cell<i64> = Cell::new(0);
+    /// }
+    ///
+    /// let mut module = Module::new("foo", "0.1.0", "");
+    /// module.enable_debug_dump(|| COUNTER.with(Cell::get));
+    /// ```
+    pub fn enable_debug_dump<D: Debug>(&mut self, globals: impl Fn() -> D + 'static) {
+        self.debug_globals = Some(Box::new(move || format!("{:#?}", globals())));
+    }
+
     /// Leak memory to generate `zend_module_entry` pointer.
+    ///
+    /// Only the first module is used, if `get_module` is called repeatedly, and
+    /// its `MINIT` fails if the module is another one, because the states are
+    /// held by the statics of `phper`.
     #[doc(hidden)]
     pub unsafe fn module_entry(self) -> *const zend_module_entry {
         if !GLOBAL_MODULE_ENTRY.is_null() {
+            let module = global_module_mut();
+            if module.name != self.name {
+                module.startup_errors.push(format!(
+                    "module {} is defined in the same library, only one module is allowed",
+                    self.name.to_string_lossy()
+                ));
+            }
             return GLOBAL_MODULE_ENTRY;
         }
 
@@ -239,7 +1666,36 @@ impl Module {
             "module version must be set"
         );
 
-        let module = Box::new(self);
+        let mut module = Box::new(self);
+
+        if module.debug_globals.is_some() {
+            let function_name = format!("{}_debug_dump", module.name.to_string_lossy());
+            module.add_function(function_name, debug_dump_handler);
+        }
+
+        if module.stats_enabled {
+            let function_name = format!("{}_phper_stats", module.name.to_string_lossy());
+            module.add_function(function_name, stats_handler);
+        }
+
+        if module.metrics_function_enabled {
+            let function_name = format!("{}_phper_metrics", module.name.to_string_lossy());
+            module.add_function(function_name, metrics_handler);
+        }
+
+        module.register_handlers();
+
+        // The functions are registered by the engine before `MINIT`, skip them if
+        // the names are invalid, the errors are reported in `MINIT`.
+        module.startup_errors = module.check_names();
+
+        if module.stats_enabled {
+            let mut names = vec![String::new(); module.handlers.len()];
+            for (name, index) in &module.handler_indexes {
+                names[*index] = name.clone();
+            }
+            module.stats = Some(StatsRecorder::new(names));
+        }
 
         let entry: Box<zend_module_entry> = Box::new(zend_module_entry {
             size: size_of::<zend_module_entry>() as c_ushort,
@@ -247,9 +1703,13 @@ impl Module {
             zend_debug: ZEND_DEBUG as c_uchar,
             zts: USING_ZTS as c_uchar,
             ini_entry: null(),
-            deps: null(),
+            deps: module.dependency_entries(),
             name: module.name.as_ptr(),
-            functions: module.function_entries(),
+            functions: if module.startup_errors.is_empty() {
+                module.function_entries()
+            } else {
+                null()
+            },
             module_startup_func: Some(module_startup),
             module_shutdown_func: Some(module_shutdown),
             request_startup_func: Some(request_startup),
@@ -263,7 +1723,7 @@ impl Module {
             globals_ptr: std::ptr::null_mut(),
             globals_ctor: None,
             globals_dtor: None,
-            post_deactivate_func: None,
+            post_deactivate_func: Some(post_deactivate),
             module_started: 0,
             type_: 0,
             handle: null_mut(),
@@ -277,22 +1737,385 @@ impl Module {
         GLOBAL_MODULE_ENTRY
     }
 
-    fn function_entries(&self) -> *const zend_function_entry {
+    fn dependency_entries(&mut self) -> *const zend_module_dep {
+        if self.dependencies.is_empty() {
+            return null();
+        }
+
+        let mut entries = Vec::with_capacity(self.dependencies.len() + 1);
+        for (name, dependency_type) in &self.dependencies {
+            entries.push(zend_module_dep {
+                name: name.as_ptr(),
+                rel: null(),
+                version: null(),
+                type_: *dependency_type as c_uchar,
+            });
+        }
+        entries.push(unsafe { zeroed::<zend_module_dep>() });
+
+        self.dependency_entries = entries;
+        self.dependency_entries.as_ptr()
+    }
+
+    fn function_entries(&mut self) -> *const zend_function_entry {
         if self.function_entities.is_empty() {
             return null();
         }
 
-        let mut entries = Vec::new();
-        for f in &self.function_entities {
+        let mut entries = Vec::with_capacity(self.function_entities.len() + 1);
+        for f in &mut self.function_entities {
             entries.push(unsafe { FunctionEntry::from_function_entity(f) });
         }
         entries.push(unsafe { zeroed::<zend_function_entry>() });
 
-        Box::into_raw(entries.into_boxed_slice()).cast()
+        self.function_entries = entries;
+        self.function_entries.as_ptr()
+    }
+
+    /// Check the names of functions, classes, interfaces, traits and methods,
+    /// and detect the duplicates.
+    fn check_names(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        let mut functions = HashSet::new();
+        for f in &self.function_entities {
+            check_name(&mut errors, &mut functions, "function", None, f.name());
+        }
+
+        let mut classes = HashSet::new();
+        let mut check_class = |kind, name: &CStr, method_names: Vec<&CStr>| {
+            check_name(&mut errors, &mut classes, kind, None, name);
+            let mut methods = HashSet::new();
+            for method_name in method_names {
+                check_name(&mut errors, &mut methods, "method", Some(name), method_name);
+            }
+        };
+        for entity in &self.class_entities {
+            check_class(
+                "class",
+                entity.class_name(),
+                entity.method_names().collect(),
+            );
+        }
+        for entity in &self.interface_entities {
+            check_class(
+                "interface",
+                entity.interface_name(),
+                entity.method_names().collect(),
+            );
+        }
+        for entity in &self.trait_entities {
+            check_class(
+                "trait",
+                entity.trait_name(),
+                entity.method_names().collect(),
+            );
+        }
+        #[cfg(phper_php_version_at_least = "8.1")]
+        for entity in &self.enum_entities {
+            check_class("enum", entity.enum_name(), entity.method_names().collect());
+        }
+        #[cfg(phper_php_version_at_least = "8.1")]
+        for entity in &self.enum_entities {
+            errors.extend(entity.check_cases());
+        }
+
+        errors
+    }
+
+    /// Detect the classes, interfaces and traits which are already declared,
+    /// by the engine or the other extensions.
+    fn check_registered_classes(&self) -> Vec<String> {
+        let class_names = self
+            .class_entities
+            .iter()
+            .map(|entity| ("class", entity.class_name()))
+            .chain(
+                self.interface_entities
+                    .iter()
+                    .map(|entity| ("interface", entity.interface_name())),
+            )
+            .chain(
+                self.trait_entities
+                    .iter()
+                    .map(|entity| ("trait", entity.trait_name())),
+            );
+        #[cfg(phper_php_version_at_least = "8.1")]
+        let class_names = class_names.chain(
+            self.enum_entities
+                .iter()
+                .map(|entity| ("enum", entity.enum_name())),
+        );
+
+        let mut errors = Vec::new();
+        for (kind, name) in class_names {
+            let name = name.to_string_lossy();
+            if ClassEntry::from_globals(&name).is_ok() {
+                errors.push(format!("{} {} is already declared", kind, name));
+            }
+        }
+        errors
+    }
+
+    /// Index the handlers by the function name or the fully-qualified method
+    /// name, the index is stored into the `reserved` slot of registered
+    /// function in `MINIT`.
+    fn register_handlers(&mut self) {
+        for f in &self.function_entities {
+            let key = f.name().to_string_lossy().into_owned();
+            self.handler_indexes.insert(key, self.handlers.len());
+            self.handlers.push(f.handler().clone());
+        }
+        for class_entity in &self.class_entities {
+            for (method_name, handler) in class_entity.method_handlers() {
+                let key = method_key(class_entity.class_name(), method_name);
+                self.handler_indexes.insert(key, self.handlers.len());
+                self.handlers.push(handler.clone());
+            }
+        }
+        for trait_entity in &self.trait_entities {
+            for (method_name, handler) in trait_entity.method_handlers() {
+                let key = method_key(trait_entity.trait_name(), method_name);
+                self.handler_indexes.insert(key, self.handlers.len());
+                self.handlers.push(handler.clone());
+            }
+        }
+        #[cfg(phper_php_version_at_least = "8.1")]
+        for enum_entity in &self.enum_entities {
+            for (method_name, handler) in enum_entity.method_handlers() {
+                let key = method_key(enum_entity.enum_name(), method_name);
+                self.handler_indexes.insert(key, self.handlers.len());
+                self.handlers.push(handler.clone());
+            }
+        }
+    }
+
+    /// Get the handler index of invoked function, from the `reserved` slot, or
+    /// lookup by name if the slot isn't available, for example, the resource
+    /// handles are exhausted.
+    #[inline]
+    pub(crate) fn get_handler_index(&self, func: &ZFunc) -> Option<usize> {
+        if self.resource_handle >= 0 {
+            let index = unsafe { func.get_reserved(self.resource_handle as usize) } as usize;
+            if index > 0 {
+                return Some(index - 1);
+            }
+        }
+
+        let name = func.get_function_or_method_name();
+        self.handler_indexes.get(name.to_str().ok()?).copied()
+    }
+
+    #[inline]
+    pub(crate) fn get_handler(&self, index: usize) -> Option<&Rc<dyn Callable>> {
+        self.handlers.get(index)
+    }
+
+    #[inline]
+    pub(crate) fn stats(&self) -> Option<&StatsRecorder> {
+        self.stats.as_ref()
+    }
+
+    #[inline]
+    pub(crate) fn symbol_tables_enabled(&self) -> bool {
+        self.symbol_tables_enabled
+    }
+
+    #[inline]
+    pub(crate) fn compile_file_hooks(&self) -> &[Box<CompileFileHook>] {
+        &self.compile_file_hooks
+    }
+
+    #[inline]
+    pub(crate) fn compile_string_hooks(&self) -> &[Box<CompileStringHook>] {
+        &self.compile_string_hooks
+    }
+
+    #[inline]
+    pub(crate) fn opcode_handler_entities(&self) -> &[OpcodeHandlerEntity] {
+        &self.opcode_handler_entities
+    }
+
+    #[inline]
+    pub(crate) fn stream_transport_entities(&self) -> &[StreamTransportEntity] {
+        &self.stream_transport_entities
+    }
+
+    #[inline]
+    pub(crate) fn post_reader_entities(&self) -> &[PostReaderEntity] {
+        &self.post_reader_entities
+    }
+
+    #[inline]
+    pub(crate) fn zend_extension_entity(&mut self) -> Option<&mut ZendExtensionEntity> {
+        self.zend_extension.as_mut()
     }
 
     #[inline]
     pub(crate) fn class_entities(&self) -> &[ClassEntity<()>] {
         &self.class_entities
     }
+
+    #[inline]
+    pub(crate) fn autoload_entities(&self) -> &[AutoloadEntity] {
+        &self.autoload_entities
+    }
+
+    /// The parent name of the class which isn't registered, because the
+    /// parent isn't found after startup.
+    pub(crate) fn deferred_parent_name(&self, class_name: &str) -> Option<&str> {
+        let class_name = class_name.trim_start_matches('\\');
+        self.deferred_classes
+            .iter()
+            .map(|&i| &self.class_entities[i])
+            .find(|class_entity| {
+                class_entity
+                    .class_name()
+                    .to_bytes()
+                    .eq_ignore_ascii_case(class_name.as_bytes())
+            })
+            .and_then(|class_entity| class_entity.unresolved_parent_name())
+    }
+
+    fn warn_deferred_classes(&self) {
+        for &i in &self.deferred_classes {
+            let class_entity = &self.class_entities[i];
+            crate::warning!(
+                "{}: class {} isn't registered, because the parent class {} isn't found",
+                self.name.to_string_lossy(),
+                class_entity.class_name().to_string_lossy(),
+                class_entity.unresolved_parent_name().unwrap_or_default()
+            );
+        }
+    }
+
+    fn debug_dump(&self) -> ZArray {
+        let mut dump = ZArray::new();
+
+        dump.insert("name", ZVal::from(self.name.to_string_lossy().as_ref()));
+        dump.insert(
+            "version",
+            ZVal::from(self.version.to_string_lossy().as_ref()),
+        );
+
+        let mut ini = ZArray::new();
+        for entity in &self.ini_entities {
+            let value = ini::ini_get::<Option<&CStr>>(entity.name())
+                .map(|value| ZVal::from(value.to_bytes()))
+                .unwrap_or_default();
+            ini.insert(entity.name(), value);
+        }
+        dump.insert("ini", ini);
+
+        if let Some(globals) = &self.debug_globals {
+            dump.insert("globals", ZVal::from(globals()));
+        }
+
+        dump.insert(
+            "functions",
+            names_array(self.function_entities.iter().map(|f| f.name())),
+        );
+        dump.insert(
+            "classes",
+            names_array(self.class_entities.iter().map(|c| c.class_name())),
+        );
+        dump.insert(
+            "interfaces",
+            names_array(self.interface_entities.iter().map(|i| i.interface_name())),
+        );
+
+        let mut constants = ZArray::new();
+        for constant in &self.constants {
+            constants.insert((), ZVal::from(constant.name()));
+        }
+        dump.insert("constants", constants);
+
+        dump
+    }
+}
+
+fn names_array<'a>(names: impl Iterator<Item = &'a CStr>) -> ZArray {
+    let mut arr = ZArray::new();
+    for name in names {
+        arr.insert((), ZVal::from(name.to_bytes()));
+    }
+    arr
+}
+
+/// The handler of `{module_name}_debug_dump()`.
+fn debug_dump_handler(_: &mut [ZVal]) -> crate::Result<ZArray> {
+    let module = unsafe { global_module() };
+    Ok(module.debug_dump())
+}
+
+fn stats_handler(_: &mut [ZVal]) -> crate::Result<ZArray> {
+    let mut arr = ZArray::new();
+    for stat in stats::function_stats() {
+        let mut item = ZArray::new();
+        item.insert("calls", ZVal::from(stat.calls() as i64));
+        item.insert("time", ZVal::from(stat.time().as_secs_f64()));
+        arr.insert(stat.name(), ZVal::from(item));
+    }
+    Ok(arr)
+}
+
+/// The handler of `{module_name}_phper_metrics()`.
+fn metrics_handler(_: &mut [ZVal]) -> crate::Result<ZArray> {
+    let module = unsafe { global_module() };
+    let mut arr = ZArray::new();
+    for metric in &module.metric_entities {
+        arr.insert(metric.name(), metric.to_z_val());
+    }
+    Ok(arr)
+}
+
+/// Check the name is valid, and isn't duplicated case-insensitively in `seen`,
+/// the method name is checked in the `scope` of class.
+fn check_name(
+    errors: &mut Vec<String>, seen: &mut HashSet<Vec<u8>>, kind: &str, scope: Option<&CStr>,
+    name: &CStr,
+) {
+    let full_name = match scope {
+        Some(scope) => method_key(scope, name),
+        None => name.to_string_lossy().into_owned(),
+    };
+    if !is_valid_name(name.to_bytes(), scope.is_none()) {
+        errors.push(format!("{} name {:?} is invalid", kind, full_name));
+    } else if !seen.insert(name.to_bytes().to_ascii_lowercase()) {
+        errors.push(format!("{} {} is registered repeatedly", kind, full_name));
+    }
+}
+
+fn method_key(class_name: &CStr, method_name: &CStr) -> String {
+    format!(
+        "{}::{}",
+        class_name.to_string_lossy(),
+        method_name.to_string_lossy()
+    )
+}
+
+/// Store the handler index (plus one, zero means unset) into the `reserved`
+/// slot of registered function.
+///
+/// The index is stable in the process, so it's still valid in the copies of
+/// the function, for example, the methods inherited by the user classes cached
+/// or preloaded by opcache.
+unsafe fn bind_handler(
+    handler_indexes: &HashMap<String, usize>, handle: c_int, function_table: *mut HashTable,
+    name: &CStr, key: &str,
+) {
+    if handle < 0 {
+        return;
+    }
+    let index = match handler_indexes.get(key) {
+        Some(index) => *index,
+        None => return,
+    };
+
+    let lc_name = name.to_bytes().to_ascii_lowercase();
+    let func = phper_zend_hash_str_find_ptr(function_table, lc_name.as_ptr().cast(), lc_name.len())
+        as *mut zend_function;
+    if !func.is_null() {
+        ZFunc::from_mut_ptr(func).set_reserved(handle as usize, (index + 1) as *mut c_void);
+    }
 }
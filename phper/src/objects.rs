@@ -12,7 +12,9 @@
 
 use crate::{
     classes::ClassEntry,
+    errors::NotInitializedError,
     functions::{call_internal, call_raw_common, ZFunc},
+    strings::ZStr,
     sys::*,
     values::ZVal,
 };
@@ -23,11 +25,41 @@ use std::{
     ffi::c_void,
     fmt::{self, Debug},
     marker::PhantomData,
-    mem::{replace, size_of, ManuallyDrop},
+    mem::{align_of, replace, size_of, ManuallyDrop},
     ops::{Deref, DerefMut},
     ptr::null_mut,
 };
 
+/// The identity of object, same as the return value of `spl_object_id()` in
+/// PHP.
+///
+/// The id is unique among the living objects, but may be reused after the
+/// object is destroyed, so the entries keyed by it should be cleaned in the
+/// destroy hook, see
+/// [`ClassEntity::on_destroy`](crate::classes::ClassEntity::on_destroy).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ObjectId(u32);
+
+impl ObjectId {
+    /// Gets the raw id.
+    #[inline]
+    pub const fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+impl From<ObjectId> for u32 {
+    fn from(id: ObjectId) -> Self {
+        id.0
+    }
+}
+
+impl fmt::Display for ObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
 /// Wrapper of [zend_object].
 #[repr(transparent)]
 pub struct ZObj {
@@ -121,6 +153,12 @@ impl ZObj {
         self.inner.handle
     }
 
+    /// Get the identity of object, same as `spl_object_id()` in PHP.
+    #[inline]
+    pub fn id(&self) -> ObjectId {
+        ObjectId(self.inner.handle)
+    }
+
     /// Get the class reference of object.
     pub fn get_class(&self) -> &ClassEntry {
         unsafe { ClassEntry::from_ptr(self.inner.ce) }
@@ -179,6 +217,47 @@ impl ZObj {
         }
     }
 
+    /// Get the property by the zend string name of object, prefer to use the
+    /// [interned](crate::strings::ZString::interned) name in hot path, avoiding
+    /// the name being allocated and hashed in every access.
+    pub fn get_property_by_z_str(&self, name: &ZStr) -> &ZVal {
+        unsafe {
+            let prop = phper_zend_read_property_ex(
+                self.inner.ce,
+                self.as_ptr() as *mut _,
+                name.as_ptr() as *mut _,
+                true,
+            );
+            ZVal::from_ptr(prop)
+        }
+    }
+
+    /// Get the mutable property by the zend string name of object.
+    pub fn get_mut_property_by_z_str(&mut self, name: &ZStr) -> &mut ZVal {
+        unsafe {
+            let prop = phper_zend_read_property_ex(
+                self.inner.ce,
+                self.as_mut_ptr(),
+                name.as_ptr() as *mut _,
+                true,
+            );
+            ZVal::from_mut_ptr(prop)
+        }
+    }
+
+    /// Set the property by the zend string name of object.
+    pub fn set_property_by_z_str(&mut self, name: &ZStr, val: impl Into<ZVal>) {
+        let mut val = val.into();
+        unsafe {
+            phper_zend_update_property_ex(
+                self.inner.ce,
+                self.as_mut_ptr(),
+                name.as_ptr() as *mut _,
+                val.as_mut_ptr(),
+            );
+        }
+    }
+
     /// Set the property by name of object.
     #[allow(clippy::useless_conversion)]
     pub fn set_property(&mut self, name: impl AsRef<str>, val: impl Into<ZVal>) {
@@ -270,6 +349,10 @@ impl ToRefOwned for ZObj {
     type Owned = ZObject;
 
     fn to_ref_owned(&mut self) -> Self::Owned {
+        zend_assert!(
+            unsafe { self.gc_refcount() } > 0,
+            "add reference to the released object"
+        );
         let mut val = ManuallyDrop::new(ZVal::default());
         unsafe {
             phper_zval_obj(val.as_mut_ptr(), self.as_mut_ptr());
@@ -378,15 +461,18 @@ pub(crate) type AnyState = *mut dyn Any;
 #[repr(C)]
 pub struct StateObj<T> {
     any_state: AnyState,
+    initialized: bool,
     object: ZObj,
     _p: PhantomData<T>,
 }
 
 impl<T> StateObj<T> {
     /// The `zend_object_alloc` often allocate more memory to hold the state
-    /// (usually is a pointer), and place it before `zend_object`.
+    /// (usually is a pointer) and the initialized flag, and place them before
+    /// `zend_object`.
     pub(crate) const fn offset() -> usize {
-        size_of::<AnyState>()
+        let align = align_of::<ZObj>();
+        (size_of::<AnyState>() + size_of::<bool>() + align - 1) / align * align
     }
 
     #[inline]
@@ -415,6 +501,35 @@ impl<T> StateObj<T> {
         &mut self.any_state
     }
 
+    #[inline]
+    pub(crate) fn any_state_ptr(&self) -> *const dyn Any {
+        self.any_state
+    }
+
+    #[inline]
+    pub(crate) fn set_initialized(&mut self, initialized: bool) {
+        self.initialized = initialized;
+    }
+
+    /// Whether the object is constructed.
+    ///
+    /// For the class with
+    /// [`ClassEntity::add_magic_construct`](crate::classes::ClassEntity::add_magic_construct),
+    /// it is `false` until the constructor returns `Ok`, so the object
+    /// created by `ReflectionClass::newInstanceWithoutConstructor()`, or by
+    /// the subclass which doesn't call `parent::__construct()`, or whose
+    /// constructor throws, is never initialized. Otherwise, it is always
+    /// `true`.
+    #[inline]
+    pub fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+
+    fn not_initialized_error(&self) -> crate::Error {
+        let class_name = self.get_class().get_name().to_str().unwrap_or_default();
+        NotInitializedError::new(class_name.to_owned()).into()
+    }
+
     /// Gets object.
     #[inline]
     pub fn as_object(&self) -> &ZObj {
@@ -444,6 +559,63 @@ impl<T: 'static> StateObj<T> {
             any_state.downcast_mut().unwrap()
         }
     }
+
+    /// Gets inner state, checking that the object is constructed, like
+    /// `PDO` and `mysqli` guard their handles.
+    ///
+    /// # Errors
+    ///
+    /// Return [`NotInitializedError`] if the object isn't initialized, see
+    /// [`StateObj::is_initialized`].
+    pub fn try_as_state(&self) -> crate::Result<&T> {
+        if !self.is_initialized() {
+            return Err(self.not_initialized_error());
+        }
+        Ok(self.as_state())
+    }
+
+    /// Gets inner mutable state, checking that the object is constructed.
+    ///
+    /// # Errors
+    ///
+    /// Return [`NotInitializedError`] if the object isn't initialized, see
+    /// [`StateObj::is_initialized`].
+    pub fn try_as_mut_state(&mut self) -> crate::Result<&mut T> {
+        if !self.is_initialized() {
+            return Err(self.not_initialized_error());
+        }
+        Ok(self.as_mut_state())
+    }
+}
+
+impl<T: 'static> StateObj<Option<T>> {
+    /// Gets inner state built by the constructor, see
+    /// [`ClassEntity::add_state_construct`](crate::classes::ClassEntity::add_state_construct).
+    ///
+    /// # Errors
+    ///
+    /// Return [`NotInitializedError`] if the constructor isn't called or
+    /// throws, for example, the object is created by
+    /// `ReflectionClass::newInstanceWithoutConstructor()`.
+    pub fn as_initialized_state(&self) -> crate::Result<&T> {
+        match self.as_state() {
+            Some(state) => Ok(state),
+            None => Err(self.not_initialized_error()),
+        }
+    }
+
+    /// Gets inner mutable state built by the constructor.
+    ///
+    /// # Errors
+    ///
+    /// Return [`NotInitializedError`] if the constructor isn't called or
+    /// throws.
+    pub fn as_mut_initialized_state(&mut self) -> crate::Result<&mut T> {
+        if self.as_state().is_none() {
+            return Err(self.not_initialized_error());
+        }
+        Ok(self.as_mut_state().as_mut().unwrap())
+    }
 }
 
 impl<T> Deref for StateObj<T> {
@@ -0,0 +1,202 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to overriding the opcode handlers, by
+//! `zend_set_user_opcode_handler`.
+
+use crate::{
+    contexts::ModuleContext, errors::throw, modules::global_module, sys::*, values::ExecuteData,
+};
+use std::os::raw::c_int;
+
+/// The opcodes which can be intercepted, only the commonly used ones of all
+/// supported PHP versions are listed.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum Opcode {
+    Nop = ZEND_NOP as u8,
+    Add = ZEND_ADD as u8,
+    Sub = ZEND_SUB as u8,
+    Mul = ZEND_MUL as u8,
+    Div = ZEND_DIV as u8,
+    Mod = ZEND_MOD as u8,
+    Concat = ZEND_CONCAT as u8,
+    Assign = ZEND_ASSIGN as u8,
+    Echo = ZEND_ECHO as u8,
+    Jmp = ZEND_JMP as u8,
+    Return = ZEND_RETURN as u8,
+    InitFcall = ZEND_INIT_FCALL as u8,
+    InitFcallByName = ZEND_INIT_FCALL_BY_NAME as u8,
+    InitMethodCall = ZEND_INIT_METHOD_CALL as u8,
+    InitStaticMethodCall = ZEND_INIT_STATIC_METHOD_CALL as u8,
+    InitUserCall = ZEND_INIT_USER_CALL as u8,
+    InitDynamicCall = ZEND_INIT_DYNAMIC_CALL as u8,
+    DoFcall = ZEND_DO_FCALL as u8,
+    DoIcall = ZEND_DO_ICALL as u8,
+    DoUcall = ZEND_DO_UCALL as u8,
+    DoFcallByName = ZEND_DO_FCALL_BY_NAME as u8,
+    New = ZEND_NEW as u8,
+    IncludeOrEval = ZEND_INCLUDE_OR_EVAL as u8,
+    Exit = ZEND_EXIT as u8,
+    Throw = ZEND_THROW as u8,
+    Catch = ZEND_CATCH as u8,
+    BeginSilence = ZEND_BEGIN_SILENCE as u8,
+    EndSilence = ZEND_END_SILENCE as u8,
+    DeclareFunction = ZEND_DECLARE_FUNCTION as u8,
+    DeclareClass = ZEND_DECLARE_CLASS as u8,
+    FetchConstant = ZEND_FETCH_CONSTANT as u8,
+    Yield = ZEND_YIELD as u8,
+    ExtStmt = ZEND_EXT_STMT as u8,
+    ExtFcallBegin = ZEND_EXT_FCALL_BEGIN as u8,
+    ExtFcallEnd = ZEND_EXT_FCALL_END as u8,
+}
+
+impl Opcode {
+    const ALL: [Opcode; 35] = [
+        Opcode::Nop,
+        Opcode::Add,
+        Opcode::Sub,
+        Opcode::Mul,
+        Opcode::Div,
+        Opcode::Mod,
+        Opcode::Concat,
+        Opcode::Assign,
+        Opcode::Echo,
+        Opcode::Jmp,
+        Opcode::Return,
+        Opcode::InitFcall,
+        Opcode::InitFcallByName,
+        Opcode::InitMethodCall,
+        Opcode::InitStaticMethodCall,
+        Opcode::InitUserCall,
+        Opcode::InitDynamicCall,
+        Opcode::DoFcall,
+        Opcode::DoIcall,
+        Opcode::DoUcall,
+        Opcode::DoFcallByName,
+        Opcode::New,
+        Opcode::IncludeOrEval,
+        Opcode::Exit,
+        Opcode::Throw,
+        Opcode::Catch,
+        Opcode::BeginSilence,
+        Opcode::EndSilence,
+        Opcode::DeclareFunction,
+        Opcode::DeclareClass,
+        Opcode::FetchConstant,
+        Opcode::Yield,
+        Opcode::ExtStmt,
+        Opcode::ExtFcallBegin,
+        Opcode::ExtFcallEnd,
+    ];
+
+    /// Converts from the raw opcode, `None` if it isn't listed.
+    pub fn from_raw(opcode: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|op| op.as_raw() == opcode)
+    }
+
+    /// Gets the raw opcode.
+    #[inline]
+    pub const fn as_raw(self) -> u8 {
+        self as u8
+    }
+}
+
+/// What the engine does after the opcode handler returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserOpcode {
+    /// Execute the opline pointed by `EX(opline)`, the handler is responsible
+    /// for moving it forward.
+    Continue,
+    /// Return from the executing function.
+    Return,
+    /// Execute the original handler of opcode, or the handler registered
+    /// before this one.
+    Dispatch,
+    /// Enter the new execute data.
+    Enter,
+    /// Leave the current execute data.
+    Leave,
+    /// Execute the original handler of the other opcode.
+    DispatchTo(Opcode),
+}
+
+impl UserOpcode {
+    fn as_raw(self) -> c_int {
+        (match self {
+            UserOpcode::Continue => ZEND_USER_OPCODE_CONTINUE,
+            UserOpcode::Return => ZEND_USER_OPCODE_RETURN,
+            UserOpcode::Dispatch => ZEND_USER_OPCODE_DISPATCH,
+            UserOpcode::Enter => ZEND_USER_OPCODE_ENTER,
+            UserOpcode::Leave => ZEND_USER_OPCODE_LEAVE,
+            UserOpcode::DispatchTo(opcode) => ZEND_USER_OPCODE_DISPATCH_TO | opcode.as_raw() as u32,
+        }) as c_int
+    }
+}
+
+pub(crate) type OpcodeHandler = dyn Fn(&mut ExecuteData) -> crate::Result<UserOpcode>;
+
+/// Builder for overriding opcode handler.
+pub(crate) struct OpcodeHandlerEntity {
+    opcode: Opcode,
+    handler: Box<OpcodeHandler>,
+    previous: user_opcode_handler_t,
+}
+
+impl OpcodeHandlerEntity {
+    pub(crate) fn new(
+        opcode: Opcode, handler: impl Fn(&mut ExecuteData) -> crate::Result<UserOpcode> + 'static,
+    ) -> Self {
+        Self {
+            opcode,
+            handler: Box::new(handler),
+            previous: None,
+        }
+    }
+
+    /// Set the handler in `MINIT`, keep the previous one to dispatch to.
+    pub(crate) unsafe fn register(&mut self, _ctx: &ModuleContext<'_>) {
+        self.previous = zend_get_user_opcode_handler(self.opcode.as_raw());
+        zend_set_user_opcode_handler(self.opcode.as_raw(), Some(opcode_handler));
+    }
+
+    /// Restore the previous handler in `MSHUTDOWN`, because the library is
+    /// going to be unloaded.
+    pub(crate) unsafe fn unregister(&self) {
+        zend_set_user_opcode_handler(self.opcode.as_raw(), self.previous);
+    }
+}
+
+unsafe extern "C" fn opcode_handler(execute_data: *mut zend_execute_data) -> c_int {
+    let opcode = (*(*execute_data).opline).opcode;
+    let entity = match global_module()
+        .opcode_handler_entities()
+        .iter()
+        .find(|entity| entity.opcode.as_raw() == opcode)
+    {
+        Some(entity) => entity,
+        None => return ZEND_USER_OPCODE_DISPATCH as c_int,
+    };
+
+    match (entity.handler)(ExecuteData::from_mut_ptr(execute_data)) {
+        Ok(UserOpcode::Dispatch) => match entity.previous {
+            Some(previous) => previous(execute_data),
+            None => ZEND_USER_OPCODE_DISPATCH as c_int,
+        },
+        Ok(user_opcode) => user_opcode.as_raw(),
+        Err(e) => {
+            // The `EX(opline)` is moved to the exception handling opline by the
+            // engine when throwing.
+            throw(e);
+            ZEND_USER_OPCODE_CONTINUE as c_int
+        }
+    }
+}
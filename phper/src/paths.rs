@@ -0,0 +1,66 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to resolving the paths like PHP.
+//!
+//! The relative paths from the userland should be resolved against the
+//! virtual cwd of PHP (changed by `chdir()`, and separated by threads in
+//! ZTS), rather than the cwd of process, otherwise the files are looked up
+//! in the wrong directory, for example, the `/` of php-fpm workers.
+
+use crate::{
+    strings::{ZStr, ZString},
+    sys::*,
+    utils::ensure_end_with_zero,
+};
+use std::path::PathBuf;
+
+/// Gets the absolute path of `path`, resolved against the virtual cwd, the
+/// `.` and `..` are normalized, but the symbolic links aren't resolved, and
+/// the path needn't exist.
+///
+/// Returns `None` if the path is invalid, for example, too long.
+pub fn expand(path: &str) -> Option<PathBuf> {
+    let path = ensure_end_with_zero(path);
+    unsafe { into_path_buf(phper_expand_filepath(path.as_ptr())) }
+}
+
+/// Gets the virtual cwd, like `getcwd()` in PHP.
+pub fn current_dir() -> Option<PathBuf> {
+    unsafe { into_path_buf(phper_vcwd_getcwd()) }
+}
+
+/// Gets the canonical absolute path of `path`, resolved against the virtual
+/// cwd, with the symbolic links resolved, like `realpath()` in PHP.
+///
+/// Returns `None` if the path doesn't exist.
+pub fn real_path(path: &str) -> Option<PathBuf> {
+    let path = ensure_end_with_zero(path);
+    unsafe { into_path_buf(phper_vcwd_realpath(path.as_ptr())) }
+}
+
+unsafe fn into_path_buf(ptr: *mut zend_string) -> Option<PathBuf> {
+    if ptr.is_null() {
+        return None;
+    }
+    let s = ZString::from_raw(ptr);
+    Some(to_path_buf(&s))
+}
+
+#[cfg(unix)]
+fn to_path_buf(s: &ZStr) -> PathBuf {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+    PathBuf::from(OsStr::from_bytes(s.to_bytes()))
+}
+
+#[cfg(not(unix))]
+fn to_path_buf(s: &ZStr) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(s.to_bytes()).into_owned())
+}
@@ -0,0 +1,498 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to implementing the PDO drivers, so `new PDO("name:...")` is
+//! served by Rust.
+//!
+//! The placeholders of prepared statements are emulated by PDO, the bound
+//! values are quoted by [`PdoConnection::quote`] and inlined into the SQL
+//! passed to [`PdoStatement::execute`].
+//!
+//! Require the `pdo` feature, only available on PHP 8.1 or later.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use phper::{
+//!     modules::Module,
+//!     pdo::{PdoConnection, PdoDataSource, PdoDriver, PdoError, PdoStatement},
+//! };
+//!
+//! struct MyDriver;
+//!
+//! impl PdoDriver for MyDriver {
+//!     fn connect(&self, source: &PdoDataSource<'_>) -> Result<Box<dyn PdoConnection>, PdoError> {
+//!         todo!("connect to {}", source.dsn())
+//!     }
+//! }
+//!
+//! fn register(module: &mut Module) {
+//!     module.add_pdo_driver("mydb", MyDriver);
+//! }
+//! ```
+
+use crate::{
+    arrays::InsertKey, contexts::ModuleContext, strings::ZString, sys::*,
+    utils::ensure_end_with_zero, values::ZVal,
+};
+use std::{
+    borrow::Cow,
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_int, c_void},
+    ptr::{null_mut, write},
+    slice::from_raw_parts,
+    str,
+};
+
+/// The error reported by the PDO driver, which becomes the `errorInfo` of
+/// `PDO` or `PDOStatement`, and thrown as `PDOException` when the error mode
+/// is `PDO::ERRMODE_EXCEPTION`.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("SQLSTATE[{sqlstate}]: {message}")]
+pub struct PdoError {
+    sqlstate: String,
+    code: i64,
+    message: String,
+}
+
+impl PdoError {
+    /// Construct the error with the five characters `sqlstate`, like `42S02`,
+    /// and the driver specific `code` and `message`.
+    pub fn new(sqlstate: impl Into<String>, code: i64, message: impl Into<String>) -> Self {
+        Self {
+            sqlstate: sqlstate.into(),
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// The general error, with the sqlstate `HY000`.
+    pub fn general(message: impl Into<String>) -> Self {
+        Self::new("HY000", 0, message)
+    }
+
+    /// The error of the optional features not implemented by the driver, with
+    /// the sqlstate `IM001`.
+    pub fn not_supported() -> Self {
+        Self::new("IM001", 0, "Driver does not support this function")
+    }
+
+    /// Gets the sqlstate.
+    #[inline]
+    pub fn sqlstate(&self) -> &str {
+        &self.sqlstate
+    }
+
+    /// Gets the driver specific code.
+    #[inline]
+    pub fn code(&self) -> i64 {
+        self.code
+    }
+
+    /// Gets the driver specific message.
+    #[inline]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    fn write_sqlstate(&self, buf: *mut c_char) {
+        let mut sqlstate = [b'0'; 5];
+        for (dst, src) in sqlstate.iter_mut().zip(self.sqlstate.bytes()) {
+            *dst = src;
+        }
+        unsafe {
+            for (i, b) in sqlstate.iter().enumerate() {
+                *buf.add(i) = *b as c_char;
+            }
+            *buf.add(5) = 0;
+        }
+    }
+}
+
+/// The data source passed to `new PDO(...)`.
+pub struct PdoDataSource<'a> {
+    dsn: Cow<'a, str>,
+    username: Option<Cow<'a, str>>,
+    password: Option<Cow<'a, str>>,
+}
+
+impl<'a> PdoDataSource<'a> {
+    unsafe fn from_dbh(dbh: &'a pdo_dbh_t) -> Self {
+        let opt_str =
+            |ptr: *const c_char| (!ptr.is_null()).then(|| CStr::from_ptr(ptr).to_string_lossy());
+        Self {
+            dsn: String::from_utf8_lossy(from_raw_parts(
+                dbh.data_source.cast(),
+                dbh.data_source_len,
+            )),
+            username: opt_str(dbh.username),
+            password: opt_str(dbh.password),
+        }
+    }
+
+    /// The part of dsn after the driver name prefix, for `mydb:host=localhost`,
+    /// it's `host=localhost`.
+    #[inline]
+    pub fn dsn(&self) -> &str {
+        &self.dsn
+    }
+
+    /// Parse the dsn as the `;` separated `key=value` pairs, like the
+    /// bundled drivers.
+    pub fn params(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.dsn
+            .split(';')
+            .filter(|param| !param.trim().is_empty())
+            .map(|param| {
+                let (key, value) = param.split_once('=').unwrap_or((param, ""));
+                (key.trim(), value.trim())
+            })
+    }
+
+    /// The username, if passed.
+    #[inline]
+    pub fn username(&self) -> Option<&str> {
+        self.username.as_deref()
+    }
+
+    /// The password, if passed.
+    #[inline]
+    pub fn password(&self) -> Option<&str> {
+        self.password.as_deref()
+    }
+}
+
+/// The PDO driver, registered by
+/// [`Module::add_pdo_driver`](crate::modules::Module::add_pdo_driver).
+pub trait PdoDriver {
+    /// Open the connection, called by `new PDO(...)`.
+    fn connect(&self, source: &PdoDataSource<'_>) -> Result<Box<dyn PdoConnection>, PdoError>;
+}
+
+/// The connection opened by [`PdoDriver::connect`], closed when dropped.
+pub trait PdoConnection {
+    /// Prepare the statement, called by `PDO::prepare` and `PDO::query`.
+    fn prepare(&mut self, sql: &str) -> Result<Box<dyn PdoStatement>, PdoError>;
+
+    /// Execute the statement without result set, returns the affected rows,
+    /// called by `PDO::exec`.
+    fn exec(&mut self, sql: &str) -> Result<u64, PdoError>;
+
+    /// Quote the string literal, also used to emulate the bound parameters.
+    ///
+    /// The default implementation quotes with `'` and doubles the `'` inside,
+    /// as standard SQL.
+    fn quote(&mut self, s: &str) -> Result<String, PdoError> {
+        Ok(format!("'{}'", s.replace('\'', "''")))
+    }
+
+    /// Begin the transaction, not supported by default.
+    fn begin(&mut self) -> Result<(), PdoError> {
+        Err(PdoError::not_supported())
+    }
+
+    /// Commit the transaction, not supported by default.
+    fn commit(&mut self) -> Result<(), PdoError> {
+        Err(PdoError::not_supported())
+    }
+
+    /// Roll back the transaction, not supported by default.
+    fn rollback(&mut self) -> Result<(), PdoError> {
+        Err(PdoError::not_supported())
+    }
+
+    /// The id of last inserted row, or the sequence value of `name`, not
+    /// supported by default.
+    fn last_insert_id(&mut self, name: Option<&str>) -> Result<String, PdoError> {
+        let _ = name;
+        Err(PdoError::not_supported())
+    }
+}
+
+/// The statement prepared by [`PdoConnection::prepare`].
+pub trait PdoStatement {
+    /// Execute the statement, the `sql` is the prepared SQL with the bound
+    /// parameters inlined.
+    fn execute(&mut self, sql: &str) -> Result<(), PdoError>;
+
+    /// The count of columns of the result set, zero if no result set.
+    fn column_count(&self) -> usize;
+
+    /// The name of column `index`.
+    fn column_name(&self, index: usize) -> Result<String, PdoError>;
+
+    /// The count of affected rows.
+    fn row_count(&self) -> u64 {
+        0
+    }
+
+    /// Move to the next row, returns `false` if no more rows.
+    fn fetch(&mut self) -> Result<bool, PdoError>;
+
+    /// The value of column `index` of the current row.
+    fn column_value(&mut self, index: usize) -> Result<ZVal, PdoError>;
+}
+
+pub(crate) struct PdoDriverEntity {
+    name: CString,
+    driver: Box<Box<dyn PdoDriver>>,
+    handle: *mut c_void,
+}
+
+impl PdoDriverEntity {
+    pub(crate) fn new(name: impl Into<String>, driver: impl PdoDriver + 'static) -> Self {
+        Self {
+            name: ensure_end_with_zero(name),
+            driver: Box::new(Box::new(driver)),
+            handle: null_mut(),
+        }
+    }
+
+    pub(crate) fn name(&self) -> &CStr {
+        &self.name
+    }
+
+    /// Register to `ext/pdo`, must be called in `MINIT`.
+    pub(crate) unsafe fn register(&mut self, _ctx: &ModuleContext<'_>) -> bool {
+        let data = &mut *self.driver as *mut Box<dyn PdoDriver> as *mut c_void;
+        self.handle = phper_pdo_register_driver(
+            self.name.as_ptr(),
+            self.name.as_bytes().len(),
+            data,
+            &CALLBACKS,
+        );
+        !self.handle.is_null()
+    }
+
+    pub(crate) unsafe fn unregister(&mut self) {
+        if !self.handle.is_null() {
+            phper_pdo_unregister_driver(self.handle);
+            self.handle = null_mut();
+        }
+    }
+}
+
+struct ConnectionState {
+    connection: Box<dyn PdoConnection>,
+    error: Option<PdoError>,
+}
+
+struct StatementState {
+    statement: Box<dyn PdoStatement>,
+    error: Option<PdoError>,
+}
+
+static CALLBACKS: phper_pdo_callbacks = phper_pdo_callbacks {
+    connect: Some(connect),
+    close: Some(close),
+    prepare: Some(prepare),
+    exec: Some(exec),
+    quote: Some(quote),
+    begin: Some(begin),
+    commit: Some(commit),
+    rollback: Some(rollback),
+    last_id: Some(last_id),
+    sqlstate: Some(sqlstate),
+    fetch_error: Some(fetch_error),
+    stmt_dtor: Some(stmt_dtor),
+    stmt_execute: Some(stmt_execute),
+    stmt_fetch: Some(stmt_fetch),
+    stmt_column_name: Some(stmt_column_name),
+    stmt_get_col: Some(stmt_get_col),
+};
+
+unsafe fn to_str<'a>(s: *const c_char, len: usize) -> Result<&'a str, PdoError> {
+    if len == 0 {
+        return Ok("");
+    }
+    str::from_utf8(from_raw_parts(s.cast(), len))
+        .map_err(|_| PdoError::general("Invalid UTF-8 sequence in SQL"))
+}
+
+unsafe fn connection<'a>(conn: *mut c_void) -> &'a mut ConnectionState {
+    &mut *conn.cast::<ConnectionState>()
+}
+
+unsafe fn statement<'a>(stmt: *mut c_void) -> &'a mut StatementState {
+    &mut *stmt.cast::<StatementState>()
+}
+
+/// Run the connection operation, keep the error for `sqlstate` and
+/// `fetch_error`.
+unsafe fn with_connection<T>(
+    conn: *mut c_void, f: impl FnOnce(&mut dyn PdoConnection) -> Result<T, PdoError>,
+) -> Option<T> {
+    let state = connection(conn);
+    match f(&mut *state.connection) {
+        Ok(t) => Some(t),
+        Err(e) => {
+            state.error = Some(e);
+            None
+        }
+    }
+}
+
+unsafe fn with_statement<T>(
+    stmt: *mut c_void, f: impl FnOnce(&mut dyn PdoStatement) -> Result<T, PdoError>,
+) -> Option<T> {
+    let state = statement(stmt);
+    match f(&mut *state.statement) {
+        Ok(t) => Some(t),
+        Err(e) => {
+            state.error = Some(e);
+            None
+        }
+    }
+}
+
+unsafe extern "C" fn connect(driver: *mut c_void, dbh: *mut pdo_dbh_t) -> *mut c_void {
+    let driver = &*driver.cast::<Box<dyn PdoDriver>>();
+    let source = PdoDataSource::from_dbh(&*dbh);
+    match driver.connect(&source) {
+        Ok(connection) => Box::into_raw(Box::new(ConnectionState {
+            connection,
+            error: None,
+        }))
+        .cast(),
+        Err(e) => {
+            let message = ensure_end_with_zero(format!(
+                "SQLSTATE[{}] [{}] {}",
+                e.sqlstate(),
+                e.code(),
+                e.message()
+            ));
+            zend_throw_exception(php_pdo_get_exception(), message.as_ptr(), e.code());
+            null_mut()
+        }
+    }
+}
+
+unsafe extern "C" fn close(conn: *mut c_void) {
+    drop(Box::from_raw(conn.cast::<ConnectionState>()));
+}
+
+unsafe extern "C" fn prepare(conn: *mut c_void, sql: *const c_char, len: usize) -> *mut c_void {
+    with_connection(conn, |connection| connection.prepare(to_str(sql, len)?))
+        .map(|statement| {
+            Box::into_raw(Box::new(StatementState {
+                statement,
+                error: None,
+            }))
+            .cast()
+        })
+        .unwrap_or(null_mut())
+}
+
+unsafe extern "C" fn exec(conn: *mut c_void, sql: *const c_char, len: usize) -> zend_long {
+    with_connection(conn, |connection| connection.exec(to_str(sql, len)?))
+        .map(|rows| rows.try_into().unwrap_or(zend_long::MAX))
+        .unwrap_or(-1)
+}
+
+unsafe extern "C" fn quote(conn: *mut c_void, s: *const c_char, len: usize) -> *mut zend_string {
+    with_connection(conn, |connection| connection.quote(to_str(s, len)?))
+        .map(|quoted| ZString::new(quoted).into_raw())
+        .unwrap_or(null_mut())
+}
+
+unsafe extern "C" fn begin(conn: *mut c_void) -> bool {
+    with_connection(conn, |connection| connection.begin()).is_some()
+}
+
+unsafe extern "C" fn commit(conn: *mut c_void) -> bool {
+    with_connection(conn, |connection| connection.commit()).is_some()
+}
+
+unsafe extern "C" fn rollback(conn: *mut c_void) -> bool {
+    with_connection(conn, |connection| connection.rollback()).is_some()
+}
+
+unsafe extern "C" fn last_id(
+    conn: *mut c_void, name: *const c_char, len: usize,
+) -> *mut zend_string {
+    with_connection(conn, |connection| {
+        let name = if name.is_null() {
+            None
+        } else {
+            Some(to_str(name, len)?)
+        };
+        connection.last_insert_id(name)
+    })
+    .map(|id| ZString::new(id).into_raw())
+    .unwrap_or(null_mut())
+}
+
+unsafe fn last_error<'a>(conn: *mut c_void, stmt: *mut c_void) -> Option<&'a PdoError> {
+    if stmt.is_null() {
+        connection(conn).error.as_ref()
+    } else {
+        statement(stmt).error.as_ref()
+    }
+}
+
+unsafe extern "C" fn sqlstate(conn: *mut c_void, stmt: *mut c_void, buf: *mut c_char) {
+    match last_error(conn, stmt) {
+        Some(e) => e.write_sqlstate(buf),
+        None => PdoError::general("").write_sqlstate(buf),
+    }
+}
+
+unsafe extern "C" fn fetch_error(conn: *mut c_void, stmt: *mut c_void, info: *mut zval) {
+    if let Some(e) = last_error(conn, stmt) {
+        if let Some(info) = ZVal::from_mut_ptr(info).as_mut_z_arr() {
+            info.insert(InsertKey::NextIndex, e.code());
+            info.insert(InsertKey::NextIndex, e.message());
+        }
+    }
+}
+
+unsafe extern "C" fn stmt_dtor(stmt: *mut c_void) {
+    drop(Box::from_raw(stmt.cast::<StatementState>()));
+}
+
+unsafe extern "C" fn stmt_execute(
+    stmt: *mut c_void, sql: *const c_char, len: usize, column_count: *mut zend_long,
+    row_count: *mut zend_long,
+) -> bool {
+    with_statement(stmt, |statement| {
+        statement.execute(to_str(sql, len)?)?;
+        *column_count = statement
+            .column_count()
+            .try_into()
+            .unwrap_or(zend_long::MAX);
+        *row_count = statement.row_count().try_into().unwrap_or(zend_long::MAX);
+        Ok(())
+    })
+    .is_some()
+}
+
+unsafe extern "C" fn stmt_fetch(stmt: *mut c_void) -> c_int {
+    match with_statement(stmt, |statement| statement.fetch()) {
+        Some(true) => 1,
+        Some(false) => 0,
+        None => -1,
+    }
+}
+
+unsafe extern "C" fn stmt_column_name(stmt: *mut c_void, colno: c_int) -> *mut zend_string {
+    with_statement(stmt, |statement| statement.column_name(colno as usize))
+        .map(|name| ZString::new(name).into_raw())
+        .unwrap_or(null_mut())
+}
+
+unsafe extern "C" fn stmt_get_col(stmt: *mut c_void, colno: c_int, result: *mut zval) -> bool {
+    match with_statement(stmt, |statement| statement.column_value(colno as usize)) {
+        Some(value) => {
+            write(result, value.into_inner());
+            true
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to pooling the expensive objects, like the connections of
+//! clients, keyed by the configuration.
+//!
+//! The pool is registered by
+//! [`Module::add_pool`](crate::modules::Module::add_pool), the returned handle
+//! is cheap to clone, and moved into the handlers. The objects are reused
+//! across the requests of process:
+//!
+//! - [`Pool::with_request`] checks out the object for the current request, the
+//!   same object is used in the whole request, and returned to the pool in
+//!   `RSHUTDOWN`.
+//!
+//! - [`Pool::checkout`] checks out the object until the [`Pooled`] guard is
+//!   dropped.
+//!
+//! The idle objects are dropped in `MSHUTDOWN`.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug},
+    hash::Hash,
+    mem::take,
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex, MutexGuard},
+    thread::{self, ThreadId},
+};
+
+type HealthCheck<T> = dyn Fn(&mut T) -> bool + Send + Sync;
+
+struct PoolInner<K, T> {
+    max_idle: usize,
+    health_check: Option<Arc<HealthCheck<T>>>,
+    idle: HashMap<K, Vec<T>>,
+    /// The objects checked out by the requests, the requests of ZTS are
+    /// distinguished by the threads.
+    requests: HashMap<(ThreadId, K), T>,
+}
+
+/// The pool of objects keyed by the configuration `K`, for example, the
+/// endpoint of server.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{modules::Module, values::ZVal};
+/// use std::net::TcpStream;
+///
+/// let mut module = Module::new("foo", "0.1.0", "");
+/// let pool = module.add_pool::<String, TcpStream>(8);
+/// pool.set_health_check(|stream| stream.peer_addr().is_ok());
+///
+/// module.add_function("foo_send", move |arguments: &mut [ZVal]| {
+///     let endpoint = arguments[0].expect_str()?.to_owned();
+///     pool.with_request(
+///         &endpoint,
+///         |endpoint| Ok(TcpStream::connect(endpoint)?),
+///         |_stream| {
+///             // Use the stream.
+///         },
+///     )
+/// });
+/// ```
+pub struct Pool<K, T> {
+    inner: Arc<Mutex<PoolInner<K, T>>>,
+}
+
+impl<K, T> Clone for Pool<K, T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K, T> Debug for Pool<K, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pool").finish_non_exhaustive()
+    }
+}
+
+impl<K: Eq + Hash + Clone, T> Pool<K, T> {
+    pub(crate) fn new(max_idle: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(PoolInner {
+                max_idle,
+                health_check: None,
+                idle: HashMap::new(),
+                requests: HashMap::new(),
+            })),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PoolInner<K, T>> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Set the health check of the idle objects, called before they are
+    /// checked out, the unhealthy objects are dropped.
+    pub fn set_health_check(&self, f: impl Fn(&mut T) -> bool + Send + Sync + 'static) {
+        self.lock().health_check = Some(Arc::new(f));
+    }
+
+    /// Takes a healthy idle object of `key`, or creates a new one by `create`.
+    fn take(&self, key: &K, create: impl FnOnce(&K) -> crate::Result<T>) -> crate::Result<T> {
+        loop {
+            let (value, health_check) = {
+                let mut inner = self.lock();
+                let value = inner.idle.get_mut(key).and_then(Vec::pop);
+                (value, inner.health_check.clone())
+            };
+            let Some(mut value) = value else {
+                return create(key);
+            };
+            // The health check may block, like pinging the server, so it's called
+            // without holding the lock.
+            if health_check.map_or(true, |f| f(&mut value)) {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Returns the object to the idle objects of `key`, dropped if the idle
+    /// objects are full.
+    fn give_back(&self, key: K, value: T) {
+        let mut inner = self.lock();
+        let max_idle = inner.max_idle;
+        let idle = inner.idle.entry(key).or_default();
+        if idle.len() < max_idle {
+            idle.push(value);
+        }
+    }
+
+    /// Checks out the object of `key` until the returned guard is dropped,
+    /// the object is created by `create` if there is no healthy idle one.
+    pub fn checkout(
+        &self, key: &K, create: impl FnOnce(&K) -> crate::Result<T>,
+    ) -> crate::Result<Pooled<K, T>> {
+        let value = self.take(key, create)?;
+        Ok(Pooled {
+            pool: self.clone(),
+            inner: Some((key.clone(), value)),
+        })
+    }
+
+    /// Calls `f` with the object of `key` checked out for the current
+    /// request, the object is kept by the request until `RSHUTDOWN`, then
+    /// returned to the pool.
+    ///
+    /// The object is dropped rather than returned if `f` panics.
+    pub fn with_request<R>(
+        &self, key: &K, create: impl FnOnce(&K) -> crate::Result<T>, f: impl FnOnce(&mut T) -> R,
+    ) -> crate::Result<R> {
+        let request_key = (thread::current().id(), key.clone());
+        let value = self.lock().requests.remove(&request_key);
+        let mut value = match value {
+            Some(value) => value,
+            None => self.take(key, create)?,
+        };
+        let r = f(&mut value);
+        self.lock().requests.insert(request_key, value);
+        Ok(r)
+    }
+
+    /// Gets the count of idle objects of `key`.
+    pub fn idle_len(&self, key: &K) -> usize {
+        self.lock().idle.get(key).map_or(0, Vec::len)
+    }
+
+    /// Drops all the idle objects.
+    pub fn clear(&self) {
+        self.lock().idle.clear();
+    }
+}
+
+/// The lifecycle of pools, managed by module.
+pub(crate) trait PoolEntity {
+    /// Returns the objects checked out by the current request, called in
+    /// `RSHUTDOWN`.
+    fn release_request(&self);
+
+    /// Drops all the objects, called in `MSHUTDOWN`.
+    fn shutdown(&self);
+}
+
+impl<K: Eq + Hash + Clone, T> PoolEntity for Pool<K, T> {
+    fn release_request(&self) {
+        let current = thread::current().id();
+        let released = {
+            let mut inner = self.lock();
+            let keys = inner
+                .requests
+                .keys()
+                .filter(|(id, _)| *id == current)
+                .cloned()
+                .collect::<Vec<_>>();
+            keys.into_iter()
+                .filter_map(|key| inner.requests.remove_entry(&key))
+                .collect::<Vec<_>>()
+        };
+        for ((_, key), value) in released {
+            self.give_back(key, value);
+        }
+    }
+
+    fn shutdown(&self) {
+        // Dropped without holding the lock, the objects may be slow to close.
+        let _objects = {
+            let mut inner = self.lock();
+            (take(&mut inner.idle), take(&mut inner.requests))
+        };
+    }
+}
+
+/// The object checked out by [`Pool::checkout`], returned to the pool when
+/// dropped.
+pub struct Pooled<K: Eq + Hash + Clone, T> {
+    pool: Pool<K, T>,
+    inner: Option<(K, T)>,
+}
+
+impl<K: Eq + Hash + Clone, T> Pooled<K, T> {
+    /// Drops the object rather than returning it to the pool, for example,
+    /// the connection is broken.
+    pub fn discard(mut self) {
+        self.inner = None;
+    }
+}
+
+impl<K: Eq + Hash + Clone, T> Deref for Pooled<K, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner.as_ref().unwrap().1
+    }
+}
+
+impl<K: Eq + Hash + Clone, T> DerefMut for Pooled<K, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner.as_mut().unwrap().1
+    }
+}
+
+impl<K: Eq + Hash + Clone, T> Drop for Pooled<K, T> {
+    fn drop(&mut self) {
+        if let Some((key, value)) = self.inner.take() {
+            self.pool.give_back(key, value);
+        }
+    }
+}
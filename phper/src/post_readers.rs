@@ -0,0 +1,112 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to parsing the POST body of custom content types into `$_POST`,
+//! like `application/x-www-form-urlencoded` by PHP itself.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use phper::{
+//!     arrays::{InsertKey, ZArr},
+//!     modules::Module,
+//!     values::ZVal,
+//! };
+//!
+//! fn register(module: &mut Module) {
+//!     module.add_post_reader(
+//!         "application/x-ndjson",
+//!         |_content_type: &str, body: &[u8], post: &mut ZArr| {
+//!             for line in body.split(|b| *b == b'\n').filter(|line| !line.is_empty()) {
+//!                 post.insert(InsertKey::NextIndex, ZVal::from(line));
+//!             }
+//!             Ok(())
+//!         },
+//!     );
+//! }
+//! ```
+
+use crate::{
+    arrays::ZArr, contexts::ModuleContext, modules::global_module, strings::ZStr, sys::*,
+    utils::ensure_end_with_zero, values::ZVal,
+};
+use std::{
+    ffi::{CStr, CString},
+    os::raw::c_char,
+};
+
+pub(crate) type PostReader = dyn Fn(&str, &[u8], &mut ZArr) -> crate::Result<()>;
+
+pub(crate) struct PostReaderEntity {
+    content_type: CString,
+    reader: Box<PostReader>,
+}
+
+impl PostReaderEntity {
+    pub(crate) fn new(
+        content_type: impl Into<String>,
+        reader: impl Fn(&str, &[u8], &mut ZArr) -> crate::Result<()> + 'static,
+    ) -> Self {
+        Self {
+            content_type: ensure_end_with_zero(content_type.into().to_ascii_lowercase()),
+            reader: Box::new(reader),
+        }
+    }
+
+    pub(crate) fn content_type(&self) -> &CStr {
+        &self.content_type
+    }
+
+    /// Register to the SAPI, must be called in `MINIT`.
+    pub(crate) unsafe fn register(&self, _ctx: &ModuleContext<'_>) -> bool {
+        phper_register_post_reader(
+            self.content_type.as_ptr(),
+            self.content_type.as_bytes().len(),
+            Some(handle),
+        )
+    }
+
+    pub(crate) unsafe fn unregister(&self) {
+        phper_unregister_post_reader(
+            self.content_type.as_ptr(),
+            self.content_type.as_bytes().len(),
+        );
+    }
+}
+
+unsafe extern "C" fn handle(content_type: *const c_char, body: *mut zend_string, post: *mut zval) {
+    let content_type = CStr::from_ptr(content_type).to_string_lossy();
+    let mime = content_type
+        .split(|c| c == ';' || c == ',' || c == ' ')
+        .next()
+        .unwrap_or_default();
+
+    let Some(entity) = global_module()
+        .post_reader_entities()
+        .iter()
+        .find(|entity| {
+            entity
+                .content_type
+                .to_bytes()
+                .eq_ignore_ascii_case(mime.as_bytes())
+        })
+    else {
+        return;
+    };
+
+    let Some(post) = ZVal::from_mut_ptr(post).as_mut_z_arr() else {
+        return;
+    };
+    let body = ZStr::from_ptr(body);
+
+    if let Err(e) = (entity.reader)(&content_type, body.to_bytes(), post) {
+        crate::warning!("failed to read the POST data of {}: {}", mime, e);
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to the identity of the current process, for tagging the
+//! background work and the logs by the worker.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use phper::processes;
+//!
+//! fn log_prefix() -> phper::Result<String> {
+//!     // Like `fpm-fcgi[www]#1234`.
+//!     Ok(processes::worker_info()?.to_string())
+//! }
+//! ```
+
+use crate::{arrays::ZArr, constants, functions, values::ZVal};
+use std::{fmt, process};
+
+/// Sets the title of process shown in `ps` and `top`, by
+/// `cli_set_process_title()`.
+///
+/// Returns `false` if the SAPI doesn't support it, only the cli does.
+pub fn set_title(title: &str) -> crate::Result<bool> {
+    if !function_exists("cli_set_process_title") {
+        return Ok(false);
+    }
+    let result = functions::call("cli_set_process_title", [ZVal::from(title)])?;
+    Ok(result.as_bool().unwrap_or(false))
+}
+
+/// Gets the title of process set by [`set_title`], by
+/// `cli_get_process_title()`.
+pub fn title() -> crate::Result<Option<String>> {
+    if !function_exists("cli_get_process_title") {
+        return Ok(None);
+    }
+    let result = functions::call("cli_get_process_title", [])?;
+    Ok(result
+        .as_z_str()
+        .map(|s| String::from_utf8_lossy(s.to_bytes()).into_owned()))
+}
+
+/// The identity of the current worker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerInfo {
+    sapi: String,
+    pid: u32,
+    pool: Option<String>,
+    requests: Option<i64>,
+}
+
+impl WorkerInfo {
+    /// The name of SAPI, the same as `PHP_SAPI`, like `cli` or `fpm-fcgi`.
+    #[inline]
+    pub fn sapi(&self) -> &str {
+        &self.sapi
+    }
+
+    /// The id of process.
+    #[inline]
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// The name of php-fpm pool, like `www`, `None` for the other SAPIs.
+    #[inline]
+    pub fn pool(&self) -> Option<&str> {
+        self.pool.as_deref()
+    }
+
+    /// The count of requests served by the php-fpm worker, `None` for the
+    /// other SAPIs.
+    #[inline]
+    pub fn requests(&self) -> Option<i64> {
+        self.requests
+    }
+}
+
+impl fmt::Display for WorkerInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.sapi)?;
+        if let Some(pool) = &self.pool {
+            write!(f, "[{}]", pool)?;
+        }
+        write!(f, "#{}", self.pid)
+    }
+}
+
+/// Gets the identity of the current worker, the php-fpm pool is read from
+/// `fpm_get_status()` (PHP 7.3 or later).
+pub fn worker_info() -> crate::Result<WorkerInfo> {
+    let sapi = constants::get("PHP_SAPI")
+        .and_then(ZVal::as_z_str)
+        .map(|s| String::from_utf8_lossy(s.to_bytes()).into_owned())
+        .unwrap_or_default();
+    let pid = process::id();
+    let mut info = WorkerInfo {
+        sapi,
+        pid,
+        pool: None,
+        requests: None,
+    };
+
+    if function_exists("fpm_get_status") {
+        let status = functions::call("fpm_get_status", [])?;
+        if let Some(status) = status.as_z_arr() {
+            info.pool = status
+                .get("pool")
+                .and_then(ZVal::as_z_str)
+                .map(|s| String::from_utf8_lossy(s.to_bytes()).into_owned());
+            info.requests = find_proc(status, pid)
+                .and_then(|proc| proc.get("requests"))
+                .and_then(ZVal::as_long);
+        }
+    }
+
+    Ok(info)
+}
+
+/// Finds the status of process `pid` in `procs` of `fpm_get_status()`.
+fn find_proc(status: &ZArr, pid: u32) -> Option<&ZArr> {
+    status
+        .get("procs")?
+        .as_z_arr()?
+        .iter()
+        .filter_map(|(_, proc)| proc.as_z_arr())
+        .find(|proc| proc.get("pid").and_then(ZVal::as_long) == Some(pid.into()))
+}
+
+fn function_exists(name: &str) -> bool {
+    unsafe { ZArr::from_ptr(eg!(function_table)) }.exists(name)
+}
@@ -0,0 +1,64 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to the cryptographically secure random number generator of
+//! PHP, the same source as the userland `random_bytes()` and `random_int()`.
+
+use crate::{errors::RandomError, sys::*};
+
+/// Fill the buffer with cryptographically secure random bytes, like
+/// `random_bytes()`.
+///
+/// # Errors
+///
+/// Return [`RandomError`] if the source of randomness can't be used.
+pub fn fill_bytes(buf: &mut [u8]) -> crate::Result<()> {
+    unsafe {
+        if phper_php_random_bytes(buf.as_mut_ptr().cast(), buf.len()) {
+            Ok(())
+        } else {
+            Err(RandomError.into())
+        }
+    }
+}
+
+/// Generate the cryptographically secure random bytes with the length, like
+/// `random_bytes()`.
+///
+/// # Errors
+///
+/// Return [`RandomError`] if the source of randomness can't be used.
+pub fn bytes(len: usize) -> crate::Result<Vec<u8>> {
+    let mut buf = vec![0; len];
+    fill_bytes(&mut buf)?;
+    Ok(buf)
+}
+
+/// Generate the cryptographically secure random integer between `min` and
+/// `max` inclusively, like `random_int()`.
+///
+/// # Errors
+///
+/// Return [`RandomError`] if the source of randomness can't be used.
+///
+/// # Panics
+///
+/// Panics if `min` is greater than `max`.
+pub fn int(min: i64, max: i64) -> crate::Result<i64> {
+    assert!(min <= max, "min must be less than or equal to max");
+    let mut result = 0;
+    unsafe {
+        if phper_php_random_int(min, max, &mut result) {
+            Ok(result)
+        } else {
+            Err(RandomError.into())
+        }
+    }
+}
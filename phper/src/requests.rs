@@ -0,0 +1,509 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to the lifecycle of the request, like cancelling the native work
+//! started in the request, the remaining time of `max_execution_time`, and the
+//! request headers, cookies and uploaded files.
+
+use crate::{
+    arrays::{IterKey, Key, ZArr},
+    errors::CancelledError,
+    sys::*,
+    values::ZVal,
+};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    mem::take,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+thread_local! {
+    static CURRENT: RefCell<Option<RequestToken>> = RefCell::new(None);
+}
+
+type Callback = Box<dyn FnOnce() + Send>;
+
+struct Inner {
+    cancelled: AtomicBool,
+    callbacks: Mutex<Vec<Callback>>,
+    condvar: Condvar,
+}
+
+/// The cancellation token of the current request, which is cancelled
+/// automatically in `RSHUTDOWN`, including the request aborted by the fatal
+/// error or `exit()`.
+///
+/// The background tasks and the long-running native calls started in the
+/// request should hold a clone of the token, and abort once it is cancelled,
+/// instead of outliving the request and touching the memory freed with it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{requests::RequestToken, values::ZVal};
+/// use std::{thread, time::Duration};
+///
+/// fn start_polling(_: &mut [ZVal]) -> phper::Result<()> {
+///     let token = RequestToken::current();
+///     thread::spawn(move || {
+///         while !token.wait_timeout(Duration::from_millis(100)) {
+///             // Poll something here.
+///         }
+///     });
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct RequestToken {
+    inner: Arc<Inner>,
+}
+
+impl RequestToken {
+    fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                cancelled: AtomicBool::new(false),
+                callbacks: Mutex::new(Vec::new()),
+                condvar: Condvar::new(),
+            }),
+        }
+    }
+
+    /// Gets the token of the current request.
+    ///
+    /// Outside of the request, like in `MINIT` or in the threads not running
+    /// PHP, the cancelled token is returned, because there is no request to
+    /// keep the work alive.
+    pub fn current() -> Self {
+        CURRENT.with(|current| match &*current.borrow() {
+            Some(token) => token.clone(),
+            None => {
+                let token = Self::new();
+                token.cancel();
+                token
+            }
+        })
+    }
+
+    /// Whether the request is shutting down.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Returns the error if the request is shutting down, for the early return
+    /// by `?`.
+    pub fn check(&self) -> Result<(), CancelledError> {
+        if self.is_cancelled() {
+            Err(CancelledError)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Blocks the current thread until the request is shutting down, or the
+    /// `timeout` elapses, returns whether the request is shutting down.
+    ///
+    /// Used as the interruptible sleep in the background loops, don't call it
+    /// in the PHP thread, which will never be woken up by the cancellation.
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let callbacks = self.inner.callbacks.lock().unwrap();
+        let _guard = self
+            .inner
+            .condvar
+            .wait_timeout_while(callbacks, timeout, |_| !self.is_cancelled())
+            .unwrap();
+        self.is_cancelled()
+    }
+
+    /// Registers the callback called when the request is shutting down, like
+    /// closing the socket to wake up the blocking read.
+    ///
+    /// The callbacks are called in `RSHUTDOWN` in the PHP thread, or
+    /// immediately in the current thread if the request is already shutting
+    /// down.
+    pub fn on_cancel(&self, f: impl FnOnce() + Send + 'static) {
+        let mut callbacks = self.inner.callbacks.lock().unwrap();
+        if self.is_cancelled() {
+            drop(callbacks);
+            f();
+        } else {
+            callbacks.push(Box::new(f));
+        }
+    }
+
+    fn cancel(&self) {
+        let callbacks = {
+            let mut callbacks = self.inner.callbacks.lock().unwrap();
+            if self.inner.cancelled.swap(true, Ordering::AcqRel) {
+                return;
+            }
+            take(&mut *callbacks)
+        };
+        self.inner.condvar.notify_all();
+        for callback in callbacks {
+            callback();
+        }
+    }
+}
+
+impl fmt::Debug for RequestToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// Gets the remaining time before the script is killed by
+/// `max_execution_time`, `None` if there is no time limit.
+///
+/// The timer of `max_execution_time` measures the CPU time on most of the
+/// platforms, so the remaining wall time is usually longer than returned,
+/// which is fine for the timeout of native calls.
+pub fn remaining_time() -> Option<Duration> {
+    let mut seconds = 0.;
+    unsafe {
+        if !phper_remaining_execution_time(&mut seconds) {
+            return None;
+        }
+    }
+    Some(Duration::from_secs_f64(seconds.max(0.)))
+}
+
+/// Calls `f` with the deadline computed from the remaining time of
+/// `max_execution_time`, `None` if there is no time limit.
+///
+/// Used to bound the native calls, like the timeout of HTTP client, so that
+/// they give up by themselves, rather than being killed in the middle of
+/// operation by the timeout of PHP.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{requests::with_remaining_time, values::ZVal};
+/// use std::time::{Duration, Instant};
+///
+/// fn fetch(_: &mut [ZVal]) -> phper::Result<()> {
+///     with_remaining_time(|deadline| {
+///         let timeout = deadline
+///             .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+///             .unwrap_or(Duration::from_secs(30));
+///         // Send the request with the timeout here.
+///         let _ = timeout;
+///     });
+///     Ok(())
+/// }
+/// ```
+pub fn with_remaining_time<R>(f: impl FnOnce(Option<Instant>) -> R) -> R {
+    let now = Instant::now();
+    f(remaining_time().map(|remaining| now + remaining))
+}
+
+/// The headers of the current request, with the names normalized to lowercase
+/// and hyphen separated, like `x-forwarded-for`.
+#[derive(Debug, Clone, Default)]
+pub struct RequestHeaders {
+    headers: Vec<(String, String)>,
+}
+
+impl RequestHeaders {
+    /// Gets the value of the header, the name is case-insensitive.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Whether the header exists, the name is case-insensitive.
+    #[inline]
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Iterates the headers, as the name and value pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// The count of headers.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.headers.len()
+    }
+
+    /// Whether there is no header, always true in the cli.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
+}
+
+impl IntoIterator for RequestHeaders {
+    type IntoIter = std::vec::IntoIter<(String, String)>;
+    type Item = (String, String);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.headers.into_iter()
+    }
+}
+
+/// Gets the headers of the current request, collected from the `HTTP_*`
+/// entries of `$_SERVER`, plus the `CONTENT_TYPE` and `CONTENT_LENGTH`, which
+/// the CGI convention used by the web SAPIs puts without the `HTTP_` prefix.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::requests;
+///
+/// fn trace_parent() -> Option<String> {
+///     requests::headers()
+///         .get("traceparent")
+///         .map(ToOwned::to_owned)
+/// }
+/// ```
+pub fn headers() -> RequestHeaders {
+    let Some(server) = auto_global("_SERVER") else {
+        return RequestHeaders::default();
+    };
+
+    let mut headers = Vec::new();
+    for (key, value) in server.iter() {
+        let IterKey::ZStr(key) = key else {
+            continue;
+        };
+        let Ok(key) = key.to_str() else {
+            continue;
+        };
+        let name = match key.strip_prefix("HTTP_") {
+            Some(name) => name,
+            None if key == "CONTENT_TYPE" || key == "CONTENT_LENGTH" => key,
+            None => continue,
+        };
+        let Some(value) = value.as_z_str() else {
+            continue;
+        };
+        headers.push((
+            name.to_ascii_lowercase().replace('_', "-"),
+            String::from_utf8_lossy(value.to_bytes()).into_owned(),
+        ));
+    }
+    RequestHeaders { headers }
+}
+
+/// Gets the cookies of the current request from `$_COOKIE`, the cookies with
+/// the array value, like `name[key]=value`, are skipped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::requests;
+///
+/// fn session_id() -> Option<String> {
+///     requests::cookies().remove("session_id")
+/// }
+/// ```
+pub fn cookies() -> HashMap<String, String> {
+    let mut cookies = HashMap::new();
+    let Some(arr) = auto_global("_COOKIE") else {
+        return cookies;
+    };
+    for (key, value) in arr.iter() {
+        if value.as_z_str().is_some() {
+            cookies.insert(iter_key_to_string(key), z_val_to_string(Some(value)));
+        }
+    }
+    cookies
+}
+
+/// The file uploaded by the `multipart/form-data` request, same as the entry of
+/// `$_FILES`.
+#[derive(Debug, Clone)]
+pub struct UploadedFile {
+    field: String,
+    name: String,
+    mime_type: String,
+    tmp_name: String,
+    error: i64,
+    size: i64,
+}
+
+impl UploadedFile {
+    /// The form field name, the nested fields are flattened like `files[0]`.
+    #[inline]
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// The original file name on the client.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The mime type provided by the client, not checked by PHP.
+    #[inline]
+    pub fn mime_type(&self) -> &str {
+        &self.mime_type
+    }
+
+    /// The temporary file path on the server, removed at the end of request.
+    #[inline]
+    pub fn tmp_name(&self) -> &str {
+        &self.tmp_name
+    }
+
+    /// The error code, same as the `UPLOAD_ERR_*` constants, `0` means
+    /// success.
+    #[inline]
+    pub fn error(&self) -> i64 {
+        self.error
+    }
+
+    /// The size of file in bytes.
+    #[inline]
+    pub fn size(&self) -> i64 {
+        self.size
+    }
+
+    /// Whether the temporary file is really uploaded by the request, see
+    /// [`is_uploaded_file`].
+    #[inline]
+    pub fn is_uploaded(&self) -> bool {
+        is_uploaded_file(&self.tmp_name)
+    }
+}
+
+/// Gets the files uploaded by the current request from `$_FILES`, the nested
+/// fields are flattened.
+///
+/// The `$_FILES` may be modified by the userland, check
+/// [`UploadedFile::is_uploaded`] before trusting the temporary file.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::requests;
+///
+/// fn scan_uploads() {
+///     for file in requests::uploaded_files() {
+///         if file.error() == 0 && file.is_uploaded() {
+///             // Scan the file at `file.tmp_name()` here.
+///         }
+///     }
+/// }
+/// ```
+pub fn uploaded_files() -> Vec<UploadedFile> {
+    let mut files = Vec::new();
+    let Some(arr) = auto_global("_FILES") else {
+        return files;
+    };
+    for (field, entry) in arr.iter() {
+        let Some(entry) = entry.as_z_arr() else {
+            continue;
+        };
+        let Some(name) = entry.get("name") else {
+            continue;
+        };
+        let attrs = ["type", "tmp_name", "error", "size"].map(|key| entry.get(key));
+        collect_uploaded_files(&mut files, iter_key_to_string(field), name, attrs);
+    }
+    files
+}
+
+/// Whether the file is uploaded by the current request, like
+/// `is_uploaded_file()`, checked against the list kept by the SAPI, which
+/// can't be modified by the userland.
+pub fn is_uploaded_file(path: &str) -> bool {
+    unsafe { phper_is_uploaded_file(path.as_ptr().cast(), path.len()) }
+}
+
+fn collect_uploaded_files(
+    files: &mut Vec<UploadedFile>, field: String, name: &ZVal, attrs: [Option<&ZVal>; 4],
+) {
+    if let Some(names) = name.as_z_arr() {
+        for (key, name) in names.iter() {
+            let sub_attrs = attrs.map(|attr| {
+                attr.and_then(ZVal::as_z_arr)
+                    .and_then(|attr| attr.get(Key::from(key.clone())))
+            });
+            let sub_field = format!("{}[{}]", field, iter_key_to_string(key));
+            collect_uploaded_files(files, sub_field, name, sub_attrs);
+        }
+        return;
+    }
+
+    let [mime_type, tmp_name, error, size] = attrs;
+    files.push(UploadedFile {
+        field,
+        name: z_val_to_string(Some(name)),
+        mime_type: z_val_to_string(mime_type),
+        tmp_name: z_val_to_string(tmp_name),
+        error: error.and_then(ZVal::as_long).unwrap_or_default(),
+        size: size.and_then(ZVal::as_long).unwrap_or_default(),
+    });
+}
+
+fn iter_key_to_string(key: IterKey<'_>) -> String {
+    match key {
+        IterKey::Index(i) => i.to_string(),
+        IterKey::ZStr(s) => String::from_utf8_lossy(s.to_bytes()).into_owned(),
+    }
+}
+
+fn z_val_to_string(val: Option<&ZVal>) -> String {
+    val.and_then(ZVal::as_z_str)
+        .map(|s| String::from_utf8_lossy(s.to_bytes()).into_owned())
+        .unwrap_or_default()
+}
+
+/// Gets the auto global array like `$_SERVER`, triggers its initialization
+/// first, for the `auto_globals_jit` ini.
+fn auto_global<'a>(name: &str) -> Option<&'a ZArr> {
+    unsafe {
+        let arr = phper_auto_global(name.as_ptr().cast(), name.len());
+        if arr.is_null() {
+            None
+        } else {
+            Some(ZArr::from_ptr(arr))
+        }
+    }
+}
+
+/// Creates the token of the new request, called in `RINIT`.
+pub(crate) fn enter_request() {
+    CURRENT.with(|current| {
+        if let Some(token) = current.replace(Some(RequestToken::new())) {
+            token.cancel();
+        }
+    });
+}
+
+/// Whether the current thread is running the request.
+pub(crate) fn in_request() -> bool {
+    CURRENT.with(|current| current.borrow().is_some())
+}
+
+/// Cancels the token of the current request, called in `RSHUTDOWN`.
+pub(crate) fn leave_request() {
+    if let Some(token) = CURRENT.with(|current| current.take()) {
+        token.cancel();
+    }
+}
@@ -0,0 +1,230 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to the response headers, through the SAPI header mechanism,
+//! consistent with the userland `header()` and `setcookie()`.
+
+use crate::{functions::call, sys::*, values::ZVal};
+use std::{
+    fmt::Write,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Whether the headers are already sent, after that the headers can't be
+/// modified.
+pub fn headers_sent() -> bool {
+    unsafe { phper_sapi_headers_sent() }
+}
+
+/// Sends the raw header line like `header()`, replaces the previous header
+/// with the same name if `replace` is true.
+///
+/// # Errors
+///
+/// Return the error if the headers are already sent, or the header line is
+/// rejected by the SAPI, like containing the line breaks.
+pub fn header(line: &str, replace: bool) -> crate::Result<()> {
+    if headers_sent() {
+        return Err(crate::Error::runtime(
+            "Cannot modify header information - headers already sent",
+        ));
+    }
+    if unsafe { phper_sapi_header(line.as_ptr().cast(), line.len(), replace) } {
+        Ok(())
+    } else {
+        Err(crate::Error::runtime(format!(
+            "Failed to send header \"{}\"",
+            line
+        )))
+    }
+}
+
+/// The `SameSite` attribute of cookie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    /// `SameSite=Strict`.
+    Strict,
+    /// `SameSite=Lax`.
+    Lax,
+    /// `SameSite=None`, requires the `Secure` attribute by the browsers.
+    None,
+}
+
+impl SameSite {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        }
+    }
+}
+
+/// The cookie sent by [`set_cookie`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::responses::{self, Cookie, SameSite};
+///
+/// fn login() -> phper::Result<()> {
+///     let mut cookie = Cookie::new("session_id", "abc");
+///     cookie
+///         .path("/")
+///         .secure(true)
+///         .http_only(true)
+///         .same_site(SameSite::Lax);
+///     responses::set_cookie(&cookie)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cookie {
+    name: String,
+    value: String,
+    expires: Option<i64>,
+    path: Option<String>,
+    domain: Option<String>,
+    secure: bool,
+    http_only: bool,
+    same_site: Option<SameSite>,
+}
+
+impl Cookie {
+    /// Constructs the session cookie, the value is url encoded when sent.
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            expires: None,
+            path: None,
+            domain: None,
+            secure: false,
+            http_only: false,
+            same_site: None,
+        }
+    }
+
+    /// Sets the expiration time as the unix timestamp, sent as both `expires`
+    /// and `Max-Age`.
+    pub fn expires(&mut self, timestamp: i64) -> &mut Self {
+        self.expires = Some(timestamp);
+        self
+    }
+
+    /// Sets the `path` attribute.
+    pub fn path(&mut self, path: impl Into<String>) -> &mut Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Sets the `domain` attribute.
+    pub fn domain(&mut self, domain: impl Into<String>) -> &mut Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Sets the `secure` attribute.
+    pub fn secure(&mut self, secure: bool) -> &mut Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Sets the `HttpOnly` attribute.
+    pub fn http_only(&mut self, http_only: bool) -> &mut Self {
+        self.http_only = http_only;
+        self
+    }
+
+    /// Sets the `SameSite` attribute.
+    pub fn same_site(&mut self, same_site: SameSite) -> &mut Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    /// Builds the value of `Set-Cookie` header.
+    ///
+    /// # Errors
+    ///
+    /// Return the error if the name is empty or contains the characters
+    /// `=,; \t\r\n\013\014`, or the attributes contain the characters
+    /// `,; \t\r\n\013\014`, same as `setcookie()`.
+    pub fn to_header_value(&self) -> crate::Result<String> {
+        const INVALID: &[char] = &[',', ';', ' ', '\t', '\r', '\n', '\x0b', '\x0c'];
+
+        if self.name.is_empty() || self.name.contains('=') || self.name.contains(INVALID) {
+            return Err(crate::Error::argument(format!(
+                "Invalid cookie name \"{}\"",
+                self.name
+            )));
+        }
+        for (attr, value) in [("path", &self.path), ("domain", &self.domain)] {
+            if let Some(value) = value {
+                if value.contains(INVALID) {
+                    return Err(crate::Error::argument(format!(
+                        "Invalid cookie {} \"{}\"",
+                        attr, value
+                    )));
+                }
+            }
+        }
+
+        let mut s = format!("{}=", self.name);
+        for b in self.value.bytes() {
+            if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+                s.push(b as char);
+            } else {
+                write!(s, "%{:02X}", b).unwrap();
+            }
+        }
+        if let Some(expires) = self.expires {
+            let date = call(
+                "gmdate",
+                [ZVal::from("D, d M Y H:i:s \\G\\M\\T"), ZVal::from(expires)],
+            )?;
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs() as i64);
+            let max_age = (expires - now).max(0);
+            write!(
+                s,
+                "; expires={}; Max-Age={}",
+                date.expect_z_str()?.to_str()?,
+                max_age
+            )
+            .unwrap();
+        }
+        if let Some(path) = &self.path {
+            write!(s, "; path={}", path).unwrap();
+        }
+        if let Some(domain) = &self.domain {
+            write!(s, "; domain={}", domain).unwrap();
+        }
+        if self.secure {
+            s.push_str("; secure");
+        }
+        if self.http_only {
+            s.push_str("; HttpOnly");
+        }
+        if let Some(same_site) = self.same_site {
+            write!(s, "; SameSite={}", same_site.as_str()).unwrap();
+        }
+        Ok(s)
+    }
+}
+
+/// Sends the cookie like `setcookie()`, by the `Set-Cookie` header.
+///
+/// # Errors
+///
+/// Return the error if the cookie is invalid, see
+/// [`Cookie::to_header_value`], or the headers are already sent.
+pub fn set_cookie(cookie: &Cookie) -> crate::Result<()> {
+    header(&format!("Set-Cookie: {}", cookie.to_header_value()?), false)
+}
@@ -0,0 +1,91 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to serializing and unserializing PHP values, consistent with
+//! the userland `serialize()` and `unserialize()`.
+
+use crate::{
+    arrays::ZArray, errors::UnserializeError, functions::call, strings::ZString, values::ZVal,
+};
+
+/// The classes allowed to be instantiated while unserializing, same as the
+/// `allowed_classes` option of `unserialize()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AllowedClasses {
+    /// All classes are allowed.
+    #[default]
+    All,
+    /// No classes are allowed, the objects are unserialized as
+    /// `__PHP_Incomplete_Class`.
+    None,
+    /// Only the specified classes are allowed, case-insensitive.
+    Only(Vec<String>),
+}
+
+impl AllowedClasses {
+    fn to_z_val(&self) -> ZVal {
+        match self {
+            AllowedClasses::All => ZVal::from(true),
+            AllowedClasses::None => ZVal::from(false),
+            AllowedClasses::Only(names) => {
+                let mut arr = ZArray::with_capacity(names.len());
+                for name in names {
+                    arr.insert((), ZVal::from(name.as_str()));
+                }
+                ZVal::from(arr)
+            }
+        }
+    }
+}
+
+/// Serialize the value, like `serialize()`.
+///
+/// # Errors
+///
+/// Return the exception thrown while serializing, for example, by
+/// `__serialize()` or serializing a `Closure`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{serialize, values::ZVal};
+///
+/// fn example() -> phper::Result<()> {
+///     let data = serialize::serialize(&ZVal::from("foo"))?;
+///     assert_eq!(data.to_str()?, r#"s:3:"foo";"#);
+///     let value = serialize::unserialize(data.to_bytes(), &Default::default())?;
+///     assert_eq!(value.expect_z_str()?.to_str()?, "foo");
+///     Ok(())
+/// }
+/// ```
+pub fn serialize(value: &ZVal) -> crate::Result<ZString> {
+    let ret = call("serialize", [value.clone()])?;
+    Ok(ret.expect_z_str()?.to_owned())
+}
+
+/// Unserialize the data, like `unserialize()`, the objects of classes not
+/// allowed are unserialized as `__PHP_Incomplete_Class`.
+///
+/// # Errors
+///
+/// Return [`UnserializeError`] if the data is malformed, or the exception
+/// thrown while unserializing, for example, by `__unserialize()`.
+pub fn unserialize(
+    data: impl AsRef<[u8]>, allowed_classes: &AllowedClasses,
+) -> crate::Result<ZVal> {
+    let data = data.as_ref();
+    let mut options = ZArray::new();
+    options.insert("allowed_classes", allowed_classes.to_z_val());
+    let ret = call("unserialize", [ZVal::from(data), ZVal::from(options)])?;
+    if ret.as_bool() == Some(false) && data != b"b:0;" {
+        return Err(UnserializeError.into());
+    }
+    Ok(ret)
+}
@@ -0,0 +1,246 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to implementing the session save handlers, so
+//! `session.save_handler=name` is served by Rust.
+//!
+//! Require the `session` feature, only available on PHP 7.1 or later.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use phper::{modules::Module, sessions::SessionHandler};
+//! use std::{cell::RefCell, collections::HashMap};
+//!
+//! #[derive(Default)]
+//! struct MemoryHandler(RefCell<HashMap<String, Vec<u8>>>);
+//!
+//! impl SessionHandler for MemoryHandler {
+//!     fn read(&self, id: &str) -> phper::Result<Vec<u8>> {
+//!         Ok(self.0.borrow().get(id).cloned().unwrap_or_default())
+//!     }
+//!
+//!     fn write(&self, id: &str, data: &[u8], _max_lifetime: i64) -> phper::Result<()> {
+//!         self.0.borrow_mut().insert(id.to_owned(), data.to_owned());
+//!         Ok(())
+//!     }
+//!
+//!     fn destroy(&self, id: &str) -> phper::Result<()> {
+//!         self.0.borrow_mut().remove(id);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! fn register(module: &mut Module) {
+//!     module.add_session_handler("memory", MemoryHandler::default());
+//! }
+//! ```
+
+use crate::{
+    contexts::ModuleContext,
+    strings::{ZStr, ZString},
+    sys::*,
+    utils::ensure_end_with_zero,
+};
+use std::{
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_void},
+    ptr::null_mut,
+};
+
+/// The session save handler, registered by
+/// [`Module::add_session_handler`](crate::modules::Module::add_session_handler).
+///
+/// The failed operations emit the warnings with the error messages, and the
+/// session extension reports them as failed.
+pub trait SessionHandler {
+    /// Called by `session_start()`, before the other operations.
+    fn open(&self, save_path: &str, session_name: &str) -> crate::Result<()> {
+        let _ = (save_path, session_name);
+        Ok(())
+    }
+
+    /// Called when the session is written or aborted.
+    fn close(&self) -> crate::Result<()> {
+        Ok(())
+    }
+
+    /// Read the serialized session data, empty if the session doesn't exist.
+    fn read(&self, id: &str) -> crate::Result<Vec<u8>>;
+
+    /// Write the serialized session data.
+    fn write(&self, id: &str, data: &[u8], max_lifetime: i64) -> crate::Result<()>;
+
+    /// Destroy the session, called by `session_destroy()` and
+    /// `session_regenerate_id(true)`.
+    fn destroy(&self, id: &str) -> crate::Result<()>;
+
+    /// Delete the sessions not updated in `max_lifetime` seconds, returns the
+    /// count of deleted sessions.
+    ///
+    /// Does nothing by default, for the backends expiring the keys by
+    /// themselves.
+    fn gc(&self, max_lifetime: i64) -> crate::Result<u64> {
+        let _ = max_lifetime;
+        Ok(0)
+    }
+
+    /// Create the new session id, `None` to use the default generator.
+    fn create_sid(&self) -> Option<String> {
+        None
+    }
+
+    /// Check the session id exists, called when `session.use_strict_mode` is
+    /// enabled.
+    ///
+    /// Accepts all ids by default, so should be overridden for the strict
+    /// mode.
+    fn validate_sid(&self, id: &str) -> crate::Result<bool> {
+        let _ = id;
+        Ok(true)
+    }
+
+    /// Update the timestamp of the unchanged session, called instead of
+    /// [`SessionHandler::write`] when `session.lazy_write` is enabled.
+    ///
+    /// Writes the data again by default.
+    fn update_timestamp(&self, id: &str, data: &[u8], max_lifetime: i64) -> crate::Result<()> {
+        self.write(id, data, max_lifetime)
+    }
+}
+
+pub(crate) struct SessionHandlerEntity {
+    name: CString,
+    handler: Box<Box<dyn SessionHandler>>,
+}
+
+impl SessionHandlerEntity {
+    pub(crate) fn new(name: impl Into<String>, handler: impl SessionHandler + 'static) -> Self {
+        Self {
+            name: ensure_end_with_zero(name),
+            handler: Box::new(Box::new(handler)),
+        }
+    }
+
+    pub(crate) fn name(&self) -> &CStr {
+        &self.name
+    }
+
+    /// Register to `ext/session`, must be called in `MINIT`.
+    pub(crate) unsafe fn register(&mut self, _ctx: &ModuleContext<'_>) -> bool {
+        let handler = &mut *self.handler as *mut Box<dyn SessionHandler> as *mut c_void;
+        phper_session_register_module(self.name.as_ptr(), handler, &CALLBACKS)
+    }
+}
+
+static CALLBACKS: phper_session_callbacks = phper_session_callbacks {
+    open: Some(open),
+    close: Some(close),
+    read: Some(read),
+    write: Some(write),
+    destroy: Some(destroy),
+    gc: Some(gc),
+    create_sid: Some(create_sid),
+    validate_sid: Some(validate_sid),
+    update_timestamp: Some(update_timestamp),
+};
+
+unsafe fn handler<'a>(handler: *mut c_void) -> &'a dyn SessionHandler {
+    &**handler.cast::<Box<dyn SessionHandler>>()
+}
+
+/// Run the operation, emit the warning if failed.
+fn report<T>(result: crate::Result<T>) -> Option<T> {
+    match result {
+        Ok(t) => Some(t),
+        Err(e) => {
+            crate::warning!("{}", e);
+            None
+        }
+    }
+}
+
+unsafe fn key<'a>(key: *mut zend_string) -> crate::Result<&'a str> {
+    Ok(ZStr::from_ptr(key).to_str()?)
+}
+
+unsafe extern "C" fn open(
+    data: *mut c_void, save_path: *const c_char, name: *const c_char,
+) -> bool {
+    report((|| {
+        handler(data).open(
+            CStr::from_ptr(save_path).to_str()?,
+            CStr::from_ptr(name).to_str()?,
+        )
+    })())
+    .is_some()
+}
+
+unsafe extern "C" fn close(data: *mut c_void) -> bool {
+    report(handler(data).close()).is_some()
+}
+
+unsafe extern "C" fn read(
+    data: *mut c_void, id: *mut zend_string, val: *mut *mut zend_string,
+) -> bool {
+    match report(key(id).and_then(|id| handler(data).read(id))) {
+        Some(session) => {
+            *val = ZString::new(session).into_raw();
+            true
+        }
+        None => false,
+    }
+}
+
+unsafe extern "C" fn write(
+    data: *mut c_void, id: *mut zend_string, val: *mut zend_string, max_lifetime: zend_long,
+) -> bool {
+    report(
+        key(id)
+            .and_then(|id| handler(data).write(id, ZStr::from_ptr(val).to_bytes(), max_lifetime)),
+    )
+    .is_some()
+}
+
+unsafe extern "C" fn destroy(data: *mut c_void, id: *mut zend_string) -> bool {
+    report(key(id).and_then(|id| handler(data).destroy(id))).is_some()
+}
+
+unsafe extern "C" fn gc(
+    data: *mut c_void, max_lifetime: zend_long, deleted: *mut zend_long,
+) -> bool {
+    match report(handler(data).gc(max_lifetime)) {
+        Some(count) => {
+            *deleted = count.try_into().unwrap_or(zend_long::MAX);
+            true
+        }
+        None => false,
+    }
+}
+
+unsafe extern "C" fn create_sid(data: *mut c_void) -> *mut zend_string {
+    handler(data)
+        .create_sid()
+        .map(|sid| ZString::new(sid).into_raw())
+        .unwrap_or(null_mut())
+}
+
+unsafe extern "C" fn validate_sid(data: *mut c_void, id: *mut zend_string) -> bool {
+    report(key(id).and_then(|id| handler(data).validate_sid(id))) == Some(true)
+}
+
+unsafe extern "C" fn update_timestamp(
+    data: *mut c_void, id: *mut zend_string, val: *mut zend_string, max_lifetime: zend_long,
+) -> bool {
+    report(key(id).and_then(|id| {
+        handler(data).update_timestamp(id, ZStr::from_ptr(val).to_bytes(), max_lifetime)
+    }))
+    .is_some()
+}
@@ -0,0 +1,190 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to embedded PHP source code.
+
+use crate::{functions::call_raw_common, sys::*, utils::ensure_end_with_zero};
+use std::{
+    env,
+    ffi::{CStr, CString},
+    fs::{self, DirBuilder},
+    io,
+    path::PathBuf,
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Builder for registering embedded PHP source code.
+pub(crate) struct SourceEntity {
+    name: String,
+    code: Box<dyn AsRef<[u8]>>,
+    path: Option<CString>,
+}
+
+impl SourceEntity {
+    pub(crate) fn new(name: impl Into<String>, code: impl AsRef<[u8]> + 'static) -> Self {
+        Self {
+            name: name.into(),
+            code: Box::new(code),
+            path: None,
+        }
+    }
+
+    /// Execute the source written in `MINIT` like `include`, or evaluate the
+    /// source code if it isn't written or can't be opened, for example, the
+    /// workers run as the other user.
+    pub(crate) fn execute(&self) -> crate::Result<()> {
+        if let Some(path) = &self.path {
+            if include_file(path)? {
+                return Ok(());
+            }
+        }
+        eval_source((*self.code).as_ref(), &self.name)
+    }
+
+    fn write(&mut self, dir: &SourceDir, index: usize) -> io::Result<()> {
+        let file_name = self
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+        let path = dir.path.join(format!("{}-{}", index, file_name));
+
+        let code = trim_open_tag((*self.code).as_ref());
+        let mut content = b"<?php".to_vec();
+        if !code
+            .first()
+            .map(u8::is_ascii_whitespace)
+            .unwrap_or_default()
+        {
+            content.push(b' ');
+        }
+        content.extend_from_slice(code);
+        fs::write(&path, content)?;
+
+        let path = path
+            .into_os_string()
+            .into_string()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "path isn't utf-8"))?;
+        self.path = Some(CString::new(path)?);
+        Ok(())
+    }
+}
+
+/// The private directory holding the embedded sources written in `MINIT`, so
+/// the sources are compiled by `zend_compile_file` like the normal scripts,
+/// and the op arrays are cached by opcache across the requests and the forked
+/// workers, rather than compiled from string in every request.
+pub(crate) struct SourceDir {
+    path: PathBuf,
+    pid: u32,
+}
+
+impl SourceDir {
+    /// Write the sources into the new directory under the temp directory, the
+    /// directory is only writable by the current user, and the name is unique
+    /// so the files can't be planted in advance.
+    pub(crate) fn write_sources<'a>(
+        module_name: &CStr, sources: impl IntoIterator<Item = &'a mut SourceEntity>,
+    ) -> io::Result<Self> {
+        let mut sources = sources.into_iter().collect::<Vec<_>>();
+        let dir = Self::create(module_name)?;
+        for (index, source) in sources.iter_mut().enumerate() {
+            if let Err(e) = source.write(&dir, index) {
+                for source in sources.iter_mut() {
+                    source.path = None;
+                }
+                dir.remove();
+                return Err(e);
+            }
+        }
+        Ok(dir)
+    }
+
+    fn create(module_name: &CStr) -> io::Result<Self> {
+        let pid = process::id();
+        let mut builder = DirBuilder::new();
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o755);
+
+        let mut last_error = None;
+        for i in 0..8 {
+            let nanos = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.subsec_nanos())
+                .unwrap_or_default();
+            let path = env::temp_dir().join(format!(
+                "phper-{}-{}-{}-{}",
+                module_name.to_string_lossy(),
+                pid,
+                nanos,
+                i
+            ));
+            match builder.create(&path) {
+                Ok(()) => return Ok(Self { path, pid }),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap())
+    }
+
+    /// Only the process which has created the directory removes it, the
+    /// forked workers inherit it but not own it.
+    pub(crate) fn remove(self) {
+        if process::id() == self.pid {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+}
+
+/// Compile and execute the PHP file like `include`, returns `false` if the
+/// file can't be read.
+fn include_file(path: &CStr) -> crate::Result<bool> {
+    let mut compiled = true;
+    call_raw_common(|ret| unsafe {
+        compiled = phper_zend_include_file(path.as_ptr(), ret.as_mut_ptr());
+    })?;
+    Ok(compiled)
+}
+
+/// Compile and execute the PHP source code, the leading `<?php` tag will be
+/// stripped.
+fn eval_source(code: &[u8], name: &str) -> crate::Result<()> {
+    let code = trim_open_tag(code);
+    let code = CString::new(code).map_err(crate::Error::boxed)?;
+    let name = ensure_end_with_zero(name);
+
+    call_raw_common(|ret| unsafe {
+        #[allow(clippy::useless_conversion)]
+        zend_eval_stringl(
+            code.as_ptr() as *mut _,
+            code.as_bytes().len().try_into().unwrap(),
+            ret.as_mut_ptr(),
+            name.as_ptr() as *mut _,
+        );
+    })?;
+
+    Ok(())
+}
+
+fn trim_open_tag(code: &[u8]) -> &[u8] {
+    let start = code
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(code.len());
+    let code = &code[start..];
+    code.strip_prefix(b"<?php").unwrap_or(code)
+}
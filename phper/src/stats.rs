@@ -0,0 +1,127 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to the execution statistics of the registered functions.
+//!
+//! Enabled by [`Module::enable_stats`](crate::modules::Module::enable_stats),
+//! the calls and the cumulative time of every registered function and method
+//! are counted in the process, to help finding the hot entry points without
+//! wiring a profiler.
+
+use crate::modules::global_module;
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+/// The execution statistics of the registered function or method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionStats {
+    name: String,
+    calls: u64,
+    time: Duration,
+}
+
+impl FunctionStats {
+    /// The function name, or the fully-qualified method name like
+    /// `Foo\Bar::baz`.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The count of calls.
+    #[inline]
+    pub fn calls(&self) -> u64 {
+        self.calls
+    }
+
+    /// The cumulative time of calls.
+    #[inline]
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+}
+
+struct StatsEntry {
+    name: String,
+    calls: Cell<u64>,
+    time: Cell<Duration>,
+}
+
+/// The counters of handlers, indexed as the handlers of module.
+pub(crate) struct StatsRecorder {
+    entries: Vec<StatsEntry>,
+}
+
+impl StatsRecorder {
+    pub(crate) fn new(names: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            entries: names
+                .into_iter()
+                .map(|name| StatsEntry {
+                    name,
+                    calls: Cell::new(0),
+                    time: Cell::new(Duration::ZERO),
+                })
+                .collect(),
+        }
+    }
+
+    /// Call `f` and count it to the handler of `index`.
+    pub(crate) fn record<R>(&self, index: usize, f: impl FnOnce() -> R) -> R {
+        let start = Instant::now();
+        let r = f();
+        if let Some(entry) = self.entries.get(index) {
+            entry.calls.set(entry.calls.get() + 1);
+            entry.time.set(entry.time.get() + start.elapsed());
+        }
+        r
+    }
+
+    pub(crate) fn snapshot(&self) -> Vec<FunctionStats> {
+        let mut stats = self
+            .entries
+            .iter()
+            .filter(|entry| entry.calls.get() > 0)
+            .map(|entry| FunctionStats {
+                name: entry.name.clone(),
+                calls: entry.calls.get(),
+                time: entry.time.get(),
+            })
+            .collect::<Vec<_>>();
+        stats.sort_by(|a, b| b.time.cmp(&a.time).then_with(|| a.name.cmp(&b.name)));
+        stats
+    }
+
+    pub(crate) fn reset(&self) {
+        for entry in &self.entries {
+            entry.calls.set(0);
+            entry.time.set(Duration::ZERO);
+        }
+    }
+}
+
+/// Gets the statistics of the called functions and methods in the process,
+/// sorted by the cumulative time descending, empty if the statistics aren't
+/// enabled.
+pub fn function_stats() -> Vec<FunctionStats> {
+    unsafe { global_module() }
+        .stats()
+        .map(StatsRecorder::snapshot)
+        .unwrap_or_default()
+}
+
+/// Resets the statistics of all the functions and methods.
+pub fn reset_function_stats() {
+    if let Some(stats) = unsafe { global_module() }.stats() {
+        stats.reset();
+    }
+}
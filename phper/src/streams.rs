@@ -0,0 +1,165 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to accessing the files by the PHP streams, rather than
+//! `std::fs`.
+//!
+//! The files are opened by `php_stream_open_wrapper`, like `fopen()` in PHP,
+//! so the `open_basedir`, `allow_url_fopen` and the stream wrappers (like
+//! `php://memory`, `phar://` or the user wrappers) are honored, as the admins
+//! expect. The reason of failure to open (for example, the `open_basedir`
+//! restriction) is reported as the PHP warning, like `fopen()`.
+//!
+//! The streams are the resources of request, so they should only be used in
+//! the request.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use phper::streams::{self, Stream};
+//! use std::io::{BufRead, BufReader};
+//!
+//! fn count_lines(path: &str) -> phper::Result<usize> {
+//!     let stream = Stream::open(path, "rb")?;
+//!     Ok(BufReader::new(stream).lines().count())
+//! }
+//!
+//! fn copy(from: &str, to: &str) -> phper::Result<()> {
+//!     let contents = streams::read(from)?;
+//!     streams::write(to, contents)?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{sys::*, utils::ensure_end_with_zero};
+use std::{
+    io::{self, Read, Seek, SeekFrom, Write},
+    os::raw::{c_char, c_int},
+};
+
+/// The opened PHP stream, closed when dropped.
+pub struct Stream {
+    ptr: *mut php_stream,
+}
+
+impl Stream {
+    /// Opens the file or the url by the stream wrappers, the `mode` is the
+    /// same as `fopen()`, like `"rb"`, `"wb"` or `"ab"`.
+    pub fn open(path: &str, mode: &str) -> crate::Result<Self> {
+        let c_path = ensure_end_with_zero(path);
+        let c_mode = ensure_end_with_zero(mode);
+        let ptr = unsafe { phper_php_stream_open(c_path.as_ptr(), c_mode.as_ptr(), true) };
+        if ptr.is_null() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("failed to open stream {:?} with mode {:?}", path, mode),
+            )
+            .into());
+        }
+        Ok(Self { ptr })
+    }
+
+    /// Gets the raw pointer of `php_stream`.
+    #[inline]
+    pub fn as_ptr(&self) -> *const php_stream {
+        self.ptr
+    }
+
+    /// Gets the mutable raw pointer of `php_stream`.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut php_stream {
+        self.ptr
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = unsafe {
+            phper_php_stream_read(self.ptr, buf.as_mut_ptr().cast::<c_char>(), buf.len())
+        };
+        usize::try_from(n).map_err(|_| io::Error::new(io::ErrorKind::Other, "read stream failed"))
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n =
+            unsafe { phper_php_stream_write(self.ptr, buf.as_ptr().cast::<c_char>(), buf.len()) };
+        match usize::try_from(n) {
+            Ok(0) if !buf.is_empty() => Err(io::ErrorKind::WriteZero.into()),
+            Ok(n) => Ok(n),
+            Err(_) => Err(io::Error::new(io::ErrorKind::Other, "write stream failed")),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if unsafe { phper_php_stream_flush(self.ptr) } {
+            Ok(())
+        } else {
+            Err(io::Error::new(io::ErrorKind::Other, "flush stream failed"))
+        }
+    }
+}
+
+impl Seek for Stream {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        // The `SEEK_SET`, `SEEK_CUR` and `SEEK_END` of C.
+        let (offset, whence): (i64, c_int) = match pos {
+            SeekFrom::Start(offset) => (
+                offset
+                    .try_into()
+                    .map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?,
+                0,
+            ),
+            SeekFrom::Current(offset) => (offset, 1),
+            SeekFrom::End(offset) => (offset, 2),
+        };
+        let mut position = 0;
+        #[allow(clippy::useless_conversion)]
+        let ok = unsafe {
+            phper_php_stream_seek(self.ptr, offset.try_into().unwrap(), whence, &mut position)
+        };
+        if ok {
+            u64::try_from(position).map_err(|_| io::ErrorKind::InvalidData.into())
+        } else {
+            Err(io::Error::new(io::ErrorKind::Other, "seek stream failed"))
+        }
+    }
+}
+
+impl Drop for Stream {
+    fn drop(&mut self) {
+        unsafe {
+            phper_php_stream_close(self.ptr);
+        }
+    }
+}
+
+/// Reads the whole contents of the file or the url, like
+/// `file_get_contents()`.
+pub fn read(path: &str) -> crate::Result<Vec<u8>> {
+    let mut contents = Vec::new();
+    Stream::open(path, "rb")?.read_to_end(&mut contents)?;
+    Ok(contents)
+}
+
+/// Reads the whole contents of the file or the url as utf-8 string.
+pub fn read_to_string(path: &str) -> crate::Result<String> {
+    Ok(String::from_utf8(read(path)?).map_err(|e| e.utf8_error())?)
+}
+
+/// Writes the whole contents to the file or the url, the file is created or
+/// truncated, like `file_put_contents()`.
+pub fn write(path: &str, contents: impl AsRef<[u8]>) -> crate::Result<()> {
+    let mut stream = Stream::open(path, "wb")?;
+    stream.write_all(contents.as_ref())?;
+    stream.flush()?;
+    Ok(())
+}
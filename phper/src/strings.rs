@@ -14,8 +14,10 @@ use crate::sys::*;
 use phper_alloc::ToRefOwned;
 use std::{
     borrow::Borrow,
+    cmp::Ordering,
     ffi::{CStr, FromBytesWithNulError},
     fmt::{self, Debug},
+    io,
     marker::PhantomData,
     mem::forget,
     ops::{Deref, DerefMut},
@@ -131,6 +133,79 @@ impl ZStr {
     pub fn to_str(&self) -> Result<&str, Utf8Error> {
         str::from_utf8(self.to_bytes())
     }
+
+    /// Returns `true` if the string is interned.
+    #[inline]
+    pub fn is_interned(&self) -> bool {
+        unsafe { phper_zstr_is_interned(self.as_ptr()) }
+    }
+
+    /// Returns `true` if the string is allocated persistently, outlives the
+    /// request.
+    #[inline]
+    pub fn is_persistent(&self) -> bool {
+        unsafe { phper_zstr_is_persistent(self.as_ptr()) }
+    }
+
+    /// Returns `true` if the strings are equal, like `zend_string_equals`,
+    /// the same pointers (for example, both interned) are equal without
+    /// comparing the contents.
+    #[inline]
+    pub fn equals(&self, other: &ZStr) -> bool {
+        unsafe { phper_zend_string_equals(self.as_ptr(), other.as_ptr()) }
+    }
+
+    /// Returns `true` if the strings are equal, ignoring the case of ASCII
+    /// letters, like `zend_string_equals_ci`.
+    #[inline]
+    pub fn equals_ignore_case(&self, other: impl AsRef<[u8]>) -> bool {
+        let other = other.as_ref();
+        self.len() == other.len() && self.binary_case_cmp(other) == Ordering::Equal
+    }
+
+    /// Compares with the other string binary safe, like `strcmp()`.
+    pub fn binary_cmp(&self, other: impl AsRef<[u8]>) -> Ordering {
+        let other = other.as_ref();
+        let result = unsafe {
+            zend_binary_strcmp(
+                self.as_c_str_ptr(),
+                self.len(),
+                other.as_ptr().cast(),
+                other.len(),
+            )
+        };
+        result.cmp(&0)
+    }
+
+    /// Compares with the other string binary safe, ignoring the case of ASCII
+    /// letters, like `strcasecmp()`.
+    pub fn binary_case_cmp(&self, other: impl AsRef<[u8]>) -> Ordering {
+        let other = other.as_ref();
+        let result = unsafe {
+            zend_binary_strcasecmp(
+                self.as_c_str_ptr(),
+                self.len(),
+                other.as_ptr().cast(),
+                other.len(),
+            )
+        };
+        result.cmp(&0)
+    }
+
+    /// Compares with the other string binary safe, ignoring the case by the
+    /// `LC_CTYPE` of the current locale.
+    pub fn binary_case_cmp_locale(&self, other: impl AsRef<[u8]>) -> Ordering {
+        let other = other.as_ref();
+        let result = unsafe {
+            zend_binary_strcasecmp_l(
+                self.as_c_str_ptr(),
+                self.len(),
+                other.as_ptr().cast(),
+                other.len(),
+            )
+        };
+        result.cmp(&0)
+    }
 }
 
 impl Debug for ZStr {
@@ -177,14 +252,52 @@ pub struct ZString {
 
 impl ZString {
     /// Creates a new zend string from a container of bytes.
-    #[allow(clippy::useless_conversion)]
+    ///
+    /// The string is allocated per request, and released at the end of
+    /// request, so it is only valid in the request (between `RINIT` and
+    /// `RSHUTDOWN`), use [`PersistentZString`] in `MINIT` instead.
     pub fn new(s: impl AsRef<[u8]>) -> Self {
+        Self::new_with_persistent(s.as_ref(), false)
+    }
+
+    /// Creates or gets the interned zend string, the interned string is
+    /// hashed and allocated only once, and shared by the same content, so it
+    /// is suitable for the hot identifiers, like property names and array
+    /// keys.
+    ///
+    /// The string interned in `MINIT` is permanent, otherwise it is released
+    /// at the end of request, so prefer to intern them in `MINIT`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{objects::ZObj, strings::ZString};
+    ///
+    /// fn get_name(obj: &ZObj, name: &ZString) -> i64 {
+    ///     obj.get_property_by_z_str(name)
+    ///         .as_long()
+    ///         .unwrap_or_default()
+    /// }
+    ///
+    /// let name = ZString::interned("name");
+    /// ```
+    #[allow(clippy::useless_conversion)]
+    pub fn interned(s: impl AsRef<[u8]>) -> Self {
         unsafe {
             let s = s.as_ref();
+            let ptr =
+                phper_zend_string_init_interned(s.as_ptr().cast(), s.len().try_into().unwrap());
+            Self::from_raw(ptr)
+        }
+    }
+
+    #[allow(clippy::useless_conversion)]
+    fn new_with_persistent(s: &[u8], persistent: bool) -> Self {
+        unsafe {
             let ptr = phper_zend_string_init(
                 s.as_ptr().cast(),
                 s.len().try_into().unwrap(),
-                false.into(),
+                persistent.into(),
             );
             Self::from_raw(ptr)
         }
@@ -223,15 +336,11 @@ impl Debug for ZString {
 
 impl Clone for ZString {
     fn clone(&self) -> Self {
-        unsafe {
-            let ptr = phper_zend_string_init(
-                phper_zstr_val(self.as_ptr()),
-                phper_zstr_len(self.as_ptr()).try_into().unwrap(),
-                false.into(),
-            );
-            Self {
-                inner: ZStr::from_mut_ptr(ptr.cast()),
-            }
+        if self.is_interned() {
+            // The interned string is immutable and not refcounted, so share it.
+            unsafe { Self::from_raw(self.inner.cast()) }
+        } else {
+            Self::new_with_persistent(self.to_bytes(), self.is_persistent())
         }
     }
 }
@@ -276,6 +385,172 @@ impl Drop for ZString {
     }
 }
 
+/// The persistent [`ZString`], allocated by `malloc` and outlives the request,
+/// so it can be created in `MINIT` and held across requests, for example, the
+/// default values and constants.
+///
+/// It's a distinct type, so only it rather than the string allocated per
+/// request can be inserted into
+/// [`PersistentZArray`](crate::arrays::PersistentZArray).
+#[derive(Clone)]
+pub struct PersistentZString {
+    inner: ZString,
+}
+
+impl PersistentZString {
+    /// Creates a new persistent zend string from a container of bytes.
+    pub fn new(s: impl AsRef<[u8]>) -> Self {
+        Self {
+            inner: ZString::new_with_persistent(s.as_ref(), true),
+        }
+    }
+
+    /// Consumes the `PersistentZString`, returning the wrapped [`ZString`].
+    #[inline]
+    pub fn into_inner(self) -> ZString {
+        self.inner
+    }
+}
+
+impl Debug for PersistentZString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+
+impl Deref for PersistentZString {
+    type Target = ZStr;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl AsRef<[u8]> for PersistentZString {
+    fn as_ref(&self) -> &[u8] {
+        self.to_bytes()
+    }
+}
+
+/// Builder of [`ZString`] appending in place, backed by `smart_str`, the buffer
+/// grows geometrically as written and is taken as the string without being
+/// copied.
+///
+/// It can be returned by the function handlers directly, as it converts into
+/// [`ZVal`](crate::values::ZVal) without copying, so the big strings built by
+/// templating or serialization are written into the returned string.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::strings::ZStringBuilder;
+/// use std::fmt::Write;
+///
+/// let mut builder = ZStringBuilder::new();
+/// for i in 0..3 {
+///     write!(builder, "{},", i).unwrap();
+/// }
+/// assert_eq!(builder.finish().to_str(), Ok("0,1,2,"));
+/// ```
+#[derive(Default)]
+pub struct ZStringBuilder {
+    inner: smart_str,
+}
+
+impl ZStringBuilder {
+    /// Creates the empty builder, without allocation.
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Creates the builder with the buffer of at least `n` bytes allocated.
+    #[allow(clippy::useless_conversion)]
+    pub fn with_capacity(n: usize) -> Self {
+        let mut builder = Self::new();
+        if n > 0 {
+            unsafe {
+                phper_smart_str_alloc(&mut builder.inner, n.try_into().unwrap());
+            }
+        }
+        builder
+    }
+
+    /// The count of bytes can be written without reallocation.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner.a
+    }
+
+    /// Append the bytes.
+    #[allow(clippy::useless_conversion)]
+    pub fn append(&mut self, s: impl AsRef<[u8]>) {
+        let s = s.as_ref();
+        unsafe {
+            phper_smart_str_appendl(
+                &mut self.inner,
+                s.as_ptr().cast(),
+                s.len().try_into().unwrap(),
+            );
+        }
+    }
+
+    /// The length of bytes written.
+    pub fn len(&self) -> usize {
+        if self.inner.s.is_null() {
+            0
+        } else {
+            unsafe { (*self.inner.s).len }
+        }
+    }
+
+    /// Detect if nothing is written.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Take the built string.
+    pub fn finish(mut self) -> ZString {
+        unsafe { ZString::from_raw(phper_smart_str_extract(&mut self.inner)) }
+    }
+}
+
+impl From<ZStringBuilder> for ZString {
+    #[inline]
+    fn from(builder: ZStringBuilder) -> Self {
+        builder.finish()
+    }
+}
+
+impl io::Write for ZStringBuilder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.append(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl fmt::Write for ZStringBuilder {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.append(s);
+        Ok(())
+    }
+}
+
+impl Drop for ZStringBuilder {
+    fn drop(&mut self) {
+        if !self.inner.s.is_null() {
+            unsafe {
+                drop(ZString::from_raw(phper_smart_str_extract(&mut self.inner)));
+            }
+        }
+    }
+}
+
 fn common_fmt(this: &ZStr, f: &mut fmt::Formatter<'_>, name: &str) -> fmt::Result {
     let mut d = f.debug_tuple(name);
     match this.to_c_str() {
@@ -0,0 +1,86 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to the symbol tables of the executing user code.
+//!
+//! The local variables of a function are compiled into the slots of its frame,
+//! the symbol table is only built on demand, like `get_defined_vars()` does,
+//! which is slower for the rest of the function, so the apis are only
+//! available after [`Module::enable_symbol_tables`] is called.
+//!
+//! [`Module::enable_symbol_tables`]: crate::modules::Module::enable_symbol_tables
+
+use crate::{
+    arrays::{IterKey, ZArr},
+    errors::Error,
+    modules::global_module,
+    sys::*,
+    values::ZVal,
+};
+
+/// Gets the symbol table of the calling scope, the nearest executing user
+/// function or the main script, `None` if no user code is executing, like in
+/// `RINIT`.
+///
+/// The symbol table is only valid until the calling scope returns, don't hold
+/// it longer, and don't modify it, the variables should be assigned by PHP.
+///
+/// # Errors
+///
+/// Returns error if the symbol tables isn't enabled by
+/// [`Module::enable_symbol_tables`](crate::modules::Module::enable_symbol_tables).
+pub fn calling_scope<'a>() -> crate::Result<Option<&'a ZArr>> {
+    ensure_enabled()?;
+    unsafe {
+        let arr = phper_rebuild_symbol_table();
+        if arr.is_null() {
+            Ok(None)
+        } else {
+            Ok(Some(ZArr::from_ptr(arr)))
+        }
+    }
+}
+
+/// Gets the defined local variables of the calling scope in order, the
+/// references are dereferenced, and the values are shared with the variables
+/// by refcount, so they're cheap to be kept for the error reports.
+///
+/// Returns empty if no user code is executing.
+///
+/// # Errors
+///
+/// Returns error if the symbol tables isn't enabled, see [`calling_scope`].
+pub fn local_variables() -> crate::Result<Vec<(String, ZVal)>> {
+    let Some(table) = calling_scope()? else {
+        return Ok(Vec::new());
+    };
+    let variables = table
+        .iter()
+        .map(|(key, val)| {
+            let name = match key {
+                IterKey::Index(index) => index.to_string(),
+                IterKey::ZStr(name) => String::from_utf8_lossy(name.to_bytes()).into_owned(),
+            };
+            let val = val.as_z_ref().map_or(val, |r| r.val());
+            (name, val.shallow_clone())
+        })
+        .collect();
+    Ok(variables)
+}
+
+fn ensure_enabled() -> crate::Result<()> {
+    if unsafe { global_module() }.symbol_tables_enabled() {
+        Ok(())
+    } else {
+        Err(Error::runtime(
+            "symbol tables aren't enabled, call `Module::enable_symbol_tables` first",
+        ))
+    }
+}
@@ -0,0 +1,334 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to timing the request, the stopwatch started in `RINIT` and the
+//! spans recorded in the request, which are finalized in `RSHUTDOWN`, as the
+//! timing backbone of the profiling built on the execute hooks and the
+//! observers.
+//!
+//! The durations are measured by the monotonic clock, the wall clock is only
+//! used to anchor them, so they aren't affected by the clock adjustment.
+
+use std::{
+    cell::RefCell,
+    marker::PhantomData,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant, SystemTime},
+};
+
+thread_local! {
+    static CURRENT: RefCell<Option<RequestTimer>> = RefCell::new(None);
+}
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// The stopwatch measuring the elapsed time by the monotonic clock, optionally
+/// anchored to the wall clock when started, to convert the instants to the
+/// timestamps for the reports.
+#[derive(Debug, Clone, Copy)]
+pub struct Stopwatch {
+    started: Instant,
+    anchor: Option<SystemTime>,
+}
+
+impl Stopwatch {
+    /// Starts the stopwatch without the wall clock anchor.
+    pub fn start() -> Self {
+        Self {
+            started: Instant::now(),
+            anchor: None,
+        }
+    }
+
+    /// Starts the stopwatch anchored to the current wall clock.
+    pub fn start_anchored() -> Self {
+        Self {
+            started: Instant::now(),
+            anchor: Some(SystemTime::now()),
+        }
+    }
+
+    /// Gets the monotonic instant when started.
+    #[inline]
+    pub fn started(&self) -> Instant {
+        self.started
+    }
+
+    /// Gets the wall clock time when started, `None` if not anchored.
+    #[inline]
+    pub fn started_at(&self) -> Option<SystemTime> {
+        self.anchor
+    }
+
+    /// Gets the elapsed time since started.
+    #[inline]
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Gets the offset of `instant` since started, zero if `instant` is
+    /// earlier.
+    #[inline]
+    pub fn offset_of(&self, instant: Instant) -> Duration {
+        instant.saturating_duration_since(self.started)
+    }
+
+    /// Converts `instant` to the wall clock time by the anchor, `None` if not
+    /// anchored.
+    pub fn wall_time_of(&self, instant: Instant) -> Option<SystemTime> {
+        self.anchor.map(|anchor| anchor + self.offset_of(instant))
+    }
+}
+
+/// The span recorded in the request, see [`span`].
+#[derive(Debug, Clone)]
+pub struct SpanRecord {
+    name: String,
+    start: Duration,
+    duration: Duration,
+    depth: usize,
+    finalized: bool,
+}
+
+impl SpanRecord {
+    /// Gets the name of span.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the start offset since the request started.
+    #[inline]
+    pub fn start(&self) -> Duration {
+        self.start
+    }
+
+    /// Gets the end offset since the request started.
+    #[inline]
+    pub fn end(&self) -> Duration {
+        self.start + self.duration
+    }
+
+    /// Gets the duration of span.
+    #[inline]
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Gets the count of the spans still open when the span started, `0` for
+    /// the outermost spans.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Whether the span was still open at the end of request and closed by
+    /// `RSHUTDOWN`, for example, the request was aborted by `exit()` or the
+    /// fatal error.
+    #[inline]
+    pub fn is_finalized(&self) -> bool {
+        self.finalized
+    }
+}
+
+/// The timings of the finished request, passed to the hook registered by
+/// [`Module::on_request_timings`](crate::modules::Module::on_request_timings).
+#[derive(Debug, Clone)]
+pub struct RequestTimings {
+    stopwatch: Stopwatch,
+    duration: Duration,
+    spans: Vec<SpanRecord>,
+}
+
+impl RequestTimings {
+    /// Gets the stopwatch of the request, anchored to the wall clock of
+    /// `RINIT`.
+    #[inline]
+    pub fn stopwatch(&self) -> &Stopwatch {
+        &self.stopwatch
+    }
+
+    /// Gets the duration from `RINIT` to `RSHUTDOWN`.
+    #[inline]
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Gets the spans in the order of started.
+    #[inline]
+    pub fn spans(&self) -> &[SpanRecord] {
+        &self.spans
+    }
+}
+
+struct SpanSlot {
+    name: String,
+    start: Duration,
+    end: Option<Duration>,
+    depth: usize,
+}
+
+struct RequestTimer {
+    id: u64,
+    stopwatch: Stopwatch,
+    spans: Vec<SpanSlot>,
+}
+
+impl RequestTimer {
+    fn close(&mut self, index: usize) -> Option<Duration> {
+        let now = self.stopwatch.elapsed();
+        let slot = self.spans.get_mut(index)?;
+        if slot.end.is_some() {
+            return None;
+        }
+        slot.end = Some(now);
+        Some(now.saturating_sub(slot.start))
+    }
+}
+
+/// The guard of the span started by [`span`], which is closed when dropped or
+/// [finished](Span::finish).
+///
+/// The span belongs to the request on the current thread, it is closed by
+/// `RSHUTDOWN` if still open, and dropping it after the request does nothing.
+#[must_use = "the span is closed immediately if dropped"]
+pub struct Span {
+    request_id: u64,
+    index: usize,
+    _p: PhantomData<*const ()>,
+}
+
+impl Span {
+    /// Closes the span and returns its duration, `None` if the span isn't
+    /// recorded, because it's started outside the request, or the request has
+    /// finished.
+    pub fn finish(mut self) -> Option<Duration> {
+        self.close()
+    }
+
+    fn close(&mut self) -> Option<Duration> {
+        let request_id = std::mem::take(&mut self.request_id);
+        if request_id == 0 {
+            return None;
+        }
+        CURRENT.with(|current| match &mut *current.borrow_mut() {
+            Some(timer) if timer.id == request_id => timer.close(self.index),
+            _ => None,
+        })
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// Starts the span of the current request, closed when the returned guard is
+/// dropped. The spans started outside the request aren't recorded.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{timers, values::ZVal};
+///
+/// fn query(_: &mut [ZVal]) -> phper::Result<()> {
+///     let _span = timers::span("query");
+///     // Do the query here.
+///     Ok(())
+/// }
+/// ```
+pub fn span(name: impl Into<String>) -> Span {
+    let (request_id, index) = CURRENT.with(|current| match &mut *current.borrow_mut() {
+        Some(timer) => {
+            let depth = timer.spans.iter().filter(|slot| slot.end.is_none()).count();
+            timer.spans.push(SpanSlot {
+                name: name.into(),
+                start: timer.stopwatch.elapsed(),
+                end: None,
+                depth,
+            });
+            (timer.id, timer.spans.len() - 1)
+        }
+        None => (0, 0),
+    });
+    Span {
+        request_id,
+        index,
+        _p: PhantomData,
+    }
+}
+
+/// Runs `f` in the span named `name`, see [`span`].
+pub fn time<R>(name: impl Into<String>, f: impl FnOnce() -> R) -> R {
+    let _span = span(name);
+    f()
+}
+
+/// Gets the stopwatch of the current request, started and anchored to the wall
+/// clock in `RINIT`, `None` if not in the request.
+pub fn request_stopwatch() -> Option<Stopwatch> {
+    CURRENT.with(|current| current.borrow().as_ref().map(|timer| timer.stopwatch))
+}
+
+/// Gets the closed spans of the current request in the order of started.
+pub fn spans() -> Vec<SpanRecord> {
+    CURRENT.with(|current| {
+        let current = current.borrow();
+        let Some(timer) = &*current else {
+            return Vec::new();
+        };
+        timer
+            .spans
+            .iter()
+            .filter_map(|slot| slot.end.map(|end| record(slot, end, false)))
+            .collect()
+    })
+}
+
+fn record(slot: &SpanSlot, end: Duration, finalized: bool) -> SpanRecord {
+    SpanRecord {
+        name: slot.name.clone(),
+        start: slot.start,
+        duration: end.saturating_sub(slot.start),
+        depth: slot.depth,
+        finalized,
+    }
+}
+
+/// Starts the stopwatch of the new request, called in `RINIT`.
+pub(crate) fn enter_request() {
+    let timer = RequestTimer {
+        id: NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed),
+        stopwatch: Stopwatch::start_anchored(),
+        spans: Vec::new(),
+    };
+    CURRENT.with(|current| *current.borrow_mut() = Some(timer));
+}
+
+/// Finalizes the timings of the current request, closing the open spans,
+/// called in `RSHUTDOWN`.
+pub(crate) fn leave_request() -> Option<RequestTimings> {
+    let timer = CURRENT.with(|current| current.borrow_mut().take())?;
+    let duration = timer.stopwatch.elapsed();
+    let spans = timer
+        .spans
+        .iter()
+        .map(|slot| match slot.end {
+            Some(end) => record(slot, end, false),
+            None => record(slot, duration, true),
+        })
+        .collect();
+    Some(RequestTimings {
+        stopwatch: timer.stopwatch,
+        duration,
+        spans,
+    })
+}
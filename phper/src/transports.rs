@@ -0,0 +1,184 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to registering the stream transports, so
+//! `stream_socket_client("name://target")` and `fsockopen("name://target")`
+//! are served by Rust.
+//!
+//! Only the client connections are supported, the servers created by
+//! `stream_socket_server` aren't.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use phper::{
+//!     modules::Module,
+//!     transports::{StreamTransport, TransportStream},
+//! };
+//! use std::{io, net::TcpStream, time::Duration};
+//!
+//! struct PlainTcp;
+//!
+//! impl StreamTransport for PlainTcp {
+//!     fn connect(
+//!         &self, target: &str, timeout: Option<Duration>,
+//!     ) -> io::Result<Box<dyn TransportStream>> {
+//!         let stream = TcpStream::connect(target)?;
+//!         stream.set_read_timeout(timeout)?;
+//!         Ok(Box::new(stream))
+//!     }
+//! }
+//!
+//! fn register(module: &mut Module) {
+//!     module.add_stream_transport("plaintcp", PlainTcp);
+//! }
+//! ```
+
+use crate::{
+    contexts::ModuleContext, modules::global_module, strings::ZString, sys::*,
+    utils::ensure_end_with_zero,
+};
+use std::{
+    ffi::{CStr, CString},
+    io::{self, Read, Write},
+    os::raw::{c_char, c_void},
+    ptr::null_mut,
+    slice::{from_raw_parts, from_raw_parts_mut},
+    str,
+    time::Duration,
+};
+
+/// The stream transport, registered by
+/// [`Module::add_stream_transport`](crate::modules::Module::add_stream_transport).
+pub trait StreamTransport {
+    /// Open the connection to `target`, the part after `name://`, like
+    /// `host:port`.
+    ///
+    /// The error is reported as the `$error_message` of
+    /// `stream_socket_client`.
+    fn connect(
+        &self, target: &str, timeout: Option<Duration>,
+    ) -> io::Result<Box<dyn TransportStream>>;
+}
+
+/// The connection opened by [`StreamTransport::connect`], closed when dropped.
+///
+/// Implemented for all [`Read`] and [`Write`] types. Reading zero bytes means
+/// the end of stream, and [`io::ErrorKind::WouldBlock`] means no data is
+/// available yet.
+pub trait TransportStream: Read + Write {}
+
+impl<T: Read + Write> TransportStream for T {}
+
+pub(crate) struct StreamTransportEntity {
+    name: CString,
+    transport: Box<Box<dyn StreamTransport>>,
+}
+
+impl StreamTransportEntity {
+    pub(crate) fn new(name: impl Into<String>, transport: impl StreamTransport + 'static) -> Self {
+        Self {
+            name: ensure_end_with_zero(name),
+            transport: Box::new(Box::new(transport)),
+        }
+    }
+
+    pub(crate) fn name(&self) -> &CStr {
+        &self.name
+    }
+
+    /// Register to the stream layer, must be called in `MINIT`.
+    pub(crate) unsafe fn register(&self, _ctx: &ModuleContext<'_>) -> bool {
+        phper_xport_register(self.name.as_ptr(), &CALLBACKS)
+    }
+
+    pub(crate) unsafe fn unregister(&self) {
+        phper_xport_unregister(self.name.as_ptr());
+    }
+}
+
+static CALLBACKS: phper_xport_callbacks = phper_xport_callbacks {
+    find: Some(find),
+    connect: Some(connect),
+    close: Some(close),
+    read: Some(read),
+    write: Some(write),
+    flush: Some(flush),
+};
+
+unsafe fn connection<'a>(conn: *mut c_void) -> &'a mut dyn TransportStream {
+    &mut **conn.cast::<Box<dyn TransportStream>>()
+}
+
+unsafe extern "C" fn find(proto: *const c_char, proto_len: usize) -> *mut c_void {
+    let proto = from_raw_parts(proto.cast::<u8>(), proto_len);
+    global_module()
+        .stream_transport_entities()
+        .iter()
+        .find(|entity| entity.name.as_bytes() == proto)
+        .map(|entity| &*entity.transport as *const Box<dyn StreamTransport> as *mut c_void)
+        .unwrap_or(null_mut())
+}
+
+unsafe extern "C" fn connect(
+    transport: *mut c_void, name: *const c_char, name_len: usize, timeout_ms: zend_long,
+    error_text: *mut *mut zend_string,
+) -> *mut c_void {
+    let transport = &*transport.cast::<Box<dyn StreamTransport>>();
+    let timeout = u64::try_from(timeout_ms).ok().map(Duration::from_millis);
+    let result = str::from_utf8(from_raw_parts(name.cast::<u8>(), name_len))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+        .and_then(|target| transport.connect(target, timeout));
+    match result {
+        Ok(stream) => Box::into_raw(Box::new(stream)).cast(),
+        Err(e) => {
+            *error_text = ZString::new(e.to_string()).into_raw();
+            null_mut()
+        }
+    }
+}
+
+unsafe extern "C" fn close(conn: *mut c_void) {
+    drop(Box::from_raw(conn.cast::<Box<dyn TransportStream>>()));
+}
+
+unsafe extern "C" fn read(
+    conn: *mut c_void, buf: *mut c_char, count: usize, eof: *mut bool,
+) -> zend_long {
+    let buf = from_raw_parts_mut(buf.cast::<u8>(), count);
+    match connection(conn).read(buf) {
+        Ok(0) if count > 0 => {
+            *eof = true;
+            0
+        }
+        Ok(n) => n as zend_long,
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => 0,
+        Err(e) => {
+            crate::notice!("Read of {} bytes failed: {}", count, e);
+            -1
+        }
+    }
+}
+
+unsafe extern "C" fn write(conn: *mut c_void, buf: *const c_char, count: usize) -> zend_long {
+    let buf = from_raw_parts(buf.cast::<u8>(), count);
+    match connection(conn).write(buf) {
+        Ok(n) => n as zend_long,
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => 0,
+        Err(e) => {
+            crate::notice!("Send of {} bytes failed: {}", count, e);
+            -1
+        }
+    }
+}
+
+unsafe extern "C" fn flush(conn: *mut c_void) -> bool {
+    connection(conn).flush().is_ok()
+}
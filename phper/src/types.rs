@@ -184,7 +184,7 @@ const fn get_base_type_by_raw(t: u32) -> u32 {
 }
 
 /// Copyable value, used in constant and class property.
-#[derive(From)]
+#[derive(Debug, Clone, PartialEq, From)]
 pub enum Scalar {
     /// Null.
     Null,
@@ -211,3 +211,133 @@ impl From<&[u8]> for Scalar {
         Self::Bytes(b.to_owned())
     }
 }
+
+/// Type declaration, used in typed class property, function argument and
+/// return type.
+///
+/// The type can't be represented by the PHP version is ignored, the
+/// declaration is left untyped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::types::TypeHint;
+///
+/// // ?int
+/// let t = TypeHint::Long.nullable();
+/// // Foo|string
+/// let t = TypeHint::Union(vec![TypeHint::class("Foo"), TypeHint::String]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeHint {
+    /// `mixed`, since PHP 8.0.
+    Mixed,
+    /// `bool`.
+    Bool,
+    /// `int`.
+    Long,
+    /// `float`.
+    Double,
+    /// `string`.
+    String,
+    /// `array`.
+    Array,
+    /// `object`.
+    Object,
+    /// The class or interface name.
+    Class(String),
+    /// Nullable type, like `?int`.
+    Nullable(Box<TypeHint>),
+    /// Union type, like `int|string`, since PHP 8.0.
+    Union(Vec<TypeHint>),
+    /// `void`, only for return type, since PHP 7.1.
+    Void,
+    /// `never`, only for return type, since PHP 8.1.
+    Never,
+    /// `static`, only for return type, since PHP 8.0.
+    Static,
+    /// `false`, in union type since PHP 8.0, and standalone since PHP 8.2.
+    False,
+    /// `true`, since PHP 8.2.
+    True,
+    /// `null`, in union type since PHP 8.0, and standalone since PHP 8.2.
+    Null,
+}
+
+impl TypeHint {
+    /// Construct the class type with the class or interface name.
+    pub fn class(name: impl Into<String>) -> Self {
+        Self::Class(name.into())
+    }
+
+    /// Make the type nullable.
+    pub fn nullable(self) -> Self {
+        match self {
+            Self::Nullable(_) | Self::Mixed | Self::Null => self,
+            t => Self::Nullable(Box::new(t)),
+        }
+    }
+
+    /// Returns `true` if the type accepts `null`.
+    pub fn allows_null(&self) -> bool {
+        match self {
+            Self::Mixed | Self::Nullable(_) | Self::Null => true,
+            Self::Union(types) => types.iter().any(TypeHint::allows_null),
+            _ => false,
+        }
+    }
+
+    /// Flatten to the `PHPER_TYPE_*` bits and the class names.
+    pub(crate) fn flatten(&self, bits: &mut u32, class_names: &mut Vec<String>) {
+        match self {
+            Self::Mixed => *bits |= PHPER_TYPE_MIXED,
+            Self::Bool => *bits |= PHPER_TYPE_BOOL,
+            Self::Long => *bits |= PHPER_TYPE_LONG,
+            Self::Double => *bits |= PHPER_TYPE_DOUBLE,
+            Self::String => *bits |= PHPER_TYPE_STRING,
+            Self::Array => *bits |= PHPER_TYPE_ARRAY,
+            Self::Object => *bits |= PHPER_TYPE_OBJECT,
+            Self::Class(name) => class_names.push(name.clone()),
+            Self::Void => *bits |= PHPER_TYPE_VOID,
+            Self::Never => *bits |= PHPER_TYPE_NEVER,
+            Self::Static => *bits |= PHPER_TYPE_STATIC,
+            Self::False => *bits |= PHPER_TYPE_FALSE,
+            Self::True => *bits |= PHPER_TYPE_TRUE,
+            Self::Null => *bits |= PHPER_TYPE_NULL,
+            Self::Nullable(t) => {
+                *bits |= PHPER_TYPE_NULL;
+                t.flatten(bits, class_names);
+            }
+            Self::Union(types) => {
+                for t in types {
+                    t.flatten(bits, class_names);
+                }
+            }
+        }
+    }
+}
+
+/// Typed bit flags, parsed from the PHP integer of OR-ed constants.
+///
+/// Normally implemented by the [`flags!`](crate::flags) macro, and read by
+/// [`ZVal::expect_flags`](crate::values::ZVal::expect_flags).
+pub trait Flags: Copy {
+    /// The names and the bits of the known flags.
+    const NAMES: &'static [(&'static str, i64)];
+
+    /// Creates from the raw bits, which are checked to be known.
+    fn from_bits_retain(bits: i64) -> Self;
+
+    /// Gets the raw bits.
+    fn bits(self) -> i64;
+
+    /// All the known bits.
+    fn all_bits() -> i64 {
+        Self::NAMES.iter().fold(0, |bits, (_, bit)| bits | bit)
+    }
+
+    /// Creates from the raw bits, returns `None` if there are unknown bits.
+    fn from_bits(bits: i64) -> Option<Self> {
+        (bits & !Self::all_bits() == 0).then(|| Self::from_bits_retain(bits))
+    }
+}
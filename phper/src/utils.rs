@@ -10,8 +10,59 @@
 
 //! Internal useful utils.
 
+use crate::sys::*;
 use std::ffi::CString;
 
 pub(crate) fn ensure_end_with_zero(s: impl Into<String>) -> CString {
-    CString::new(s.into()).expect("CString::new failed")
+    CString::new(s.into()).unwrap_or_else(|e| {
+        let position = e.nul_position();
+        let s = String::from_utf8_lossy(&e.into_vec()).into_owned();
+        panic!("{:?} contains the NUL byte at {}", s, position)
+    })
+}
+
+/// Checks the PHP label `[a-zA-Z_\x80-\xff][a-zA-Z0-9_\x80-\xff]*`, or the
+/// labels separated by `\` if `namespaced`.
+pub(crate) fn is_valid_name(name: &[u8], namespaced: bool) -> bool {
+    fn is_label(label: &[u8]) -> bool {
+        match label.split_first() {
+            Some((first, rest)) => {
+                (first.is_ascii_alphabetic() || *first == b'_' || *first >= 0x80)
+                    && rest
+                        .iter()
+                        .all(|c| c.is_ascii_alphanumeric() || *c == b'_' || *c >= 0x80)
+            }
+            None => false,
+        }
+    }
+
+    if namespaced {
+        name.split(|c| *c == b'\\').all(is_label)
+    } else {
+        is_label(name)
+    }
+}
+
+/// Blocks all the signals of the current thread until dropped, the threads
+/// spawned meanwhile inherit the blocked mask, so the signals like the timer of
+/// `max_execution_time` are never delivered to them, even before they start
+/// running.
+pub(crate) struct SignalsBlocked(phper_sigset);
+
+impl SignalsBlocked {
+    pub(crate) fn new() -> Self {
+        let mut old = phper_sigset::default();
+        unsafe {
+            phper_block_signals(&mut old);
+        }
+        Self(old)
+    }
+}
+
+impl Drop for SignalsBlocked {
+    fn drop(&mut self) {
+        unsafe {
+            phper_restore_signals(&self.0);
+        }
+    }
 }
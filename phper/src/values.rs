@@ -12,15 +12,16 @@
 
 use crate::{
     alloc::EBox,
-    arrays::{ZArr, ZArray},
-    errors::ExpectTypeError,
-    functions::{call_internal, ZFunc},
+    arrays::{IterKey, PersistentZArray, ZArr, ZArray},
+    classes::ClassEntry,
+    errors::{ExpectTypeError, UnknownFlagsError},
+    functions::{call_internal, FunctionType, ZFunc},
     objects::{StateObject, ZObj, ZObject},
     references::ZRef,
     resources::ZRes,
-    strings::{ZStr, ZString},
+    strings::{PersistentZString, ZStr, ZString, ZStringBuilder},
     sys::*,
-    types::TypeInfo,
+    types::{Flags, TypeInfo},
 };
 use phper_alloc::RefClone;
 use std::{
@@ -28,8 +29,9 @@ use std::{
     fmt,
     fmt::Debug,
     marker::PhantomData,
-    mem::{transmute, zeroed, ManuallyDrop, MaybeUninit},
-    str,
+    mem::{ManuallyDrop, MaybeUninit},
+    ops::Deref,
+    slice, str,
 };
 
 /// Wrapper of [zend_execute_data].
@@ -127,10 +129,106 @@ impl ExecuteData {
     }
 
     /// Gets associated function.
+    #[inline]
     pub fn func(&self) -> &ZFunc {
         unsafe { ZFunc::from_mut_ptr(self.inner.func) }
     }
 
+    /// Gets the frame of the executing function, `None` if nothing is
+    /// executing, like in `RINIT`.
+    ///
+    /// The frame is only valid until the executing function returns, don't
+    /// hold it longer.
+    pub fn current<'a>() -> Option<&'a ExecuteData> {
+        unsafe { Self::try_from_ptr(phper_get_current_execute_data()) }
+    }
+
+    /// Gets associated function, `None` for the dummy frame pushed when the
+    /// internal code calls a function.
+    pub fn try_func(&self) -> Option<&ZFunc> {
+        unsafe {
+            let func = self.inner.func;
+            if func.is_null() {
+                None
+            } else {
+                Some(ZFunc::from_ptr(func))
+            }
+        }
+    }
+
+    /// Whether the caller of the executing function is in the
+    /// `declare(strict_types=1)` mode, which decides whether the arguments
+    /// are coerced, like the `ZEND_ARG_USES_STRICT_TYPES()` of the core
+    /// functions.
+    pub fn caller_uses_strict_types(&self) -> bool {
+        self.prev_execute_data()
+            .and_then(ExecuteData::try_func)
+            .map_or(false, ZFunc::uses_strict_types)
+    }
+
+    /// Gets the name of the executing function, `None` for the main script.
+    pub fn function_name(&self) -> Option<&ZStr> {
+        self.try_func()?.get_function_name()
+    }
+
+    /// Gets the class declaring the executing method, `None` for the function.
+    pub fn declaring_class(&self) -> Option<&ClassEntry> {
+        self.try_func()?.get_class()
+    }
+
+    /// Gets the calling scope, the class of `$this` or `static::class`, which
+    /// may be the subclass of the [declaring class](Self::declaring_class).
+    pub fn called_scope(&self) -> Option<&ClassEntry> {
+        unsafe {
+            let ce = zend_get_called_scope(self.as_ptr() as *mut _);
+            if ce.is_null() {
+                None
+            } else {
+                Some(ClassEntry::from_ptr(ce))
+            }
+        }
+    }
+
+    /// Gets the frame of the caller, `None` for the outermost frame.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::values::ExecuteData;
+    ///
+    /// fn backtrace(execute_data: &ExecuteData) -> Vec<String> {
+    ///     let mut frames = Vec::new();
+    ///     let mut frame = Some(execute_data);
+    ///     while let Some(ex) = frame {
+    ///         if let (Some(filename), Some(lineno)) = (ex.filename(), ex.lineno()) {
+    ///             let filename = String::from_utf8_lossy(filename.to_bytes());
+    ///             frames.push(format!("{}:{}", filename, lineno));
+    ///         }
+    ///         frame = ex.prev_execute_data();
+    ///     }
+    ///     frames
+    /// }
+    /// ```
+    pub fn prev_execute_data(&self) -> Option<&ExecuteData> {
+        unsafe { Self::try_from_ptr(self.inner.prev_execute_data) }
+    }
+
+    /// Gets the file name of the executing user function or script, `None` for
+    /// the internal function.
+    pub fn filename(&self) -> Option<&ZStr> {
+        self.try_func()?.filename()
+    }
+
+    /// Gets the line number of the executing opline in the user function or
+    /// script, `None` for the internal function.
+    ///
+    /// For the frame calling the current function, it is the line of the call
+    /// site.
+    pub fn lineno(&self) -> Option<u32> {
+        self.try_func()?.op_array()?;
+        unsafe { self.inner.opline.as_ref().map(|opline| opline.lineno) }
+    }
+
     /// Gets associated `$this` object if exists.
     pub fn get_this(&mut self) -> Option<&ZObj> {
         unsafe {
@@ -147,36 +245,96 @@ impl ExecuteData {
         }
     }
 
-    pub(crate) unsafe fn get_parameters_array(&mut self) -> Vec<ManuallyDrop<ZVal>> {
-        let num_args = self.num_args();
-        let mut arguments = vec![zeroed::<zval>(); num_args];
-        if num_args > 0 {
-            phper_zend_get_parameters_array_ex(
-                num_args.try_into().unwrap(),
-                arguments.as_mut_ptr(),
-            );
-        }
-        transmute(arguments)
+    /// Gets the arguments as the slice, borrowed from the VM stack slots
+    /// directly, because the arguments of internal function are contiguous.
+    #[inline]
+    pub(crate) unsafe fn get_parameters_slice<'a>(&mut self) -> &'a mut [ZVal] {
+        let mut num_args = 0;
+        let first = phper_zend_call_args(self.as_mut_ptr(), &mut num_args);
+        slice::from_raw_parts_mut(first.cast(), num_args as usize)
     }
 
     /// Gets parameter by index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameter is undefined, for example, the optional
+    /// parameter that isn't passed, use [`ExecuteData::try_get_parameter`]
+    /// instead.
     pub fn get_parameter(&self, index: usize) -> &ZVal {
         unsafe {
             let val = phper_zend_call_var_num(self.as_ptr() as *mut _, index.try_into().unwrap());
-            ZVal::from_ptr(val)
+            let val = ZVal::from_ptr(val);
+            assert!(!val.is_undef(), "parameter {} is undefined", index);
+            val
         }
     }
 
     /// Gets mutable parameter by index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameter is undefined, use
+    /// [`ExecuteData::try_get_mut_parameter`] instead.
     pub fn get_mut_parameter(&mut self, index: usize) -> &mut ZVal {
         unsafe {
             let val = phper_zend_call_var_num(self.as_mut_ptr(), index.try_into().unwrap());
-            ZVal::from_mut_ptr(val)
+            let val = ZVal::from_mut_ptr(val);
+            assert!(!val.is_undef(), "parameter {} is undefined", index);
+            val
+        }
+    }
+
+    /// Gets parameter by index, `None` if the index is out of the parameters,
+    /// or the parameter is undefined, distinguished from the `null` passed
+    /// explicitly.
+    pub fn try_get_parameter(&self, index: usize) -> Option<&ZVal> {
+        if !self.has_parameter_slot(index) {
+            return None;
+        }
+        unsafe {
+            let val = phper_zend_call_var_num(self.as_ptr() as *mut _, index.try_into().ok()?);
+            ZVal::from_ptr(val).as_defined()
+        }
+    }
+
+    /// Gets mutable parameter by index, `None` if the index is out of the
+    /// parameters, or the parameter is undefined.
+    pub fn try_get_mut_parameter(&mut self, index: usize) -> Option<&mut ZVal> {
+        if !self.has_parameter_slot(index) {
+            return None;
+        }
+        unsafe {
+            let val = phper_zend_call_var_num(self.as_mut_ptr(), index.try_into().ok()?);
+            ZVal::from_mut_ptr(val).as_mut_defined()
+        }
+    }
+
+    /// The arguments of internal function are passed only, but the user
+    /// function reserves the slots for all declared parameters.
+    fn has_parameter_slot(&self, index: usize) -> bool {
+        match self.try_func().map(ZFunc::function_type) {
+            Some(FunctionType::Internal) => index < self.num_args(),
+            Some(FunctionType::User) => index < self.common_num_args() as usize,
+            None => false,
         }
     }
 }
 
 /// Wrapper of [zval].
+///
+/// The owned `ZVal` holds a reference of the refcounted value (string, array,
+/// object, etc.), and releases it when dropped, while the borrowed `&ZVal` and
+/// `&mut ZVal` (for example, the arguments) don't.
+///
+/// There are three ways to copy a `ZVal`:
+///
+/// - [`ZVal::shallow_clone`] increases the refcount only, the values are
+///   shared, like the assignment in PHP.
+/// - [`ZVal::duplicate`] (also [`Clone`]) copies the string and array, so the
+///   copy can be modified independently, but the object is still shared.
+/// - [`ZVal::separate`] copies the string and array in place, only if they are
+///   shared by others, like the copy-on-write in PHP.
 #[repr(transparent)]
 pub struct ZVal {
     inner: zval,
@@ -243,10 +401,27 @@ impl ZVal {
         &mut self.inner
     }
 
-    /// Consumes the `ZVal`, returning the wrapped `zval`.
+    /// Takes the ownership of the `zval`, which will be released when the
+    /// `ZVal` dropped, in contrast to [`ZVal::from_ptr`] and
+    /// [`ZVal::from_mut_ptr`], which borrow the `zval`.
+    ///
+    /// # Safety
+    ///
+    /// Make sure the `zval` is initialized and owns a reference of the
+    /// refcounted value.
+    #[inline]
+    pub unsafe fn from_raw(inner: zval) -> Self {
+        Self {
+            inner,
+            _p: PhantomData,
+        }
+    }
+
+    /// Consumes the `ZVal`, returning the wrapped `zval`, the ownership is
+    /// transferred, so the `zval` should be released by the caller.
     #[inline]
     pub fn into_inner(self) -> zval {
-        self.inner
+        ManuallyDrop::new(self).inner
     }
 
     /// Gets the type info of `ZVal`.
@@ -255,6 +430,48 @@ impl ZVal {
         t.into()
     }
 
+    /// Whether the `ZVal` is undefined, like the optional parameter that
+    /// isn't passed, or the unset variable, different from `null`.
+    #[inline]
+    pub fn is_undef(&self) -> bool {
+        self.get_type_info().is_undef()
+    }
+
+    /// Whether the `ZVal` is `null`.
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        self.get_type_info().is_null()
+    }
+
+    /// Gets the `ZVal` itself, `None` if it is undefined.
+    pub fn as_defined(&self) -> Option<&ZVal> {
+        (!self.is_undef()).then_some(self)
+    }
+
+    /// Gets the mutable `ZVal` itself, `None` if it is undefined.
+    pub fn as_mut_defined(&mut self) -> Option<&mut ZVal> {
+        if self.is_undef() {
+            None
+        } else {
+            Some(self)
+        }
+    }
+
+    /// Gets the `ZVal` itself, `None` if it is `null` or undefined, for the
+    /// nullable values.
+    pub fn as_option(&self) -> Option<&ZVal> {
+        (!self.is_undef() && !self.is_null()).then_some(self)
+    }
+
+    /// Gets the mutable `ZVal` itself, `None` if it is `null` or undefined.
+    pub fn as_mut_option(&mut self) -> Option<&mut ZVal> {
+        if self.is_undef() || self.is_null() {
+            None
+        } else {
+            Some(self)
+        }
+    }
+
     /// Converts to null if `ZVal` is null.
     pub fn as_null(&self) -> Option<()> {
         self.expect_null().ok()
@@ -299,6 +516,36 @@ impl ZVal {
         self.inner_expect_long().cloned()
     }
 
+    /// Converts to typed bit flags if `ZVal` is long, otherwise returns
+    /// [`ExpectTypeError`], or [`UnknownFlagsError`] if there are unknown
+    /// bits.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{flags, values::ZVal};
+    ///
+    /// flags! {
+    ///     pub struct Mode {
+    ///         const FAST = 1;
+    ///         const SAFE = 2;
+    ///     }
+    /// }
+    ///
+    /// fn handle(arguments: &mut [ZVal]) -> phper::Result<bool> {
+    ///     let mode = arguments[0].expect_flags::<Mode>()?;
+    ///     Ok(mode.contains(Mode::SAFE))
+    /// }
+    /// ```
+    pub fn expect_flags<F: Flags>(&self) -> crate::Result<F> {
+        let bits = self.expect_long()?;
+        let unknown = bits & !F::all_bits();
+        if unknown != 0 {
+            return Err(UnknownFlagsError::new(unknown).into());
+        }
+        Ok(F::from_bits_retain(bits))
+    }
+
     /// Converts to mutable long if `ZVal` is long.
     ///
     /// # Examples
@@ -390,6 +637,37 @@ impl ZVal {
         self.inner_expect_z_str()
     }
 
+    /// Borrows the bytes if `ZVal` is string, without copying.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        self.expect_bytes().ok()
+    }
+
+    /// Borrows the bytes if `ZVal` is string, without copying, otherwise
+    /// returns [`ExpectTypeError`].
+    pub fn expect_bytes(&self) -> crate::Result<&[u8]> {
+        self.expect_z_str().map(ZStr::to_bytes)
+    }
+
+    /// Borrows the UTF-8 str if `ZVal` is string, without copying, otherwise
+    /// returns [`ExpectTypeError`], or [`Utf8Error`](std::str::Utf8Error) if
+    /// the string isn't valid UTF-8.
+    pub fn expect_str(&self) -> crate::Result<&str> {
+        Ok(self.expect_z_str()?.to_str()?)
+    }
+
+    /// Copies the bytes if `ZVal` is string, otherwise returns
+    /// [`ExpectTypeError`].
+    pub fn expect_owned_bytes(&self) -> crate::Result<Vec<u8>> {
+        self.expect_bytes().map(ToOwned::to_owned)
+    }
+
+    /// Copies the UTF-8 string if `ZVal` is string, otherwise returns
+    /// [`ExpectTypeError`], or [`Utf8Error`](std::str::Utf8Error) if the
+    /// string isn't valid UTF-8.
+    pub fn expect_owned_string(&self) -> crate::Result<String> {
+        self.expect_str().map(ToOwned::to_owned)
+    }
+
     fn inner_expect_z_str(&self) -> crate::Result<&mut ZStr> {
         if self.get_type_info().is_string() {
             unsafe { Ok(ZStr::from_mut_ptr(phper_z_str_p(self.as_ptr()))) }
@@ -398,6 +676,58 @@ impl ZVal {
         }
     }
 
+    /// Converts to long like the `int` parameter of the core functions, which
+    /// coerces the scalar values if the calling file isn't in the
+    /// `strict_types` mode, otherwise returns [`ExpectTypeError`].
+    ///
+    /// Must be called in the handler of the function, because the mode is
+    /// decided by the caller, see [`ExecuteData::caller_uses_strict_types`].
+    pub fn coerce_long(&mut self) -> crate::Result<i64> {
+        let mut dest = 0;
+        if unsafe { phper_zpp_long(self.as_mut_ptr(), &mut dest) } {
+            Ok(dest)
+        } else {
+            Err(ExpectTypeError::new(TypeInfo::LONG, self.get_type_info()).into())
+        }
+    }
+
+    /// Converts to double like the `float` parameter of the core functions,
+    /// see [`ZVal::coerce_long`].
+    pub fn coerce_double(&mut self) -> crate::Result<f64> {
+        let mut dest = 0.;
+        if unsafe { phper_zpp_double(self.as_mut_ptr(), &mut dest) } {
+            Ok(dest)
+        } else {
+            Err(ExpectTypeError::new(TypeInfo::DOUBLE, self.get_type_info()).into())
+        }
+    }
+
+    /// Converts to bool like the `bool` parameter of the core functions, see
+    /// [`ZVal::coerce_long`].
+    pub fn coerce_bool(&mut self) -> crate::Result<bool> {
+        let mut dest = false;
+        if unsafe { phper_zpp_bool(self.as_mut_ptr(), &mut dest) } {
+            Ok(dest)
+        } else {
+            Err(ExpectTypeError::new(TypeInfo::BOOL, self.get_type_info()).into())
+        }
+    }
+
+    /// Converts to string like the `string` parameter of the core functions,
+    /// the value is converted to string in place if coerced, see
+    /// [`ZVal::coerce_long`].
+    pub fn coerce_z_str(&mut self) -> crate::Result<&ZStr> {
+        let type_info = self.get_type_info();
+        unsafe {
+            let s = phper_zpp_str(self.as_mut_ptr());
+            if s.is_null() {
+                Err(ExpectTypeError::new(TypeInfo::STRING, type_info).into())
+            } else {
+                Ok(ZStr::from_ptr(s))
+            }
+        }
+    }
+
     /// Converts to array if `ZVal` is array.
     pub fn as_z_arr(&self) -> Option<&ZArr> {
         self.expect_z_arr().ok()
@@ -521,6 +851,17 @@ impl ZVal {
         }
     }
 
+    /// Converts the value to reference in place if it isn't, like
+    /// `ZVAL_MAKE_REF`, so the copies made by [`ZVal::shallow_clone`] share the
+    /// same value, for example, returned by the function declared by
+    /// [`FunctionEntity::return_by_ref`](crate::functions::FunctionEntity::return_by_ref).
+    pub fn make_ref(&mut self) -> &mut ZRef {
+        unsafe {
+            phper_zval_make_ref(self.as_mut_ptr());
+            ZRef::from_mut_ptr(phper_z_ref_p(self.as_ptr()))
+        }
+    }
+
     /// Internally convert to long.
     ///
     /// TODO To fix assertion failed.
@@ -539,6 +880,72 @@ impl ZVal {
         }
     }
 
+    /// Gets the refcount of the value, `None` if the value isn't refcounted,
+    /// for example, the scalars, interned strings and immutable arrays.
+    pub fn refcount(&self) -> Option<u32> {
+        unsafe {
+            let ptr = self.as_ptr() as *mut _;
+            if phper_z_refcounted_p(ptr) {
+                Some(phper_z_refcount_p(ptr))
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Returns a copy sharing the value by increasing the refcount, without
+    /// copying the string and array.
+    pub fn shallow_clone(&self) -> ZVal {
+        let mut val = ZVal::default();
+        unsafe {
+            phper_zval_copy(val.as_mut_ptr(), self.as_ptr());
+        }
+        val
+    }
+
+    /// Returns a copy with the string and array copied, so the modification of
+    /// the copy doesn't affect the original, the object is still shared as PHP
+    /// does.
+    pub fn duplicate(&self) -> ZVal {
+        let mut val = self.shallow_clone();
+        val.separate();
+        val
+    }
+
+    /// Copies the string or array in place if they are shared with others
+    /// (refcount is greater than one) or immutable (interned string and
+    /// immutable array), so the value is safe to be modified.
+    #[inline]
+    pub fn separate(&mut self) {
+        unsafe {
+            phper_zval_separate(self.as_mut_ptr());
+        }
+    }
+
+    /// Render the value like `var_dump()` in PHP, including the types, lengths
+    /// and the nested arrays and objects, the recursive ones are rendered as
+    /// `*RECURSION*`.
+    ///
+    /// The alternate debug format (`{:#?}`) renders the same.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use phper::{arrays::ZArray, values::ZVal};
+    ///
+    /// let mut arr = ZArray::new();
+    /// arr.insert("foo", ZVal::from(1));
+    /// assert_eq!(
+    ///     ZVal::from(arr).dump(),
+    ///     "array(1) {\n  [\"foo\"]=>\n  int(1)\n}\n"
+    /// );
+    /// ```
+    pub fn dump(&self) -> String {
+        let mut dumper = Dumper::default();
+        dumper.dump(self, 0);
+        dumper.output
+    }
+
     /// Call only when self is a callable (string or array or closure).
     ///
     /// # Errors
@@ -560,6 +967,10 @@ impl Debug for ZVal {
         #[allow(non_camel_case_types)]
         struct unknown;
 
+        if f.alternate() {
+            return f.write_str(&self.dump());
+        }
+
         let mut d = f.debug_tuple("ZVal");
 
         let t = self.get_type_info();
@@ -606,6 +1017,164 @@ impl Debug for ZVal {
     }
 }
 
+/// Renderer of [`ZVal::dump`], the pointers of arrays and objects being
+/// rendered are recorded, to guard the recursion.
+#[derive(Default)]
+struct Dumper {
+    output: String,
+    rendering: Vec<*const ()>,
+}
+
+impl Dumper {
+    fn dump(&mut self, val: &ZVal, level: usize) {
+        use std::fmt::Write;
+
+        let indent = " ".repeat(level * 2);
+        self.output.push_str(&indent);
+
+        let t = val.get_type_info();
+        if t.is_null() {
+            self.output.push_str("NULL\n");
+        } else if let Some(b) = val.as_bool() {
+            let _ = writeln!(self.output, "bool({})", b);
+        } else if let Some(l) = val.as_long() {
+            let _ = writeln!(self.output, "int({})", l);
+        } else if let Some(d) = val.as_double() {
+            let _ = writeln!(self.output, "float({})", format_double(d));
+        } else if let Some(s) = val.as_z_str() {
+            let _ = writeln!(
+                self.output,
+                "string({}) \"{}\"",
+                s.len(),
+                String::from_utf8_lossy(s.to_bytes())
+            );
+        } else if let Some(arr) = val.as_z_arr() {
+            let ptr = arr.as_ptr().cast();
+            if self.rendering.contains(&ptr) {
+                self.output.push_str("*RECURSION*\n");
+                return;
+            }
+            let _ = writeln!(self.output, "array({}) {{", arr.len());
+            self.dump_elements(arr, level, None);
+            let _ = writeln!(self.output, "{}}}", indent);
+        } else if let Some(obj) = val.as_z_obj() {
+            let ptr = obj.as_ptr().cast();
+            if self.rendering.contains(&ptr) {
+                self.output.push_str("*RECURSION*\n");
+                return;
+            }
+            let class_name = obj.get_class().get_name();
+            let class_name = String::from_utf8_lossy(class_name.to_bytes()).into_owned();
+            let properties = unsafe {
+                ZArr::try_from_ptr(phper_zend_object_get_properties(obj.as_ptr() as *mut _))
+            };
+            let _ = writeln!(
+                self.output,
+                "object({})#{} ({}) {{",
+                class_name,
+                obj.handle(),
+                properties.map_or(0, ZArr::len)
+            );
+            if let Some(properties) = properties {
+                self.rendering.push(ptr);
+                self.dump_elements(properties, level, Some(&class_name));
+                self.rendering.pop();
+            }
+            let _ = writeln!(self.output, "{}}}", indent);
+        } else if let Some(res) = val.as_z_res() {
+            let type_name = unsafe { phper_zend_resource_type_name(res.as_ptr() as *mut _) };
+            let type_name = if type_name.is_null() {
+                "Unknown".into()
+            } else {
+                unsafe { CStr::from_ptr(type_name) }.to_string_lossy()
+            };
+            let _ = writeln!(
+                self.output,
+                "resource({}) of type ({})",
+                res.handle(),
+                type_name
+            );
+        } else if let Some(r) = val.as_z_ref() {
+            self.output.truncate(self.output.len() - indent.len());
+            self.dump(r.val(), level);
+        } else {
+            self.output.push_str("UNKNOWN\n");
+        }
+    }
+
+    fn dump_elements(&mut self, arr: &ZArr, level: usize, class_name: Option<&str>) {
+        use std::fmt::Write;
+
+        let ptr = arr.as_ptr().cast();
+        let is_array = class_name.is_none();
+        if is_array {
+            self.rendering.push(ptr);
+        }
+
+        let indent = " ".repeat((level + 1) * 2);
+        for (key, val) in arr.iter() {
+            let _ = match key {
+                IterKey::Index(i) => writeln!(self.output, "{}[{}]=>", indent, i),
+                IterKey::ZStr(s) => {
+                    let key = String::from_utf8_lossy(s.to_bytes());
+                    match class_name.and_then(|_| demangle_property_name(&key)) {
+                        Some((name, "*")) => {
+                            writeln!(self.output, "{}[\"{}\":protected]=>", indent, name)
+                        }
+                        Some((name, class)) => writeln!(
+                            self.output,
+                            "{}[\"{}\":\"{}\":private]=>",
+                            indent, name, class
+                        ),
+                        None => writeln!(self.output, "{}[\"{}\"]=>", indent, key),
+                    }
+                }
+            };
+            self.dump(val, level + 1);
+        }
+
+        if is_array {
+            self.rendering.pop();
+        }
+    }
+}
+
+/// Demangle the property name like `\0*\0name` or `\0Class\0name`, into the
+/// name and the class, `*` for protected.
+fn demangle_property_name(key: &str) -> Option<(&str, &str)> {
+    let rest = key.strip_prefix('\0')?;
+    let (class, name) = rest.split_once('\0')?;
+    Some((name, class))
+}
+
+/// Format the double like `var_dump()`, with the `serialize_precision` -1.
+fn format_double(d: f64) -> String {
+    if d.is_nan() {
+        "NAN".to_owned()
+    } else if d.is_infinite() {
+        (if d > 0. { "INF" } else { "-INF" }).to_owned()
+    } else if d != 0. && (d.abs() >= 1e15 || d.abs() < 1e-4) {
+        let s = format!("{:E}", d);
+        match s.split_once('E') {
+            Some((mantissa, exp)) if !exp.starts_with('-') => {
+                format!("{}E+{}", with_fraction(mantissa), exp)
+            }
+            Some((mantissa, exp)) => format!("{}E{}", with_fraction(mantissa), exp),
+            None => s,
+        }
+    } else {
+        d.to_string()
+    }
+}
+
+fn with_fraction(mantissa: &str) -> String {
+    if mantissa.contains('.') {
+        mantissa.to_owned()
+    } else {
+        format!("{}.0", mantissa)
+    }
+}
+
 impl Default for ZVal {
     #[inline]
     fn default() -> Self {
@@ -614,39 +1183,41 @@ impl Default for ZVal {
 }
 
 impl Clone for ZVal {
+    #[inline]
     fn clone(&self) -> Self {
-        let mut val = ZVal::default();
-        unsafe {
-            phper_zval_copy(val.as_mut_ptr(), self.as_ptr());
-            if val.get_type_info().is_string() {
-                phper_separate_string(val.as_mut_ptr());
-            } else if val.get_type_info().is_array() {
-                phper_separate_array(val.as_mut_ptr());
-            }
-        }
-        val
+        self.duplicate()
     }
 }
 
 impl RefClone for ZVal {
+    #[inline]
     fn ref_clone(&mut self) -> Self {
-        let mut val = ZVal::default();
-        unsafe {
-            phper_zval_copy(val.as_mut_ptr(), self.as_ptr());
-        }
-        val
+        self.shallow_clone()
     }
 }
 
 impl Drop for ZVal {
     fn drop(&mut self) {
         unsafe {
+            zend_assert!(
+                !phper_z_refcounted_p(self.as_mut_ptr())
+                    || phper_z_refcount_p(self.as_mut_ptr()) > 0,
+                "drop the zval referencing the released value"
+            );
             phper_zval_ptr_dtor(self.as_mut_ptr());
+
+            // Poison the released zval, so the dangling copy of it is noisy.
+            #[cfg(feature = "debug")]
+            self.as_mut_ptr().cast::<u8>().write_bytes(
+                crate::alloc::debug::POISON_BYTE,
+                std::mem::size_of::<zval>(),
+            );
         }
     }
 }
 
 impl From<()> for ZVal {
+    #[inline]
     fn from(_: ()) -> Self {
         unsafe {
             let mut val = MaybeUninit::<ZVal>::uninit();
@@ -657,6 +1228,7 @@ impl From<()> for ZVal {
 }
 
 impl From<bool> for ZVal {
+    #[inline]
     fn from(b: bool) -> Self {
         unsafe {
             let mut val = MaybeUninit::<ZVal>::uninit();
@@ -671,6 +1243,7 @@ impl From<bool> for ZVal {
 }
 
 impl From<i64> for ZVal {
+    #[inline]
     #[allow(clippy::useless_conversion)]
     fn from(i: i64) -> Self {
         unsafe {
@@ -682,6 +1255,7 @@ impl From<i64> for ZVal {
 }
 
 impl From<f64> for ZVal {
+    #[inline]
     #[allow(clippy::useless_conversion)]
     fn from(f: f64) -> Self {
         unsafe {
@@ -741,6 +1315,13 @@ impl From<ZString> for ZVal {
     }
 }
 
+impl From<ZStringBuilder> for ZVal {
+    #[inline]
+    fn from(builder: ZStringBuilder) -> Self {
+        builder.finish().into()
+    }
+}
+
 impl From<ZArray> for ZVal {
     fn from(arr: ZArray) -> Self {
         unsafe {
@@ -751,6 +1332,23 @@ impl From<ZArray> for ZVal {
     }
 }
 
+impl From<PersistentZString> for ZVal {
+    fn from(s: PersistentZString) -> Self {
+        s.into_inner().into()
+    }
+}
+
+/// The persistent array is copied into the request, so it's released by the
+/// request as the other arrays, and the persistent one is freed at once.
+impl From<PersistentZArray> for ZVal {
+    fn from(arr: PersistentZArray) -> Self {
+        unsafe {
+            let copy = phper_zend_persistent_array_to_request(arr.as_ptr());
+            ZVal::from(ZArray::from_raw(copy))
+        }
+    }
+}
+
 impl From<ZObject> for ZVal {
     fn from(obj: ZObject) -> Self {
         unsafe {
@@ -781,3 +1379,84 @@ impl<T: Into<ZVal>> From<EBox<T>> for ZVal {
         t.into_inner().into()
     }
 }
+
+/// The value can be held across requests, only constructed from the scalars,
+/// [`PersistentZString`] and [`PersistentZArray`], so it can be inserted into
+/// [`PersistentZArray`].
+pub struct PersistentZVal {
+    inner: ZVal,
+}
+
+impl PersistentZVal {
+    /// Consumes the `PersistentZVal`, returning the wrapped [`ZVal`].
+    #[inline]
+    pub fn into_inner(self) -> ZVal {
+        self.inner
+    }
+}
+
+impl Debug for PersistentZVal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+
+impl Deref for PersistentZVal {
+    type Target = ZVal;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl From<()> for PersistentZVal {
+    fn from(_: ()) -> Self {
+        Self {
+            inner: ZVal::from(()),
+        }
+    }
+}
+
+impl From<bool> for PersistentZVal {
+    fn from(b: bool) -> Self {
+        Self {
+            inner: ZVal::from(b),
+        }
+    }
+}
+
+impl From<i64> for PersistentZVal {
+    fn from(i: i64) -> Self {
+        Self {
+            inner: ZVal::from(i),
+        }
+    }
+}
+
+impl From<f64> for PersistentZVal {
+    fn from(f: f64) -> Self {
+        Self {
+            inner: ZVal::from(f),
+        }
+    }
+}
+
+impl From<PersistentZString> for PersistentZVal {
+    fn from(s: PersistentZString) -> Self {
+        Self {
+            inner: ZVal::from(s),
+        }
+    }
+}
+
+/// The nested persistent array is made immutable, and freed with the array it's
+/// inserted into.
+impl From<PersistentZArray> for PersistentZVal {
+    fn from(arr: PersistentZArray) -> Self {
+        let mut inner = ZVal::from(arr.into_inner());
+        unsafe {
+            phper_zval_make_array_immutable(inner.as_mut_ptr());
+        }
+        Self { inner }
+    }
+}
@@ -0,0 +1,151 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to `WeakReference` (PHP 7.4+) and `WeakMap` (PHP 8.0+).
+//!
+//! The wrapped objects are allocated per request, so they shouldn't be kept
+//! after the request is finished.
+
+use crate::{
+    alloc::ToRefOwned,
+    functions::call,
+    objects::{ZObj, ZObject},
+    values::ZVal,
+};
+
+/// Wrapper of PHP `WeakReference`, which holds an object without preventing
+/// it from being destroyed.
+#[derive(Debug)]
+pub struct WeakReference {
+    inner: ZObject,
+}
+
+impl WeakReference {
+    /// Create the weak reference of the object, like
+    /// `WeakReference::create($obj)` in PHP.
+    pub fn new(obj: &mut ZObj) -> crate::Result<Self> {
+        let mut reference = call("WeakReference::create", [ZVal::from(obj.to_ref_owned())])?;
+        let inner = reference.expect_mut_z_obj()?.to_ref_owned();
+        Ok(Self { inner })
+    }
+
+    /// Gets the referenced object, return `None` if the object has been
+    /// destroyed.
+    pub fn get(&mut self) -> crate::Result<Option<ZObject>> {
+        let mut obj = self.inner.call("get", &mut [])?;
+        Ok(obj.as_mut_z_obj().map(ToRefOwned::to_ref_owned))
+    }
+
+    /// Returns `true` if the referenced object is still alive.
+    pub fn is_alive(&mut self) -> crate::Result<bool> {
+        Ok(self.get()?.is_some())
+    }
+
+    /// Gets the inner `WeakReference` object.
+    #[inline]
+    pub fn as_z_obj(&self) -> &ZObj {
+        &self.inner
+    }
+
+    /// Consumes the wrapper, returning the inner `WeakReference` object.
+    #[inline]
+    pub fn into_inner(self) -> ZObject {
+        self.inner
+    }
+}
+
+/// Wrapper of PHP `WeakMap`, which uses objects as keys without preventing
+/// them from being destroyed, the entry is removed when the key object is
+/// destroyed.
+///
+/// Useful for caching the per object metadata.
+///
+/// # Examples
+///
+/// ```no_run
+/// use phper::{objects::ZObj, values::ZVal, weak::WeakMap};
+///
+/// fn visit(map: &mut WeakMap, obj: &mut ZObj) -> phper::Result<i64> {
+///     let count = match map.get(obj)? {
+///         Some(count) => count.expect_long()? + 1,
+///         None => 1,
+///     };
+///     map.insert(obj, ZVal::from(count))?;
+///     Ok(count)
+/// }
+/// ```
+#[cfg(phper_major_version = "8")]
+#[derive(Debug)]
+pub struct WeakMap {
+    inner: ZObject,
+}
+
+#[cfg(phper_major_version = "8")]
+impl WeakMap {
+    /// Create an empty `WeakMap`, like `new WeakMap()` in PHP.
+    pub fn new() -> crate::Result<Self> {
+        let inner = ZObject::new_by_class_name("WeakMap", &mut [])?;
+        Ok(Self { inner })
+    }
+
+    /// Gets the value associated with the key object.
+    pub fn get(&mut self, key: &mut ZObj) -> crate::Result<Option<ZVal>> {
+        if !self.contains_key(key)? {
+            return Ok(None);
+        }
+        self.inner
+            .call("offsetGet", [ZVal::from(key.to_ref_owned())])
+            .map(Some)
+    }
+
+    /// Associates the value with the key object, the old value is replaced.
+    pub fn insert(&mut self, key: &mut ZObj, value: impl Into<ZVal>) -> crate::Result<()> {
+        self.inner
+            .call("offsetSet", [ZVal::from(key.to_ref_owned()), value.into()])?;
+        Ok(())
+    }
+
+    /// Returns `true` if the map contains the key object.
+    pub fn contains_key(&mut self, key: &mut ZObj) -> crate::Result<bool> {
+        self.inner
+            .call("offsetExists", [ZVal::from(key.to_ref_owned())])?
+            .expect_bool()
+    }
+
+    /// Removes the key object from the map.
+    pub fn remove(&mut self, key: &mut ZObj) -> crate::Result<()> {
+        self.inner
+            .call("offsetUnset", [ZVal::from(key.to_ref_owned())])?;
+        Ok(())
+    }
+
+    /// Gets the count of alive entries.
+    pub fn len(&mut self) -> crate::Result<usize> {
+        let count = self.inner.call("count", &mut [])?.expect_long()?;
+        Ok(count.try_into().unwrap())
+    }
+
+    /// Returns `true` if the map has no alive entry.
+    pub fn is_empty(&mut self) -> crate::Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Gets the inner `WeakMap` object.
+    #[inline]
+    pub fn as_z_obj(&self) -> &ZObj {
+        &self.inner
+    }
+
+    /// Consumes the wrapper, returning the inner `WeakMap` object.
+    #[inline]
+    pub fn into_inner(self) -> ZObject {
+        self.inner
+    }
+}
@@ -0,0 +1,416 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to splitting the CPU-bound work of a handler across threads.
+//!
+//! Only the PHP thread can touch the PHP values, so the work is done in three
+//! steps, and the types enforce it:
+//!
+//! 1. The inputs are extracted from the PHP values into the plain Rust values
+//!    in the PHP thread.
+//! 2. The Rust values are processed by the worker threads, the inputs, outputs
+//!    and the processing function must be [`Send`] or [`Sync`], which the PHP
+//!    values ([`ZVal`], [`ZArr`], etc.) aren't, so they can't be touched by the
+//!    workers.
+//! 3. The outputs are converted back into the PHP values in the PHP thread.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use phper::{modules::Module, php_get_module, values::ZVal};
+//!
+//! #[php_get_module]
+//! pub fn get_module() -> Module {
+//!     let mut module = Module::new(
+//!         env!("CARGO_PKG_NAME"),
+//!         env!("CARGO_PKG_VERSION"),
+//!         env!("CARGO_PKG_AUTHORS"),
+//!     );
+//!
+//!     let pool = module.add_worker_pool(4);
+//!     module.add_function("hash_all", move |arguments: &mut [ZVal]| {
+//!         pool.map_z_arr(
+//!             arguments[0].expect_z_arr()?,
+//!             |value| Ok(value.expect_z_str()?.to_bytes().to_vec()),
+//!             |data| format!("{:x}", data.iter().map(|b| *b as u64).sum::<u64>()),
+//!         )
+//!     });
+//!
+//!     module
+//! }
+//! ```
+
+use crate::{
+    arrays::{InsertKey, ZArr, ZArray},
+    crashes::SignalStack,
+    utils::SignalsBlocked,
+    values::ZVal,
+};
+use std::{
+    cell::Cell,
+    collections::VecDeque,
+    fmt::{self, Debug},
+    mem::{forget, transmute},
+    num::NonZeroUsize,
+    panic::{catch_unwind, AssertUnwindSafe},
+    process,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    thread::{self, JoinHandle},
+};
+
+thread_local! {
+    static IN_WORKER: Cell<bool> = const { Cell::new(false) };
+}
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// The bounded pool of worker threads, shared by all the calls of the process.
+///
+/// Register it by [`Module::add_worker_pool`] to be stopped in `MSHUTDOWN`,
+/// the handle is cheap to clone, and moved into the handlers.
+///
+/// It is designed to be safe with the signal handling and fork model of PHP:
+///
+/// - The fixed set of worker threads is spawned lazily in the first call of the
+///   process, with all the signals blocked, so the signals like the timer of
+///   `max_execution_time` are always delivered to the PHP thread.
+/// - The calls from the different requests, or the threads of ZTS, share the
+///   queue of the pool, so at most `threads` chunks are processed at the same
+///   time in the process.
+/// - If the process is forked (like the php-fpm workers), the new worker
+///   threads are spawned in the child process, rather than touching the locks
+///   may be held by the threads which don't exist in the child.
+/// - The calls made in the worker threads, or after the pool is stopped, are
+///   processed in the calling thread, rather than waiting for the workers.
+///
+/// [`Module::add_worker_pool`]: crate::modules::Module::add_worker_pool
+#[derive(Clone)]
+pub struct WorkerPool {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    threads: usize,
+    workers: Mutex<Option<Workers>>,
+}
+
+struct Workers {
+    pid: u32,
+    shared: Arc<Shared>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+struct Shared {
+    queue: Mutex<Queue>,
+    condvar: Condvar,
+}
+
+struct Queue {
+    jobs: VecDeque<Job>,
+    closed: bool,
+}
+
+/// Counts the pending jobs of a call, and waits for them when dropped, so the
+/// data borrowed by the jobs outlives them, even if the call panics.
+struct Pending {
+    count: Mutex<usize>,
+    condvar: Condvar,
+}
+
+impl WorkerPool {
+    /// Creates the pool running at most `threads` threads, at least one, the
+    /// threads are spawned in the first call.
+    pub fn new(threads: usize) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                threads: threads.max(1),
+                workers: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// The max count of threads.
+    #[inline]
+    pub fn threads(&self) -> usize {
+        self.inner.threads
+    }
+
+    /// Process the inputs in the worker threads, returns the outputs in the
+    /// same order.
+    ///
+    /// The inputs are split into the contiguous chunks, one chunk for each
+    /// thread, so the inputs should take the similar time.
+    ///
+    /// # Errors
+    ///
+    /// Return the error if processing any chunk panics.
+    pub fn map<T, R, F>(&self, inputs: Vec<T>, f: F) -> crate::Result<Vec<R>>
+    where
+        T: Send,
+        R: Send,
+        F: Fn(T) -> R + Sync,
+    {
+        let len = inputs.len();
+        let threads = self.inner.threads.min(len);
+        if threads <= 1 || IN_WORKER.with(Cell::get) {
+            return Ok(inputs.into_iter().map(f).collect());
+        }
+
+        let chunk_size = (len + threads - 1) / threads;
+        let mut chunks = Vec::with_capacity(threads);
+        let mut inputs = inputs.into_iter();
+        loop {
+            let chunk = inputs.by_ref().take(chunk_size).collect::<Vec<_>>();
+            if chunk.is_empty() {
+                break;
+            }
+            chunks.push(chunk);
+        }
+
+        let slots = chunks.iter().map(|_| Mutex::new(None)).collect::<Vec<_>>();
+        {
+            let pending = Pending {
+                count: Mutex::new(0),
+                condvar: Condvar::new(),
+            };
+            let f = &f;
+            let pending = &pending;
+            let _wait = WaitGuard(pending);
+            let shared = self.inner.shared();
+
+            for (chunk, slot) in chunks.into_iter().zip(&slots) {
+                let job = move || {
+                    let outputs = catch_unwind(AssertUnwindSafe(|| {
+                        chunk.into_iter().map(f).collect::<Vec<_>>()
+                    }));
+                    *slot.lock().unwrap() = Some(outputs);
+                };
+
+                let Some(shared) = &shared else {
+                    job();
+                    continue;
+                };
+                let job: Box<dyn FnOnce() + Send + '_> = Box::new(move || {
+                    job();
+                    pending.done();
+                });
+                // Safety: the job only borrows the data of this call, which
+                // outlives the job, because `_wait` waits for all the pushed
+                // jobs before returning or unwinding.
+                let job = unsafe { transmute::<_, Job>(job) };
+                *pending.count.lock().unwrap() += 1;
+                if let Err(job) = shared.push(job) {
+                    job();
+                }
+            }
+        }
+
+        let mut outputs = Vec::with_capacity(len);
+        for slot in slots {
+            match slot.into_inner().unwrap() {
+                Some(Ok(chunk)) => outputs.extend(chunk),
+                _ => return Err(crate::Error::boxed("the worker thread panicked")),
+            }
+        }
+        Ok(outputs)
+    }
+
+    /// Process the elements of PHP array in the worker threads, returns the
+    /// array of outputs with the same keys.
+    ///
+    /// The `extract` is called in the PHP thread to copy each element into the
+    /// Rust value, and the outputs are converted into the PHP values in the PHP
+    /// thread after all are processed.
+    ///
+    /// # Errors
+    ///
+    /// Return the error of `extract`, or if processing any chunk panics.
+    pub fn map_z_arr<T, R, F>(
+        &self, arr: &ZArr, mut extract: impl FnMut(&ZVal) -> crate::Result<T>, f: F,
+    ) -> crate::Result<ZArray>
+    where
+        T: Send,
+        R: Send + Into<ZVal>,
+        F: Fn(T) -> R + Sync,
+    {
+        let mut keys = Vec::new();
+        let mut inputs = Vec::new();
+        for (key, value) in arr.iter() {
+            keys.push(key);
+            inputs.push(extract(value)?);
+        }
+
+        let outputs = self.map(inputs, f)?;
+
+        let mut result = ZArray::with_capacity(outputs.len());
+        for (key, output) in keys.into_iter().zip(outputs) {
+            result.insert(InsertKey::from(key), output);
+        }
+        Ok(result)
+    }
+
+    /// Stop the worker threads after the queued jobs are processed, the
+    /// following calls in the process are processed in the calling thread.
+    pub fn shutdown(&self) {
+        self.inner.shutdown();
+    }
+}
+
+impl Debug for WorkerPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WorkerPool")
+            .field("threads", &self.inner.threads)
+            .finish()
+    }
+}
+
+impl Default for WorkerPool {
+    /// Creates the pool running as many threads as the available parallelism.
+    fn default() -> Self {
+        Self::new(thread::available_parallelism().map_or(1, NonZeroUsize::get))
+    }
+}
+
+impl Inner {
+    /// Gets the queue of the current process, spawns the worker threads if
+    /// they aren't spawned in the process, `None` if the pool is stopped or
+    /// the threads can't be spawned.
+    fn shared(&self) -> Option<Arc<Shared>> {
+        let mut workers = self.current_workers();
+        let workers = workers.get_or_insert_with(|| Workers::spawn(self.threads));
+        if workers.threads.is_empty() {
+            None
+        } else {
+            Some(workers.shared.clone())
+        }
+    }
+
+    fn shutdown(&self) {
+        let workers = self.current_workers().take();
+        if let Some(mut workers) = workers {
+            workers.shared.queue.lock().unwrap().closed = true;
+            workers.shared.condvar.notify_all();
+            for thread in workers.threads.drain(..) {
+                let _ = thread.join();
+            }
+            // Keep the stopped workers to process the following calls in the
+            // calling thread.
+            *self.workers.lock().unwrap() = Some(workers);
+        }
+    }
+
+    /// Lock the workers of the current process, the inherited ones are
+    /// discarded.
+    fn current_workers(&self) -> MutexGuard<'_, Option<Workers>> {
+        let mut workers = self.workers.lock().unwrap();
+        if workers.as_ref().map(|workers| workers.pid) != Some(process::id()) {
+            if let Some(inherited) = workers.take() {
+                forget(inherited);
+            }
+        }
+        workers
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+impl Workers {
+    fn spawn(threads: usize) -> Self {
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue {
+                jobs: VecDeque::new(),
+                closed: false,
+            }),
+            condvar: Condvar::new(),
+        });
+        // Block the signals once for all the worker threads, which inherit the
+        // mask.
+        let _blocked = SignalsBlocked::new();
+        let threads = (0..threads)
+            .map_while(|i| {
+                let shared = shared.clone();
+                thread::Builder::new()
+                    .name(format!("phper-worker-{}", i))
+                    .spawn(move || {
+                        let _stack = SignalStack::new();
+                        IN_WORKER.with(|flag| flag.set(true));
+                        run(&shared);
+                    })
+                    .ok()
+            })
+            .collect();
+        Self {
+            pid: process::id(),
+            shared,
+            threads,
+        }
+    }
+}
+
+impl Shared {
+    /// Push the job to the queue, return it back if the pool is stopped.
+    fn push(&self, job: Job) -> Result<(), Job> {
+        let mut queue = self.queue.lock().unwrap();
+        if queue.closed {
+            return Err(job);
+        }
+        queue.jobs.push_back(job);
+        self.condvar.notify_one();
+        Ok(())
+    }
+}
+
+fn run(shared: &Shared) {
+    loop {
+        let job = {
+            let mut queue = shared.queue.lock().unwrap();
+            loop {
+                if let Some(job) = queue.jobs.pop_front() {
+                    break job;
+                }
+                if queue.closed {
+                    return;
+                }
+                queue = shared.condvar.wait(queue).unwrap();
+            }
+        };
+        // The job catches the panic of processing itself.
+        job();
+    }
+}
+
+impl Pending {
+    fn done(&self) {
+        let mut count = self.count.lock().unwrap();
+        *count -= 1;
+        if *count == 0 {
+            self.condvar.notify_all();
+        }
+    }
+}
+
+struct WaitGuard<'a>(&'a Pending);
+
+impl Drop for WaitGuard<'_> {
+    fn drop(&mut self) {
+        let mut count = self.0.count.lock().unwrap_or_else(|e| e.into_inner());
+        while *count > 0 {
+            count = self
+                .0
+                .condvar
+                .wait(count)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+}
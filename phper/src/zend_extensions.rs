@@ -0,0 +1,204 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Apis relate to registering the module as a zend extension (engine
+//! extension) too, for the engine level hooks, like the op_array handlers used
+//! by profilers and coverage collectors.
+//!
+//! The zend extension is registered in `MINIT`, so the extension is loaded by
+//! `extension=` as usual, not `zend_extension=`.
+
+use crate::{
+    contexts::ModuleContext, functions::ZOpArray, modules::global_module_mut, sys::*,
+    utils::ensure_end_with_zero,
+};
+use std::{
+    ffi::CString,
+    mem::{take, zeroed},
+    os::raw::c_int,
+};
+
+/// Builder for registering zend extension, created by
+/// [`Module::zend_extension`](crate::modules::Module::zend_extension).
+#[allow(clippy::type_complexity)]
+pub struct ZendExtensionEntity {
+    name: CString,
+    version: CString,
+    author: CString,
+    url: CString,
+    copyright: CString,
+    startup: Option<Box<dyn FnOnce()>>,
+    shutdown: Option<Box<dyn FnOnce()>>,
+    activate: Option<Box<dyn Fn()>>,
+    deactivate: Option<Box<dyn Fn()>>,
+    op_array_ctor: Option<Box<dyn Fn(&mut ZOpArray)>>,
+    op_array_handler: Option<Box<dyn Fn(&mut ZOpArray)>>,
+    op_array_dtor: Option<Box<dyn Fn(&mut ZOpArray)>>,
+}
+
+impl ZendExtensionEntity {
+    pub(crate) fn new(name: &CString, version: &CString, author: &CString) -> Self {
+        Self {
+            name: name.clone(),
+            version: version.clone(),
+            author: author.clone(),
+            url: Default::default(),
+            copyright: Default::default(),
+            startup: None,
+            shutdown: None,
+            activate: None,
+            deactivate: None,
+            op_array_ctor: None,
+            op_array_handler: None,
+            op_array_dtor: None,
+        }
+    }
+
+    /// Set the url, shown by `php -v`.
+    pub fn url(&mut self, url: impl Into<String>) -> &mut Self {
+        self.url = ensure_end_with_zero(url);
+        self
+    }
+
+    /// Set the copyright, shown by `php -v`.
+    pub fn copyright(&mut self, copyright: impl Into<String>) -> &mut Self {
+        self.copyright = ensure_end_with_zero(copyright);
+        self
+    }
+
+    /// Register the `startup` hook, called after the `MINIT` of all modules.
+    pub fn on_startup(&mut self, func: impl FnOnce() + 'static) -> &mut Self {
+        self.startup = Some(Box::new(func));
+        self
+    }
+
+    /// Register the `shutdown` hook, called after the `MSHUTDOWN` of all
+    /// modules.
+    pub fn on_shutdown(&mut self, func: impl FnOnce() + 'static) -> &mut Self {
+        self.shutdown = Some(Box::new(func));
+        self
+    }
+
+    /// Register the `activate` hook, called at the start of request, before
+    /// the `RINIT` of modules.
+    pub fn on_activate(&mut self, func: impl Fn() + 'static) -> &mut Self {
+        self.activate = Some(Box::new(func));
+        self
+    }
+
+    /// Register the `deactivate` hook, called at the end of request, after
+    /// the `RSHUTDOWN` of modules.
+    pub fn on_deactivate(&mut self, func: impl Fn() + 'static) -> &mut Self {
+        self.deactivate = Some(Box::new(func));
+        self
+    }
+
+    /// Register the hook called when the op_array is created, before it's
+    /// compiled.
+    pub fn on_op_array_ctor(&mut self, func: impl Fn(&mut ZOpArray) + 'static) -> &mut Self {
+        self.op_array_ctor = Some(Box::new(func));
+        self
+    }
+
+    /// Register the hook called when the op_array is compiled, the opcodes are
+    /// available.
+    pub fn on_op_array_handler(&mut self, func: impl Fn(&mut ZOpArray) + 'static) -> &mut Self {
+        self.op_array_handler = Some(Box::new(func));
+        self
+    }
+
+    /// Register the hook called when the op_array is destroyed.
+    pub fn on_op_array_dtor(&mut self, func: impl Fn(&mut ZOpArray) + 'static) -> &mut Self {
+        self.op_array_dtor = Some(Box::new(func));
+        self
+    }
+
+    /// Register to the engine, the struct is copied by the engine.
+    pub(crate) unsafe fn register(&self, _ctx: &ModuleContext<'_>, module_name: &CString) {
+        let mut extension: zend_extension = zeroed();
+        extension.name = self.name.as_ptr() as _;
+        extension.version = self.version.as_ptr() as _;
+        extension.author = self.author.as_ptr() as _;
+        extension.URL = self.url.as_ptr() as _;
+        extension.copyright = self.copyright.as_ptr() as _;
+        extension.startup = Some(startup);
+        extension.shutdown = Some(shutdown);
+        if self.activate.is_some() {
+            extension.activate = Some(activate);
+        }
+        if self.deactivate.is_some() {
+            extension.deactivate = Some(deactivate);
+        }
+        if self.op_array_ctor.is_some() {
+            extension.op_array_ctor = Some(op_array_ctor);
+        }
+        if self.op_array_handler.is_some() {
+            extension.op_array_handler = Some(op_array_handler);
+        }
+        if self.op_array_dtor.is_some() {
+            extension.op_array_dtor = Some(op_array_dtor);
+        }
+
+        phper_zend_register_extension(
+            &mut extension,
+            module_name.as_ptr(),
+            module_name.as_bytes().len(),
+        );
+    }
+}
+
+unsafe fn entity<'a>() -> &'a mut ZendExtensionEntity {
+    global_module_mut()
+        .zend_extension_entity()
+        .expect("zend extension isn't registered")
+}
+
+unsafe extern "C" fn startup(_extension: *mut zend_extension) -> c_int {
+    if let Some(f) = take(&mut entity().startup) {
+        f();
+    }
+    ZEND_RESULT_CODE_SUCCESS
+}
+
+unsafe extern "C" fn shutdown(_extension: *mut zend_extension) {
+    if let Some(f) = take(&mut entity().shutdown) {
+        f();
+    }
+}
+
+unsafe extern "C" fn activate() {
+    if let Some(f) = &entity().activate {
+        f();
+    }
+}
+
+unsafe extern "C" fn deactivate() {
+    if let Some(f) = &entity().deactivate {
+        f();
+    }
+}
+
+unsafe extern "C" fn op_array_ctor(op_array: *mut zend_op_array) {
+    if let Some(f) = &entity().op_array_ctor {
+        f(ZOpArray::from_mut_ptr(op_array));
+    }
+}
+
+unsafe extern "C" fn op_array_handler(op_array: *mut zend_op_array) {
+    if let Some(f) = &entity().op_array_handler {
+        f(ZOpArray::from_mut_ptr(op_array));
+    }
+}
+
+unsafe extern "C" fn op_array_dtor(op_array: *mut zend_op_array) {
+    if let Some(f) = &entity().op_array_dtor {
+        f(ZOpArray::from_mut_ptr(op_array));
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! Compare the call overhead of phper with the hand-written C extension, the
+//! elapsed time is measured by the PHP script `php/bench.php`, excluding the
+//! startup of PHP process.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use once_cell::sync::Lazy;
+use phper_test::utils::get_lib_path;
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::Command,
+    time::Duration,
+};
+
+const CASES: &[(&str, &[&str])] = &[
+    ("noop", &["bench_c_noop", "bench_phper_noop"]),
+    ("add", &["bench_c_add", "bench_phper_add"]),
+    ("concat", &["bench_c_concat", "bench_phper_concat"]),
+    (
+        "sum",
+        &["bench_c_sum", "bench_phper_sum", "bench_phper_sum_bulk"],
+    ),
+    (
+        "str_equals",
+        &[
+            "bench_c_str_equals",
+            "bench_phper_str_equals",
+            "bench_phper_str_equals_bytes",
+        ],
+    ),
+    (
+        "str_casecmp",
+        &[
+            "bench_c_str_casecmp",
+            "bench_phper_str_casecmp",
+            "bench_phper_str_casecmp_bytes",
+        ],
+    ),
+    (
+        "pass_1mb",
+        &[
+            "bench_c_pass_through",
+            "bench_phper_pass_through_borrowed",
+            "bench_phper_pass_through_owned",
+        ],
+    ),
+    (
+        "callback",
+        &[
+            "bench_c_callback",
+            "bench_phper_callback_cached",
+            "bench_phper_callback_uncached",
+        ],
+    ),
+    (
+        "args",
+        &[
+            "bench_c_args",
+            "bench_phper_args",
+            "bench_phper_args_copied",
+        ],
+    ),
+];
+
+static PHP_BIN: Lazy<String> = Lazy::new(|| {
+    let php_config = env::var("PHP_CONFIG").unwrap_or_else(|_| "php-config".to_string());
+    let output = Command::new(php_config)
+        .arg("--php-binary")
+        .output()
+        .expect("run php-config failed");
+    String::from_utf8(output.stdout).unwrap().trim().to_owned()
+});
+
+fn run_php(case: &str, function: &str, iterations: u64) -> Duration {
+    let lib_path = get_lib_path(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("..")
+            .join("target"),
+        "benchmark",
+    );
+    let script = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("php")
+        .join("bench.php");
+
+    let output = Command::new(&*PHP_BIN)
+        .arg("-n")
+        .arg("-d")
+        .arg(format!("extension={}", lib_path.display()))
+        .arg(script)
+        .args([case, function, &iterations.to_string()])
+        .output()
+        .expect("run php failed");
+    assert!(
+        output.status.success(),
+        "run php failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let nanos = String::from_utf8(output.stdout)
+        .unwrap()
+        .trim()
+        .parse()
+        .expect("invalid elapsed nanoseconds");
+    Duration::from_nanos(nanos)
+}
+
+fn bench_call(c: &mut Criterion) {
+    for (case, functions) in CASES {
+        let mut group = c.benchmark_group(*case);
+        // Every iteration is a call, so the throughput is the calls per second.
+        group.throughput(Throughput::Elements(1));
+        for function in *functions {
+            group.bench_function(BenchmarkId::from_parameter(function), |b| {
+                b.iter_custom(|iters| run_php(case, function, iters))
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_call);
+criterion_main!(benches);
@@ -0,0 +1,33 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use std::{env, process::Command};
+
+fn main() {
+    phper_build::register_all();
+
+    println!("cargo:rerun-if-changed=src/raw.c");
+    println!("cargo:rerun-if-env-changed=PHP_CONFIG");
+
+    // Build the hand-written C functions, which are the baseline of benchmark.
+    let php_config = env::var("PHP_CONFIG").unwrap_or_else(|_| "php-config".to_string());
+    let output = Command::new(&php_config)
+        .arg("--includes")
+        .output()
+        .unwrap_or_else(|_| panic!("Execute command {:?} failed", php_config))
+        .stdout;
+    let includes = String::from_utf8(output).unwrap();
+
+    let mut builder = cc::Build::new();
+    for include in includes.split_whitespace() {
+        builder.flag(include);
+    }
+    builder.file("src/raw.c").compile("benchmarkraw");
+}
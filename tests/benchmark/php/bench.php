@@ -0,0 +1,99 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+// The micro-benchmarks of the call overhead, compare the phper functions with
+// the hand-written C ones.
+//
+// Usage:
+//
+//   php -d extension=libbenchmark.so bench.php
+//   php -d extension=libbenchmark.so bench.php <case> <function> <iterations>
+//
+// Without arguments, all the cases are run and printed as table, otherwise only
+// the elapsed nanoseconds of the function are printed, used by the criterion
+// benchmark.
+
+$cases = [
+    "noop" => [[], ["bench_c_noop", "bench_phper_noop"]],
+    "add" => [[1, 2], ["bench_c_add", "bench_phper_add"]],
+    "concat" => [
+        [str_repeat("a", 64), str_repeat("b", 64)],
+        ["bench_c_concat", "bench_phper_concat"],
+    ],
+    "sum" => [[range(1, 100)], ["bench_c_sum", "bench_phper_sum", "bench_phper_sum_bulk"]],
+    // The strings are equal but not the same `zend_string`, so they are
+    // compared byte by byte.
+    "str_equals" => [
+        [str_repeat("a", 64), str_repeat("a", 64)],
+        ["bench_c_str_equals", "bench_phper_str_equals", "bench_phper_str_equals_bytes"],
+    ],
+    "str_casecmp" => [
+        [str_repeat("Ab", 32), str_repeat("aB", 32)],
+        ["bench_c_str_casecmp", "bench_phper_str_casecmp", "bench_phper_str_casecmp_bytes"],
+    ],
+    "pass_1mb" => [
+        [str_repeat("a", 1024 * 1024)],
+        [
+            "bench_c_pass_through",
+            "bench_phper_pass_through_borrowed",
+            "bench_phper_pass_through_owned",
+        ],
+    ],
+    "callback" => [
+        [function ($value) { return $value + 1; }, range(1, 100)],
+        ["bench_c_callback", "bench_phper_callback_cached", "bench_phper_callback_uncached"],
+    ],
+    "args" => [[1, 2, 3, 4], ["bench_c_args", "bench_phper_args", "bench_phper_args_copied"]],
+];
+
+// The heavy cases are run less in the table.
+$case_iterations = [
+    "pass_1mb" => 10000,
+    "callback" => 100000,
+];
+
+function now_ns(): int {
+    return function_exists('hrtime') ? hrtime(true) : (int) (microtime(true) * 1e9);
+}
+
+function run(string $function, array $args, int $iterations): int {
+    $start = now_ns();
+    for ($i = 0; $i < $iterations; $i++) {
+        $function(...$args);
+    }
+    return now_ns() - $start;
+}
+
+if ($argc >= 4) {
+    list($args) = $cases[$argv[1]];
+    echo run($argv[2], $args, (int) $argv[3]);
+    exit;
+}
+
+$iterations = 1000000;
+printf("%-12s %-30s %12s %14s\n", "case", "function", "ns/call", "calls/s");
+foreach ($cases as $case => list($args, $functions)) {
+    $n = isset($case_iterations[$case]) ? $case_iterations[$case] : $iterations;
+    foreach ($functions as $function) {
+        // Warm up.
+        run($function, $args, 1000);
+        $elapsed = run($function, $args, $n);
+        printf(
+            "%-12s %-30s %12.2f %14.0f\n",
+            $case,
+            $function,
+            $elapsed / $n,
+            $n * 1e9 / $elapsed
+        );
+    }
+}
@@ -0,0 +1,237 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! The extension for measuring the call overhead of phper, every function has
+//! the hand-written C equivalent in `raw.c`, named `bench_c_*` rather than
+//! `bench_phper_*`.
+
+use phper::{
+    functions::{Argument, CachedCallable},
+    modules::Module,
+    php_get_module,
+    values::ZVal,
+};
+use std::{mem::ManuallyDrop, ptr};
+
+extern "C" {
+    fn bench_register_c_functions();
+}
+
+#[php_get_module]
+pub fn get_module() -> Module {
+    let mut module = Module::new(
+        env!("CARGO_CRATE_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        env!("CARGO_PKG_AUTHORS"),
+    );
+
+    module.on_module_init(|| unsafe { bench_register_c_functions() });
+
+    module.add_function("bench_phper_noop", |_: &mut [ZVal]| phper::ok(()));
+
+    module
+        .add_function(
+            "bench_phper_add",
+            |arguments: &mut [ZVal]| -> phper::Result<i64> {
+                Ok(arguments[0].expect_long()? + arguments[1].expect_long()?)
+            },
+        )
+        .arguments([Argument::by_val("a"), Argument::by_val("b")]);
+
+    module
+        .add_function(
+            "bench_phper_concat",
+            |arguments: &mut [ZVal]| -> phper::Result<Vec<u8>> {
+                let a = arguments[0].expect_bytes()?;
+                let b = arguments[1].expect_bytes()?;
+                let mut r = Vec::with_capacity(a.len() + b.len());
+                r.extend_from_slice(a);
+                r.extend_from_slice(b);
+                Ok(r)
+            },
+        )
+        .arguments([Argument::by_val("a"), Argument::by_val("b")]);
+
+    module
+        .add_function(
+            "bench_phper_sum",
+            |arguments: &mut [ZVal]| -> phper::Result<i64> {
+                let mut sum = 0;
+                for (_, value) in arguments[0].expect_z_arr()?.iter() {
+                    sum += value.expect_long()?;
+                }
+                Ok(sum)
+            },
+        )
+        .argument(Argument::by_val("values"));
+
+    module
+        .add_function(
+            "bench_phper_sum_bulk",
+            |arguments: &mut [ZVal]| -> phper::Result<i64> {
+                let arr = arguments[0].expect_z_arr()?;
+                match arr.to_vec_i64() {
+                    Some(values) => Ok(values.iter().sum()),
+                    None => {
+                        let mut sum = 0;
+                        for (_, value) in arr.iter() {
+                            sum += value.expect_long()?;
+                        }
+                        Ok(sum)
+                    }
+                }
+            },
+        )
+        .argument(Argument::by_val("values"));
+
+    module
+        .add_function(
+            "bench_phper_str_equals",
+            |arguments: &mut [ZVal]| -> phper::Result<bool> {
+                Ok(arguments[0]
+                    .expect_z_str()?
+                    .equals(arguments[1].expect_z_str()?))
+            },
+        )
+        .arguments([Argument::by_val("a"), Argument::by_val("b")]);
+
+    // Compare by the Rust slices, the baseline of `ZStr::equals`.
+    module
+        .add_function(
+            "bench_phper_str_equals_bytes",
+            |arguments: &mut [ZVal]| -> phper::Result<bool> {
+                Ok(arguments[0].expect_bytes()? == arguments[1].expect_bytes()?)
+            },
+        )
+        .arguments([Argument::by_val("a"), Argument::by_val("b")]);
+
+    module
+        .add_function(
+            "bench_phper_str_casecmp",
+            |arguments: &mut [ZVal]| -> phper::Result<i64> {
+                let a = arguments[0].expect_z_str()?;
+                Ok(a.binary_case_cmp(arguments[1].expect_bytes()?) as i64)
+            },
+        )
+        .arguments([Argument::by_val("a"), Argument::by_val("b")]);
+
+    // Compare by the Rust iterators, the baseline of `ZStr::binary_case_cmp`.
+    module
+        .add_function(
+            "bench_phper_str_casecmp_bytes",
+            |arguments: &mut [ZVal]| -> phper::Result<i64> {
+                let a = arguments[0]
+                    .expect_bytes()?
+                    .iter()
+                    .map(u8::to_ascii_lowercase);
+                let b = arguments[1]
+                    .expect_bytes()?
+                    .iter()
+                    .map(u8::to_ascii_lowercase);
+                Ok(a.cmp(b) as i64)
+            },
+        )
+        .arguments([Argument::by_val("a"), Argument::by_val("b")]);
+
+    // Borrow the string and return it by adding the refcount.
+    module
+        .add_function(
+            "bench_phper_pass_through_borrowed",
+            |arguments: &mut [ZVal]| -> phper::Result<ZVal> {
+                arguments[0].expect_bytes()?;
+                Ok(arguments[0].shallow_clone())
+            },
+        )
+        .argument(Argument::by_val("s"));
+
+    // Copy the string in and out, like before the borrowing apis.
+    module
+        .add_function(
+            "bench_phper_pass_through_owned",
+            |arguments: &mut [ZVal]| -> phper::Result<Vec<u8>> {
+                arguments[0].expect_owned_bytes()
+            },
+        )
+        .argument(Argument::by_val("s"));
+
+    // Resolve the callback once, and call it for every value.
+    module
+        .add_function(
+            "bench_phper_callback_cached",
+            |arguments: &mut [ZVal]| -> phper::Result<i64> {
+                let mut callback = CachedCallable::new(arguments[0].clone())?;
+                let mut sum = 0;
+                for (_, value) in arguments[1].expect_z_arr()?.iter() {
+                    sum += callback.call([value.clone()])?.expect_long()?;
+                }
+                Ok(sum)
+            },
+        )
+        .arguments([Argument::by_val("callback"), Argument::by_val("values")]);
+
+    // Resolve the callback in every call.
+    module
+        .add_function(
+            "bench_phper_callback_uncached",
+            |arguments: &mut [ZVal]| -> phper::Result<i64> {
+                let mut callback = arguments[0].clone();
+                let mut sum = 0;
+                for (_, value) in arguments[1].expect_z_arr()?.iter() {
+                    sum += callback.call([value.clone()])?.expect_long()?;
+                }
+                Ok(sum)
+            },
+        )
+        .arguments([Argument::by_val("callback"), Argument::by_val("values")]);
+
+    let args = || {
+        [
+            Argument::by_val("a"),
+            Argument::by_val("b"),
+            Argument::by_val("c"),
+            Argument::by_val("d"),
+        ]
+    };
+
+    // The arguments are borrowed from the VM stack slots.
+    module
+        .add_function(
+            "bench_phper_args",
+            |arguments: &mut [ZVal]| -> phper::Result<i64> {
+                let mut sum = 0;
+                for argument in arguments.iter() {
+                    sum += argument.expect_long()?;
+                }
+                Ok(sum)
+            },
+        )
+        .arguments(args());
+
+    // Copy the arguments into a `Vec` first, like `zend_get_parameters_array_ex`
+    // did before borrowing the VM stack slots, as the baseline.
+    module
+        .add_function(
+            "bench_phper_args_copied",
+            |arguments: &mut [ZVal]| -> phper::Result<i64> {
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| ManuallyDrop::new(unsafe { ptr::read(argument) }))
+                    .collect::<Vec<_>>();
+                let mut sum = 0;
+                for argument in arguments.iter() {
+                    sum += argument.expect_long()?;
+                }
+                Ok(sum)
+            },
+        )
+        .arguments(args());
+
+    module
+}
@@ -0,0 +1,185 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+// The hand-written C functions, equivalent to the phper ones in `lib.rs`, as
+// the baseline of benchmark.
+
+#include <php.h>
+
+PHP_FUNCTION(bench_c_noop) {
+    if (zend_parse_parameters_none() == FAILURE) {
+        return;
+    }
+}
+
+PHP_FUNCTION(bench_c_add) {
+    zend_long a, b;
+
+    ZEND_PARSE_PARAMETERS_START(2, 2)
+        Z_PARAM_LONG(a)
+        Z_PARAM_LONG(b)
+    ZEND_PARSE_PARAMETERS_END();
+
+    RETURN_LONG(a + b);
+}
+
+PHP_FUNCTION(bench_c_concat) {
+    zend_string *a, *b, *r;
+
+    ZEND_PARSE_PARAMETERS_START(2, 2)
+        Z_PARAM_STR(a)
+        Z_PARAM_STR(b)
+    ZEND_PARSE_PARAMETERS_END();
+
+    r = zend_string_alloc(ZSTR_LEN(a) + ZSTR_LEN(b), 0);
+    memcpy(ZSTR_VAL(r), ZSTR_VAL(a), ZSTR_LEN(a));
+    memcpy(ZSTR_VAL(r) + ZSTR_LEN(a), ZSTR_VAL(b), ZSTR_LEN(b));
+    ZSTR_VAL(r)[ZSTR_LEN(r)] = '\0';
+    RETURN_NEW_STR(r);
+}
+
+PHP_FUNCTION(bench_c_sum) {
+    HashTable *ht;
+    zval *val;
+    zend_long sum = 0;
+
+    ZEND_PARSE_PARAMETERS_START(1, 1)
+        Z_PARAM_ARRAY_HT(ht)
+    ZEND_PARSE_PARAMETERS_END();
+
+    ZEND_HASH_FOREACH_VAL(ht, val) {
+        if (Z_TYPE_P(val) != IS_LONG) {
+            zend_type_error("must be of type int, %s given", zend_zval_type_name(val));
+            return;
+        }
+        sum += Z_LVAL_P(val);
+    } ZEND_HASH_FOREACH_END();
+
+    RETURN_LONG(sum);
+}
+
+PHP_FUNCTION(bench_c_str_equals) {
+    zend_string *a, *b;
+
+    ZEND_PARSE_PARAMETERS_START(2, 2)
+        Z_PARAM_STR(a)
+        Z_PARAM_STR(b)
+    ZEND_PARSE_PARAMETERS_END();
+
+    RETURN_BOOL(zend_string_equals(a, b));
+}
+
+PHP_FUNCTION(bench_c_str_casecmp) {
+    zend_string *a, *b;
+
+    ZEND_PARSE_PARAMETERS_START(2, 2)
+        Z_PARAM_STR(a)
+        Z_PARAM_STR(b)
+    ZEND_PARSE_PARAMETERS_END();
+
+    RETURN_LONG(ZEND_NORMALIZE_BOOL(
+        zend_binary_strcasecmp(ZSTR_VAL(a), ZSTR_LEN(a), ZSTR_VAL(b), ZSTR_LEN(b))));
+}
+
+PHP_FUNCTION(bench_c_pass_through) {
+    zend_string *s;
+
+    ZEND_PARSE_PARAMETERS_START(1, 1)
+        Z_PARAM_STR(s)
+    ZEND_PARSE_PARAMETERS_END();
+
+    RETURN_STR_COPY(s);
+}
+
+PHP_FUNCTION(bench_c_callback) {
+    zend_fcall_info fci;
+    zend_fcall_info_cache fcc;
+    HashTable *ht;
+    zval *val, retval;
+    zend_long sum = 0;
+
+    ZEND_PARSE_PARAMETERS_START(2, 2)
+        Z_PARAM_FUNC(fci, fcc)
+        Z_PARAM_ARRAY_HT(ht)
+    ZEND_PARSE_PARAMETERS_END();
+
+    fci.retval = &retval;
+    fci.param_count = 1;
+    ZEND_HASH_FOREACH_VAL(ht, val) {
+        fci.params = val;
+        if (zend_call_function(&fci, &fcc) == FAILURE || Z_ISUNDEF(retval)) {
+            return;
+        }
+        sum += zval_get_long(&retval);
+        zval_ptr_dtor(&retval);
+    } ZEND_HASH_FOREACH_END();
+
+    RETURN_LONG(sum);
+}
+
+PHP_FUNCTION(bench_c_args) {
+    zend_long a, b, c, d;
+
+    ZEND_PARSE_PARAMETERS_START(4, 4)
+        Z_PARAM_LONG(a)
+        Z_PARAM_LONG(b)
+        Z_PARAM_LONG(c)
+        Z_PARAM_LONG(d)
+    ZEND_PARSE_PARAMETERS_END();
+
+    RETURN_LONG(a + b + c + d);
+}
+
+ZEND_BEGIN_ARG_INFO_EX(arginfo_bench_c_noop, 0, 0, 0)
+ZEND_END_ARG_INFO()
+
+ZEND_BEGIN_ARG_INFO_EX(arginfo_bench_c_binary, 0, 0, 2)
+    ZEND_ARG_INFO(0, a)
+    ZEND_ARG_INFO(0, b)
+ZEND_END_ARG_INFO()
+
+ZEND_BEGIN_ARG_INFO_EX(arginfo_bench_c_sum, 0, 0, 1)
+    ZEND_ARG_INFO(0, values)
+ZEND_END_ARG_INFO()
+
+ZEND_BEGIN_ARG_INFO_EX(arginfo_bench_c_pass_through, 0, 0, 1)
+    ZEND_ARG_INFO(0, s)
+ZEND_END_ARG_INFO()
+
+ZEND_BEGIN_ARG_INFO_EX(arginfo_bench_c_args, 0, 0, 4)
+    ZEND_ARG_INFO(0, a)
+    ZEND_ARG_INFO(0, b)
+    ZEND_ARG_INFO(0, c)
+    ZEND_ARG_INFO(0, d)
+ZEND_END_ARG_INFO()
+
+ZEND_BEGIN_ARG_INFO_EX(arginfo_bench_c_callback, 0, 0, 2)
+    ZEND_ARG_INFO(0, callback)
+    ZEND_ARG_INFO(0, values)
+ZEND_END_ARG_INFO()
+
+static const zend_function_entry bench_c_functions[] = {
+    PHP_FE(bench_c_noop, arginfo_bench_c_noop)
+    PHP_FE(bench_c_add, arginfo_bench_c_binary)
+    PHP_FE(bench_c_concat, arginfo_bench_c_binary)
+    PHP_FE(bench_c_sum, arginfo_bench_c_sum)
+    PHP_FE(bench_c_str_equals, arginfo_bench_c_binary)
+    PHP_FE(bench_c_str_casecmp, arginfo_bench_c_binary)
+    PHP_FE(bench_c_pass_through, arginfo_bench_c_pass_through)
+    PHP_FE(bench_c_callback, arginfo_bench_c_callback)
+    PHP_FE(bench_c_args, arginfo_bench_c_args)
+    PHP_FE_END
+};
+
+// Register the C functions, must be called in `MINIT`.
+void bench_register_c_functions(void) {
+    zend_register_functions(NULL, bench_c_functions, NULL, MODULE_PERSISTENT);
+}
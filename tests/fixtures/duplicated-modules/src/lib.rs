@@ -0,0 +1,34 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! The extension bundling the module entry of another extension in the same
+//! library, which isn't supported, so its `MINIT` fails.
+
+use phper::{modules::Module, php_get_module, values::ZVal};
+
+/// Like the `get_module` of another extension built in the library.
+fn bundled_get_module() -> Module {
+    let mut module = Module::new("fixture_bundled", "0.0.0", "");
+    module.add_function("fixture_bundled_foo", |_: &mut [ZVal]| phper::ok(()));
+    module
+}
+
+#[php_get_module]
+pub fn get_module() -> Module {
+    unsafe {
+        bundled_get_module().module_entry();
+    }
+
+    Module::new(
+        env!("CARGO_CRATE_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        env!("CARGO_PKG_AUTHORS"),
+    )
+}
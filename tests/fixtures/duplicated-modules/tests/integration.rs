@@ -0,0 +1,47 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper_test::{cli::test_php_scripts_with_condition, utils::get_lib_path};
+use std::{
+    path::{Path, PathBuf},
+    process::Output,
+};
+
+#[test]
+fn test_startup_fails() {
+    test_php_scripts_with_condition(
+        get_lib_path(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("..")
+                .join("..")
+                .join("..")
+                .join("target"),
+            "fixture_duplicated_modules",
+        ),
+        &[(
+            &Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("php")
+                .join("startup.php"),
+            &|output: Output| {
+                // The errors are displayed or logged, depends on the php version.
+                let mut message = String::from_utf8_lossy(&output.stdout).into_owned();
+                message.push_str(&String::from_utf8_lossy(&output.stderr));
+
+                !output.status.success()
+                    && message.contains(
+                        "module fixture_duplicated_modules is defined in the same library",
+                    )
+                    && message.contains("Unable to start fixture_bundled module")
+                    && !message.contains("script is run")
+            },
+        )],
+    );
+}
@@ -0,0 +1,31 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! The extension registering the duplicated and invalid names, so its `MINIT`
+//! fails, for testing the startup checks.
+
+use phper::{classes::ClassEntity, modules::Module, php_get_module, values::ZVal};
+
+#[php_get_module]
+pub fn get_module() -> Module {
+    let mut module = Module::new(
+        env!("CARGO_CRATE_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        env!("CARGO_PKG_AUTHORS"),
+    );
+
+    // The function names are case-insensitive.
+    module.add_function("fixture_foo", |_: &mut [ZVal]| phper::ok(()));
+    module.add_function("FIXTURE_FOO", |_: &mut [ZVal]| phper::ok(()));
+
+    module.add_class(ClassEntity::new("Fixture Bar"));
+
+    module
+}
@@ -0,0 +1,183 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+#[cfg(feature = "debug")]
+use phper::alloc::{debug::live_allocations, EBox};
+use phper::{
+    alloc::{ebox, EAllocator, EString, EVec},
+    contexts::RequestContext,
+    functions::{call, Argument},
+    modules::Module,
+    values::ZVal,
+};
+use std::{fmt::Write, thread};
+
+#[global_allocator]
+static GLOBAL: EAllocator = EAllocator;
+
+pub fn integrate(module: &mut Module) {
+    module.add_function(
+        "integrate_alloc_evec",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            let mut v = EVec::new();
+            for i in 0..100i64 {
+                v.push(i);
+            }
+            assert_eq!(v.len(), 100);
+            assert_eq!(v.iter().sum::<i64>(), 4950);
+
+            v.insert(0, -1);
+            assert_eq!(v[0], -1);
+            assert_eq!(v.remove(1), 0);
+            assert_eq!(v.pop(), Some(99));
+
+            v.truncate(10);
+            assert_eq!(v.len(), 10);
+            assert_eq!(v.clone(), v);
+
+            let v = (0..3).map(|i| i.to_string()).collect::<EVec<_>>();
+            assert_eq!(&*v, &["0", "1", "2"]);
+
+            Ok(())
+        },
+    );
+
+    module.add_function(
+        "integrate_alloc_estring",
+        |_: &mut [ZVal]| -> phper::Result<ZVal> {
+            let mut s = EString::from("hello");
+            s.push(',');
+            write!(s, " {}", "world").unwrap();
+            assert_eq!(s, "hello, world");
+            Ok(ZVal::from(s.as_str()))
+        },
+    );
+
+    module.add_function(
+        "integrate_alloc_memory_usage",
+        |_: &mut [ZVal]| -> phper::Result<bool> {
+            const SIZE: usize = 1024 * 1024;
+
+            let before = call("memory_get_usage", [])?.expect_long()?;
+            let v = EVec::<u8>::with_capacity(SIZE);
+            let after = call("memory_get_usage", [])?.expect_long()?;
+            drop(v);
+
+            Ok(after - before >= SIZE as i64)
+        },
+    );
+    module.add_function(
+        "integrate_alloc_debug_enabled",
+        |_: &mut [ZVal]| -> phper::Result<bool> { Ok(cfg!(feature = "debug")) },
+    );
+
+    #[cfg(feature = "debug")]
+    module.add_function(
+        "integrate_alloc_debug_tracking",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            let before = live_allocations();
+
+            let b = EBox::new(1i64);
+            let mut v = EVec::<i64>::with_capacity(4);
+            assert_eq!(live_allocations().count, before.count + 2);
+            assert_eq!(live_allocations().bytes, before.bytes + 8 + 32);
+
+            v.reserve_exact(8);
+            assert_eq!(live_allocations().bytes, before.bytes + 8 + 64);
+
+            assert_eq!(b.into_inner(), 1);
+            drop(v);
+            assert_eq!(live_allocations(), before);
+
+            Ok(())
+        },
+    );
+
+    module.add_function(
+        "integrate_alloc_scoped",
+        |_: &mut [ZVal]| -> phper::Result<ZVal> {
+            RequestContext::with(|ctx| {
+                let mut v = EVec::with_capacity_in(ctx, 2);
+                assert!(v.capacity() >= 2);
+                v.extend(0..3i64);
+                v.insert(0, -1);
+                assert_eq!(v.remove(0), -1);
+                assert_eq!(v.pop(), Some(2));
+                assert_eq!(&*v, &[0, 1]);
+                assert_eq!(v.clone(), v);
+
+                let b = ebox!(ctx, 64i64);
+                assert_eq!(*b, 64);
+                assert_eq!(b.into_inner(), 64);
+
+                let mut s = EString::new_in(ctx);
+                s.push_str("hello");
+                s.push(',');
+                write!(s, " {}", "world").unwrap();
+                Ok(ZVal::from(&*s))
+            })
+            .unwrap()
+        },
+    );
+
+    module.add_function(
+        "integrate_alloc_global_usage",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            const SIZE: usize = 1024 * 1024;
+
+            let before = EAllocator::request_usage();
+            let v = Vec::<u8>::with_capacity(SIZE);
+            assert!(EAllocator::request_usage() >= before + SIZE);
+            drop(v);
+            assert!(EAllocator::request_usage() < before + SIZE);
+
+            // Allocated by the other thread, not counted by the request.
+            let v = thread::spawn(|| Vec::<u8>::with_capacity(SIZE))
+                .join()
+                .unwrap();
+            assert!(EAllocator::request_usage() < before + SIZE);
+            drop(v);
+
+            Ok(())
+        },
+    );
+
+    module
+        .add_function(
+            "integrate_alloc_global_exhausted",
+            |arguments: &mut [ZVal]| -> phper::Result<()> {
+                let size = arguments[0].expect_long()? as usize;
+                drop(Vec::<u8>::with_capacity(size));
+                Ok(())
+            },
+        )
+        .argument(Argument::by_val("size"));
+
+    module.add_function(
+        "integrate_alloc_outside_request",
+        |_: &mut [ZVal]| -> phper::Result<bool> {
+            Ok(
+                thread::spawn(|| RequestContext::with(|ctx| EVec::<u8>::new_in(ctx).len()))
+                    .join()
+                    .unwrap()
+                    .is_none(),
+            )
+        },
+    );
+
+    module.add_function(
+        "integrate_alloc_unchecked_outside_request",
+        |_: &mut [ZVal]| -> phper::Result<bool> {
+            Ok(thread::spawn(|| EVec::<u8>::with_capacity(1).len())
+                .join()
+                .is_err())
+        },
+    );
+}
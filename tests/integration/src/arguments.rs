@@ -9,12 +9,28 @@
 // See the Mulan PSL v2 for more details.
 
 use phper::{
-    alloc::ToRefOwned, arrays::ZArray, functions::Argument, modules::Module, objects::ZObject,
+    alloc::ToRefOwned,
+    arrays::ZArray,
+    flags,
+    functions::Argument,
+    modules::Module,
+    objects::ZObject,
+    types::{Flags, TypeHint},
     values::ZVal,
 };
 
+flags! {
+    /// Options of `integrate_arguments_flags`.
+    struct OpenFlags {
+        const READ = 1;
+        const WRITE = 2;
+        const CREATE = 4;
+    }
+}
+
 pub fn integrate(module: &mut Module) {
     integrate_arguments(module);
+    integrate_typed_arguments(module);
 }
 
 fn integrate_arguments(module: &mut Module) {
@@ -95,4 +111,81 @@ fn integrate_arguments(module: &mut Module) {
         )
         .argument(Argument::by_val("a"))
         .argument(Argument::by_val_optional("b"));
+
+    for (name, bits) in OpenFlags::NAMES {
+        module.add_constant(format!("INTEGRATION_TEST_OPEN_{}", name), *bits);
+    }
+
+    module
+        .add_function(
+            "integrate_arguments_flags",
+            |arguments: &mut [ZVal]| -> phper::Result<String> {
+                let flags = arguments[0].expect_flags::<OpenFlags>()?;
+                let mut modes = String::new();
+                if flags.contains(OpenFlags::READ) {
+                    modes.push('r');
+                }
+                if flags.contains(OpenFlags::WRITE | OpenFlags::CREATE) {
+                    modes.push_str("w+");
+                } else if flags.contains(OpenFlags::WRITE) {
+                    modes.push('w');
+                }
+                Ok(modes)
+            },
+        )
+        .argument(Argument::by_val("flags"));
+}
+
+fn integrate_typed_arguments(module: &mut Module) {
+    module
+        .add_function(
+            "integrate_arguments_typed",
+            |arguments: &mut [ZVal]| -> phper::Result<Option<i64>> {
+                let a = arguments[0].expect_long()?;
+                Ok((a != 0).then_some(a))
+            },
+        )
+        .arguments([
+            Argument::by_val("a").with_type(TypeHint::Long),
+            Argument::by_val("b").with_type(TypeHint::String.nullable()),
+            Argument::by_val_optional("c").with_type(TypeHint::class("ArrayAccess").nullable()),
+        ])
+        .set_return_type(TypeHint::Long.nullable());
+
+    module
+        .add_function(
+            "integrate_arguments_typed_union",
+            |arguments: &mut [ZVal]| Ok::<_, phper::Error>(arguments[0].clone()),
+        )
+        .argument(Argument::by_val("a").with_type(TypeHint::Union(vec![
+            TypeHint::class("ArrayAccess"),
+            TypeHint::class("Countable"),
+            TypeHint::False,
+        ])))
+        .set_return_type(TypeHint::Union(vec![TypeHint::Long, TypeHint::String]));
+
+    module
+        .add_function("integrate_arguments_return_void", |_: &mut [ZVal]| {
+            Ok::<_, phper::Error>(())
+        })
+        .set_return_type(TypeHint::Void);
+
+    module
+        .add_function(
+            "integrate_arguments_return_never",
+            |_: &mut [ZVal]| -> phper::Result<()> { Err(phper::Error::boxed("never returns")) },
+        )
+        .set_return_type(TypeHint::Never);
+
+    module
+        .add_function("integrate_arguments_return_true", |_: &mut [ZVal]| {
+            Ok::<_, phper::Error>(true)
+        })
+        .set_return_type(TypeHint::True);
+
+    module
+        .add_function("integrate_arguments_return_null", |_: &mut [ZVal]| {
+            Ok::<_, phper::Error>(())
+        })
+        .set_return_type(TypeHint::Null);
 }
@@ -9,10 +9,12 @@
 // See the Mulan PSL v2 for more details.
 
 use phper::{
-    arrays::{InsertKey, IterKey, ZArray},
+    alloc::ToRefOwned,
+    arrays::{InsertKey, IterKey, PersistentZArray, ZArray},
+    functions::{Argument, CachedCallable},
     modules::Module,
     objects::{ZObj, ZObject},
-    strings::ZString,
+    strings::{PersistentZString, ZString},
     values::ZVal,
 };
 
@@ -249,4 +251,141 @@ pub fn integrate(module: &mut Module) {
             Ok(())
         },
     );
+
+    module.add_function(
+        "integrate_arrays_persistent",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            let mut a = PersistentZArray::new();
+            assert!(a.is_persistent());
+            assert!(!ZArray::new().is_persistent());
+
+            let mut nested = PersistentZArray::with_capacity(1);
+            nested.insert((), true);
+
+            a.insert("foo", 1i64);
+            a.insert((), PersistentZString::new("bar"));
+            a.insert("nested", nested);
+            assert_eq!(a.get("foo").unwrap().as_long(), Some(1));
+            assert_eq!(a.get(0).unwrap().as_z_str().unwrap().to_str(), Ok("bar"));
+            assert!(a.get("nested").unwrap().as_z_arr().unwrap().is_persistent());
+
+            // The request copy is refcounted, and the persistent array is
+            // freed, so it no longer holds the string.
+            let val = ZVal::from(a);
+            let arr = val.as_z_arr().unwrap();
+            assert!(!arr.is_persistent());
+            assert_eq!(val.refcount(), Some(1));
+            assert_eq!(arr.get(0).unwrap().refcount(), Some(1));
+            let nested = arr.get("nested").unwrap();
+            assert!(!nested.as_z_arr().unwrap().is_persistent());
+            assert_eq!(nested.refcount(), Some(1));
+            assert_eq!(
+                nested.as_z_arr().unwrap().get(0).unwrap().as_bool(),
+                Some(true)
+            );
+
+            Ok(())
+        },
+    );
+
+    module.add_function(
+        "integrate_arrays_to_ref_owned",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            let mut a = ZArray::new();
+            a.insert((), ZVal::from(1));
+
+            let mut b = a.to_ref_owned();
+            assert_eq!(b.len(), 1);
+            drop(b);
+
+            assert_eq!(a.len(), 1);
+            assert_eq!(a.get(0).unwrap().as_long(), Some(1));
+
+            Ok(())
+        },
+    );
+
+    module.add_function(
+        "integrate_arrays_sort_map_filter_merge",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            let mut a = ZArray::new();
+            a.insert("c", ZVal::from(3));
+            a.insert("a", ZVal::from(1));
+            a.insert("b", ZVal::from(2));
+
+            let mut b = a.clone();
+            b.sort_by_preserve_keys(|x, y| x.as_long().cmp(&y.as_long()));
+            let keys = b
+                .iter()
+                .map(|(k, _)| match k {
+                    IterKey::ZStr(s) => s.to_str().unwrap().to_owned(),
+                    IterKey::Index(i) => i.to_string(),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(keys, ["a", "b", "c"]);
+
+            a.sort_by(|x, y| y.as_long().cmp(&x.as_long()));
+            assert_eq!(a.get(0).unwrap().as_long(), Some(3));
+            assert_eq!(a.get(2).unwrap().as_long(), Some(1));
+            assert!(!a.exists("a"));
+
+            let doubled = a.map(|v| ZVal::from(v.as_long().unwrap() * 2));
+            assert_eq!(doubled.get(0).unwrap().as_long(), Some(6));
+
+            let odd = a.filter(|v| v.as_long().unwrap() % 2 == 1);
+            assert_eq!(odd.get(0).unwrap().as_long(), Some(3));
+            assert_eq!(odd.get(2).unwrap().as_long(), Some(1));
+            assert!(!odd.exists(1));
+
+            let mut c = ZArray::new();
+            c.insert("foo", ZVal::from("FOO"));
+            c.insert((), ZVal::from(100));
+            a.merge(&c);
+            assert_eq!(a.get(3).unwrap().as_long(), Some(100));
+            assert_eq!(
+                a.get("foo").unwrap().as_z_str().unwrap().to_str(),
+                Ok("FOO")
+            );
+
+            Ok(())
+        },
+    );
+
+    module
+        .add_function(
+            "integrate_arrays_sort_by_callable",
+            |arguments: &mut [ZVal]| -> phper::Result<ZArray> {
+                let mut callback = CachedCallable::new(arguments[1].clone())?;
+                let mut arr = arguments[0].expect_z_arr()?.to_owned();
+                arr.sort_by_callable(&mut callback)?;
+                Ok(arr)
+            },
+        )
+        .arguments([Argument::by_val("arr"), Argument::by_val("fn")]);
+
+    module
+        .add_function(
+            "integrate_arrays_sum_i64",
+            |arguments: &mut [ZVal]| -> phper::Result<Option<i64>> {
+                let values = arguments[0].expect_z_arr()?.to_vec_i64();
+                Ok(values.map(|values| values.iter().sum()))
+            },
+        )
+        .argument(Argument::by_val("arr"));
+
+    module
+        .add_function(
+            "integrate_arrays_to_vec_f64",
+            |arguments: &mut [ZVal]| -> phper::Result<Option<ZArray>> {
+                let values = arguments[0].expect_z_arr()?.to_vec_f64();
+                Ok(values.map(|values| {
+                    let mut arr = ZArray::new();
+                    for value in values {
+                        arr.insert((), value);
+                    }
+                    arr
+                }))
+            },
+        )
+        .argument(Argument::by_val("arr"));
 }
@@ -0,0 +1,40 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::modules::Module;
+
+pub fn integrate(module: &mut Module) {
+    module.add_source(
+        "integrate_sources.php",
+        r#"<?php
+        function integrate_sources_embedded() {
+            return "embedded";
+        }
+        "#,
+    );
+
+    module.add_autoloader(|class_name| class_name == "IntegrationTest\\Autoload\\Missing");
+
+    module.add_autoload_prefix(
+        "IntegrationTest\\Autoload\\",
+        [(
+            "IntegrationTest\\Autoload\\Foo",
+            r#"<?php
+            namespace IntegrationTest\Autoload;
+
+            class Foo {
+                public function name() {
+                    return "foo";
+                }
+            }
+            "#,
+        )],
+    );
+}
@@ -10,21 +10,46 @@
 
 use phper::{
     alloc::RefClone,
+    arrays::ZArray,
     classes::{
-        array_access_class, iterator_class, ClassEntity, ClassEntry, InterfaceEntity,
-        StaticInterface, StaticStateClass, Visibility,
+        array_access_class, defined_classes, iterator_class, ClassEntity, ClassEntry, ClassType,
+        InterfaceEntity, StaticInterface, StaticStateClass, TraitEntity, Visibility,
     },
+    constants::ConstantValue,
     functions::Argument,
-    modules::Module,
+    modules::{DependencyType, Module},
+    types::TypeHint,
     values::ZVal,
 };
-use std::{collections::HashMap, convert::Infallible};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc,
+    },
+};
 
 pub fn integrate(module: &mut Module) {
     integrate_a(module);
     integrate_foo(module);
     integrate_i_bar(module);
     integrate_static_props(module);
+    integrate_typed_props(module);
+    integrate_extends_by_name(module);
+    integrate_traits(module);
+    integrate_magic_methods(module);
+    integrate_state_construct(module);
+    integrate_initialized_guard(module);
+    integrate_clone_behaviors(module);
+    integrate_state_comparators(module);
+    integrate_invokable(module);
+    integrate_constants(module);
+    integrate_modifiers(module);
+    #[cfg(phper_php_version_at_least = "8.1")]
+    integrate_readonly(module);
+    integrate_defined_classes(module);
+    integrate_return_by_ref(module);
 }
 
 fn integrate_a(module: &mut Module) {
@@ -181,3 +206,346 @@ fn integrate_static_props(module: &mut Module) {
 
     module.add_class(class);
 }
+
+fn integrate_typed_props(module: &mut Module) {
+    let mut class = ClassEntity::new("IntegrationTest\\TypedProps");
+
+    class
+        .add_property("id", Visibility::Public, 0)
+        .set_type(TypeHint::Long);
+    class
+        .add_property("name", Visibility::Public, ())
+        .set_type(TypeHint::String.nullable());
+    class
+        .add_property("count", Visibility::Public, ())
+        .set_type(TypeHint::Long);
+    class
+        .add_property("value", Visibility::Public, 1)
+        .set_type(TypeHint::Union(vec![TypeHint::Long, TypeHint::String]));
+    class
+        .add_property("obj", Visibility::Public, ())
+        .set_type(TypeHint::class("ArrayAccess").nullable());
+    class
+        .add_static_property("flag", Visibility::Public, true)
+        .set_type(TypeHint::Bool);
+
+    module.add_class(class);
+}
+
+fn integrate_extends_by_name(module: &mut Module) {
+    module.add_dependency("spl", DependencyType::Required);
+
+    // Added before the parent class.
+    let mut class = ClassEntity::new("IntegrationTest\\ChildObject");
+    class.extends_by_name("IntegrationTest\\ParentObject");
+    module.add_class(class);
+
+    let mut class = ClassEntity::new("IntegrationTest\\ParentObject");
+    class.add_method("hello", Visibility::Public, |_, _| {
+        Ok::<_, Infallible>("hello from parent")
+    });
+    module.add_class(class);
+
+    let mut class = ClassEntity::new("IntegrationTest\\MyLogicException");
+    class.extends_by_name("LogicException");
+    module.add_class(class);
+}
+
+fn integrate_traits(module: &mut Module) {
+    let mut tr = TraitEntity::new("IntegrationTest\\Greeting");
+    tr.add_abstract_method("name", Visibility::Protected);
+    tr.add_method("greet", Visibility::Public, |this, arguments| {
+        let name = this.call("name", [])?;
+        let greeting = arguments[0].expect_z_str()?.to_str()?;
+        Ok::<_, phper::Error>(format!("{} {}", greeting, name.expect_z_str()?.to_str()?))
+    })
+    .argument(Argument::by_val("greeting"));
+    tr.add_static_method("version", Visibility::Public, |_| Ok::<_, Infallible>(1));
+    module.add_trait(tr);
+}
+
+fn integrate_magic_methods(module: &mut Module) {
+    let mut class = ClassEntity::<HashMap<String, String>>::new_with_default_state_constructor(
+        "IntegrationTest\\Magic",
+    );
+
+    class
+        .add_magic_construct(Visibility::Public, |this, arguments| {
+            let prefix = arguments[0].expect_z_str()?.to_str()?.to_owned();
+            this.as_mut_state().insert("prefix".to_owned(), prefix);
+            Ok::<_, phper::Error>(())
+        })
+        .argument(Argument::by_val("prefix"));
+    class.add_magic_to_string(|this| {
+        let mut keys = this.as_state().keys().cloned().collect::<Vec<_>>();
+        keys.sort();
+        Ok::<_, Infallible>(keys.join(","))
+    });
+    class.add_magic_get(|this, name| {
+        let value = this.as_state().get(name.to_str()?).cloned();
+        Ok::<_, phper::Error>(value)
+    });
+    class.add_magic_set(|this, name, value| {
+        let value = value.expect_z_str()?.to_str()?.to_owned();
+        this.as_mut_state().insert(name.to_str()?.to_owned(), value);
+        Ok::<_, phper::Error>(())
+    });
+    class.add_magic_call(|this, name, arguments| {
+        let prefix = this.as_state()["prefix"].clone();
+        Ok::<_, phper::Error>(format!("{}{}:{}", prefix, name.to_str()?, arguments.len()))
+    });
+    class.add_magic_call_static(|name, arguments| {
+        Ok::<_, phper::Error>(format!("static {}:{}", name.to_str()?, arguments.len()))
+    });
+    class
+        .add_magic_invoke(|this, arguments| {
+            let prefix = this.as_state()["prefix"].clone();
+            Ok::<_, phper::Error>(format!("{}{}", prefix, arguments[0].expect_long()?))
+        })
+        .argument(Argument::by_val("n"));
+    class.add_magic_clone(Visibility::Public, |this| {
+        this.as_mut_state()
+            .insert("cloned".to_owned(), "yes".to_owned());
+        Ok::<_, Infallible>(())
+    });
+    class.state_cloner(Clone::clone);
+
+    module.add_class(class);
+}
+
+fn integrate_state_construct(module: &mut Module) {
+    struct Connection {
+        dsn: String,
+    }
+
+    let mut class = ClassEntity::<Option<Connection>>::new_with_default_state_constructor(
+        "IntegrationTest\\Connection",
+    );
+    class
+        .add_state_construct(Visibility::Public, |arguments| {
+            let dsn = arguments[0].expect_z_str()?.to_str()?.to_owned();
+            if dsn.is_empty() {
+                return Err(phper::Error::boxed("dsn can't be empty"));
+            }
+            Ok(Connection { dsn })
+        })
+        .argument(Argument::by_val("dsn"));
+    class.add_method("dsn", Visibility::Public, |this, _| {
+        let connection = this.as_initialized_state()?;
+        Ok::<_, phper::Error>(connection.dsn.clone())
+    });
+
+    module.add_class(class);
+}
+
+fn integrate_initialized_guard(module: &mut Module) {
+    let mut class =
+        ClassEntity::<i64>::new_with_default_state_constructor("IntegrationTest\\Handle");
+    class
+        .add_magic_construct(Visibility::Public, |this, arguments| {
+            let value = arguments[0].expect_long()?;
+            if value < 0 {
+                return Err(phper::Error::boxed("value can't be negative"));
+            }
+            *this.as_mut_state() = value;
+            Ok(())
+        })
+        .argument(Argument::by_val("value"));
+    class.add_method("value", Visibility::Public, |this, _| {
+        this.try_as_state().map(|value| *value)
+    });
+    class.add_method("isInitialized", Visibility::Public, |this, _| {
+        Ok::<_, Infallible>(this.is_initialized())
+    });
+    class.state_cloner(Clone::clone);
+
+    module.add_class(class);
+}
+
+fn integrate_clone_behaviors(module: &mut Module) {
+    let mut class =
+        ClassEntity::<i64>::new_with_default_state_constructor("IntegrationTest\\CloneState");
+    class.clone_state();
+    class.add_method("increment", Visibility::Public, |this, _| {
+        *this.as_mut_state() += 1;
+        Ok::<_, Infallible>(*this.as_state())
+    });
+    module.add_class(class);
+
+    let mut class = ClassEntity::<Arc<AtomicI64>>::new_with_default_state_constructor(
+        "IntegrationTest\\ShareState",
+    );
+    class.share_state();
+    class.add_method("increment", Visibility::Public, |this, _| {
+        Ok::<_, Infallible>(this.as_state().fetch_add(1, Ordering::SeqCst) + 1)
+    });
+    module.add_class(class);
+
+    let mut class =
+        ClassEntity::<i64>::new_with_default_state_constructor("IntegrationTest\\DenyClone");
+    class.clone_state();
+    class.deny_clone();
+    module.add_class(class);
+}
+
+fn integrate_state_comparators(module: &mut Module) {
+    let mut class =
+        ClassEntity::<i64>::new_with_default_state_constructor("IntegrationTest\\Money");
+    class.compare_state_by_ord();
+    class
+        .add_magic_construct(Visibility::Public, |this, arguments| {
+            *this.as_mut_state() = arguments[0].expect_long()?;
+            Ok::<_, phper::Error>(())
+        })
+        .argument(Argument::by_val("amount"));
+    module.add_class(class);
+
+    let mut class =
+        ClassEntity::<String>::new_with_default_state_constructor("IntegrationTest\\Uuid");
+    class.compare_state_by_eq();
+    class
+        .add_magic_construct(Visibility::Public, |this, arguments| {
+            *this.as_mut_state() = arguments[0].expect_z_str()?.to_str()?.to_owned();
+            Ok::<_, phper::Error>(())
+        })
+        .argument(Argument::by_val("uuid"));
+    module.add_class(class);
+}
+
+fn integrate_invokable(module: &mut Module) {
+    let mut class =
+        ClassEntity::<i64>::new_with_default_state_constructor("IntegrationTest\\UpperMatcher");
+    class
+        .add_magic_invoke(|this, arguments| {
+            let matches = arguments[0].expect_z_arr()?;
+            let matched = matches
+                .get(0)
+                .ok_or_else(|| phper::Error::boxed("no matched text"))?
+                .expect_z_str()?
+                .to_str()?
+                .to_uppercase();
+            *this.as_mut_state() += 1;
+            Ok::<_, phper::Error>(format!("{}{}", matched, this.as_state()))
+        })
+        .argument(Argument::by_val("matches"));
+    module.add_class(class);
+}
+
+fn integrate_constants(module: &mut Module) {
+    let mut class = ClassEntity::new("IntegrationTest\\Http");
+    class.add_constant("NAME", "http");
+    class.add_constant("VERSION", 1.1);
+    class.add_constant("SECURE", false);
+    class.add_constant("NOTHING", ());
+    class.add_constant("METHODS", ConstantValue::list(["GET", "POST"]));
+    class.add_constant(
+        "PORTS",
+        ConstantValue::map([
+            ("http", ConstantValue::from(80i64)),
+            ("https", ConstantValue::list([443i64, 8443])),
+        ]),
+    );
+    module.add_class(class);
+
+    let mut class = ClassEntity::new("IntegrationTest\\Mode");
+    module.add_constant_group(
+        &mut class,
+        "INTEGRATION_TEST_MODE_",
+        [("FAST", 1), ("SAFE", 2)],
+    );
+    module.add_class(class);
+}
+
+fn integrate_modifiers(module: &mut Module) {
+    let mut class = ClassEntity::new("IntegrationTest\\FinalClass");
+    class.mark_final();
+    module.add_class(class);
+
+    let mut class = ClassEntity::new("IntegrationTest\\AbstractClass");
+    class.mark_abstract();
+    class
+        .add_abstract_method("render", Visibility::Protected)
+        .argument(Argument::by_val("name"));
+    class
+        .add_method("display", Visibility::Public, |this, arguments| {
+            let rendered = this.call("render", [arguments[0].clone()])?;
+            Ok::<_, phper::Error>(format!("<{}>", rendered.expect_z_str()?.to_str()?))
+        })
+        .argument(Argument::by_val("name"));
+    module.add_class(class);
+}
+
+#[cfg(phper_php_version_at_least = "8.1")]
+fn integrate_readonly(module: &mut Module) {
+    let mut class = ClassEntity::new("IntegrationTest\\ReadonlyProps");
+    class
+        .add_property("id", Visibility::Public, ())
+        .set_type(TypeHint::Long)
+        .mark_readonly();
+    class
+        .add_property("tag", Visibility::Public, "ignored")
+        .mark_readonly();
+    class
+        .add_method("__construct", Visibility::Public, |this, arguments| {
+            this.set_property("id", arguments[0].expect_long()?);
+            this.set_property("tag", arguments[1].clone());
+            Ok::<_, phper::Error>(())
+        })
+        .arguments([Argument::by_val("id"), Argument::by_val("tag")]);
+    module.add_class(class);
+
+    #[cfg(phper_php_version_at_least = "8.2")]
+    {
+        let mut class = ClassEntity::new("IntegrationTest\\ReadonlyClass");
+        class.mark_readonly();
+        class
+            .add_property("name", Visibility::Public, ())
+            .set_type(TypeHint::String);
+        class
+            .add_method("__construct", Visibility::Public, |this, arguments| {
+                this.set_property("name", arguments[0].expect_z_str()?.to_str()?);
+                Ok::<_, phper::Error>(())
+            })
+            .argument(Argument::by_val("name"));
+        module.add_class(class);
+    }
+}
+
+fn integrate_defined_classes(module: &mut Module) {
+    module.add_function(
+        "integrate_classes_defined_classes",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut classes = ZArray::new();
+            for ce in defined_classes() {
+                let class_type = match ce.class_type() {
+                    ClassType::Class => "class",
+                    ClassType::Interface => "interface",
+                    ClassType::Trait => "trait",
+                    ClassType::Enum => "enum",
+                };
+                let mut info = ZArray::new();
+                info.insert("type", class_type);
+                info.insert("internal", ce.is_internal());
+                match ce.filename() {
+                    Some(filename) => info.insert("filename", filename.to_str()?),
+                    None => info.insert("filename", ()),
+                }
+                classes.insert(ce.get_name().to_str()?, info);
+            }
+            Ok(classes)
+        },
+    );
+}
+
+fn integrate_return_by_ref(module: &mut Module) {
+    let mut class = ClassEntity::new("IntegrationTest\\RefHolder");
+    class.add_property("items", Visibility::Public, ());
+    class
+        .add_method("items", Visibility::Public, |this, _| {
+            let items = this.get_mut_property("items");
+            items.make_ref();
+            phper::ok(items.shallow_clone())
+        })
+        .return_by_ref();
+    module.add_class(class);
+}
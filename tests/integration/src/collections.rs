@@ -0,0 +1,23 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{collections, modules::Module};
+
+pub fn integrate(module: &mut Module) {
+    collections::register(module);
+
+    module.add_function("integrate_collections_new_vector", |_| {
+        collections::new_vector([1i64, 2, 3])
+    });
+
+    module.add_function("integrate_collections_new_map", |_| {
+        collections::new_map([("a", 1i64), ("10", 2)])
+    });
+}
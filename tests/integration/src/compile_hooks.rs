@@ -0,0 +1,66 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{arrays::ZArray, modules::Module, strings::ZString, values::ZVal};
+use std::cell::RefCell;
+
+thread_local! {
+    static COMPILED_FILES: RefCell<Vec<String>> = RefCell::new(Vec::new());
+}
+
+pub fn integrate(module: &mut Module) {
+    module.on_compile_file(|file| {
+        let filename = file.filename().to_string_lossy().into_owned();
+        if !filename.ends_with("_compile_hooks_included.php") {
+            return Ok(());
+        }
+
+        let source = file.source().unwrap_or_default().to_vec();
+        assert!(String::from_utf8_lossy(&source).contains("original"));
+
+        #[cfg(not(all(
+            phper_major_version = "7",
+            any(
+                phper_minor_version = "0",
+                phper_minor_version = "1",
+                phper_minor_version = "2",
+                phper_minor_version = "3",
+            )
+        )))]
+        assert!(file.set_source(String::from_utf8_lossy(&source).replace("original", "rewritten")));
+
+        COMPILED_FILES.with(|files| files.borrow_mut().push(filename));
+        Ok(())
+    });
+
+    module.on_compile_string(|source, _filename| {
+        let source = String::from_utf8_lossy(source.to_bytes());
+        if source.contains("__DENY__") {
+            return Err(phper::Error::boxed("eval is denied"));
+        }
+        if !source.contains("__ANSWER__") {
+            return Ok(None);
+        }
+        Ok(Some(ZString::new(source.replace("__ANSWER__", "42"))))
+    });
+
+    module.add_function(
+        "integrate_compile_hooks_compiled_files",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut files = ZArray::new();
+            COMPILED_FILES.with(|compiled| {
+                for file in compiled.borrow().iter() {
+                    files.insert((), file.as_str());
+                }
+            });
+            Ok(files)
+        },
+    );
+}
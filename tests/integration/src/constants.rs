@@ -8,7 +8,7 @@
 // NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
 // See the Mulan PSL v2 for more details.
 
-use phper::modules::Module;
+use phper::{arrays::ZArray, constants, modules::Module, values::ZVal};
 
 pub fn integrate(module: &mut Module) {
     module.add_constant("INTEGRATE_CONST_NULL", ());
@@ -18,4 +18,23 @@ pub fn integrate(module: &mut Module) {
     module.add_constant("INTEGRATE_CONST_DOUBLE", 200.);
     module.add_constant("INTEGRATE_CONST_STRING", "something");
     module.add_constant("INTEGRATE_CONST_BYTES", "something".as_bytes().to_owned());
+
+    module.add_function(
+        "integrate_constants_get",
+        |arguments: &mut [ZVal]| -> phper::Result<ZVal> {
+            let name = arguments[0].expect_z_str()?.to_str()?;
+            Ok(constants::get(name).cloned().unwrap_or_default())
+        },
+    );
+
+    module.add_function(
+        "integrate_constants_defined_constants",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut arr = ZArray::new();
+            for (name, value) in constants::defined_constants() {
+                arr.insert(name.to_str()?, value.clone());
+            }
+            Ok(arr)
+        },
+    );
 }
@@ -0,0 +1,52 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{
+    contexts::{ModuleContext, RequestContext},
+    modules::Module,
+    values::ZVal,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static MODULE_CONTEXT_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+pub fn integrate(module: &mut Module) {
+    module.on_module_init_with_context(|_| {
+        MODULE_CONTEXT_ACTIVE.store(ModuleContext::with(|_| ()).is_some(), Ordering::SeqCst);
+    });
+
+    module.add_function(
+        "integrate_contexts_module_init_active",
+        |_: &mut [ZVal]| -> phper::Result<bool> {
+            Ok(MODULE_CONTEXT_ACTIVE.load(Ordering::SeqCst))
+        },
+    );
+
+    module.add_function(
+        "integrate_contexts_module_active",
+        |_: &mut [ZVal]| -> phper::Result<bool> { Ok(ModuleContext::is_active()) },
+    );
+
+    module.add_function(
+        "integrate_contexts_request_token_cancelled",
+        |_: &mut [ZVal]| -> phper::Result<Option<bool>> {
+            Ok(RequestContext::with(|ctx| ctx.token().is_cancelled()))
+        },
+    );
+
+    module.add_function(
+        "integrate_contexts_request_active_in_other_thread",
+        |_: &mut [ZVal]| -> phper::Result<bool> {
+            Ok(std::thread::spawn(RequestContext::is_active)
+                .join()
+                .unwrap())
+        },
+    );
+}
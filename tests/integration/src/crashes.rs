@@ -0,0 +1,21 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{modules::Module, values::ZVal};
+use std::{convert::Infallible, process};
+
+pub fn integrate(module: &mut Module) {
+    module.enable_crash_handler();
+
+    module.add_function(
+        "integrate_crashes_abort",
+        |_: &mut [ZVal]| -> Result<(), Infallible> { process::abort() },
+    );
+}
@@ -0,0 +1,30 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{curl, functions::Argument, modules::Module, values::ZVal};
+
+pub fn integrate(module: &mut Module) {
+    module
+        .add_function(
+            "integrate_curl_easy_handle",
+            |arguments: &mut [ZVal]| -> phper::Result<i64> {
+                let easy = curl::easy_handle(&arguments[0])?;
+                Ok(easy.as_ptr() as i64)
+            },
+        )
+        .argument(Argument::by_val("handle"));
+
+    module
+        .add_function(
+            "integrate_curl_is_curl_handle",
+            |arguments: &mut [ZVal]| phper::ok(curl::is_curl_handle(&arguments[0])),
+        )
+        .argument(Argument::by_val("handle"));
+}
@@ -0,0 +1,43 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{
+    classes::Visibility,
+    enums::{EnumEntity, EnumType},
+    modules::Module,
+};
+
+pub fn integrate(module: &mut Module) {
+    let mut suit = EnumEntity::new("IntegrationTest\\Suit", EnumType::Pure);
+    suit.add_case("Hearts", ());
+    suit.add_case("Spades", ());
+    suit.add_method("color", Visibility::Public, |this, _| {
+        let name = this
+            .get_property("name")
+            .expect_z_str()?
+            .to_str()?
+            .to_owned();
+        Ok::<_, phper::Error>(if name == "Hearts" { "Red" } else { "Black" })
+    });
+    module.add_enum(suit);
+
+    let mut status = EnumEntity::new("IntegrationTest\\Status", EnumType::String);
+    status.add_case("Active", "active");
+    status.add_case("Inactive", "inactive");
+    status.add_static_method("describe", Visibility::Public, |_| {
+        Ok::<_, phper::Error>("status")
+    });
+    module.add_enum(status);
+
+    let mut priority = EnumEntity::new("IntegrationTest\\Priority", EnumType::Int);
+    priority.add_case("Low", 1);
+    priority.add_case("High", 10);
+    module.add_enum(priority);
+}
@@ -0,0 +1,54 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{env, functions::Argument, modules::Module, values::ZVal};
+
+pub fn integrate(module: &mut Module) {
+    module
+        .add_function(
+            "integrate_env_sapi_var",
+            |arguments: &mut [ZVal]| -> phper::Result<Option<String>> {
+                let name = arguments[0].expect_z_str()?.to_str()?;
+                Ok(env::sapi_var(name))
+            },
+        )
+        .argument(Argument::by_val("name"));
+
+    module
+        .add_function(
+            "integrate_env_var",
+            |arguments: &mut [ZVal]| -> phper::Result<Option<String>> {
+                let name = arguments[0].expect_z_str()?.to_str()?;
+                Ok(env::var(name))
+            },
+        )
+        .argument(Argument::by_val("name"));
+
+    module
+        .add_function(
+            "integrate_env_set_var",
+            |arguments: &mut [ZVal]| -> phper::Result<()> {
+                let name = arguments[0].expect_z_str()?.to_str()?.to_owned();
+                let value = arguments[1].expect_z_str()?.to_str()?;
+                env::set_var(&name, value)
+            },
+        )
+        .arguments([Argument::by_val("name"), Argument::by_val("value")]);
+
+    module
+        .add_function(
+            "integrate_env_remove_var",
+            |arguments: &mut [ZVal]| -> phper::Result<()> {
+                let name = arguments[0].expect_z_str()?.to_str()?;
+                env::remove_var(name)
+            },
+        )
+        .argument(Argument::by_val("name"));
+}
@@ -9,8 +9,12 @@
 // See the Mulan PSL v2 for more details.
 
 use phper::{
+    arrays::ZArray,
+    classes::ClassEntry,
     errors::{exception_class, ThrowObject},
+    functions::{call, Argument},
     modules::Module,
+    values::ZVal,
 };
 use std::io;
 
@@ -36,4 +40,70 @@ pub fn integrate(module: &mut Module) {
         let obj = ThrowObject::new(obj)?;
         Err::<(), _>(phper::Error::Throw(obj))
     });
+
+    module.add_function("integrate_throw_type_error", |_arguments| {
+        Err::<(), _>(phper::Error::type_error("expect int"))
+    });
+
+    module.add_function("integrate_throw_argument", |_arguments| {
+        Err::<(), _>(phper::Error::argument("invalid argument"))
+    });
+
+    module.add_function("integrate_throw_runtime", |_arguments| {
+        Err::<(), _>(phper::Error::runtime("runtime failure"))
+    });
+
+    module.add_function("integrate_throw_custom", |_arguments| {
+        Err::<(), _>(phper::Error::custom("OutOfRangeException", "out of range"))
+    });
+
+    module.add_function("integrate_throw_custom_not_found", |_arguments| {
+        Err::<(), _>(phper::Error::custom("NotExistsException", "fallback"))
+    });
+
+    module.add_function("integrate_throw_io", |_arguments| {
+        std::fs::File::open("/integrate_throw_io/not_exists")?;
+        Ok::<_, phper::Error>(())
+    });
+
+    module
+        .add_function("integrate_errors_catch", |arguments| {
+            let e = match call(arguments[0].clone(), []) {
+                Err(phper::Error::Throw(e)) => e,
+                result => return result.map(|_| ZVal::from(())),
+            };
+            let mut arr = ZArray::new();
+            arr.insert("class", e.class_name());
+            arr.insert("message", e.message());
+            arr.insert("code", e.code());
+            arr.insert("line", e.line());
+            arr.insert("file", e.file());
+            arr.insert("has_trace", !e.trace_as_string().is_empty());
+            arr.insert("previous", e.previous().map(|previous| previous.message()));
+            Ok::<ZVal, phper::Error>(arr.into())
+        })
+        .argument(Argument::by_val("callback"));
+
+    module
+        .add_function("integrate_errors_fallback", |arguments| {
+            let runtime = ClassEntry::from_globals("RuntimeException")?;
+            match call(arguments[0].clone(), []) {
+                Err(phper::Error::Throw(e)) if e.is_instance_of(runtime) => {
+                    Ok(ZVal::from("fallback"))
+                }
+                result => result,
+            }
+        })
+        .argument(Argument::by_val("callback"));
+
+    module
+        .add_function("integrate_errors_chain", |arguments| {
+            call(arguments[0].clone(), []).map_err(|e| match e {
+                phper::Error::Throw(previous) => {
+                    phper::Error::runtime("callback failed").with_previous(previous)
+                }
+                e => e,
+            })
+        })
+        .argument(Argument::by_val("callback"));
 }
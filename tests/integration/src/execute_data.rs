@@ -0,0 +1,45 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{
+    arrays::{InsertKey, ZArray},
+    modules::Module,
+    values::{ExecuteData, ZVal},
+};
+
+pub fn integrate(module: &mut Module) {
+    module.add_function(
+        "integrate_execute_data_frames",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut frames = ZArray::new();
+            let mut frame = ExecuteData::current();
+            while let Some(ex) = frame {
+                let mut item = ZArray::new();
+                item.insert(
+                    "function",
+                    ZVal::from(ex.function_name().map(ToOwned::to_owned)),
+                );
+                item.insert(
+                    "class",
+                    ZVal::from(ex.declaring_class().map(|ce| ce.get_name().to_owned())),
+                );
+                item.insert(
+                    "scope",
+                    ZVal::from(ex.called_scope().map(|ce| ce.get_name().to_owned())),
+                );
+                item.insert("file", ZVal::from(ex.filename().map(ToOwned::to_owned)));
+                item.insert("line", ZVal::from(ex.lineno().map(i64::from)));
+                frames.insert(InsertKey::NextIndex, ZVal::from(item));
+                frame = ex.prev_execute_data();
+            }
+            Ok(frames)
+        },
+    );
+}
@@ -0,0 +1,73 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{arrays::ZArray, filters, functions::Argument, modules::Module, values::ZVal};
+
+pub fn integrate(module: &mut Module) {
+    module
+        .add_function(
+            "integrate_filters_validate_email",
+            |arguments: &mut [ZVal]| -> phper::Result<ZVal> {
+                let valid = filters::validate(
+                    &arguments[0],
+                    filters::VALIDATE_EMAIL,
+                    filters::FLAG_NONE,
+                    None,
+                )?;
+                Ok(valid.unwrap_or_else(|| ZVal::from(false)))
+            },
+        )
+        .argument(Argument::by_val("value"));
+
+    module
+        .add_function(
+            "integrate_filters_validate_bool",
+            |arguments: &mut [ZVal]| -> phper::Result<ZVal> {
+                let valid = filters::validate(
+                    &arguments[0],
+                    filters::VALIDATE_BOOL,
+                    filters::FLAG_NONE,
+                    None,
+                )?;
+                Ok(valid.unwrap_or_default())
+            },
+        )
+        .argument(Argument::by_val("value"));
+
+    module
+        .add_function(
+            "integrate_filters_require_port",
+            |arguments: &mut [ZVal]| -> phper::Result<ZVal> {
+                let mut options = ZArray::new();
+                options.insert("min_range", ZVal::from(1i64));
+                options.insert("max_range", ZVal::from(65535i64));
+                filters::require(
+                    &arguments[0],
+                    filters::VALIDATE_INT,
+                    filters::FLAG_NONE,
+                    Some(options),
+                )
+            },
+        )
+        .argument(Argument::by_val("value"));
+
+    module
+        .add_function(
+            "integrate_filters_sanitize_number_int",
+            |arguments: &mut [ZVal]| {
+                filters::sanitize(
+                    &arguments[0],
+                    filters::SANITIZE_NUMBER_INT,
+                    filters::FLAG_NONE,
+                )
+            },
+        )
+        .argument(Argument::by_val("value"));
+}
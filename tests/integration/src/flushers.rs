@@ -0,0 +1,56 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use once_cell::sync::Lazy;
+use phper::{arrays::ZArray, flushers::BackgroundFlusher, modules::Module, values::ZVal};
+use std::{
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+static FLUSHED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+static FLUSHER: Lazy<BackgroundFlusher<String>> = Lazy::new(|| {
+    BackgroundFlusher::new(16, Duration::from_secs(60), |items| {
+        FLUSHED.lock().unwrap().extend(items);
+    })
+});
+
+pub fn integrate(module: &mut Module) {
+    module.add_background_flusher(&FLUSHER);
+
+    module.add_function(
+        "integrate_flushers_push",
+        |arguments: &mut [ZVal]| -> phper::Result<bool> {
+            let item = arguments[0].expect_z_str()?.to_str()?.to_owned();
+            Ok(FLUSHER.push(item))
+        },
+    );
+
+    module.add_function(
+        "integrate_flushers_flush_and_wait",
+        |arguments: &mut [ZVal]| -> phper::Result<ZArray> {
+            let count = arguments[0].expect_long()? as usize;
+            FLUSHER.flush();
+
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while FLUSHED.lock().unwrap().len() < count && Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(10));
+            }
+
+            let mut arr = ZArray::new();
+            for item in FLUSHED.lock().unwrap().drain(..) {
+                arr.insert((), item);
+            }
+            Ok(arr)
+        },
+    );
+}
@@ -9,13 +9,16 @@
 // See the Mulan PSL v2 for more details.
 
 use phper::{
-    arrays::ZArray,
+    arrays::{InsertKey, ZArray},
     errors::throw,
-    functions::{call, Argument},
+    functions::{call, defined_functions, Argument, CachedCallable, FunctionType},
     modules::Module,
     values::ZVal,
 };
-use std::{convert::Infallible, io};
+use std::{
+    convert::Infallible,
+    io::{self, Write},
+};
 
 pub fn integrate(module: &mut Module) {
     module.add_function(
@@ -30,6 +33,49 @@ pub fn integrate(module: &mut Module) {
         },
     );
 
+    module.add_function("integrate_functions_version", |_: &mut [ZVal]| {
+        phper::ok(env!("CARGO_PKG_VERSION"))
+    });
+
+    module
+        .add_function("integrate_functions_return_ref", |_: &mut [ZVal]| {
+            phper::ok("fresh")
+        })
+        .return_by_ref();
+
+    module
+        .add_function_with_return(
+            "integrate_functions_return_array",
+            |arguments: &mut [ZVal], ret| -> phper::Result<()> {
+                let n = arguments[0].expect_long()?;
+                let arr = ret.as_array_with_capacity(n as usize);
+                for i in 0..n {
+                    arr.insert(InsertKey::NextIndex, i * i);
+                }
+                Ok(())
+            },
+        )
+        .argument(Argument::by_val("n"));
+
+    module
+        .add_function_with_return(
+            "integrate_functions_return_string",
+            |arguments: &mut [ZVal], ret| -> phper::Result<()> {
+                let n = arguments[0].expect_long()?;
+                let mut writer = ret.as_string_writer();
+                for i in 0..n {
+                    write!(writer, "{},", i)?;
+                }
+                Ok(())
+            },
+        )
+        .argument(Argument::by_val("n"));
+
+    module.add_function(
+        "integrate_functions_count_undeclared",
+        |arguments: &mut [ZVal]| phper::ok(arguments.len() as i64),
+    );
+
     module
         .add_function(
             "integrate_functions_call_callable",
@@ -43,6 +89,21 @@ pub fn integrate(module: &mut Module) {
         )
         .argument(Argument::by_val("fn"));
 
+    module
+        .add_function(
+            "integrate_functions_cached_callable_map",
+            |arguments: &mut [ZVal]| -> phper::Result<ZArray> {
+                let mut callback = CachedCallable::new(arguments[0].clone())?;
+                let items = arguments[1].expect_z_arr()?;
+                let mut arr = ZArray::new();
+                for (_, item) in items.iter() {
+                    arr.insert((), callback.call([item.clone()])?);
+                }
+                Ok(arr)
+            },
+        )
+        .arguments([Argument::by_val("fn"), Argument::by_val("items")]);
+
     module.add_function(
         "integrate_functions_throw_error_exception",
         |_| -> phper::Result<()> { Err(phper::Error::boxed("throw error exception")) },
@@ -59,4 +120,22 @@ pub fn integrate(module: &mut Module) {
         assert_eq!(e.to_string(), "throw error exception");
         Ok::<_, Infallible>(())
     });
+
+    module.add_function(
+        "integrate_functions_defined_user_functions",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut functions = ZArray::new();
+            for f in defined_functions() {
+                let name = f.get_function_name().unwrap().to_str()?.to_owned();
+                match f.function_type() {
+                    FunctionType::Internal => assert!(f.filename().is_none()),
+                    FunctionType::User => {
+                        let filename = f.filename().unwrap().to_str()?.to_owned();
+                        functions.insert(name.as_str(), filename);
+                    }
+                }
+            }
+            Ok(functions)
+        },
+    );
 }
@@ -9,11 +9,15 @@
 // See the Mulan PSL v2 for more details.
 
 use phper::{
+    arrays::{IterKey, ZArray},
     c_str,
-    ini::{ini_get, Policy},
+    errors::IniError,
+    functions::Argument,
+    ini::{self, ini_get, IniConfig, Policy, Stage},
     modules::Module,
+    values::ZVal,
 };
-use std::{convert::Infallible, ffi::CStr};
+use std::{cell::Cell, convert::Infallible, ffi::CStr};
 
 pub fn integrate(module: &mut Module) {
     module.add_ini("INTEGRATE_INI_TRUE", true, Policy::System);
@@ -25,6 +29,41 @@ pub fn integrate(module: &mut Module) {
         "something".to_owned(),
         Policy::System,
     );
+    module.add_ini("INTEGRATE_INI_ALL", "foo".to_owned(), Policy::All);
+    module.add_ini(
+        "INTEGRATE_INI_PERDIR",
+        "default".to_owned(),
+        Policy::PerdirSystem,
+    );
+    module
+        .add_ini("INTEGRATE_INI_SECRET", "hunter2".to_owned(), Policy::All)
+        .displayer(|value| "*".repeat(value.len()));
+    module
+        .add_ini("INTEGRATE_INI_VALIDATED", 10i64, Policy::All)
+        .on_modify(|value| {
+            let level = value
+                .parse::<i64>()
+                .ok()
+                .filter(|level| *level > 0)
+                .ok_or_else(|| {
+                    IniError::new(format!("expect positive integer, got {:?}", value))
+                })?;
+            VALIDATED_LEVEL.with(|l| l.set(level));
+            Ok(())
+        });
+    module
+        .add_ini("INTEGRATE_INI_RELOADABLE", 1i64, Policy::System)
+        .on_modify(|value| {
+            let level = value
+                .parse::<i64>()
+                .ok()
+                .filter(|level| *level > 0)
+                .ok_or_else(|| {
+                    IniError::new(format!("expect positive integer, got {:?}", value))
+                })?;
+            RELOADABLE_LEVEL.with(|l| l.set(level));
+            Ok(())
+        });
 
     module.add_function("integrate_ini_assert", |_| {
         assert!(ini_get::<bool>("INTEGRATE_INI_TRUE"));
@@ -37,4 +76,115 @@ pub fn integrate(module: &mut Module) {
         );
         Ok::<_, Infallible>(())
     });
+
+    module.add_function("integrate_ini_get_set", |_| -> phper::Result<()> {
+        assert_eq!(ini::get_str("INTEGRATE_INI_LONG").as_deref(), Some("100"));
+        assert!(ini::get_str("memory_limit").is_some());
+        assert_eq!(ini::get_str("INTEGRATE_INI_NOT_EXISTS"), None);
+
+        ini::set("INTEGRATE_INI_ALL", "bar", Stage::Runtime)?;
+        assert_eq!(ini::get_str("INTEGRATE_INI_ALL").as_deref(), Some("bar"));
+        ini::restore("INTEGRATE_INI_ALL", Stage::Runtime);
+        assert_eq!(ini::get_str("INTEGRATE_INI_ALL").as_deref(), Some("foo"));
+
+        assert!(ini::set("INTEGRATE_INI_LONG", "200", Stage::Runtime).is_err());
+        assert!(ini::set("INTEGRATE_INI_NOT_EXISTS", "1", Stage::Runtime).is_err());
+
+        Ok(())
+    });
+
+    module.add_function("integrate_ini_validated_level", |_| {
+        Ok::<_, Infallible>(VALIDATED_LEVEL.with(Cell::get))
+    });
+
+    module
+        .add_function(
+            "integrate_ini_reload",
+            |arguments: &mut [ZVal]| -> phper::Result<i64> {
+                let values = arguments[0]
+                    .expect_z_arr()?
+                    .iter()
+                    .map(|(key, value)| {
+                        let IterKey::ZStr(name) = key else {
+                            unreachable!()
+                        };
+                        Ok((name.to_str()?, value.expect_z_str()?.to_str()?))
+                    })
+                    .collect::<phper::Result<Vec<_>>>()?;
+                ini::reload(values)?;
+                Ok(RELOADABLE_LEVEL.with(Cell::get))
+            },
+        )
+        .argument(Argument::by_val("values"));
+
+    module.add_function("integrate_ini_reloadable_level", |_| {
+        Ok::<_, Infallible>(RELOADABLE_LEVEL.with(Cell::get))
+    });
+
+    module
+        .add_function(
+            "integrate_ini_perdir",
+            |arguments: &mut [ZVal]| -> phper::Result<()> {
+                let is_fpm = arguments[0].expect_bool()?;
+                let expect = if is_fpm { "perdir" } else { "default" };
+
+                assert_eq!(
+                    ini::get_str("INTEGRATE_INI_PERDIR").as_deref(),
+                    Some(expect)
+                );
+                assert_eq!(
+                    ini::get_original_str("INTEGRATE_INI_PERDIR").as_deref(),
+                    Some("default")
+                );
+                assert_eq!(ini::is_modified("INTEGRATE_INI_PERDIR"), is_fpm);
+
+                assert!(Policy::PerdirSystem.is_changeable_in(Stage::Htaccess));
+                assert!(!Policy::PerdirSystem.is_changeable_in(Stage::Runtime));
+                assert!(ini::set("INTEGRATE_INI_PERDIR", "runtime", Stage::Runtime).is_err());
+
+                Ok(())
+            },
+        )
+        .argument(Argument::by_val("is_fpm"));
+
+    module.add_ini_config::<IntegrateIniConfig>(Policy::All);
+
+    module.add_function(
+        "integrate_ini_config",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let config = IntegrateIniConfig::load();
+            let mut arr = ZArray::new();
+            arr.insert("enable", config.enable);
+            arr.insert("size", config.size);
+            arr.insert("ratio", config.ratio);
+            arr.insert("endpoint", config.endpoint);
+            Ok(arr)
+        },
+    );
+}
+
+#[derive(phper::IniConfig)]
+#[ini(prefix = "integrate_ini_config")]
+struct IntegrateIniConfig {
+    enable: bool,
+    #[ini(rename = "max_size")]
+    size: i64,
+    ratio: f64,
+    endpoint: String,
+}
+
+impl Default for IntegrateIniConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            size: 1024,
+            ratio: 0.5,
+            endpoint: "localhost:9000".to_owned(),
+        }
+    }
+}
+
+thread_local! {
+    static VALIDATED_LEVEL: Cell<i64> = Cell::new(0);
+    static RELOADABLE_LEVEL: Cell<i64> = Cell::new(0);
 }
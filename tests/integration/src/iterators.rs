@@ -0,0 +1,47 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{
+    classes::StaticStateClass,
+    functions::Argument,
+    iterators::{make_iterator_class, new_iterator_object, IteratorState},
+    modules::Module,
+};
+use std::cell::Cell;
+
+static LINES_CLASS: StaticStateClass<IteratorState> = StaticStateClass::null();
+
+thread_local! {
+    static PRODUCED: Cell<i64> = Cell::new(0);
+}
+
+pub fn integrate(module: &mut Module) {
+    let mut class = make_iterator_class("IntegrationTest\\Lines");
+    class.bind(&LINES_CLASS);
+    module.add_class(class);
+
+    module
+        .add_function("integrate_iterators_lines", |arguments| {
+            let count = arguments[0].expect_long()?;
+            PRODUCED.with(|produced| produced.set(0));
+            new_iterator_object(
+                &LINES_CLASS,
+                (1..=count).map(|i| {
+                    PRODUCED.with(|produced| produced.set(i));
+                    format!("line {}", i)
+                }),
+            )
+        })
+        .argument(Argument::by_val("count"));
+
+    module.add_function("integrate_iterators_produced", |_| {
+        Ok::<_, phper::Error>(PRODUCED.with(|produced| produced.get()))
+    });
+}
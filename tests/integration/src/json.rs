@@ -0,0 +1,86 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{
+    arrays::ZArray,
+    classes::ClassEntry,
+    errors::{Error, Throwable},
+    json,
+    modules::Module,
+    values::ZVal,
+};
+
+pub fn integrate(module: &mut Module) {
+    module.add_function(
+        "integrate_json_is_loaded",
+        |_: &mut [ZVal]| -> phper::Result<bool> { Ok(json::is_loaded()) },
+    );
+
+    module.add_function(
+        "integrate_json_encode",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            let mut arr = ZArray::new();
+            arr.insert("foo", ZVal::from("a/b"));
+            arr.insert("bar", ZVal::from(1.0));
+            let value = ZVal::from(arr);
+
+            let s = json::encode(&value, 0, json::DEFAULT_DEPTH)?;
+            assert_eq!(s.to_str()?, r#"{"foo":"a\/b","bar":1.0}"#);
+
+            let flags = json::UNESCAPED_SLASHES | json::PRESERVE_ZERO_FRACTION;
+            let s = json::encode(&value, flags, json::DEFAULT_DEPTH)?;
+            assert_eq!(s.to_str()?, r#"{"foo":"a/b","bar":1.0}"#);
+
+            let e =
+                json::encode(&ZVal::from(b"\xff".as_slice()), 0, json::DEFAULT_DEPTH).unwrap_err();
+            assert!(matches!(&e, Error::Json(e) if e.code() == 5));
+            if ClassEntry::from_globals("JsonException").is_ok() {
+                assert_eq!(e.get_class().get_name().to_str()?, "JsonException");
+            }
+
+            Ok(())
+        },
+    );
+
+    module.add_function(
+        "integrate_json_decode",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            let value = json::decode(
+                r#"{"foo":[1,2]}"#,
+                json::OBJECT_AS_ARRAY,
+                json::DEFAULT_DEPTH,
+            )?;
+            let arr = value.expect_z_arr()?;
+            let foo = arr.get("foo").unwrap().expect_z_arr()?;
+            assert_eq!(foo.len(), 2);
+
+            let value = json::decode("null", 0, json::DEFAULT_DEPTH)?;
+            assert!(value.get_type_info().is_null());
+
+            let e = json::decode("{", 0, json::DEFAULT_DEPTH).unwrap_err();
+            assert!(
+                matches!(&e, Error::Json(e) if e.code() == 4 && e.to_string() == "Syntax error")
+            );
+
+            Ok(())
+        },
+    );
+
+    module.add_function(
+        "integrate_json_decode_throw",
+        |arguments: &mut [ZVal]| -> phper::Result<ZVal> {
+            json::decode(
+                arguments[0].expect_z_str()?.to_bytes(),
+                json::THROW_ON_ERROR,
+                json::DEFAULT_DEPTH,
+            )
+        },
+    );
+}
@@ -10,17 +10,66 @@
 
 #![warn(rust_2018_idioms, clippy::dbg_macro, clippy::print_stdout)]
 
+mod alloc;
 mod arguments;
 mod arrays;
+mod autoload;
 mod classes;
+mod collections;
+mod compile_hooks;
 mod constants;
+mod contexts;
+mod crashes;
+mod curl;
+#[cfg(phper_php_version_at_least = "8.1")]
+mod enums;
+mod env;
 mod errors;
+mod execute_data;
+mod filters;
+mod flushers;
 mod functions;
 mod ini;
+mod iterators;
+mod json;
+mod loggers;
+mod metrics;
+mod modules;
 mod objects;
+mod opcodes;
+mod paths;
+#[cfg(all(phper_major_version = "8", not(phper_minor_version = "0")))]
+mod pdo;
+mod pools;
+mod post_readers;
+mod processes;
+mod random;
 mod references;
+mod requests;
+mod responses;
+mod serialize;
+#[cfg(not(all(phper_major_version = "7", phper_minor_version = "0")))]
+mod sessions;
+mod snapshots;
+mod streams;
+mod strict_types;
 mod strings;
+mod symbols;
+mod timers;
+mod transports;
 mod values;
+#[cfg(not(all(
+    phper_major_version = "7",
+    any(
+        phper_minor_version = "0",
+        phper_minor_version = "1",
+        phper_minor_version = "2",
+        phper_minor_version = "3",
+    )
+)))]
+mod weak;
+mod workers;
+mod zend_extensions;
 
 use phper::{modules::Module, php_get_module};
 
@@ -43,6 +92,58 @@ pub fn get_module() -> Module {
     ini::integrate(&mut module);
     errors::integrate(&mut module);
     references::integrate(&mut module);
+    autoload::integrate(&mut module);
+    alloc::integrate(&mut module);
+    opcodes::integrate(&mut module);
+    compile_hooks::integrate(&mut module);
+    modules::integrate(&mut module);
+    flushers::integrate(&mut module);
+    loggers::integrate(&mut module);
+    json::integrate(&mut module);
+    serialize::integrate(&mut module);
+    random::integrate(&mut module);
+    iterators::integrate(&mut module);
+    collections::integrate(&mut module);
+    curl::integrate(&mut module);
+    transports::integrate(&mut module);
+    filters::integrate(&mut module);
+    workers::integrate(&mut module);
+    requests::integrate(&mut module);
+    env::integrate(&mut module);
+    responses::integrate(&mut module);
+    post_readers::integrate(&mut module);
+    contexts::integrate(&mut module);
+    execute_data::integrate(&mut module);
+    strict_types::integrate(&mut module);
+    metrics::integrate(&mut module);
+    pools::integrate(&mut module);
+    crashes::integrate(&mut module);
+    streams::integrate(&mut module);
+    paths::integrate(&mut module);
+    processes::integrate(&mut module);
+    symbols::integrate(&mut module);
+    snapshots::integrate(&mut module);
+    timers::integrate(&mut module);
+    #[cfg(all(phper_major_version = "8", not(phper_minor_version = "0")))]
+    pdo::integrate(&mut module);
+    #[cfg(not(all(phper_major_version = "7", phper_minor_version = "0")))]
+    sessions::integrate(&mut module);
+    #[cfg(not(all(
+        phper_major_version = "7",
+        any(
+            phper_minor_version = "0",
+            phper_minor_version = "1",
+            phper_minor_version = "2",
+            phper_minor_version = "3",
+        )
+    )))]
+    weak::integrate(&mut module);
+    zend_extensions::integrate(&mut module);
+    #[cfg(phper_php_version_at_least = "8.1")]
+    enums::integrate(&mut module);
+
+    module.enable_debug_dump(|| "integration globals");
+    module.enable_stats();
 
     module
 }
@@ -0,0 +1,30 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use log::LevelFilter;
+use phper::{loggers, modules::Module, values::ZVal};
+
+pub fn integrate(module: &mut Module) {
+    loggers::init(LevelFilter::Info).unwrap();
+
+    module.add_function(
+        "integrate_loggers_log",
+        |arguments: &mut [ZVal]| -> phper::Result<()> {
+            let message = arguments[1].expect_z_str()?.to_str()?;
+            match arguments[0].expect_z_str()?.to_str()? {
+                "error" => log::error!("{}", message),
+                "warn" => log::warn!("{}", message),
+                "info" => log::info!("{}", message),
+                _ => log::debug!("{}", message),
+            }
+            Ok(())
+        },
+    );
+}
@@ -0,0 +1,36 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{functions::Argument, modules::Module, values::ZVal};
+
+pub fn integrate(module: &mut Module) {
+    module.enable_metrics_function();
+
+    let calls = module.add_counter("integrate_metrics_calls", "The calls of handler.");
+    let level = module.add_gauge("integrate_metrics_level", "The current level.");
+    let sizes = module.add_histogram(
+        "integrate_metrics_sizes",
+        "The observed sizes.",
+        [10., 1., 100.],
+    );
+
+    module
+        .add_function(
+            "integrate_metrics_observe",
+            move |arguments: &mut [ZVal]| -> phper::Result<()> {
+                calls.inc();
+                level.set(arguments[0].expect_long()?);
+                sizes.observe(arguments[1].expect_double()?);
+                Ok(())
+            },
+        )
+        .argument(Argument::by_val("level"))
+        .argument(Argument::by_val("size"));
+}
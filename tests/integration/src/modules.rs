@@ -0,0 +1,164 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{
+    arrays::ZArray,
+    functions::Argument,
+    modules::{get_loaded, is_loaded, loaded_modules, php_version, Module},
+    values::ZVal,
+};
+use std::{
+    process,
+    sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
+};
+
+static PROCESS_INIT_COUNT: AtomicUsize = AtomicUsize::new(0);
+static PROCESS_INIT_PID: AtomicU32 = AtomicU32::new(0);
+static FORK_COUNT: AtomicUsize = AtomicUsize::new(0);
+static FORK_PARENT_PID: AtomicU32 = AtomicU32::new(0);
+static REQUEST_SHUTDOWN_COUNT: AtomicUsize = AtomicUsize::new(0);
+static POST_DEACTIVATE_COUNT: AtomicUsize = AtomicUsize::new(0);
+static POST_DEACTIVATE_ORDERED: AtomicBool = AtomicBool::new(true);
+
+pub fn integrate(module: &mut Module) {
+    module.on_process_init(|| {
+        PROCESS_INIT_COUNT.fetch_add(1, Ordering::SeqCst);
+        PROCESS_INIT_PID.store(process::id(), Ordering::SeqCst);
+    });
+    module.on_process_shutdown(|| {
+        PROCESS_INIT_PID.store(0, Ordering::SeqCst);
+    });
+
+    module.on_request_shutdown(|| {
+        REQUEST_SHUTDOWN_COUNT.fetch_add(1, Ordering::SeqCst);
+    });
+    module.on_post_deactivate(|| {
+        let count = POST_DEACTIVATE_COUNT.fetch_add(1, Ordering::SeqCst) + 1;
+        if count != REQUEST_SHUTDOWN_COUNT.load(Ordering::SeqCst) {
+            POST_DEACTIVATE_ORDERED.store(false, Ordering::SeqCst);
+        }
+    });
+
+    module.on_fork(|parent_pid| {
+        FORK_COUNT.fetch_add(1, Ordering::SeqCst);
+        FORK_PARENT_PID.store(parent_pid, Ordering::SeqCst);
+    });
+
+    module.add_function(
+        "integrate_modules_fork",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut arr = ZArray::new();
+            arr.insert("count", FORK_COUNT.load(Ordering::SeqCst) as i64);
+            arr.insert("parent_pid", FORK_PARENT_PID.load(Ordering::SeqCst) as i64);
+            Ok(arr)
+        },
+    );
+
+    module.add_function(
+        "integrate_modules_php_version",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let version = php_version();
+            let mut arr = ZArray::new();
+            arr.insert("id", version.id() as i64);
+            arr.insert("string", version.to_string());
+            arr.insert("json", is_loaded("JSON"));
+            arr.insert("unknown", is_loaded("integrate_unknown"));
+            Ok(arr)
+        },
+    );
+
+    module
+        .add_function(
+            "integrate_modules_loaded",
+            |arguments: &mut [ZVal]| -> phper::Result<ZVal> {
+                let name = arguments[0].expect_str()?;
+                let entry = match get_loaded(name) {
+                    Some(entry) => entry,
+                    None => return Ok(ZVal::from(())),
+                };
+                let mut functions = entry
+                    .functions()
+                    .filter_map(|f| f.get_function_name())
+                    .map(|name| name.to_str().map(ToOwned::to_owned))
+                    .collect::<Result<Vec<_>, _>>()?;
+                functions.sort();
+
+                let mut arr = ZArray::new();
+                arr.insert("name", entry.name().to_str()?);
+                arr.insert(
+                    "version",
+                    match entry.version() {
+                        Some(version) => ZVal::from(version.to_str()?),
+                        None => ZVal::from(()),
+                    },
+                );
+                arr.insert("started", entry.is_started());
+                arr.insert("functions", {
+                    let mut arr = ZArray::new();
+                    for f in functions {
+                        arr.insert((), f);
+                    }
+                    arr
+                });
+                arr.insert("has_str_repeat", entry.get_function("STR_REPEAT").is_some());
+                Ok(arr.into())
+            },
+        )
+        .argument(Argument::by_val("name"));
+
+    module.add_function(
+        "integrate_modules_loaded_names",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut arr = ZArray::new();
+            for entry in loaded_modules() {
+                arr.insert((), entry.name().to_str()?);
+            }
+            Ok(arr)
+        },
+    );
+
+    module.when(php_version() >= (7, 0), |module| {
+        module.add_function("integrate_modules_when_true", |_: &mut [ZVal]| {
+            phper::ok(true)
+        });
+    });
+    module.when(php_version() < (7, 0), |module| {
+        module.add_function("integrate_modules_when_false", |_: &mut [ZVal]| {
+            phper::ok(false)
+        });
+    });
+
+    module.add_function(
+        "integrate_modules_process_init",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut arr = ZArray::new();
+            arr.insert("count", PROCESS_INIT_COUNT.load(Ordering::SeqCst) as i64);
+            arr.insert("pid", PROCESS_INIT_PID.load(Ordering::SeqCst) as i64);
+            Ok(arr)
+        },
+    );
+
+    module.add_function(
+        "integrate_modules_post_deactivate",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut arr = ZArray::new();
+            arr.insert(
+                "request_shutdown",
+                REQUEST_SHUTDOWN_COUNT.load(Ordering::SeqCst) as i64,
+            );
+            arr.insert(
+                "post_deactivate",
+                POST_DEACTIVATE_COUNT.load(Ordering::SeqCst) as i64,
+            );
+            arr.insert("ordered", POST_DEACTIVATE_ORDERED.load(Ordering::SeqCst));
+            Ok(arr)
+        },
+    );
+}
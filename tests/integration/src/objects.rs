@@ -10,14 +10,16 @@
 
 use phper::{
     alloc::{RefClone, ToRefOwned},
+    arrays::ZArray,
     classes::{ClassEntity, ClassEntry, Visibility},
     functions::Argument,
     modules::Module,
-    objects::ZObject,
+    objects::{ObjectId, ZObject},
+    strings::ZString,
     types::TypeInfo,
     values::ZVal,
 };
-use std::convert::Infallible;
+use std::{cell::RefCell, convert::Infallible};
 
 pub fn integrate(module: &mut Module) {
     module.add_function(
@@ -41,6 +43,12 @@ pub fn integrate(module: &mut Module) {
             let not_exists = o.get_property("no_exists");
             not_exists.expect_null()?;
 
+            let name = ZString::interned("baz");
+            o.set_property_by_z_str(&name, ZVal::from(1));
+            assert_eq!(o.get_property_by_z_str(&name).expect_long()?, 1);
+            *o.get_mut_property_by_z_str(&name) = ZVal::from(2);
+            assert_eq!(o.get_property("baz").expect_long()?, 2);
+
             Ok(())
         },
     );
@@ -160,4 +168,36 @@ pub fn integrate(module: &mut Module) {
         Ok::<_, Infallible>(*this.as_state())
     });
     module.add_class(class_b);
+
+    let mut class_c = ClassEntity::new("IntegrationTest\\Objects\\C");
+    class_c.on_destroy(|id| {
+        DESTROYED_IDS.with(|ids| ids.borrow_mut().push(id));
+    });
+    module.add_class(class_c);
+
+    module
+        .add_function(
+            "integrate_objects_id",
+            |arguments: &mut [ZVal]| -> phper::Result<i64> {
+                Ok(arguments[0].expect_z_obj()?.id().as_u32().into())
+            },
+        )
+        .argument(Argument::by_val("obj"));
+
+    module.add_function(
+        "integrate_objects_destroyed_ids",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut arr = ZArray::new();
+            DESTROYED_IDS.with(|ids| {
+                for id in ids.borrow_mut().drain(..) {
+                    arr.insert((), ZVal::from(i64::from(id.as_u32())));
+                }
+            });
+            Ok(arr)
+        },
+    );
+}
+
+thread_local! {
+    static DESTROYED_IDS: RefCell<Vec<ObjectId>> = Default::default();
 }
@@ -0,0 +1,45 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{
+    modules::Module,
+    opcodes::{Opcode, UserOpcode},
+    values::ZVal,
+};
+use std::cell::Cell;
+
+thread_local! {
+    static INCLUDE_OR_EVAL_COUNT: Cell<i64> = Cell::new(0);
+    static DENY_INCLUDE_OR_EVAL: Cell<bool> = Cell::new(false);
+}
+
+pub fn integrate(module: &mut Module) {
+    module.add_opcode_handler(Opcode::IncludeOrEval, |_| {
+        if DENY_INCLUDE_OR_EVAL.with(Cell::get) {
+            return Err(phper::Error::boxed("include or eval is denied"));
+        }
+        INCLUDE_OR_EVAL_COUNT.with(|count| count.set(count.get() + 1));
+        Ok(UserOpcode::Dispatch)
+    });
+
+    module.add_function(
+        "integrate_opcodes_include_or_eval_count",
+        |_: &mut [ZVal]| -> phper::Result<i64> { Ok(INCLUDE_OR_EVAL_COUNT.with(Cell::get)) },
+    );
+
+    module.add_function(
+        "integrate_opcodes_deny_include_or_eval",
+        |arguments: &mut [ZVal]| -> phper::Result<()> {
+            let deny = arguments[0].expect_bool()?;
+            DENY_INCLUDE_OR_EVAL.with(|d| d.set(deny));
+            Ok(())
+        },
+    );
+}
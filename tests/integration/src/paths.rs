@@ -0,0 +1,41 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{functions::Argument, modules::Module, paths, values::ZVal};
+use std::{convert::Infallible, path::PathBuf};
+
+fn to_z_val(path: Option<PathBuf>) -> ZVal {
+    ZVal::from(path.map(|path| path.to_string_lossy().into_owned()))
+}
+
+pub fn integrate(module: &mut Module) {
+    module
+        .add_function(
+            "integrate_paths_expand",
+            |arguments: &mut [ZVal]| -> phper::Result<ZVal> {
+                Ok(to_z_val(paths::expand(arguments[0].expect_str()?)))
+            },
+        )
+        .argument(Argument::by_val("path"));
+
+    module
+        .add_function(
+            "integrate_paths_real_path",
+            |arguments: &mut [ZVal]| -> phper::Result<ZVal> {
+                Ok(to_z_val(paths::real_path(arguments[0].expect_str()?)))
+            },
+        )
+        .argument(Argument::by_val("path"));
+
+    module.add_function(
+        "integrate_paths_current_dir",
+        |_: &mut [ZVal]| -> Result<ZVal, Infallible> { Ok(to_z_val(paths::current_dir())) },
+    );
+}
@@ -0,0 +1,151 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! The in-memory PDO driver `phpertest`, the table has the columns `id` and
+//! `name`, and accepts the statements:
+//!
+//! - `INSERT 'name'`
+//! - `DELETE`
+//! - `SELECT` or `SELECT 'name'`
+
+use phper::{
+    modules::Module,
+    pdo::{PdoConnection, PdoDataSource, PdoDriver, PdoError, PdoStatement},
+    values::ZVal,
+};
+use std::{cell::RefCell, rc::Rc};
+
+type Rows = Rc<RefCell<Vec<(i64, String)>>>;
+
+struct TestDriver;
+
+impl PdoDriver for TestDriver {
+    fn connect(&self, source: &PdoDataSource<'_>) -> Result<Box<dyn PdoConnection>, PdoError> {
+        if source.params().any(|(key, _)| key == "refuse") {
+            return Err(PdoError::new("08006", 1, "connection refused"));
+        }
+        if source.username().is_some() && source.password() != Some("secret") {
+            return Err(PdoError::new("28000", 2, "access denied"));
+        }
+        Ok(Box::new(TestConnection {
+            rows: Default::default(),
+            next_id: 1,
+        }))
+    }
+}
+
+struct TestConnection {
+    rows: Rows,
+    next_id: i64,
+}
+
+impl PdoConnection for TestConnection {
+    fn prepare(&mut self, sql: &str) -> Result<Box<dyn PdoStatement>, PdoError> {
+        if !sql.starts_with("SELECT") {
+            return Err(syntax_error(sql));
+        }
+        Ok(Box::new(TestStatement {
+            rows: self.rows.clone(),
+            result: Vec::new(),
+            current: None,
+        }))
+    }
+
+    fn exec(&mut self, sql: &str) -> Result<u64, PdoError> {
+        if let Some(name) = sql.strip_prefix("INSERT ") {
+            let name = unquote(name).ok_or_else(|| syntax_error(sql))?;
+            self.rows.borrow_mut().push((self.next_id, name));
+            self.next_id += 1;
+            Ok(1)
+        } else if sql == "DELETE" {
+            Ok(self.rows.borrow_mut().drain(..).count() as u64)
+        } else {
+            Err(syntax_error(sql))
+        }
+    }
+
+    fn last_insert_id(&mut self, _name: Option<&str>) -> Result<String, PdoError> {
+        Ok((self.next_id - 1).to_string())
+    }
+}
+
+struct TestStatement {
+    rows: Rows,
+    result: Vec<(i64, String)>,
+    current: Option<usize>,
+}
+
+impl PdoStatement for TestStatement {
+    fn execute(&mut self, sql: &str) -> Result<(), PdoError> {
+        let filter = match sql.strip_prefix("SELECT") {
+            Some("") => None,
+            Some(name) => Some(unquote(name.trim()).ok_or_else(|| syntax_error(sql))?),
+            None => return Err(syntax_error(sql)),
+        };
+        self.result = self
+            .rows
+            .borrow()
+            .iter()
+            .filter(|(_, name)| filter.as_ref().map_or(true, |filter| filter == name))
+            .cloned()
+            .collect();
+        self.current = None;
+        Ok(())
+    }
+
+    fn column_count(&self) -> usize {
+        2
+    }
+
+    fn column_name(&self, index: usize) -> Result<String, PdoError> {
+        match index {
+            0 => Ok("id".to_owned()),
+            1 => Ok("name".to_owned()),
+            _ => Err(PdoError::general("column out of range")),
+        }
+    }
+
+    fn row_count(&self) -> u64 {
+        self.result.len() as u64
+    }
+
+    fn fetch(&mut self) -> Result<bool, PdoError> {
+        let next = self.current.map_or(0, |current| current + 1);
+        self.current = Some(next);
+        Ok(next < self.result.len())
+    }
+
+    fn column_value(&mut self, index: usize) -> Result<ZVal, PdoError> {
+        let (id, name) = self
+            .current
+            .and_then(|current| self.result.get(current))
+            .ok_or_else(|| PdoError::general("no current row"))?;
+        match index {
+            0 => Ok((*id).into()),
+            1 => Ok(name.as_str().into()),
+            _ => Err(PdoError::general("column out of range")),
+        }
+    }
+}
+
+fn syntax_error(sql: &str) -> PdoError {
+    PdoError::new("42000", 7, format!("unknown statement: {}", sql))
+}
+
+/// Parse the SQL string literal quoted by the default
+/// [`PdoConnection::quote`].
+fn unquote(s: &str) -> Option<String> {
+    let inner = s.strip_prefix('\'')?.strip_suffix('\'')?;
+    Some(inner.replace("''", "'"))
+}
+
+pub fn integrate(module: &mut Module) {
+    module.add_pdo_driver("phpertest", TestDriver);
+}
@@ -0,0 +1,75 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{functions::Argument, modules::Module, values::ZVal};
+use std::{
+    cell::Cell,
+    sync::atomic::{AtomicI64, Ordering},
+};
+
+static NEXT_ID: AtomicI64 = AtomicI64::new(1);
+
+struct Connection {
+    id: i64,
+    healthy: Cell<bool>,
+}
+
+fn connect<K>(_: &K) -> phper::Result<Connection> {
+    Ok(Connection {
+        id: NEXT_ID.fetch_add(1, Ordering::SeqCst),
+        healthy: Cell::new(true),
+    })
+}
+
+pub fn integrate(module: &mut Module) {
+    let pool = module.add_pool::<String, Connection>(2);
+    pool.set_health_check(|conn| conn.healthy.get());
+
+    let request_pool = pool.clone();
+    module
+        .add_function(
+            "integrate_pools_request",
+            move |arguments: &mut [ZVal]| -> phper::Result<i64> {
+                let key = arguments[0].expect_str()?.to_owned();
+                request_pool.with_request(&key, connect, |conn| conn.id)
+            },
+        )
+        .argument(Argument::by_val("key"));
+
+    let checkout_pool = pool.clone();
+    module
+        .add_function(
+            "integrate_pools_checkout",
+            move |arguments: &mut [ZVal]| -> phper::Result<i64> {
+                let key = arguments[0].expect_str()?.to_owned();
+                let action = arguments[1].expect_str()?;
+                let conn = checkout_pool.checkout(&key, connect)?;
+                let id = conn.id;
+                match action {
+                    "discard" => conn.discard(),
+                    "break" => conn.healthy.set(false),
+                    _ => {}
+                }
+                Ok(id)
+            },
+        )
+        .argument(Argument::by_val("key"))
+        .argument(Argument::by_val("action"));
+
+    module
+        .add_function(
+            "integrate_pools_idle",
+            move |arguments: &mut [ZVal]| -> phper::Result<i64> {
+                let key = arguments[0].expect_str()?.to_owned();
+                Ok(pool.idle_len(&key) as i64)
+            },
+        )
+        .argument(Argument::by_val("key"));
+}
@@ -0,0 +1,26 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{arrays::ZArr, modules::Module, values::ZVal};
+
+pub fn integrate(module: &mut Module) {
+    module.add_post_reader(
+        "application/x-phper-kv",
+        |content_type: &str, body: &[u8], post: &mut ZArr| {
+            post.insert("_content_type", ZVal::from(content_type));
+            for line in std::str::from_utf8(body)?.lines() {
+                if let Some((key, value)) = line.split_once('=') {
+                    post.insert(key, ZVal::from(value));
+                }
+            }
+            Ok(())
+        },
+    );
+}
@@ -0,0 +1,41 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{arrays::ZArray, functions::Argument, modules::Module, processes, values::ZVal};
+
+pub fn integrate(module: &mut Module) {
+    module.add_function(
+        "integrate_processes_worker",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let info = processes::worker_info()?;
+            let mut arr = ZArray::new();
+            arr.insert("sapi", info.sapi());
+            arr.insert("pid", info.pid() as i64);
+            arr.insert("pool", info.pool());
+            arr.insert("requests", info.requests());
+            arr.insert("display", info.to_string());
+            Ok(arr)
+        },
+    );
+
+    module
+        .add_function(
+            "integrate_processes_set_title",
+            |arguments: &mut [ZVal]| -> phper::Result<bool> {
+                processes::set_title(arguments[0].expect_str()?)
+            },
+        )
+        .argument(Argument::by_val("title"));
+
+    module.add_function(
+        "integrate_processes_title",
+        |_: &mut [ZVal]| -> phper::Result<Option<String>> { processes::title() },
+    );
+}
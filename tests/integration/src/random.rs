@@ -0,0 +1,56 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{errors::Error, hashes, modules::Module, random, values::ZVal};
+use std::io::Write;
+
+pub fn integrate(module: &mut Module) {
+    module.add_function(
+        "integrate_hashes_hash",
+        |arguments: &mut [ZVal]| -> phper::Result<ZVal> {
+            let algo = arguments[0].expect_z_str()?.to_str()?;
+            let data = arguments[1].expect_z_str()?.to_bytes();
+            Ok(hashes::hash(algo, data)?.as_slice().into())
+        },
+    );
+
+    module.add_function(
+        "integrate_hashes_context",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            let mut context = hashes::HashContext::new("SHA256")?;
+            assert_eq!(context.digest_size(), 32);
+            context.update("foo");
+            let mut cloned = context.clone();
+            context.update("bar");
+            write!(cloned, "bar").unwrap();
+            assert_eq!(context.finalize(), cloned.finalize());
+
+            let e = hashes::HashContext::new("not-exists").err().unwrap();
+            assert!(matches!(e, Error::HashAlgo(_)));
+
+            Ok(())
+        },
+    );
+
+    module.add_function(
+        "integrate_random_bytes",
+        |arguments: &mut [ZVal]| -> phper::Result<ZVal> {
+            let len = arguments[0].expect_long()?;
+            Ok(random::bytes(len as usize)?.as_slice().into())
+        },
+    );
+
+    module.add_function(
+        "integrate_random_int",
+        |arguments: &mut [ZVal]| -> phper::Result<i64> {
+            random::int(arguments[0].expect_long()?, arguments[1].expect_long()?)
+        },
+    );
+}
@@ -0,0 +1,134 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{
+    arrays::{InsertKey, ZArray},
+    functions::Argument,
+    modules::Module,
+    requests::{self, RequestToken},
+    values::ZVal,
+};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+pub fn integrate(module: &mut Module) {
+    module.add_function(
+        "integrate_requests_token_is_cancelled",
+        |_: &mut [ZVal]| -> phper::Result<bool> {
+            let token = RequestToken::current();
+            token.check()?;
+            Ok(token.is_cancelled())
+        },
+    );
+
+    module
+        .add_function(
+            "integrate_requests_token_wait",
+            |arguments: &mut [ZVal]| -> phper::Result<bool> {
+                let millis = arguments[0].expect_long()?;
+                let token = RequestToken::current();
+                let waiter =
+                    thread::spawn(move || token.wait_timeout(Duration::from_millis(millis as u64)));
+                Ok(waiter.join().unwrap())
+            },
+        )
+        .argument(Argument::by_val("millis"));
+
+    module.add_function(
+        "integrate_requests_token_in_other_thread",
+        |_: &mut [ZVal]| -> phper::Result<bool> {
+            let outside = thread::spawn(|| RequestToken::current().is_cancelled());
+            Ok(outside.join().unwrap())
+        },
+    );
+
+    module.add_function(
+        "integrate_requests_remaining_time",
+        |_: &mut [ZVal]| -> phper::Result<Option<f64>> {
+            Ok(requests::remaining_time().map(|remaining| remaining.as_secs_f64()))
+        },
+    );
+
+    module.add_function(
+        "integrate_requests_with_remaining_time",
+        |_: &mut [ZVal]| -> phper::Result<Option<f64>> {
+            Ok(requests::with_remaining_time(|deadline| {
+                deadline.map(|deadline| {
+                    deadline
+                        .saturating_duration_since(Instant::now())
+                        .as_secs_f64()
+                })
+            }))
+        },
+    );
+
+    module.add_function(
+        "integrate_requests_headers",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut arr = ZArray::new();
+            for (name, value) in requests::headers() {
+                arr.insert(name.as_str(), ZVal::from(value));
+            }
+            Ok(arr)
+        },
+    );
+
+    module
+        .add_function(
+            "integrate_requests_header",
+            |arguments: &mut [ZVal]| -> phper::Result<Option<String>> {
+                let name = arguments[0].expect_z_str()?.to_str()?;
+                Ok(requests::headers().get(name).map(ToOwned::to_owned))
+            },
+        )
+        .argument(Argument::by_val("name"));
+
+    module.add_function(
+        "integrate_requests_cookies",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut arr = ZArray::new();
+            for (name, value) in requests::cookies() {
+                arr.insert(name.as_str(), ZVal::from(value));
+            }
+            Ok(arr)
+        },
+    );
+
+    module.add_function(
+        "integrate_requests_uploaded_files",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut arr = ZArray::new();
+            for file in requests::uploaded_files() {
+                let mut item = ZArray::new();
+                item.insert("field", ZVal::from(file.field()));
+                item.insert("name", ZVal::from(file.name()));
+                item.insert("type", ZVal::from(file.mime_type()));
+                item.insert("tmp_name", ZVal::from(file.tmp_name()));
+                item.insert("error", ZVal::from(file.error()));
+                item.insert("size", ZVal::from(file.size()));
+                item.insert("uploaded", ZVal::from(file.is_uploaded()));
+                arr.insert(InsertKey::NextIndex, ZVal::from(item));
+            }
+            Ok(arr)
+        },
+    );
+
+    module
+        .add_function(
+            "integrate_requests_is_uploaded_file",
+            |arguments: &mut [ZVal]| -> phper::Result<bool> {
+                let path = arguments[0].expect_z_str()?.to_str()?;
+                Ok(requests::is_uploaded_file(path))
+            },
+        )
+        .argument(Argument::by_val("path"));
+}
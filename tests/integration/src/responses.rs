@@ -0,0 +1,66 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{
+    functions::Argument,
+    modules::Module,
+    responses::{self, Cookie, SameSite},
+    values::ZVal,
+};
+
+pub fn integrate(module: &mut Module) {
+    module.add_function(
+        "integrate_responses_cookie_header",
+        |_: &mut [ZVal]| -> phper::Result<String> {
+            let mut cookie = Cookie::new("foo", "bar baz");
+            cookie
+                .path("/")
+                .domain("example.com")
+                .secure(true)
+                .http_only(true)
+                .same_site(SameSite::Lax);
+            cookie.to_header_value()
+        },
+    );
+
+    module
+        .add_function(
+            "integrate_responses_cookie_expires",
+            |arguments: &mut [ZVal]| -> phper::Result<String> {
+                let expires = arguments[0].expect_long()?;
+                Cookie::new("foo", "bar").expires(expires).to_header_value()
+            },
+        )
+        .argument(Argument::by_val("expires"));
+
+    module
+        .add_function(
+            "integrate_responses_cookie_name",
+            |arguments: &mut [ZVal]| -> phper::Result<String> {
+                let name = arguments[0].expect_z_str()?.to_str()?;
+                Cookie::new(name, "bar").to_header_value()
+            },
+        )
+        .argument(Argument::by_val("name"));
+
+    module.add_function(
+        "integrate_responses_set_cookie",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            let mut cookie = Cookie::new("foo", "bar");
+            cookie.same_site(SameSite::Strict);
+            responses::set_cookie(&cookie)
+        },
+    );
+
+    module.add_function(
+        "integrate_responses_headers_sent",
+        |_: &mut [ZVal]| -> phper::Result<bool> { Ok(responses::headers_sent()) },
+    );
+}
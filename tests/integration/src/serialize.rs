@@ -0,0 +1,56 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{
+    errors::Error,
+    modules::Module,
+    serialize::{self, AllowedClasses},
+    values::ZVal,
+};
+
+pub fn integrate(module: &mut Module) {
+    module.add_function(
+        "integrate_serialize_serialize",
+        |arguments: &mut [ZVal]| -> phper::Result<ZVal> {
+            Ok(serialize::serialize(&arguments[0])?.into())
+        },
+    );
+
+    module.add_function(
+        "integrate_serialize_unserialize",
+        |arguments: &mut [ZVal]| -> phper::Result<ZVal> {
+            let allowed_classes = match arguments[1].as_bool() {
+                Some(true) => AllowedClasses::All,
+                Some(false) => AllowedClasses::None,
+                None => AllowedClasses::Only(
+                    arguments[1]
+                        .expect_z_arr()?
+                        .iter()
+                        .map(|(_, name)| Ok(name.expect_z_str()?.to_str()?.to_owned()))
+                        .collect::<phper::Result<_>>()?,
+                ),
+            };
+            serialize::unserialize(arguments[0].expect_z_str()?.to_bytes(), &allowed_classes)
+        },
+    );
+
+    module.add_function(
+        "integrate_serialize_unserialize_malformed",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            let value = serialize::unserialize("b:0;", &AllowedClasses::All)?;
+            assert_eq!(value.as_bool(), Some(false));
+
+            let e = serialize::unserialize("s:3:\"fo", &AllowedClasses::All).unwrap_err();
+            assert!(matches!(e, Error::Unserialize(_)));
+
+            Ok(())
+        },
+    );
+}
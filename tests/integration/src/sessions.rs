@@ -0,0 +1,128 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use once_cell::sync::Lazy;
+use phper::{
+    arrays::{InsertKey, ZArray},
+    functions::Argument,
+    modules::Module,
+    sessions::SessionHandler,
+    values::ZVal,
+};
+use std::{
+    collections::HashMap,
+    mem::take,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+static SESSIONS: Lazy<Mutex<HashMap<String, Vec<u8>>>> = Lazy::new(Default::default);
+static CALLS: Lazy<Mutex<Vec<String>>> = Lazy::new(Default::default);
+static NEXT_SID: AtomicUsize = AtomicUsize::new(1);
+
+fn record(call: impl Into<String>) {
+    CALLS.lock().unwrap().push(call.into());
+}
+
+struct TestHandler;
+
+impl SessionHandler for TestHandler {
+    fn open(&self, _save_path: &str, session_name: &str) -> phper::Result<()> {
+        record(format!("open:{}", session_name));
+        Ok(())
+    }
+
+    fn close(&self) -> phper::Result<()> {
+        record("close");
+        Ok(())
+    }
+
+    fn read(&self, id: &str) -> phper::Result<Vec<u8>> {
+        record(format!("read:{}", id));
+        if id == "unreadable" {
+            return Err(phper::Error::runtime("session unreadable"));
+        }
+        Ok(SESSIONS
+            .lock()
+            .unwrap()
+            .get(id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn write(&self, id: &str, data: &[u8], _max_lifetime: i64) -> phper::Result<()> {
+        record(format!("write:{}", id));
+        SESSIONS
+            .lock()
+            .unwrap()
+            .insert(id.to_owned(), data.to_owned());
+        Ok(())
+    }
+
+    fn destroy(&self, id: &str) -> phper::Result<()> {
+        record(format!("destroy:{}", id));
+        SESSIONS.lock().unwrap().remove(id);
+        Ok(())
+    }
+
+    fn create_sid(&self) -> Option<String> {
+        let sid = format!("phpertest{}", NEXT_SID.fetch_add(1, Ordering::SeqCst));
+        record(format!("create_sid:{}", sid));
+        Some(sid)
+    }
+
+    fn update_timestamp(&self, id: &str, _data: &[u8], _max_lifetime: i64) -> phper::Result<()> {
+        record(format!("update_timestamp:{}", id));
+        Ok(())
+    }
+}
+
+pub fn integrate(module: &mut Module) {
+    module.add_session_handler("phpertest", TestHandler);
+
+    module
+        .add_function(
+            "integrate_sessions_store",
+            |arguments: &mut [ZVal]| -> phper::Result<()> {
+                let id = arguments[0].expect_str()?.to_owned();
+                let data = arguments[1].expect_bytes()?.to_owned();
+                SESSIONS.lock().unwrap().insert(id, data);
+                Ok(())
+            },
+        )
+        .argument(Argument::by_val("id"))
+        .argument(Argument::by_val("data"));
+
+    module
+        .add_function(
+            "integrate_sessions_stored",
+            |arguments: &mut [ZVal]| -> phper::Result<Option<String>> {
+                let id = arguments[0].expect_str()?;
+                let sessions = SESSIONS.lock().unwrap();
+                Ok(sessions
+                    .get(id)
+                    .map(|data| String::from_utf8_lossy(data).into_owned()))
+            },
+        )
+        .argument(Argument::by_val("id"));
+
+    module.add_function(
+        "integrate_sessions_take_calls",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut arr = ZArray::new();
+            for call in take(&mut *CALLS.lock().unwrap()) {
+                arr.insert(InsertKey::NextIndex, call.as_str());
+            }
+            Ok(arr)
+        },
+    );
+}
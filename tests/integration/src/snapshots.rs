@@ -0,0 +1,61 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+//! The api surface checked against the golden file `tests/api.txt`, only
+//! declared with the signatures rendered the same on all PHP versions.
+
+use phper::{
+    classes::{ClassEntity, Visibility},
+    functions::Argument,
+    modules::Module,
+    values::ZVal,
+};
+use std::convert::Infallible;
+
+pub fn integrate(module: &mut Module) {
+    module
+        .add_function(
+            "IntegrationTest\\Snapshot\\echo_value",
+            |arguments: &mut [ZVal]| -> Result<ZVal, Infallible> { Ok(arguments[0].clone()) },
+        )
+        .argument(Argument::by_val("value"));
+
+    module
+        .add_function(
+            "IntegrationTest\\Snapshot\\swap",
+            |arguments: &mut [ZVal]| -> phper::Result<()> {
+                let left = arguments[0].expect_z_ref()?.val().clone();
+                let right = arguments[1].expect_z_ref()?.val().clone();
+                *arguments[0].expect_mut_z_ref()?.val_mut() = right;
+                *arguments[1].expect_mut_z_ref()?.val_mut() = left;
+                Ok(())
+            },
+        )
+        .arguments([Argument::by_ref("left"), Argument::by_ref("right")]);
+
+    let mut class = ClassEntity::new("IntegrationTest\\Snapshot\\Point");
+    class.add_constant("ORIGIN", 0i64);
+    class.add_property("x", Visibility::Public, 0);
+    class.add_property("y", Visibility::Public, 0);
+    class
+        .add_method("__construct", Visibility::Public, |this, arguments| {
+            this.set_property("x", arguments[0].clone());
+            this.set_property("y", arguments[1].clone());
+            Ok::<_, Infallible>(())
+        })
+        .arguments([Argument::by_val("x"), Argument::by_val("y")]);
+    class.add_method("length", Visibility::Public, |this, _| {
+        let x = this.get_property("x").expect_long()?;
+        let y = this.get_property("y").expect_long()?;
+        Ok::<_, phper::Error>(((x * x + y * y) as f64).sqrt())
+    });
+    class.add_static_method("origin", Visibility::Public, |_| Ok::<_, Infallible>(()));
+    module.add_class(class);
+}
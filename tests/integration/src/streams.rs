@@ -0,0 +1,50 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{
+    functions::Argument,
+    modules::Module,
+    streams::{self, Stream},
+    values::ZVal,
+};
+use std::io::{Read, Seek, SeekFrom, Write};
+
+pub fn integrate(module: &mut Module) {
+    module
+        .add_function(
+            "integrate_streams_roundtrip",
+            |arguments: &mut [ZVal]| -> phper::Result<String> {
+                let path = arguments[0].expect_str()?;
+                streams::write(path, "hello\n")?;
+
+                let mut stream = Stream::open(path, "ab")?;
+                stream.write_all(b"world")?;
+                drop(stream);
+
+                assert_eq!(streams::read_to_string(path)?, "hello\nworld");
+
+                let mut stream = Stream::open(path, "rb")?;
+                assert_eq!(stream.seek(SeekFrom::Start(6))?, 6);
+                let mut tail = String::new();
+                stream.read_to_string(&mut tail)?;
+                Ok(tail)
+            },
+        )
+        .argument(Argument::by_val("path"));
+
+    module
+        .add_function(
+            "integrate_streams_read",
+            |arguments: &mut [ZVal]| -> phper::Result<Vec<u8>> {
+                streams::read(arguments[0].expect_str()?)
+            },
+        )
+        .argument(Argument::by_val("path"));
+}
@@ -0,0 +1,53 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{
+    functions::Argument,
+    modules::Module,
+    strings::ZString,
+    values::{ExecuteData, ZVal},
+};
+
+pub fn integrate(module: &mut Module) {
+    module.add_function(
+        "integrate_strict_types_caller",
+        |_: &mut [ZVal]| -> phper::Result<bool> {
+            Ok(ExecuteData::current().map_or(false, ExecuteData::caller_uses_strict_types))
+        },
+    );
+
+    module
+        .add_function("integrate_strict_types_long", |arguments: &mut [ZVal]| {
+            arguments[0].coerce_long()
+        })
+        .argument(Argument::by_val("value"));
+
+    module
+        .add_function(
+            "integrate_strict_types_double",
+            |arguments: &mut [ZVal]| arguments[0].coerce_double(),
+        )
+        .argument(Argument::by_val("value"));
+
+    module
+        .add_function("integrate_strict_types_bool", |arguments: &mut [ZVal]| {
+            arguments[0].coerce_bool()
+        })
+        .argument(Argument::by_val("value"));
+
+    module
+        .add_function(
+            "integrate_strict_types_string",
+            |arguments: &mut [ZVal]| -> phper::Result<ZString> {
+                Ok(arguments[0].coerce_z_str()?.to_owned())
+            },
+        )
+        .argument(Argument::by_val("value"));
+}
@@ -8,7 +8,13 @@
 // NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
 // See the Mulan PSL v2 for more details.
 
-use phper::{modules::Module, strings::ZString, values::ZVal};
+use phper::{
+    functions::Argument,
+    modules::Module,
+    strings::{PersistentZString, ZString, ZStringBuilder},
+    values::ZVal,
+};
+use std::{cmp::Ordering, fmt::Write};
 
 pub fn integrate(module: &mut Module) {
     module.add_function(
@@ -22,7 +28,78 @@ pub fn integrate(module: &mut Module) {
 
             assert!(ZString::new("hello") == ZString::new(b"hello"));
 
+            let zs = PersistentZString::new("hello");
+            assert!(zs.is_persistent());
+            assert!(zs.clone().is_persistent());
+            assert!(zs.clone().into_inner().clone().is_persistent());
+            assert!(!ZString::new("hello").is_persistent());
+
+            let zs = ZString::interned("hello");
+            assert!(zs.is_interned());
+            assert_eq!(zs.clone().as_ptr(), zs.as_ptr());
+            assert_eq!(ZString::interned("hello").as_ptr(), zs.as_ptr());
+
+            Ok(())
+        },
+    );
+
+    module.add_function(
+        "integrate_strings_zend_string_cmp",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            let zs = ZString::new("Hello");
+            assert!(zs.equals(&ZString::new("Hello")));
+            assert!(!zs.equals(&ZString::new("hello")));
+            assert!(ZString::interned("hello").equals(&ZString::interned("hello")));
+            assert!(zs.equals_ignore_case("hELLO"));
+            assert!(!zs.equals_ignore_case("hELLO!"));
+
+            assert_eq!(zs.binary_cmp("Hello"), Ordering::Equal);
+            assert_eq!(zs.binary_cmp("Hello!"), Ordering::Less);
+            assert_eq!(zs.binary_cmp("Hell"), Ordering::Greater);
+            assert_eq!(zs.binary_cmp("hello"), Ordering::Less);
+            assert_eq!(ZString::new(b"a\0b").binary_cmp(b"a\0c"), Ordering::Less);
+
+            assert_eq!(zs.binary_case_cmp("hello"), Ordering::Equal);
+            assert_eq!(zs.binary_case_cmp("HELLO!"), Ordering::Less);
+            assert_eq!(zs.binary_case_cmp("help"), Ordering::Less);
+            assert_eq!(zs.binary_case_cmp_locale("hELLo"), Ordering::Equal);
+
+            Ok(())
+        },
+    );
+
+    module.add_function(
+        "integrate_strings_zend_string_builder",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            let builder = ZStringBuilder::new();
+            assert!(builder.is_empty());
+            assert_eq!(builder.finish().to_str()?, "");
+
+            let mut builder = ZStringBuilder::with_capacity(1024);
+            assert!(builder.capacity() >= 1024);
+            builder.append("foo");
+            write!(builder, "-{}-", 42).unwrap();
+            builder.append(b"\0bar");
+            assert_eq!(builder.len(), 11);
+            assert_eq!(ZString::from(builder).as_ref(), b"foo-42-\0bar");
+
             Ok(())
         },
     );
+
+    module
+        .add_function(
+            "integrate_strings_zend_string_builder_join",
+            |arguments: &mut [ZVal]| -> phper::Result<ZStringBuilder> {
+                let mut builder = ZStringBuilder::new();
+                for (i, (_, value)) in arguments[0].expect_z_arr()?.iter().enumerate() {
+                    if i > 0 {
+                        builder.append(",");
+                    }
+                    builder.append(value.expect_z_str()?.to_bytes());
+                }
+                Ok(builder)
+            },
+        )
+        .argument(Argument::by_val("items"));
 }
@@ -0,0 +1,34 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{arrays::ZArray, modules::Module, symbols, values::ZVal};
+
+pub fn integrate(module: &mut Module) {
+    module.enable_symbol_tables();
+
+    module.add_function(
+        "integrate_symbols_local_variables",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut arr = ZArray::new();
+            for (name, val) in symbols::local_variables()? {
+                arr.insert(name.as_str(), val);
+            }
+            Ok(arr)
+        },
+    );
+
+    module.add_function(
+        "integrate_symbols_calling_scope_exists",
+        |arguments: &mut [ZVal]| -> phper::Result<bool> {
+            let name = arguments[0].expect_str()?;
+            Ok(symbols::calling_scope()?.map_or(false, |table| table.exists(name)))
+        },
+    );
+}
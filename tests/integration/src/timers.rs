@@ -0,0 +1,131 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{
+    arrays::{InsertKey, ZArray},
+    functions::Argument,
+    modules::Module,
+    timers,
+    values::ZVal,
+};
+use std::{
+    mem,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+static REQUEST_TIMINGS_COUNT: AtomicUsize = AtomicUsize::new(0);
+static REQUEST_TIMINGS_CONSISTENT: AtomicBool = AtomicBool::new(true);
+
+pub fn integrate(module: &mut Module) {
+    module.on_request_timings(|timings| {
+        REQUEST_TIMINGS_COUNT.fetch_add(1, Ordering::SeqCst);
+        let consistent = timings.stopwatch().started_at().is_some()
+            && timings.spans().iter().all(|span| {
+                span.end() <= timings.duration()
+                    && span.is_finalized() == span.name().starts_with("open:")
+            });
+        if !consistent {
+            REQUEST_TIMINGS_CONSISTENT.store(false, Ordering::SeqCst);
+        }
+    });
+
+    module
+        .add_function(
+            "integrate_timers_span",
+            |arguments: &mut [ZVal]| -> phper::Result<Option<f64>> {
+                let name = arguments[0].expect_str()?.to_owned();
+                let micros = arguments[1].expect_long()?;
+                let span = timers::span(name);
+                thread::sleep(Duration::from_micros(micros as u64));
+                Ok(span.finish().map(|duration| duration.as_secs_f64()))
+            },
+        )
+        .argument(Argument::by_val("name"))
+        .argument(Argument::by_val("micros"));
+
+    module.add_function(
+        "integrate_timers_nested",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            timers::time("outer", || {
+                timers::time("inner", || thread::sleep(Duration::from_millis(1)));
+            });
+            Ok(())
+        },
+    );
+
+    module
+        .add_function(
+            "integrate_timers_open",
+            |arguments: &mut [ZVal]| -> phper::Result<()> {
+                let name = arguments[0].expect_str()?;
+                mem::forget(timers::span(format!("open:{}", name)));
+                Ok(())
+            },
+        )
+        .argument(Argument::by_val("name"));
+
+    module.add_function(
+        "integrate_timers_spans",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut arr = ZArray::new();
+            for span in timers::spans() {
+                let mut item = ZArray::new();
+                item.insert("name", span.name());
+                item.insert("depth", span.depth() as i64);
+                item.insert("finalized", span.is_finalized());
+                item.insert("duration", span.duration().as_secs_f64());
+                item.insert("ordered", span.start() <= span.end());
+                arr.insert(InsertKey::NextIndex, item);
+            }
+            Ok(arr)
+        },
+    );
+
+    module.add_function(
+        "integrate_timers_request",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut arr = ZArray::new();
+            if let Some(stopwatch) = timers::request_stopwatch() {
+                let now = SystemTime::now();
+                let wall_time = stopwatch.wall_time_of(stopwatch.started());
+                arr.insert("elapsed", stopwatch.elapsed().as_secs_f64());
+                arr.insert(
+                    "anchored",
+                    wall_time.is_some() && wall_time == stopwatch.started_at(),
+                );
+                arr.insert(
+                    "before_now",
+                    stopwatch
+                        .started_at()
+                        .map_or(false, |started| started <= now),
+                );
+            }
+            Ok(arr)
+        },
+    );
+
+    module.add_function(
+        "integrate_timers_report",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut arr = ZArray::new();
+            arr.insert(
+                "requests",
+                REQUEST_TIMINGS_COUNT.load(Ordering::SeqCst) as i64,
+            );
+            arr.insert(
+                "consistent",
+                REQUEST_TIMINGS_CONSISTENT.load(Ordering::SeqCst),
+            );
+            Ok(arr)
+        },
+    );
+}
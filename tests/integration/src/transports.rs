@@ -0,0 +1,62 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{
+    modules::Module,
+    transports::{StreamTransport, TransportStream},
+};
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write},
+    time::Duration,
+};
+
+/// Echo the written bytes back, until all are read.
+#[derive(Default)]
+struct EchoStream(VecDeque<u8>);
+
+impl Read for EchoStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Write for EchoStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.extend(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct EchoTransport;
+
+impl StreamTransport for EchoTransport {
+    fn connect(
+        &self, target: &str, _timeout: Option<Duration>,
+    ) -> io::Result<Box<dyn TransportStream>> {
+        if target == "refused" {
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                "connection refused by echo",
+            ));
+        }
+        let mut stream = EchoStream::default();
+        writeln!(stream, "hello {}", target)?;
+        Ok(Box::new(stream))
+    }
+}
+
+pub fn integrate(module: &mut Module) {
+    module.add_stream_transport("integration-echo", EchoTransport);
+}
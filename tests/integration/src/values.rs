@@ -10,16 +10,20 @@
 
 use phper::{
     alloc::{ebox, EBox},
-    arrays::{InsertKey, ZArray},
+    arrays::{InsertKey, ZArr, ZArray},
+    functions::Argument,
     modules::Module,
     objects::ZObject,
-    values::ZVal,
+    values::{ExecuteData, ZVal},
 };
 use std::convert::Infallible;
 
 pub fn integrate(module: &mut Module) {
     integrate_returns(module);
     integrate_as(module);
+    integrate_copies(module);
+    integrate_borrows(module);
+    integrate_undef(module);
 }
 
 fn integrate_returns(module: &mut Module) {
@@ -195,3 +199,137 @@ fn integrate_as(_module: &mut Module) {
         assert_eq!(val.as_double(), Some(200.));
     }
 }
+
+fn integrate_copies(module: &mut Module) {
+    module.add_function(
+        "integration_values_copies",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            assert_eq!(ZVal::from(1).refcount(), None);
+
+            let mut arr = ZArray::new();
+            arr.insert(InsertKey::NextIndex, ZVal::from(1));
+            let mut val = ZVal::from(arr);
+            assert_eq!(val.refcount(), Some(1));
+
+            let mut shallow = val.shallow_clone();
+            assert_eq!(val.refcount(), Some(2));
+
+            shallow.separate();
+            assert_eq!(val.refcount(), Some(1));
+            assert_eq!(shallow.refcount(), Some(1));
+
+            shallow
+                .expect_mut_z_arr()?
+                .insert(InsertKey::NextIndex, ZVal::from(2));
+            assert_eq!(shallow.expect_mut_z_arr()?.len(), 2);
+            assert_eq!(val.expect_mut_z_arr()?.len(), 1);
+
+            let mut dup = val.duplicate();
+            assert_eq!(val.refcount(), Some(1));
+            assert_eq!(dup.refcount(), Some(1));
+            dup.expect_mut_z_arr()?
+                .insert(InsertKey::NextIndex, ZVal::from(3));
+            assert_eq!(val.expect_mut_z_arr()?.len(), 1);
+
+            let obj = ZVal::from(ZObject::new_by_std_class());
+            let obj_dup = obj.duplicate();
+            assert_eq!(obj.refcount(), Some(2));
+            drop(obj_dup);
+
+            Ok(())
+        },
+    );
+
+    module.add_function(
+        "integration_values_dump",
+        |arguments: &mut [ZVal]| -> phper::Result<String> {
+            assert_eq!(format!("{:#?}", arguments[0]), arguments[0].dump());
+            Ok(arguments[0].dump())
+        },
+    );
+}
+
+fn integrate_borrows(module: &mut Module) {
+    module.add_function(
+        "integration_values_borrow_bytes",
+        |arguments: &mut [ZVal]| -> phper::Result<i64> {
+            let bytes = arguments[0].expect_bytes()?;
+            assert_eq!(
+                bytes.as_ptr(),
+                arguments[0].expect_z_str()?.to_bytes().as_ptr()
+            );
+            assert_eq!(arguments[0].expect_owned_bytes()?, bytes);
+            Ok(bytes.len() as i64)
+        },
+    );
+
+    module.add_function(
+        "integration_values_borrow_str",
+        |arguments: &mut [ZVal]| -> phper::Result<String> {
+            let s = arguments[0].expect_str()?;
+            assert_eq!(arguments[0].expect_owned_string()?, s);
+            Ok(s.to_uppercase())
+        },
+    );
+
+    module.add_function(
+        "integration_values_pass_through",
+        |arguments: &mut [ZVal]| -> phper::Result<ZVal> {
+            arguments[0].expect_bytes()?;
+            Ok(arguments[0].clone())
+        },
+    );
+}
+
+fn integrate_undef(module: &mut Module) {
+    module
+        .add_function(
+            "integration_values_defined_parameters",
+            |_: &mut [ZVal]| -> phper::Result<ZArray> {
+                let ex = ExecuteData::current().unwrap();
+                let mut arr = ZArray::new();
+                for i in 0..2 {
+                    arr.insert(InsertKey::NextIndex, ex.try_get_parameter(i).is_some());
+                }
+                Ok(arr)
+            },
+        )
+        .argument(Argument::by_val("a"))
+        .argument(Argument::by_val_optional("b"));
+
+    module.add_function(
+        "integration_values_as_option",
+        |arguments: &mut [ZVal]| -> phper::Result<ZVal> {
+            assert!(!arguments[0].is_undef());
+            assert!(arguments[0].as_defined().is_some());
+            let value = arguments[0].as_option().cloned();
+            assert_eq!(value.is_none(), arguments[0].is_null());
+            Ok(value.unwrap_or_else(|| ZVal::from("none")))
+        },
+    );
+
+    module.add_function(
+        "integration_values_array_keys",
+        |arguments: &mut [ZVal]| -> phper::Result<ZArray> {
+            let arr: &ZArr = arguments[0].expect_z_arr()?;
+            let mut keys = ZArray::new();
+            for (key, val) in arr.iter() {
+                assert!(!val.is_undef());
+                keys.insert(key, true);
+            }
+            Ok(keys)
+        },
+    );
+
+    module.add_function(
+        "integration_values_array_defined",
+        |arguments: &mut [ZVal]| -> phper::Result<ZArray> {
+            let name = arguments[1].expect_z_str()?.to_str()?.to_owned();
+            let arr = arguments[0].expect_z_arr()?;
+            let mut result = ZArray::new();
+            result.insert(InsertKey::NextIndex, arr.exists(&*name));
+            result.insert(InsertKey::NextIndex, arr.get(&*name).is_some());
+            Ok(result)
+        },
+    );
+}
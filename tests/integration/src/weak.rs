@@ -0,0 +1,60 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{modules::Module, objects::ZObject, values::ZVal, weak::WeakReference};
+
+pub fn integrate(module: &mut Module) {
+    module.add_function(
+        "integrate_weak_reference",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            let mut obj = ZObject::new_by_std_class();
+            let mut reference = WeakReference::new(&mut obj)?;
+
+            let got = reference.get()?.unwrap();
+            assert_eq!(got.handle(), obj.handle());
+            drop(got);
+
+            drop(obj);
+            assert!(reference.get()?.is_none());
+            assert!(!reference.is_alive()?);
+
+            Ok(())
+        },
+    );
+
+    #[cfg(phper_major_version = "8")]
+    module.add_function(
+        "integrate_weak_map",
+        |_: &mut [ZVal]| -> phper::Result<()> {
+            use phper::weak::WeakMap;
+
+            let mut map = WeakMap::new()?;
+            let mut foo = ZObject::new_by_std_class();
+            let mut bar = ZObject::new_by_std_class();
+
+            map.insert(&mut foo, ZVal::from("FOO"))?;
+            map.insert(&mut bar, ZVal::from("BAR"))?;
+            assert_eq!(map.len()?, 2);
+            assert_eq!(
+                map.get(&mut foo)?.unwrap().expect_z_str()?.to_str(),
+                Ok("FOO")
+            );
+
+            map.remove(&mut foo)?;
+            assert!(!map.contains_key(&mut foo)?);
+            assert!(map.get(&mut foo)?.is_none());
+
+            drop(bar);
+            assert!(map.is_empty()?);
+
+            Ok(())
+        },
+    );
+}
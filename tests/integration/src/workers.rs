@@ -0,0 +1,89 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{functions::Argument, modules::Module, values::ZVal};
+use std::{collections::HashSet, thread};
+
+pub fn integrate(module: &mut Module) {
+    let pools = (1..=8)
+        .map(|threads| module.add_worker_pool(threads))
+        .collect::<Vec<_>>();
+
+    module
+        .add_function(
+            "integrate_workers_map_squares",
+            move |arguments: &mut [ZVal]| {
+                let threads = arguments[1].expect_long()?;
+                pools[threads as usize - 1].map_z_arr(
+                    arguments[0].expect_z_arr()?,
+                    |value| value.expect_long(),
+                    |n| n * n,
+                )
+            },
+        )
+        .arguments([Argument::by_val("items"), Argument::by_val("threads")]);
+
+    let pool = module.add_worker_pool(4);
+    module
+        .add_function(
+            "integrate_workers_count_primes",
+            move |arguments: &mut [ZVal]| -> phper::Result<i64> {
+                let limit = arguments[0].expect_long()?;
+                let counts = pool.map((2..limit).collect(), |n| {
+                    (2..n).take_while(|i| i * i <= n).all(|i| n % i != 0) as i64
+                })?;
+                Ok(counts.into_iter().sum())
+            },
+        )
+        .argument(Argument::by_val("limit"));
+
+    let pool = module.add_worker_pool(2);
+    module.add_function(
+        "integrate_workers_reused",
+        move |_: &mut [ZVal]| -> phper::Result<bool> {
+            let caller = thread::current().id();
+            let mut ids = HashSet::new();
+            for _ in 0..10 {
+                ids.extend(pool.map(vec![(); 4], |_| thread::current().id())?);
+            }
+            Ok(ids.len() <= 2 && !ids.contains(&caller))
+        },
+    );
+
+    let pool = module.add_worker_pool(2);
+    module.add_function(
+        "integrate_workers_nested",
+        move |_: &mut [ZVal]| -> phper::Result<i64> {
+            let inner = pool.clone();
+            let sums = pool.map(vec![vec![1, 2], vec![3, 4]], move |items: Vec<i64>| {
+                inner
+                    .map(items, |n| n * 10)
+                    .unwrap()
+                    .into_iter()
+                    .sum::<i64>()
+            })?;
+            Ok(sums.into_iter().sum())
+        },
+    );
+
+    let pool = module.add_worker_pool(2);
+    module.add_function(
+        "integrate_workers_panic",
+        move |_: &mut [ZVal]| -> phper::Result<()> {
+            pool.map(vec![1, 2, 3, 4], |n: i64| {
+                if n == 3 {
+                    panic!("panic in worker");
+                }
+                n
+            })?;
+            Ok(())
+        },
+    );
+}
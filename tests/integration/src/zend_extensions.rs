@@ -0,0 +1,55 @@
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+use phper::{arrays::ZArray, modules::Module, values::ZVal};
+use std::cell::{Cell, RefCell};
+
+thread_local! {
+    static STARTED: Cell<bool> = Cell::new(false);
+    static ACTIVATED: Cell<bool> = Cell::new(false);
+    static COMPILED_FUNCTIONS: RefCell<Vec<String>> = RefCell::new(Vec::new());
+}
+
+pub fn integrate(module: &mut Module) {
+    module
+        .zend_extension()
+        .on_startup(|| STARTED.with(|started| started.set(true)))
+        .on_activate(|| {
+            ACTIVATED.with(|activated| activated.set(true));
+            COMPILED_FUNCTIONS.with(|functions| functions.borrow_mut().clear());
+        })
+        .on_deactivate(|| ACTIVATED.with(|activated| activated.set(false)))
+        .on_op_array_handler(|op_array| {
+            assert!(!op_array.opcodes().is_empty());
+            if let Some(name) = op_array.function_name() {
+                let name = name.to_str().unwrap().to_owned();
+                COMPILED_FUNCTIONS.with(|functions| functions.borrow_mut().push(name));
+            }
+        });
+
+    module.add_function(
+        "integrate_zend_extension_status",
+        |_: &mut [ZVal]| -> phper::Result<ZArray> {
+            let mut status = ZArray::new();
+            status.insert("started", STARTED.with(Cell::get));
+            status.insert("activated", ACTIVATED.with(Cell::get));
+
+            let mut functions = ZArray::new();
+            COMPILED_FUNCTIONS.with(|compiled| {
+                for name in compiled.borrow().iter() {
+                    functions.insert((), name.as_str());
+                }
+            });
+            status.insert("compiled_functions", functions);
+
+            Ok(status)
+        },
+    );
+}
@@ -8,10 +8,19 @@
 // NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
 // See the Mulan PSL v2 for more details.
 
-use phper_test::{cli::test_php_scripts, fpm, fpm::test_fpm_request, utils::get_lib_path};
+use phper_test::{
+    calls::{call_function, Thrown},
+    cli::{test_php_scripts, test_php_scripts_with_condition, test_php_scripts_with_ini},
+    fpm,
+    fpm::test_fpm_request,
+    snapshots::{assert_api_snapshot, assert_api_snapshot_with_prefix, render_api},
+    utils::get_lib_path,
+};
 use std::{
-    env,
+    env, fs,
+    panic::catch_unwind,
     path::{Path, PathBuf},
+    process::{self, Output},
 };
 
 #[test]
@@ -41,10 +50,176 @@ fn test_cli() {
             &tests_php_dir.join("references.php"),
             &tests_php_dir.join("errors.php"),
             &tests_php_dir.join("reflection.php"),
+            &tests_php_dir.join("autoload.php"),
+            &tests_php_dir.join("alloc.php"),
+            &tests_php_dir.join("weak.php"),
+            &tests_php_dir.join("debug_dump.php"),
+            &tests_php_dir.join("zend_extensions.php"),
+            &tests_php_dir.join("opcodes.php"),
+            &tests_php_dir.join("compile_hooks.php"),
+            &tests_php_dir.join("modules.php"),
+            &tests_php_dir.join("flushers.php"),
+            &tests_php_dir.join("loggers.php"),
+            &tests_php_dir.join("json.php"),
+            &tests_php_dir.join("serialize.php"),
+            &tests_php_dir.join("random.php"),
+            &tests_php_dir.join("iterators.php"),
+            &tests_php_dir.join("collections.php"),
+            &tests_php_dir.join("stats.php"),
+            &tests_php_dir.join("curl.php"),
+            &tests_php_dir.join("transports.php"),
+            &tests_php_dir.join("filters.php"),
+            &tests_php_dir.join("workers.php"),
+            &tests_php_dir.join("requests.php"),
+            &tests_php_dir.join("env.php"),
+            &tests_php_dir.join("responses.php"),
+            &tests_php_dir.join("uploads.php"),
+            &tests_php_dir.join("post_readers.php"),
+            &tests_php_dir.join("contexts.php"),
+            &tests_php_dir.join("execute_data.php"),
+            &tests_php_dir.join("strict_types.php"),
+            &tests_php_dir.join("weak_types.php"),
+            &tests_php_dir.join("metrics.php"),
+            &tests_php_dir.join("pools.php"),
+            &tests_php_dir.join("streams.php"),
+            &tests_php_dir.join("paths.php"),
+            &tests_php_dir.join("processes.php"),
+            &tests_php_dir.join("symbols.php"),
+            &tests_php_dir.join("timers.php"),
+            &tests_php_dir.join("pdo.php"),
+            &tests_php_dir.join("sessions.php"),
+            &tests_php_dir.join("enums.php"),
         ],
     );
 }
 
+#[test]
+fn test_calls() {
+    let lib_path = get_lib_path(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("..")
+            .join("target"),
+        "integration",
+    );
+
+    assert_eq!(
+        call_function(&lib_path, "integration_values_borrow_str", &["'foo'"]),
+        Ok("'FOO'".to_owned())
+    );
+    assert_eq!(
+        call_function(&lib_path, "integration_values_defined_parameters", &["1"]),
+        Ok("array (\n  0 => true,\n  1 => false,\n)".to_owned())
+    );
+    assert_eq!(
+        call_function(&lib_path, "integration_values_borrow_bytes", &["1"]),
+        Err(Thrown {
+            class: "TypeError".to_owned(),
+            code: 0,
+            message: "type error: must be of type string, int given".to_owned(),
+        })
+    );
+}
+
+#[test]
+fn test_api_snapshot() {
+    let lib_path = get_lib_path(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("..")
+            .join("target"),
+        "integration",
+    );
+
+    let api = render_api(&lib_path, "integration");
+    assert_eq!(api, render_api(&lib_path, "integration"));
+    assert!(api
+        .lines()
+        .any(|line| line == "const INTEGRATE_CONST_TRUE = true"));
+    assert!(api
+        .lines()
+        .any(|line| line == "function integration_values_defined_parameters($a, $b = ?)"));
+
+    let golden = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("api.txt");
+    assert_api_snapshot_with_prefix(
+        &lib_path,
+        "integration",
+        "IntegrationTest\\Snapshot\\",
+        &golden,
+    );
+
+    // The missing golden file fails rather than being created.
+    let path = env::temp_dir().join(format!("phper-api-snapshot-{}.txt", process::id()));
+    let missing = catch_unwind(|| assert_api_snapshot(&lib_path, "integration", &path));
+    assert!(missing.is_err());
+    assert!(!path.exists());
+
+    fs::write(
+        &path,
+        api.replacen("INTEGRATE_CONST_TRUE", "INTEGRATE_CONST_RENAMED", 1),
+    )
+    .unwrap();
+    let mismatched = catch_unwind(|| assert_api_snapshot(&lib_path, "integration", &path));
+    fs::remove_file(&path).unwrap();
+    assert!(mismatched.is_err());
+}
+
+#[test]
+fn test_crash_handler() {
+    let tests_php_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("php");
+
+    let condition = |output: Output| {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        !output.status.success()
+            && stderr.contains("phper caught signal")
+            && stderr.contains("#0 integrate_crashes_abort\n")
+            && stderr.contains("#1 integrate_crashes_caller at ")
+            && stderr.contains("crashes.php:")
+            && stderr.contains("Native stack trace:\n")
+            && stderr.contains("integration::crashes::integrate::{{closure}}")
+    };
+    test_php_scripts_with_condition(
+        get_lib_path(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("..")
+                .join("..")
+                .join("target"),
+            "integration",
+        ),
+        &[(&tests_php_dir.join("crashes.php"), &condition)],
+    );
+}
+
+#[test]
+fn test_cli_opcache() {
+    let tests_php_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("php");
+    let preload = tests_php_dir.join("opcache_preload.php");
+
+    test_php_scripts_with_ini(
+        get_lib_path(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("..")
+                .join("..")
+                .join("target"),
+            "integration",
+        ),
+        &[
+            ("zend_extension", "opcache.so"),
+            ("opcache.enable_cli", "1"),
+            // Ignored before PHP 7.4.
+            ("opcache.preload", preload.to_str().unwrap()),
+            ("opcache.preload_user", "root"),
+        ],
+        &[&tests_php_dir.join("opcache.php")],
+    );
+}
+
 #[test]
 fn test_fpm() {
     let tests_php_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
@@ -68,4 +243,71 @@ fn test_fpm() {
     test_fpm_request("GET", &tests_php_dir, "/values.php", None, None);
     test_fpm_request("GET", &tests_php_dir, "/constants.php", None, None);
     test_fpm_request("GET", &tests_php_dir, "/ini.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/autoload.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/alloc.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/weak.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/debug_dump.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/zend_extensions.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/opcodes.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/compile_hooks.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/modules.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/flushers.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/loggers.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/json.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/serialize.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/random.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/iterators.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/collections.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/stats.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/curl.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/transports.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/filters.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/workers.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/requests.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/env.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/responses.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/uploads.php", None, None);
+    test_fpm_request(
+        "POST",
+        &tests_php_dir,
+        "/uploads.php",
+        Some("multipart/form-data; boundary=phper".to_owned()),
+        Some(
+            concat!(
+                "--phper\r\n",
+                "Content-Disposition: form-data; name=\"single\"; filename=\"a.txt\"\r\n",
+                "Content-Type: text/plain\r\n\r\n",
+                "foo\r\n",
+                "--phper\r\n",
+                "Content-Disposition: form-data; name=\"multi[]\"; filename=\"b.txt\"\r\n",
+                "Content-Type: text/plain\r\n\r\n",
+                "barbar\r\n",
+                "--phper--\r\n",
+            )
+            .as_bytes()
+            .to_vec(),
+        ),
+    );
+    test_fpm_request("GET", &tests_php_dir, "/post_readers.php", None, None);
+    test_fpm_request(
+        "POST",
+        &tests_php_dir,
+        "/post_readers.php",
+        Some("application/x-phper-kv; charset=utf-8".to_owned()),
+        Some(b"foo=1\nbar=baz\n".to_vec()),
+    );
+    test_fpm_request("GET", &tests_php_dir, "/contexts.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/execute_data.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/strict_types.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/weak_types.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/metrics.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/pools.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/streams.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/paths.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/processes.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/symbols.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/timers.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/pdo.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/sessions.php", None, None);
+    test_fpm_request("GET", &tests_php_dir, "/enums.php", None, None);
 }
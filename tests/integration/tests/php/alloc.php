@@ -0,0 +1,35 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+integrate_alloc_evec();
+assert_eq(integrate_alloc_estring(), "hello, world");
+assert_true(integrate_alloc_memory_usage());
+assert_eq(integrate_alloc_scoped(), "hello, world");
+if (integrate_alloc_debug_enabled()) {
+    integrate_alloc_debug_tracking();
+} else {
+    assert_false(function_exists("integrate_alloc_debug_tracking"));
+}
+integrate_alloc_global_usage();
+
+$memory_limit = ini_get("memory_limit");
+ini_set("memory_limit", "64M");
+assert_throw(function () {
+    integrate_alloc_global_exhausted(128 * 1024 * 1024);
+}, "Error", 0, "Allowed memory size of 67108864 bytes exhausted by the Rust allocations");
+ini_set("memory_limit", $memory_limit);
+
+assert_true(integrate_alloc_outside_request());
+assert_true(integrate_alloc_unchecked_outside_request());
@@ -47,3 +47,58 @@ assert_throw(function () { integrate_arguments_optional(); }, $argumentCountErro
 assert_eq(integrate_arguments_optional("foo"), "foo: false");
 assert_eq(integrate_arguments_optional("foo", true), "foo: true");
 assert_eq(integrate_arguments_optional("foo", true, "bar"), "foo: true");
+
+assert_eq(INTEGRATION_TEST_OPEN_CREATE, 4);
+assert_eq(integrate_arguments_flags(0), "");
+assert_eq(integrate_arguments_flags(INTEGRATION_TEST_OPEN_READ), "r");
+assert_eq(integrate_arguments_flags(INTEGRATION_TEST_OPEN_READ | INTEGRATION_TEST_OPEN_WRITE), "rw");
+assert_eq(integrate_arguments_flags(INTEGRATION_TEST_OPEN_WRITE | INTEGRATION_TEST_OPEN_CREATE), "w+");
+$valueErrorName = PHP_VERSION_ID >= 80000 ? "ValueError" : "Exception";
+assert_throw(function () { integrate_arguments_flags(INTEGRATION_TEST_OPEN_READ | 8); }, $valueErrorName, 0, "Unknown flags: 0x8");
+assert_throw(function () { integrate_arguments_flags("1"); }, "TypeError", 0, "type error: must be of type int, string given");
+
+// Test typed arguments and return types.
+function integrate_arguments_type_string($type) {
+    if (PHP_VERSION_ID >= 80000) {
+        return (string) $type;
+    }
+    $name = PHP_VERSION_ID >= 70100 ? $type->getName() : (string) $type;
+    return ($type->allowsNull() ? "?" : "") . $name;
+}
+
+$typed = new ReflectionFunction("integrate_arguments_typed");
+$params = $typed->getParameters();
+assert_eq(integrate_arguments_type_string($params[0]->getType()), "int");
+assert_eq(integrate_arguments_type_string($params[1]->getType()), "?string");
+assert_eq(integrate_arguments_type_string($params[2]->getType()), "?ArrayAccess");
+assert_true($params[2]->isOptional());
+assert_eq(integrate_arguments_type_string($typed->getReturnType()), "?int");
+assert_eq(integrate_arguments_typed(1, null), 1);
+assert_eq(integrate_arguments_typed(0, "foo", new ArrayObject()), null);
+
+if (PHP_VERSION_ID >= 70100) {
+    $void = new ReflectionFunction("integrate_arguments_return_void");
+    assert_eq(integrate_arguments_type_string($void->getReturnType()), "void");
+    assert_eq(integrate_arguments_return_void(), null);
+}
+
+if (PHP_VERSION_ID >= 80000) {
+    assert_throw(function () { integrate_arguments_typed([], null); }, "TypeError", 0, "type error: must be of type int, array given");
+}
+
+if (PHP_VERSION_ID >= 80100) {
+    $union = new ReflectionFunction("integrate_arguments_typed_union");
+    assert_eq((string) $union->getParameters()[0]->getType(), "ArrayAccess|Countable|false");
+    assert_eq((string) $union->getReturnType(), "string|int");
+
+    $never = new ReflectionFunction("integrate_arguments_return_never");
+    assert_eq((string) $never->getReturnType(), "never");
+    assert_throw("integrate_arguments_return_never", "ErrorException", 0, "never returns");
+}
+
+if (PHP_VERSION_ID >= 80200) {
+    assert_eq((string) (new ReflectionFunction("integrate_arguments_return_true"))->getReturnType(), "true");
+    assert_eq((string) (new ReflectionFunction("integrate_arguments_return_null"))->getReturnType(), "null");
+    assert_true(integrate_arguments_return_true());
+    assert_eq(integrate_arguments_return_null(), null);
+}
@@ -18,3 +18,24 @@ integrate_arrays_types();
 integrate_arrays_insert();
 integrate_arrays_exists();
 integrate_arrays_for_each();
+integrate_arrays_persistent();
+integrate_arrays_to_ref_owned();
+integrate_arrays_sort_map_filter_merge();
+
+assert_eq(integrate_arrays_sort_by_callable([3, 1, 2], function ($a, $b) { return $a - $b; }), [1, 2, 3]);
+assert_throw(function () {
+    integrate_arrays_sort_by_callable([3, 1, 2], function () { throw new RuntimeException("sort failed", 500); });
+}, "RuntimeException", 500, "sort failed");
+
+assert_eq(integrate_arrays_sum_i64([]), 0);
+assert_eq(integrate_arrays_sum_i64(range(1, 100000)), 5000050000);
+assert_eq(integrate_arrays_sum_i64(["a" => 1, "b" => 2]), 3);
+$holes = [1, 2, 3, 4];
+unset($holes[1]);
+assert_eq(integrate_arrays_sum_i64($holes), 8);
+$ref = 5;
+assert_eq(integrate_arrays_sum_i64([1, &$ref]), 6);
+assert_eq(integrate_arrays_sum_i64([1, 2.5]), null);
+assert_eq(integrate_arrays_sum_i64([1, "2"]), null);
+assert_eq(integrate_arrays_to_vec_f64([1, 2.5, 3]), [1.0, 2.5, 3.0]);
+assert_eq(integrate_arrays_to_vec_f64([1, "x"]), null);
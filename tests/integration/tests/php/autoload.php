@@ -0,0 +1,38 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+assert_true(class_exists("IntegrationTest\\Autoload\\Foo"));
+$foo = new \IntegrationTest\Autoload\Foo();
+assert_eq($foo->name(), "foo");
+
+// The embedded class source is written in `MINIT` and included when autoloaded.
+$foo_file = (new ReflectionClass("IntegrationTest\\Autoload\\Foo"))->getFileName();
+assert_true(is_file($foo_file));
+
+assert_false(class_exists("IntegrationTest\\Autoload\\Missing"));
+assert_false(class_exists("IntegrationTest\\Autoload\\Bar"));
+
+// The autoloader is registered as closure, not the callable global function.
+assert_false(function_exists("__phper_autoload_integration"));
+$autoloaders = array_filter(spl_autoload_functions(), function ($f) {
+    return $f instanceof Closure;
+});
+assert_eq(count($autoloaders), 1);
+
+assert_eq(integrate_sources_embedded(), "embedded");
+// The embedded source is written in `MINIT` and included like the normal script.
+$embedded_file = (new ReflectionFunction("integrate_sources_embedded"))->getFileName();
+assert_true(is_file($embedded_file));
+assert_eq(substr($embedded_file, -strlen("integrate_sources.php")), "integrate_sources.php");
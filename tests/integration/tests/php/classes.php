@@ -26,6 +26,13 @@ assert_eq($a->speak(), "name: foo, number: 99");
 
 $reflection_class = new ReflectionClass(\IntegrationTest\A::class);
 
+$constructor_params = $reflection_class->getMethod("__construct")->getParameters();
+assert_eq(array_map(function ($p) { return $p->getName(); }, $constructor_params), ["name", "number"]);
+
+class IntegrationTestSubA extends \IntegrationTest\A {}
+$sub_a = new IntegrationTestSubA("bar", 1);
+assert_eq($sub_a->speak(), "name: bar, number: 1");
+
 $property_name = $reflection_class->getProperty("name");
 assert_true($property_name->isPrivate());
 
@@ -69,7 +76,239 @@ $pre_foo1 = IntegrationTest\PropsHolder::setFoo1("baz");
 assert_eq($pre_foo1, 12345);
 assert_eq(IntegrationTest\PropsHolder::getFoo1(), "baz");
 
+// Test typed properties.
+if (PHP_VERSION_ID >= 70400) {
+    $assert_type_error = function ($callable) {
+        try {
+            $callable();
+        } catch (TypeError $e) {
+            return;
+        }
+        throw new AssertionError("TypeError not throws");
+    };
+
+    $typed = new IntegrationTest\TypedProps();
+    assert_eq($typed->id, 0);
+    $typed->id = "12";
+    assert_eq($typed->id, 12);
+    $assert_type_error(function () use ($typed) { $typed->id = "foo"; });
+
+    assert_eq($typed->name, null);
+    $typed->name = "foo";
+    assert_eq($typed->name, "foo");
+    $typed->name = null;
+
+    assert_throw(function () use ($typed) {
+        return $typed->count;
+    }, "Error", 0, "Typed property IntegrationTest\\TypedProps::\$count must not be accessed before initialization");
+    $typed->count = 1;
+    assert_eq($typed->count, 1);
+
+    $typed->obj = new ArrayObject();
+    $assert_type_error(function () use ($typed) { $typed->obj = new stdClass(); });
+
+    assert_eq(IntegrationTest\TypedProps::$flag, true);
+    $assert_type_error(function () { IntegrationTest\TypedProps::$flag = []; });
+
+    if (PHP_VERSION_ID >= 80000) {
+        $typed->value = "foo";
+        assert_eq($typed->value, "foo");
+        $assert_type_error(function () use ($typed) { $typed->value = []; });
+    }
+}
+
+// Test extends the parent class by name.
+$child = new IntegrationTest\ChildObject();
+assert_true($child instanceof IntegrationTest\ParentObject);
+assert_eq($child->hello(), "hello from parent");
+assert_throw(function () {
+    throw new IntegrationTest\MyLogicException("oops", 7);
+}, "IntegrationTest\\MyLogicException", 7, "oops");
+
+// Test traits.
+class IntegrationTestGreeter {
+    use IntegrationTest\Greeting;
+
+    protected function name() {
+        return "world";
+    }
+}
+assert_true((new ReflectionClass(IntegrationTest\Greeting::class))->isTrait());
+assert_eq(class_uses(IntegrationTestGreeter::class), ["IntegrationTest\\Greeting" => "IntegrationTest\\Greeting"]);
+assert_eq((new IntegrationTestGreeter())->greet("hello"), "hello world");
+assert_eq(IntegrationTestGreeter::version(), 1);
+
+// Test magic methods.
+$magic = new IntegrationTest\Magic("p_");
+$magic->foo = "bar";
+assert_eq($magic->foo, "bar");
+assert_eq($magic->not_exists, null);
+assert_eq((string) $magic, "foo,prefix");
+assert_eq($magic->doSomething(1, 2), "p_doSomething:2");
+assert_eq(IntegrationTest\Magic::doStatic(), "static doStatic:0");
+assert_eq($magic(7), "p_7");
+$cloned = clone $magic;
+assert_eq((string) $cloned, "cloned,foo,prefix");
+assert_eq((string) $magic, "foo,prefix");
+if (PHP_VERSION_ID >= 80000) {
+    assert_true($magic instanceof Stringable);
+    $to_string = new ReflectionMethod(IntegrationTest\Magic::class, "__toString");
+    assert_eq((string) $to_string->getReturnType(), "string");
+}
+
+// Test the state built by constructor.
+$connection = new IntegrationTest\Connection("mysql://localhost");
+assert_eq($connection->dsn(), "mysql://localhost");
+assert_throw(function () {
+    new IntegrationTest\Connection("");
+}, "ErrorException", 0, "dsn can't be empty");
+$connection = (new ReflectionClass(IntegrationTest\Connection::class))->newInstanceWithoutConstructor();
+assert_throw(function () use ($connection) {
+    $connection->dsn();
+}, "Error", 0, "Object of class IntegrationTest\\Connection is not initialized");
+
+// Test guard against objects which aren't constructed.
+$handle = new IntegrationTest\Handle(10);
+assert_true($handle->isInitialized());
+assert_eq($handle->value(), 10);
+assert_eq((clone $handle)->value(), 10);
+
+class IntegrationTestFailedHandle extends IntegrationTest\Handle {
+    public function __construct() {
+        try {
+            parent::__construct(-1);
+        } catch (ErrorException $e) {
+        }
+    }
+}
+$handle = new IntegrationTestFailedHandle();
+assert_false($handle->isInitialized());
+assert_throw(function () use ($handle) {
+    $handle->value();
+}, "Error", 0, "Object of class IntegrationTestFailedHandle is not initialized");
+
+class IntegrationTestForgottenHandle extends IntegrationTest\Handle {
+    public function __construct() {}
+}
+assert_false((new IntegrationTestForgottenHandle())->isInitialized());
+
+// Test clone behaviors of the state.
+$state = new IntegrationTest\CloneState();
+assert_eq($state->increment(), 1);
+$cloned = clone $state;
+assert_eq($cloned->increment(), 2);
+assert_eq($state->increment(), 2);
+
+$state = new IntegrationTest\ShareState();
+assert_eq($state->increment(), 1);
+$cloned = clone $state;
+assert_eq($cloned->increment(), 2);
+assert_eq($state->increment(), 3);
+
+assert_throw(function () {
+    clone new IntegrationTest\DenyClone();
+}, "Error", 0, "Trying to clone an uncloneable object of class IntegrationTest\\DenyClone");
+
+// Test state comparators.
+$a = new IntegrationTest\Money(100);
+$b = new IntegrationTest\Money(200);
+assert_true($a == new IntegrationTest\Money(100));
+assert_true($a != $b);
+assert_true($a < $b);
+assert_true($b >= $a);
+assert_eq($a <=> $b, -1);
+assert_eq($b <=> $a, 1);
+
+$a = new IntegrationTest\Uuid("a");
+assert_true($a == new IntegrationTest\Uuid("a"));
+assert_true($a != new IntegrationTest\Uuid("b"));
+assert_false($a < new IntegrationTest\Uuid("b"));
+assert_false($a > new IntegrationTest\Uuid("b"));
+
+// Test invokable objects as callbacks.
+$matcher = new IntegrationTest\UpperMatcher();
+assert_true(is_callable($matcher));
+assert_eq(preg_replace_callback('/[a-z]+/', $matcher, "ab 12 cd"), "AB1 12 CD2");
+assert_eq(Closure::fromCallable($matcher)(["ef"]), "EF3");
+assert_eq(integrate_functions_cached_callable_map($matcher, [["g"], ["h"]]), ["G4", "H5"]);
+
+// Test class constants.
+assert_eq(IntegrationTest\Http::NAME, "http");
+assert_eq(IntegrationTest\Http::VERSION, 1.1);
+assert_eq(IntegrationTest\Http::SECURE, false);
+assert_eq(IntegrationTest\Http::NOTHING, null);
+assert_eq(IntegrationTest\Http::METHODS, ["GET", "POST"]);
+assert_eq(IntegrationTest\Http::PORTS, ["http" => 80, "https" => [443, 8443]]);
+$methods = IntegrationTest\Http::METHODS;
+$methods[] = "PUT";
+assert_eq(count(IntegrationTest\Http::METHODS), 2);
+assert_eq(IntegrationTest\Mode::FAST, 1);
+assert_eq(IntegrationTest\Mode::SAFE, 2);
+assert_eq(INTEGRATION_TEST_MODE_FAST, 1);
+assert_eq(INTEGRATION_TEST_MODE_SAFE, 2);
+
 // Test php class extends from phper registered class.
 class Foo2 extends IntegrationTest\Foo {}
 $foo2 = new Foo2();
 assert_eq($foo2->current(), 'Current: 0');
+
+// Test class modifiers.
+assert_true((new ReflectionClass(IntegrationTest\FinalClass::class))->isFinal());
+assert_true((new ReflectionClass(IntegrationTest\AbstractClass::class))->isAbstract());
+assert_throw(function () {
+    new IntegrationTest\AbstractClass();
+}, "Error", 0, "Cannot instantiate abstract class IntegrationTest\\AbstractClass");
+
+$render = (new ReflectionClass(IntegrationTest\AbstractClass::class))->getMethod("render");
+assert_true($render->isAbstract());
+assert_true($render->isProtected());
+assert_eq($render->getNumberOfParameters(), 1);
+
+class IntegrationTestConcreteClass extends IntegrationTest\AbstractClass {
+    protected function render($name) {
+        return "hello " . $name;
+    }
+}
+assert_eq((new IntegrationTestConcreteClass())->display("world"), "<hello world>");
+
+// Test readonly properties and classes.
+if (PHP_VERSION_ID >= 80100) {
+    $props = new IntegrationTest\ReadonlyProps(7, "tag");
+    assert_eq($props->id, 7);
+    assert_eq($props->tag, "tag");
+    assert_true((new ReflectionProperty(IntegrationTest\ReadonlyProps::class, "id"))->isReadOnly());
+    assert_eq((string) (new ReflectionProperty(IntegrationTest\ReadonlyProps::class, "tag"))->getType(), "mixed");
+    assert_throw(function () use ($props) {
+        $props->id = 8;
+    }, "Error", 0, "Cannot modify readonly property IntegrationTest\\ReadonlyProps::\$id");
+    assert_throw(function () {
+        (new ReflectionClass(IntegrationTest\ReadonlyProps::class))->newInstanceWithoutConstructor()->id;
+    }, "Error", 0, "Typed property IntegrationTest\\ReadonlyProps::\$id must not be accessed before initialization");
+}
+
+if (PHP_VERSION_ID >= 80200) {
+    $readonly = new IntegrationTest\ReadonlyClass("foo");
+    assert_eq($readonly->name, "foo");
+    assert_true((new ReflectionClass(IntegrationTest\ReadonlyClass::class))->isReadOnly());
+    assert_true((new ReflectionProperty(IntegrationTest\ReadonlyClass::class, "name"))->isReadOnly());
+    assert_throw(function () use ($readonly) {
+        $readonly->other = 1;
+    }, "Error", 0, "Cannot create dynamic property IntegrationTest\\ReadonlyClass::\$other");
+}
+
+interface IntegrationTestDefinedInterface {}
+trait IntegrationTestDefinedTrait {}
+
+$classes = integrate_classes_defined_classes();
+assert_eq($classes["IntegrationTest\\A"], ["type" => "class", "internal" => true, "filename" => null]);
+assert_eq($classes["IntegrationTestDefinedInterface"], ["type" => "interface", "internal" => false, "filename" => __FILE__]);
+assert_eq($classes["IntegrationTestDefinedTrait"]["type"], "trait");
+assert_eq($classes["Iterator"]["type"], "interface");
+
+// Test the methods returning by reference.
+$holder = new IntegrationTest\RefHolder();
+$holder->items = [];
+$items = &$holder->items();
+$items[] = "foo";
+assert_eq($holder->items, ["foo"]);
+assert_true((new ReflectionMethod(IntegrationTest\RefHolder::class, "items"))->returnsReference());
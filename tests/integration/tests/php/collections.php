@@ -0,0 +1,75 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+use Phper\Collection\Map;
+use Phper\Collection\Vector;
+
+// Test vector.
+$vector = integrate_collections_new_vector();
+assert_true($vector instanceof Vector);
+assert_true($vector instanceof ArrayAccess);
+assert_true($vector instanceof Countable);
+assert_true($vector instanceof IteratorAggregate);
+assert_eq(count($vector), 3);
+assert_eq($vector->toArray(), [1, 2, 3]);
+
+$vector[] = 4;
+$vector->push(5);
+assert_eq($vector->pop(), 5);
+$vector[0] = "first";
+assert_eq($vector[0], "first");
+assert_eq($vector->get(3), 4);
+assert_true(isset($vector[3]));
+assert_false(isset($vector[4]));
+unset($vector[1]);
+assert_eq($vector->toArray(), ["first", 3, 4]);
+assert_eq(iterator_to_array($vector), ["first", 3, 4]);
+assert_throw(function () use ($vector) {
+    $vector[10];
+}, "OutOfRangeException", 0, "Offset is out of range of the vector");
+
+$cloned = clone $vector;
+$cloned->push("cloned");
+assert_eq(count($vector), 3);
+assert_eq(count($cloned), 4);
+
+assert_eq((new Vector(["x" => 1, "y" => 2]))->toArray(), [1, 2]);
+assert_eq((new Vector())->pop(), null);
+
+// Test map.
+$map = integrate_collections_new_map();
+assert_true($map instanceof Map);
+assert_eq($map->toArray(), ["a" => 1, 10 => 2]);
+assert_true($map->has(10));
+assert_true(isset($map["10"]));
+assert_eq($map[10], 2);
+
+$map["b"] = 3;
+$map->set(-1, 4);
+assert_eq($map->keys(), ["a", 10, "b", -1]);
+assert_eq($map->values(), [1, 2, 3, 4]);
+assert_eq($map->remove("a"), 1);
+assert_eq($map->remove("a"), null);
+unset($map[10]);
+assert_eq(count($map), 2);
+assert_eq(iterator_to_array($map), ["b" => 3, -1 => 4]);
+assert_throw(function () use ($map) {
+    $map["missing"];
+}, "OutOfBoundsException", 0, "Key isn't found in the map");
+assert_throw(function () use ($map) {
+    $map[[]] = 1;
+}, "TypeError", 0, "Illegal offset type");
+
+assert_eq((new Map(["01" => 1, "1" => 2]))->keys(), ["01", 1]);
@@ -0,0 +1,27 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+$result = include __DIR__ . '/_compile_hooks_included.php';
+assert_eq($result, PHP_VERSION_ID >= 70400 ? 'rewritten' : 'original');
+
+$files = integrate_compile_hooks_compiled_files();
+assert_true(in_array(__DIR__ . '/_compile_hooks_included.php', $files));
+
+assert_eq(eval('return __ANSWER__;'), 42);
+assert_eq(eval('return "keep";'), 'keep');
+
+assert_throw(function () {
+    eval('return "__DENY__";');
+}, "ErrorException", 0, "eval is denied");
@@ -20,3 +20,15 @@ assert_eq(INTEGRATE_CONST_LONG, 100);
 assert_eq(INTEGRATE_CONST_DOUBLE, 200.0);
 assert_eq(INTEGRATE_CONST_STRING, "something");
 assert_eq(INTEGRATE_CONST_BYTES, "something");
+
+define("INTEGRATE_CONST_USERLAND", "userland");
+
+assert_eq(integrate_constants_get("PHP_VERSION"), PHP_VERSION);
+assert_eq(integrate_constants_get("INTEGRATE_CONST_LONG"), 100);
+assert_eq(integrate_constants_get("INTEGRATE_CONST_USERLAND"), "userland");
+assert_eq(integrate_constants_get("INTEGRATE_CONST_NOT_EXISTS"), null);
+
+$constants = integrate_constants_defined_constants();
+assert_eq($constants["PHP_VERSION"], PHP_VERSION);
+assert_eq($constants["INTEGRATE_CONST_STRING"], "something");
+assert_eq($constants["INTEGRATE_CONST_USERLAND"], "userland");
@@ -0,0 +1,35 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+assert_false(integrate_curl_is_curl_handle(null));
+assert_false(integrate_curl_is_curl_handle(new stdClass()));
+assert_throw(function () { integrate_curl_easy_handle(1); }, "TypeError", 0, "must be of type CurlHandle, int given");
+
+// The `ext/curl` may not be loaded, since the tests are run without `php.ini`.
+if (extension_loaded("curl")) {
+    $ch = curl_init();
+    assert_true(integrate_curl_is_curl_handle($ch));
+    $easy = integrate_curl_easy_handle($ch);
+    assert_true($easy != 0);
+    assert_eq(integrate_curl_easy_handle($ch), $easy);
+
+    $copied = curl_copy_handle($ch);
+    assert_true(integrate_curl_easy_handle($copied) != $easy);
+
+    if (PHP_VERSION_ID < 80000) {
+        curl_close($ch);
+        assert_false(integrate_curl_is_curl_handle($ch));
+    }
+}
@@ -0,0 +1,24 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+$dump = integration_debug_dump();
+assert_eq($dump["name"], "integration");
+assert_eq($dump["ini"]["INTEGRATE_INI_TRUE"], "1");
+assert_eq($dump["globals"], '"integration globals"');
+assert_true(in_array("integrate_arrays_new_drop", $dump["functions"]));
+assert_true(in_array("integration_debug_dump", $dump["functions"]));
+assert_true(in_array("IntegrationTest\\A", $dump["classes"]));
+assert_true(in_array("IntegrationTest\\IBar", $dump["interfaces"]));
+assert_true(in_array("INTEGRATE_CONST_NULL", $dump["constants"]));
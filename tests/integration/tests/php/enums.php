@@ -0,0 +1,47 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+if (PHP_VERSION_ID >= 80100) {
+    // Pure enum.
+    $hearts = IntegrationTest\Suit::Hearts;
+    assert_true($hearts instanceof UnitEnum);
+    assert_false($hearts instanceof BackedEnum);
+    assert_true($hearts === IntegrationTest\Suit::Hearts);
+    assert_eq($hearts->name, "Hearts");
+    assert_eq($hearts->color(), "Red");
+    assert_eq(IntegrationTest\Suit::Spades->color(), "Black");
+    assert_eq(count(IntegrationTest\Suit::cases()), 2);
+    assert_true((new ReflectionClass(IntegrationTest\Suit::class))->isEnum());
+
+    // String backed enum.
+    assert_true(IntegrationTest\Status::Active instanceof BackedEnum);
+    assert_eq(IntegrationTest\Status::Active->value, "active");
+    assert_true(IntegrationTest\Status::from("inactive") === IntegrationTest\Status::Inactive);
+    assert_eq(IntegrationTest\Status::tryFrom("unknown"), null);
+    assert_eq(IntegrationTest\Status::describe(), "status");
+
+    // Int backed enum.
+    assert_eq(IntegrationTest\Priority::High->value, 10);
+    assert_true(IntegrationTest\Priority::from(1) === IntegrationTest\Priority::Low);
+    try {
+        IntegrationTest\Priority::from(5);
+        throw new AssertionError("invalid backing value not throws");
+    } catch (ValueError $e) {
+        assert_true(strpos($e->getMessage(), "5 is not a valid backing value") === 0);
+    }
+    assert_throw(function () {
+        new IntegrationTest\Priority();
+    }, "Error", 0, "Cannot instantiate enum IntegrationTest\\Priority");
+}
@@ -0,0 +1,32 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+if (PHP_SAPI === "fpm-fcgi") {
+    assert_eq(integrate_env_sapi_var("REQUEST_METHOD"), "GET");
+    assert_eq(integrate_env_var("REQUEST_METHOD"), "GET");
+} else {
+    assert_eq(integrate_env_sapi_var("REQUEST_METHOD"), null);
+}
+assert_eq(integrate_env_var("PATH"), getenv("PATH") === false ? null : getenv("PATH"));
+
+integrate_env_set_var("PHPER_INTEGRATE_ENV", "foo");
+assert_eq(getenv("PHPER_INTEGRATE_ENV"), "foo");
+assert_eq(integrate_env_var("PHPER_INTEGRATE_ENV"), "foo");
+
+integrate_env_remove_var("PHPER_INTEGRATE_ENV");
+assert_eq(getenv("PHPER_INTEGRATE_ENV"), false);
+assert_eq(integrate_env_var("PHPER_INTEGRATE_ENV"), null);
+
+assert_throw(function () { integrate_env_set_var("FOO=BAR", "baz"); }, "InvalidArgumentException", 0, "Invalid environment variable name \"FOO=BAR\"");
@@ -15,3 +15,44 @@ require_once __DIR__ . '/_common.php';
 
 assert_throw("integrate_throw_boxed", "ErrorException", 0, "What's wrong with you?");
 assert_throw("integrate_throw_object", "Exception", 403, "Forbidden");
+assert_throw("integrate_throw_type_error", "TypeError", 0, "expect int");
+assert_throw("integrate_throw_argument", "InvalidArgumentException", 0, "invalid argument");
+assert_throw("integrate_throw_runtime", "RuntimeException", 0, "runtime failure");
+assert_throw("integrate_throw_custom", "OutOfRangeException", 0, "out of range");
+assert_throw("integrate_throw_custom_not_found", "Exception", 0, "fallback");
+assert_throw("integrate_throw_io", "ErrorException", 0, "No such file or directory (os error 2)");
+
+$line = __LINE__ + 2;
+$result = integrate_errors_catch(function () {
+    throw new RuntimeException("boom", 7, new LogicException("inner"));
+});
+assert_eq($result, [
+    "class" => "RuntimeException",
+    "message" => "boom",
+    "code" => 7,
+    "line" => $line,
+    "file" => __FILE__,
+    "has_trace" => true,
+    "previous" => "inner",
+]);
+assert_eq(integrate_errors_catch(function () {}), null);
+
+assert_eq(integrate_errors_fallback(function () { throw new RuntimeException("retry"); }), "fallback");
+assert_eq(integrate_errors_fallback(function () { return "ok"; }), "ok");
+assert_throw(function () {
+    integrate_errors_fallback(function () { throw new LogicException("rethrown", 3); });
+}, "LogicException", 3, "rethrown");
+
+try {
+    integrate_errors_chain(function () {
+        throw new LogicException("outer", 1, new InvalidArgumentException("inner", 2));
+    });
+    throw new AssertionError("expect exception");
+} catch (RuntimeException $e) {
+    assert_eq($e->getMessage(), "callback failed");
+    assert_eq(get_class($e->getPrevious()), "LogicException");
+    assert_eq($e->getPrevious()->getMessage(), "outer");
+    assert_eq($e->getPrevious()->getPrevious()->getMessage(), "inner");
+    assert_eq($e->getPrevious()->getPrevious()->getPrevious(), null);
+}
+assert_eq(integrate_errors_chain(function () { return 1; }), 1);
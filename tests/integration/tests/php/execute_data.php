@@ -0,0 +1,46 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+class ExecuteDataBase {
+    public static function call() {
+        return [integrate_execute_data_frames(), __LINE__];
+    }
+}
+
+class ExecuteDataChild extends ExecuteDataBase {}
+
+list($frames, $line) = ExecuteDataChild::call(); $main_line = __LINE__;
+
+assert_eq($frames[0], [
+    "function" => "integrate_execute_data_frames",
+    "class" => null,
+    "scope" => "ExecuteDataChild",
+    "file" => null,
+    "line" => null,
+]);
+assert_eq($frames[1], [
+    "function" => "call",
+    "class" => "ExecuteDataBase",
+    "scope" => "ExecuteDataChild",
+    "file" => __FILE__,
+    "line" => $line,
+]);
+assert_eq($frames[2], [
+    "function" => null,
+    "class" => null,
+    "scope" => null,
+    "file" => __FILE__,
+    "line" => $main_line,
+]);
@@ -0,0 +1,30 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+// The `ext/filter` can be disabled by `--disable-filter`.
+if (extension_loaded("filter")) {
+    foreach (["foo@example.com", "foo", "", "a@b.c"] as $email) {
+        assert_eq(integrate_filters_validate_email($email), filter_var($email, FILTER_VALIDATE_EMAIL));
+    }
+
+    assert_eq(integrate_filters_validate_bool("yes"), true);
+    assert_eq(integrate_filters_validate_bool("off"), false);
+    assert_eq(integrate_filters_validate_bool("maybe"), null);
+
+    assert_eq(integrate_filters_require_port("8080"), 8080);
+    assert_throw(function () { integrate_filters_require_port("70000"); }, "ValueError", 0, "Value is not valid for filter \"int\"");
+
+    assert_eq(integrate_filters_sanitize_number_int("a1b2-3"), filter_var("a1b2-3", FILTER_SANITIZE_NUMBER_INT));
+}
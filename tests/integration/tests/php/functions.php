@@ -23,4 +23,47 @@ assert_throw(function() {
     integrate_functions_call_callable(function () { throw new RuntimeException("oh no", 403); });
 }, "RuntimeException", 403, "oh no");
 
+assert_eq(integrate_functions_cached_callable_map(function ($n) { return $n * 2; }, [1, 2, 3]), [2, 4, 6]);
+assert_eq(integrate_functions_cached_callable_map("strtoupper", ["a", "b"]), ["A", "B"]);
+try {
+    integrate_functions_cached_callable_map("integrate_functions_not_exists", [1]);
+    throw new AssertionError("not callable value not throws");
+} catch (TypeError $e) {
+}
+
+$reflection = new ReflectionFunction("integrate_functions_cached_callable_map");
+assert_eq($reflection->getNumberOfParameters(), 2);
+assert_eq($reflection->getNumberOfRequiredParameters(), 2);
+assert_eq(array_map(function ($p) { return $p->getName(); }, $reflection->getParameters()), ["fn", "items"]);
+
+if (PHP_VERSION_ID >= 70100) {
+    $closure = Closure::fromCallable("integrate_functions_call_callable");
+    assert_eq($closure(function () { return 1 + 1; }), 2);
+}
+
 assert_throw("integrate_functions_exception_guard", "ErrorException", 0, "other io error");
+
+function integrate_functions_user_defined() {}
+
+$user_functions = integrate_functions_defined_user_functions();
+assert_eq($user_functions["integrate_functions_user_defined"], __FILE__);
+assert_false(isset($user_functions["integrate_functions_call"]));
+
+// The functions declared without arguments.
+assert_eq(integrate_functions_version(), "0.0.0");
+assert_eq(integrate_functions_count_undeclared(), 0);
+assert_eq(integrate_functions_count_undeclared(1, "2", [3]), 3);
+$reflection = new ReflectionFunction("integrate_functions_version");
+assert_eq($reflection->getNumberOfParameters(), 0);
+
+// The returned value is converted to reference.
+$fresh = &integrate_functions_return_ref();
+assert_eq($fresh, "fresh");
+assert_true((new ReflectionFunction("integrate_functions_return_ref"))->returnsReference());
+assert_false((new ReflectionFunction("integrate_functions_version"))->returnsReference());
+
+// The return values constructed in place.
+assert_eq(integrate_functions_return_array(4), [0, 1, 4, 9]);
+assert_eq(integrate_functions_return_array(0), []);
+assert_eq(integrate_functions_return_string(3), "0,1,2,");
+assert_eq(integrate_functions_return_string(0), "");
@@ -20,3 +20,49 @@ assert_eq(ini_get("INTEGRATE_INI_DOUBLE"), '200');
 assert_eq(ini_get("INTEGRATE_INI_STRING"), "something");
 
 integrate_ini_assert();
+integrate_ini_get_set();
+
+assert_eq(integrate_ini_validated_level(), 10);
+assert_eq(ini_set("INTEGRATE_INI_VALIDATED", "20"), "10");
+assert_eq(integrate_ini_validated_level(), 20);
+assert_false(@ini_set("INTEGRATE_INI_VALIDATED", "abc"));
+assert_false(@ini_set("INTEGRATE_INI_VALIDATED", "-1"));
+assert_eq(ini_get("INTEGRATE_INI_VALIDATED"), "20");
+assert_eq(integrate_ini_validated_level(), 20);
+ini_restore("INTEGRATE_INI_VALIDATED");
+assert_eq(integrate_ini_validated_level(), 10);
+
+assert_eq(ini_get("INTEGRATE_INI_SECRET"), "hunter2");
+ob_start();
+phpinfo(INFO_MODULES);
+$info = ob_get_clean();
+assert_true(strpos($info, "INTEGRATE_INI_SECRET") !== false);
+assert_true(strpos($info, "*******") !== false);
+assert_false(strpos($info, "hunter2"));
+
+// The `INTEGRATE_INI_PERDIR` is overridden by `.user.ini` in php-fpm.
+$is_fpm = PHP_SAPI == "fpm-fcgi";
+assert_eq(ini_get("INTEGRATE_INI_PERDIR"), $is_fpm ? "perdir" : "default");
+integrate_ini_perdir($is_fpm);
+
+assert_eq(integrate_ini_reloadable_level(), 1);
+assert_eq(integrate_ini_reload(["INTEGRATE_INI_RELOADABLE" => "5"]), 5);
+assert_eq(ini_get("INTEGRATE_INI_RELOADABLE"), "5");
+assert_throw(function () {
+    integrate_ini_reload(["INTEGRATE_INI_RELOADABLE" => "7", "INTEGRATE_INI_NOT_EXISTS" => "1"]);
+}, "Exception", 0, "Failed to set ini entry INTEGRATE_INI_NOT_EXISTS");
+assert_throw(function () {
+    @integrate_ini_reload(["INTEGRATE_INI_RELOADABLE" => "-1"]);
+}, "Exception", 0, "Failed to set ini entry INTEGRATE_INI_RELOADABLE");
+assert_eq(integrate_ini_reloadable_level(), 5);
+assert_eq(ini_get("INTEGRATE_INI_RELOADABLE"), "5");
+assert_eq(integrate_ini_reload(["INTEGRATE_INI_RELOADABLE" => "1"]), 1);
+
+assert_eq(ini_get("integrate_ini_config.enable"), "1");
+assert_eq(ini_get("integrate_ini_config.max_size"), "1024");
+assert_false(ini_get("integrate_ini_config.size"));
+assert_eq(integrate_ini_config(), ["enable" => true, "size" => 1024, "ratio" => 0.5, "endpoint" => "localhost:9000"]);
+ini_set("integrate_ini_config.enable", "0");
+ini_set("integrate_ini_config.max_size", "10");
+ini_set("integrate_ini_config.endpoint", "example.com:80");
+assert_eq(integrate_ini_config(), ["enable" => false, "size" => 10, "ratio" => 0.5, "endpoint" => "example.com:80"]);
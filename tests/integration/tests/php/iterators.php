@@ -0,0 +1,45 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+$lines = integrate_iterators_lines(3);
+assert_true($lines instanceof Traversable);
+assert_true($lines instanceof IntegrationTest\Lines);
+assert_eq(integrate_iterators_produced(), 0);
+
+$result = [];
+foreach ($lines as $key => $line) {
+    $result[$key] = $line;
+    assert_eq(integrate_iterators_produced(), $key + 1);
+}
+assert_eq($result, ["line 1", "line 2", "line 3"]);
+assert_false($lines->valid());
+assert_eq($lines->current(), null);
+assert_eq($lines->key(), null);
+
+assert_throw(function () use ($lines) {
+    foreach ($lines as $line) {
+    }
+}, "Exception", 0, "Cannot rewind a generator that was already run");
+
+assert_eq(iterator_to_array(integrate_iterators_lines(2)), ["line 1", "line 2"]);
+assert_eq(iterator_to_array(integrate_iterators_lines(0)), []);
+
+$lines = integrate_iterators_lines(1000000);
+foreach ($lines as $line) {
+    if ($line === "line 5") {
+        break;
+    }
+}
+assert_eq(integrate_iterators_produced(), 5);
@@ -0,0 +1,24 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+assert_true(integrate_json_is_loaded());
+
+integrate_json_encode();
+integrate_json_decode();
+
+if (PHP_VERSION_ID >= 70300) {
+    assert_eq(integrate_json_decode_throw("[1]"), [1]);
+    assert_throw(function () { integrate_json_decode_throw("{"); }, "JsonException", 4, "Syntax error");
+}
@@ -0,0 +1,33 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+$log_file = tempnam(sys_get_temp_dir(), "phper_loggers_");
+ini_set("log_errors", "1");
+ini_set("error_log", $log_file);
+
+integrate_loggers_log("warn", "something warned");
+integrate_loggers_log("info", "something noticed");
+integrate_loggers_log("debug", "something debugged");
+
+ini_set("log_errors", "0");
+integrate_loggers_log("error", "something ignored");
+
+$content = file_get_contents($log_file);
+unlink($log_file);
+
+assert_true(strpos($content, "PHP Warning [integration::loggers]:  something warned") !== false);
+assert_true(strpos($content, "PHP Notice [integration::loggers]:  something noticed") !== false);
+assert_false(strpos($content, "something debugged"));
+assert_false(strpos($content, "something ignored"));
@@ -0,0 +1,41 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+$metrics = integration_phper_metrics();
+assert_eq(array_keys($metrics), ["integrate_metrics_calls", "integrate_metrics_level", "integrate_metrics_sizes"]);
+$base_calls = $metrics["integrate_metrics_calls"];
+$base_count = $metrics["integrate_metrics_sizes"]["count"];
+$base_buckets = $metrics["integrate_metrics_sizes"]["buckets"];
+assert_eq(array_keys($base_buckets), [1, 10, 100, "+Inf"]);
+
+integrate_metrics_observe(3, 0.5);
+integrate_metrics_observe(-2, 50.0);
+integrate_metrics_observe(7, 1000.0);
+
+$metrics = integration_phper_metrics();
+assert_eq($metrics["integrate_metrics_calls"], $base_calls + 3);
+assert_eq($metrics["integrate_metrics_level"], 7);
+$sizes = $metrics["integrate_metrics_sizes"];
+assert_eq($sizes["count"], $base_count + 3);
+assert_eq($sizes["buckets"]["1"], $base_buckets["1"] + 1);
+assert_eq($sizes["buckets"]["10"], $base_buckets["10"] + 1);
+assert_eq($sizes["buckets"]["100"], $base_buckets["100"] + 2);
+assert_eq($sizes["buckets"]["+Inf"], $base_buckets["+Inf"] + 3);
+
+ob_start();
+phpinfo(INFO_MODULES);
+$info = ob_get_clean();
+assert_true(strpos($info, "integrate_metrics_calls") !== false);
+assert_true(strpos($info, "The observed sizes.") !== false);
@@ -0,0 +1,67 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+// The process init hook is run once in the first request of each process.
+$init = integrate_modules_process_init();
+assert_eq($init["count"], 1);
+assert_eq($init["pid"], getmypid());
+
+// The php-fpm workers are forked by the master, which runs `MINIT`.
+$fork = integrate_modules_fork();
+if (PHP_SAPI == "fpm-fcgi") {
+    assert_eq($fork["count"], 1);
+    assert_true($fork["parent_pid"] != 0 && $fork["parent_pid"] != getmypid());
+} else {
+    assert_eq($fork["count"], 0);
+}
+
+// The runtime feature detection.
+$version = integrate_modules_php_version();
+assert_eq($version["id"], PHP_VERSION_ID);
+assert_eq($version["string"], PHP_MAJOR_VERSION . "." . PHP_MINOR_VERSION . "." . PHP_RELEASE_VERSION);
+assert_eq($version["json"], extension_loaded("json"));
+assert_false($version["unknown"]);
+
+// The conditionally registered functions.
+assert_true(function_exists("integrate_modules_when_true"));
+assert_true(integrate_modules_when_true());
+assert_false(function_exists("integrate_modules_when_false"));
+
+// The loaded extensions introspection.
+assert_eq(integrate_modules_loaded("integrate_unknown"), null);
+
+$standard = integrate_modules_loaded("STANDARD");
+assert_eq($standard["name"], "standard");
+assert_eq($standard["version"], phpversion("standard"));
+assert_true($standard["started"]);
+assert_true($standard["has_str_repeat"]);
+$expected = get_extension_funcs("standard");
+sort($expected);
+assert_eq($standard["functions"], $expected);
+
+$integration = integrate_modules_loaded("integration");
+assert_false($integration["has_str_repeat"]);
+assert_true(in_array("integrate_modules_loaded", $integration["functions"]));
+
+$names = array_map("strtolower", integrate_modules_loaded_names());
+$expected = array_map("strtolower", get_loaded_extensions());
+sort($names);
+sort($expected);
+assert_eq($names, $expected);
+
+// Every finished request runs `RSHUTDOWN` and then the post deactivate hook.
+$post_deactivate = integrate_modules_post_deactivate();
+assert_eq($post_deactivate["post_deactivate"], $post_deactivate["request_shutdown"]);
+assert_true($post_deactivate["ordered"]);
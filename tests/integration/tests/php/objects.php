@@ -34,3 +34,12 @@ class B2 extends IntegrationTest\Objects\B {}
 $b2 = new B2();
 $b22 = clone $b2;
 assert_eq($b22->get(), 123456);
+
+$c = new IntegrationTest\Objects\C();
+$id = integrate_objects_id($c);
+if (PHP_VERSION_ID >= 70200) {
+    assert_eq(spl_object_id($c), $id);
+}
+assert_eq(integrate_objects_destroyed_ids(), []);
+unset($c);
+assert_eq(integrate_objects_destroyed_ids(), [$id]);
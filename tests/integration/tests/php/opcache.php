@@ -0,0 +1,45 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+// The test is run with opcache loaded by the harness, so failing to load it
+// fails the test rather than skipping the checks silently.
+if (!function_exists('opcache_get_status')) {
+    throw new AssertionError("opcache isn't loaded");
+}
+
+$status = opcache_get_status(false);
+assert_true($status['opcache_enabled']);
+
+// The functions and classes of extension run with the cached scripts.
+assert_eq(integrate_alloc_estring(), "hello, world");
+
+$a = new \IntegrationTest\A("foo", 99);
+assert_eq($a->speak(), "name: foo, number: 99");
+
+class IntegrationTestOpcacheSubA extends \IntegrationTest\A {}
+$sub_a = new IntegrationTestOpcacheSubA("bar", 1);
+assert_eq($sub_a->speak(), "name: bar, number: 1");
+
+// The embedded source is compiled as file, and cached by opcache.
+$embedded_file = (new ReflectionFunction("integrate_sources_embedded"))->getFileName();
+assert_true(opcache_is_script_cached($embedded_file));
+
+if (PHP_VERSION_ID >= 70400) {
+    // The preloaded class inherits the methods of extension class.
+    assert_true(class_exists(IntegrationTestPreloadedA::class, false));
+    $preloaded = new IntegrationTestPreloadedA("baz", 2);
+    assert_eq($preloaded->speakTwice(), "name: baz, number: 2, name: baz, number: 2");
+    assert_eq(integration_test_preloaded_speak("qux", 3), "name: qux, number: 3");
+}
@@ -0,0 +1,24 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+// Preloaded by `opcache.preload` in the opcache test, PHP 7.4+.
+
+class IntegrationTestPreloadedA extends \IntegrationTest\A {
+    public function speakTwice() {
+        return $this->speak() . ", " . $this->speak();
+    }
+}
+
+function integration_test_preloaded_speak($name, $number) {
+    $a = new \IntegrationTest\A($name, $number);
+    return $a->speak();
+}
@@ -0,0 +1,27 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+$count = integrate_opcodes_include_or_eval_count();
+assert_eq(eval('return 1 + 1;'), 2);
+assert_eq(integrate_opcodes_include_or_eval_count(), $count + 1);
+
+integrate_opcodes_deny_include_or_eval(true);
+assert_throw(function () {
+    eval('return 1;');
+}, "ErrorException", 0, "include or eval is denied");
+integrate_opcodes_deny_include_or_eval(false);
+
+assert_eq(eval('return 2;'), 2);
+assert_eq(integrate_opcodes_include_or_eval_count(), $count + 2);
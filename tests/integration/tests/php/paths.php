@@ -0,0 +1,27 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+chdir(__DIR__);
+assert_eq(integrate_paths_current_dir(), getcwd());
+assert_eq(integrate_paths_expand("not_exists/../foo.txt"), __DIR__ . "/foo.txt");
+assert_eq(integrate_paths_expand("/tmp/./foo/../bar"), "/tmp/bar");
+assert_eq(integrate_paths_real_path("_common.php"), realpath("_common.php"));
+assert_eq(integrate_paths_real_path("not_exists.php"), null);
+
+// The relative paths follow the `chdir()` of PHP.
+$dir = realpath(sys_get_temp_dir());
+chdir($dir);
+assert_eq(integrate_paths_current_dir(), $dir);
+assert_eq(integrate_paths_expand("foo.txt"), $dir . "/foo.txt");
@@ -0,0 +1,91 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+// The PDO driver apis are only available on PHP 8.1 or later.
+if (PHP_VERSION_ID >= 80100) {
+    assert_true(in_array("phpertest", PDO::getAvailableDrivers(), true));
+
+    function pdo_error_info(callable $f) {
+        try {
+            $f();
+        } catch (PDOException $e) {
+            return [$e->getCode(), $e->errorInfo];
+        }
+        throw new AssertionError("PDOException not throws");
+    }
+
+    // Connect.
+    assert_throw(function () {
+        new PDO("phpertest:refuse");
+    }, "PDOException", 1, "SQLSTATE[08006] [1] connection refused");
+    assert_throw(function () {
+        new PDO("phpertest:", "root", "wrong");
+    }, "PDOException", 2, "SQLSTATE[28000] [2] access denied");
+
+    $pdo = new PDO("phpertest:memory", "root", "secret");
+    assert_eq($pdo->getAttribute(PDO::ATTR_DRIVER_NAME), "phpertest");
+    $pdo->setAttribute(PDO::ATTR_ERRMODE, PDO::ERRMODE_EXCEPTION);
+
+    // Exec.
+    assert_eq($pdo->exec("INSERT 'foo'"), 1);
+    assert_eq($pdo->exec("INSERT " . $pdo->quote("bar's")), 1);
+    assert_eq($pdo->lastInsertId(), "2");
+
+    // Prepare, execute and fetch.
+    $stmt = $pdo->prepare("SELECT");
+    assert_true($stmt->execute());
+    assert_eq($stmt->columnCount(), 2);
+    assert_eq($stmt->rowCount(), 2);
+    assert_eq($stmt->fetchAll(PDO::FETCH_ASSOC), [
+        ["id" => 1, "name" => "foo"],
+        ["id" => 2, "name" => "bar's"],
+    ]);
+
+    // The placeholders are emulated with the quoted values.
+    $stmt = $pdo->prepare("SELECT ?");
+    assert_true($stmt->execute(["bar's"]));
+    assert_eq($stmt->fetch(PDO::FETCH_NUM), [2, "bar's"]);
+    assert_false($stmt->fetch(PDO::FETCH_NUM));
+
+    $stmt = $pdo->prepare("SELECT :name");
+    $stmt->bindValue(":name", "nobody");
+    assert_true($stmt->execute());
+    assert_eq($stmt->fetchAll(), []);
+
+    assert_eq($pdo->query("SELECT 'foo'")->fetchColumn(1), "foo");
+
+    // The errors of the connection and the statement.
+    assert_eq(pdo_error_info(function () use ($pdo) {
+        $pdo->exec("BOGUS");
+    }), ["42000", ["42000", 7, "unknown statement: BOGUS"]]);
+    assert_eq(pdo_error_info(function () use ($pdo) {
+        $pdo->prepare("UPDATE");
+    }), ["42000", ["42000", 7, "unknown statement: UPDATE"]]);
+    assert_eq(pdo_error_info(function () use ($pdo) {
+        $pdo->query("SELECT unquoted");
+    }), ["42000", ["42000", 7, "unknown statement: SELECT unquoted"]]);
+    assert_eq(pdo_error_info(function () use ($pdo) {
+        $pdo->beginTransaction();
+    })[1], ["IM001", 0, "Driver does not support this function"]);
+
+    // The silent mode keeps the error info.
+    $pdo->setAttribute(PDO::ATTR_ERRMODE, PDO::ERRMODE_SILENT);
+    assert_false($pdo->exec("BOGUS"));
+    assert_eq($pdo->errorCode(), "42000");
+    assert_eq($pdo->errorInfo(), ["42000", 7, "unknown statement: BOGUS"]);
+
+    assert_eq($pdo->exec("DELETE"), 2);
+    assert_eq($pdo->query("SELECT")->fetchAll(), []);
+}
@@ -0,0 +1,37 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+// The same object is used in the whole request.
+$a = integrate_pools_request("a");
+assert_eq(integrate_pools_request("a"), $a);
+assert_true(integrate_pools_request("b") !== $a);
+assert_eq(integrate_pools_idle("a"), 0);
+
+// The checked out object is returned when the guard is dropped.
+$key = "checkout_" . getmypid() . "_" . mt_rand();
+$c = integrate_pools_checkout($key, "");
+assert_eq(integrate_pools_idle($key), 1);
+assert_eq(integrate_pools_checkout($key, ""), $c);
+
+// The discarded object isn't returned.
+assert_eq(integrate_pools_checkout($key, "discard"), $c);
+assert_eq(integrate_pools_idle($key), 0);
+
+// The broken object is dropped by the health check.
+$d = integrate_pools_checkout($key, "break");
+assert_true($d !== $c);
+assert_eq(integrate_pools_idle($key), 1);
+$e = integrate_pools_checkout($key, "");
+assert_true($e !== $d);
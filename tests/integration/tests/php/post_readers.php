@@ -0,0 +1,25 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+if (($_SERVER["REQUEST_METHOD"] ?? "") === "POST") {
+    assert_eq($_POST, [
+        "_content_type" => "application/x-phper-kv; charset=utf-8",
+        "foo" => "1",
+        "bar" => "baz",
+    ]);
+    assert_eq(file_get_contents("php://input"), "foo=1\nbar=baz\n");
+} else {
+    assert_eq($_POST, []);
+}
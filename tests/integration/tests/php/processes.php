@@ -0,0 +1,36 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+$worker = integrate_processes_worker();
+assert_eq($worker["sapi"], PHP_SAPI);
+assert_eq($worker["pid"], getmypid());
+if (PHP_SAPI == "fpm-fcgi" && function_exists("fpm_get_status")) {
+    assert_eq($worker["pool"], "www");
+    assert_eq($worker["display"], "fpm-fcgi[www]#" . getmypid());
+} else {
+    assert_eq($worker["pool"], null);
+    assert_eq($worker["requests"], null);
+    assert_eq($worker["display"], PHP_SAPI . "#" . getmypid());
+}
+
+if (PHP_SAPI == "cli") {
+    // Fails if the title of process can't be changed, like in the containers.
+    if (@integrate_processes_set_title("phper-integration")) {
+        assert_eq(integrate_processes_title(), "phper-integration");
+    }
+} else {
+    assert_false(integrate_processes_set_title("phper-integration"));
+    assert_eq(integrate_processes_title(), null);
+}
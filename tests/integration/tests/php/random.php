@@ -0,0 +1,27 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+assert_eq(integrate_hashes_hash("sha256", "foobar"), hash("sha256", "foobar", true));
+assert_eq(integrate_hashes_hash("md5", ""), md5("", true));
+assert_eq(integrate_hashes_hash("crc32b", "foo"), hash("crc32b", "foo", true));
+integrate_hashes_context();
+
+assert_eq(strlen(integrate_random_bytes(16)), 16);
+assert_eq(integrate_random_bytes(0), "");
+for ($i = 0; $i < 100; $i++) {
+    $n = integrate_random_int(-3, 3);
+    assert_true($n >= -3 && $n <= 3);
+}
+assert_eq(integrate_random_int(7, 7), 7);
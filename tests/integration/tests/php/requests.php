@@ -0,0 +1,47 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+assert_false(integrate_requests_token_is_cancelled());
+assert_false(integrate_requests_token_wait(10));
+assert_true(integrate_requests_token_in_other_thread());
+
+set_time_limit(0);
+assert_eq(integrate_requests_remaining_time(), null);
+assert_eq(integrate_requests_with_remaining_time(), null);
+
+set_time_limit(30);
+$remaining = integrate_requests_remaining_time();
+assert_true($remaining > 0 && $remaining <= 30);
+$remaining = integrate_requests_with_remaining_time();
+assert_true($remaining > 0 && $remaining <= 30);
+set_time_limit(0);
+
+$_SERVER["HTTP_X_PHPER_TEST"] = "foo";
+$_SERVER["CONTENT_TYPE"] = "text/plain";
+$_SERVER["HTTPS"] = "on";
+$headers = integrate_requests_headers();
+assert_eq($headers["x-phper-test"], "foo");
+assert_eq($headers["content-type"], "text/plain");
+assert_false(isset($headers["https"]));
+assert_false(isset($headers["s"]));
+assert_eq(integrate_requests_header("X-Phper-Test"), "foo");
+assert_eq(integrate_requests_header("Content-Type"), "text/plain");
+assert_eq(integrate_requests_header("X-Not-Exists"), null);
+
+$_COOKIE["foo"] = "bar";
+$_COOKIE["arr"] = ["baz"];
+$cookies = integrate_requests_cookies();
+assert_eq($cookies["foo"], "bar");
+assert_false(isset($cookies["arr"]));
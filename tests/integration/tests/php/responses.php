@@ -0,0 +1,27 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+assert_eq(integrate_responses_cookie_header(), "foo=bar%20baz; path=/; domain=example.com; secure; HttpOnly; SameSite=Lax");
+assert_eq(integrate_responses_cookie_expires(0), "foo=bar; expires=Thu, 01 Jan 1970 00:00:00 GMT; Max-Age=0");
+assert_throw(function () { integrate_responses_cookie_name("a=b"); }, "InvalidArgumentException", 0, "Invalid cookie name \"a=b\"");
+assert_throw(function () { integrate_responses_cookie_name(""); }, "InvalidArgumentException", 0, "Invalid cookie name \"\"");
+
+assert_eq(integrate_responses_headers_sent(), headers_sent());
+if (headers_sent()) {
+    assert_throw(function () { integrate_responses_set_cookie(); }, "RuntimeException", 0, "Cannot modify header information - headers already sent");
+} else {
+    integrate_responses_set_cookie();
+    assert_true(in_array("Set-Cookie: foo=bar; SameSite=Strict", headers_list(), true));
+}
@@ -0,0 +1,39 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+class IntegrationSerializeFoo {
+    public $name = "foo";
+}
+
+$value = ["a" => 1, "b" => [true, null, 1.5], "c" => new IntegrationSerializeFoo()];
+
+$data = integrate_serialize_serialize($value);
+assert_eq($data, serialize($value));
+
+$unserialized = integrate_serialize_unserialize($data, true);
+assert_eq($unserialized["b"], [true, null, 1.5]);
+assert_eq(get_class($unserialized["c"]), "IntegrationSerializeFoo");
+
+$unserialized = integrate_serialize_unserialize($data, false);
+assert_eq(get_class($unserialized["c"]), "__PHP_Incomplete_Class");
+
+$unserialized = integrate_serialize_unserialize($data, ["integrationserializefoo"]);
+assert_eq($unserialized["c"]->name, "foo");
+
+assert_throw(function () {
+    integrate_serialize_serialize(function () {});
+}, "Exception", 0, "Serialization of 'Closure' is not allowed");
+
+integrate_serialize_unserialize_malformed();
@@ -0,0 +1,80 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+// The session save handler apis are only available on PHP 7.1 or later.
+if (PHP_VERSION_ID >= 70100) {
+    ini_set("session.save_handler", "phpertest");
+    ini_set("session.use_cookies", "0");
+    ini_set("session.use_strict_mode", "0");
+    integrate_sessions_take_calls();
+
+    // Read and write.
+    integrate_sessions_store("phper-session", 'count|i:41;');
+    session_id("phper-session");
+    assert_true(session_start());
+    assert_eq($_SESSION, ["count" => 41]);
+    $_SESSION["count"]++;
+    session_write_close();
+    assert_eq(integrate_sessions_stored("phper-session"), 'count|i:42;');
+    assert_eq(integrate_sessions_take_calls(), [
+        "open:PHPSESSID",
+        "read:phper-session",
+        "write:phper-session",
+        "close",
+    ]);
+
+    // The unchanged session only updates the timestamp.
+    assert_true(session_start());
+    session_write_close();
+    assert_eq(integrate_sessions_take_calls(), [
+        "open:PHPSESSID",
+        "read:phper-session",
+        "update_timestamp:phper-session",
+        "close",
+    ]);
+
+    // Destroy.
+    assert_true(session_start());
+    assert_true(session_destroy());
+    assert_eq(integrate_sessions_stored("phper-session"), null);
+    assert_eq(integrate_sessions_take_calls(), [
+        "open:PHPSESSID",
+        "read:phper-session",
+        "destroy:phper-session",
+        "close",
+    ]);
+
+    // The new session id is created by the handler.
+    assert_true(session_start());
+    $id = session_id();
+    assert_eq(strpos($id, "phpertest"), 0);
+    session_abort();
+    assert_eq(integrate_sessions_take_calls(), [
+        "open:PHPSESSID",
+        "create_sid:" . $id,
+        "read:" . $id,
+        "close",
+    ]);
+
+    // The failed read aborts the session.
+    session_id("unreadable");
+    assert_false(@session_start());
+    assert_eq(session_status(), PHP_SESSION_NONE);
+    assert_eq(integrate_sessions_take_calls(), [
+        "open:PHPSESSID",
+        "read:unreadable",
+        "close",
+    ]);
+}
@@ -0,0 +1,38 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+function calls_of(array $stats, string $name): int {
+    return isset($stats[$name]) ? $stats[$name]["calls"] : 0;
+}
+
+$before = integration_phper_stats();
+
+integrate_arguments_long(1, 2);
+integrate_arguments_long(3, 4);
+$a = new IntegrationTest\A("foo", 1);
+$a->speak();
+
+$after = integration_phper_stats();
+assert_eq(calls_of($after, "integrate_arguments_long") - calls_of($before, "integrate_arguments_long"), 2);
+assert_eq(calls_of($after, "IntegrationTest\\A::speak") - calls_of($before, "IntegrationTest\\A::speak"), 1);
+assert_true(is_float($after["integrate_arguments_long"]["time"]));
+assert_true($after["integrate_arguments_long"]["time"] >= 0.0);
+
+// The failed calls before the handler runs aren't counted.
+try {
+    integrate_arguments_long();
+} catch (Throwable $e) {
+}
+assert_eq(calls_of(integration_phper_stats(), "integrate_arguments_long"), calls_of($after, "integrate_arguments_long"));
@@ -0,0 +1,35 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+$path = tempnam(sys_get_temp_dir(), "phper");
+assert_eq(integrate_streams_roundtrip($path), "world");
+assert_eq(file_get_contents($path), "hello\nworld");
+assert_eq(integrate_streams_read($path), "hello\nworld");
+
+// The stream wrappers are used.
+assert_eq(integrate_streams_read("data://text/plain;base64,SGVsbG8="), "Hello");
+assert_eq(integrate_streams_read("php://memory"), "");
+
+assert_throw(function () {
+    @integrate_streams_read($path . ".not_exists");
+}, "ErrorException", 0, "failed to open stream \"" . $path . ".not_exists\" with mode \"rb\"");
+
+// The `open_basedir` is honored, keep it in the end, it can't be loosened.
+ini_set("open_basedir", dirname($path));
+assert_eq(integrate_streams_read($path), "hello\nworld");
+assert_throw(function () {
+    @integrate_streams_read(__FILE__);
+}, "ErrorException", 0, "failed to open stream \"" . __FILE__ . "\" with mode \"rb\"");
+unlink($path);
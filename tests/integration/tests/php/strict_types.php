@@ -0,0 +1,31 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+declare(strict_types=1);
+
+require_once __DIR__ . '/_common.php';
+
+assert_true(integrate_strict_types_caller());
+
+assert_eq(integrate_strict_types_long(42), 42);
+assert_throw(function () { integrate_strict_types_long("42"); }, "TypeError", 0, "type error: must be of type int, string given");
+assert_throw(function () { integrate_strict_types_long(true); }, "TypeError", 0, "type error: must be of type int, bool given");
+
+assert_eq(integrate_strict_types_double(1.5), 1.5);
+assert_eq(integrate_strict_types_double(1), 1.0);
+assert_throw(function () { integrate_strict_types_double("1.5"); }, "TypeError", 0, "type error: must be of type float, string given");
+
+assert_eq(integrate_strict_types_bool(true), true);
+assert_throw(function () { integrate_strict_types_bool(1); }, "TypeError", 0, "type error: must be of type bool, int given");
+
+assert_eq(integrate_strict_types_string("foo"), "foo");
+assert_throw(function () { integrate_strict_types_string(42); }, "TypeError", 0, "type error: must be of type string, int given");
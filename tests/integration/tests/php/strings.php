@@ -14,3 +14,9 @@
 require_once __DIR__ . '/_common.php';
 
 integrate_strings_zend_string_new();
+integrate_strings_zend_string_cmp();
+integrate_strings_zend_string_builder();
+
+assert_eq(integrate_strings_zend_string_builder_join(["foo", "bar", "baz"]), "foo,bar,baz");
+assert_eq(integrate_strings_zend_string_builder_join([]), "");
+assert_eq(strlen(integrate_strings_zend_string_builder_join(array_fill(0, 10000, "abc"))), 39999);
@@ -0,0 +1,51 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+function symbols_function_scope($a, $b) {
+    $c = [1, 2];
+    $d = &$a;
+    unset($b);
+    $result = integrate_symbols_local_variables();
+    return $result;
+}
+
+// The unset and not yet assigned variables are skipped, the references are
+// dereferenced.
+assert_eq(symbols_function_scope(10, "b"), ["a" => 10, "c" => [1, 2], "d" => 10]);
+
+// The variables are shared, modifying them later doesn't change the snapshot.
+function symbols_snapshot() {
+    $items = ["foo"];
+    $snapshot = integrate_symbols_local_variables();
+    $items[] = "bar";
+    return $snapshot;
+}
+assert_eq(symbols_snapshot(), ["items" => ["foo"]]);
+
+$closure_outer = "outer";
+$closure = function ($x) use ($closure_outer) {
+    return integrate_symbols_local_variables();
+};
+assert_eq($closure(1), ["x" => 1, "closure_outer" => "outer"]);
+
+// The main script scope is the global symbol table.
+$symbols_global = 1;
+assert_true(integrate_symbols_calling_scope_exists("symbols_global"));
+assert_true(array_key_exists("symbols_global", integrate_symbols_local_variables()));
+
+function symbols_not_in_global() {
+    return integrate_symbols_calling_scope_exists("symbols_global");
+}
+assert_false(symbols_not_in_global());
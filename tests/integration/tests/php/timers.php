@@ -0,0 +1,39 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+$request = integrate_timers_request();
+assert_true($request["elapsed"] > 0);
+assert_true($request["anchored"]);
+assert_true($request["before_now"]);
+
+$duration = integrate_timers_span("sleep", 2000);
+assert_true($duration >= 0.002);
+
+integrate_timers_nested();
+
+// The open spans are closed in `RSHUTDOWN`, not listed before.
+integrate_timers_open("leaked");
+
+$spans = integrate_timers_spans();
+assert_eq(count($spans), 3);
+assert_eq(array_column($spans, "name"), ["sleep", "outer", "inner"]);
+assert_eq(array_column($spans, "depth"), [0, 0, 1]);
+assert_eq(array_column($spans, "finalized"), [false, false, false]);
+assert_eq(array_column($spans, "ordered"), [true, true, true]);
+assert_true($spans[0]["duration"] >= 0.002);
+assert_true($spans[1]["duration"] >= $spans[2]["duration"]);
+
+// The timings of the finished requests are passed to the hook.
+assert_true(integrate_timers_report()["consistent"]);
@@ -0,0 +1,34 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+assert_true(in_array("integration-echo", stream_get_transports()));
+
+$fp = stream_socket_client("integration-echo://localhost:1234", $errno, $errstr, 1);
+assert_true(is_resource($fp));
+assert_eq(fgets($fp), "hello localhost:1234\n");
+assert_eq(fwrite($fp, "ping"), 4);
+assert_eq(fread($fp, 4), "ping");
+assert_eq(fread($fp, 4), "");
+assert_true(feof($fp));
+assert_eq(stream_get_meta_data($fp)["stream_type"], "phper_socket");
+fclose($fp);
+
+$fp = @stream_socket_client("integration-echo://refused", $errno, $errstr, 1);
+assert_false($fp);
+assert_eq($errstr, "connection refused by echo");
+
+$fp = fsockopen("integration-echo://example.com", 80);
+assert_eq(fgets($fp), "hello example.com:80\n");
+fclose($fp);
@@ -0,0 +1,44 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+$files = integrate_requests_uploaded_files();
+
+if (($_SERVER["REQUEST_METHOD"] ?? "") === "POST") {
+    assert_eq(count($files), 2);
+
+    assert_eq($files[0]["field"], "single");
+    assert_eq($files[0]["name"], "a.txt");
+    assert_eq($files[0]["type"], "text/plain");
+    assert_eq($files[0]["tmp_name"], $_FILES["single"]["tmp_name"]);
+    assert_eq($files[0]["error"], UPLOAD_ERR_OK);
+    assert_eq($files[0]["size"], 3);
+    assert_true($files[0]["uploaded"]);
+    assert_true(integrate_requests_is_uploaded_file($files[0]["tmp_name"]));
+
+    assert_eq($files[1]["field"], "multi[0]");
+    assert_eq($files[1]["name"], "b.txt");
+    assert_eq($files[1]["tmp_name"], $_FILES["multi"]["tmp_name"][0]);
+    assert_eq($files[1]["size"], 6);
+    assert_true($files[1]["uploaded"]);
+
+    $_FILES["single"]["tmp_name"] = __FILE__;
+    $files = integrate_requests_uploaded_files();
+    assert_eq($files[0]["tmp_name"], __FILE__);
+    assert_false($files[0]["uploaded"]);
+} else {
+    assert_eq($files, []);
+}
+
+assert_false(integrate_requests_is_uploaded_file(__FILE__));
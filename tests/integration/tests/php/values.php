@@ -29,3 +29,55 @@ assert_eq(integration_values_return_ebox_i64(), 64);
 assert_eq(integration_values_return_result_string_ok(), "foo");
 assert_throw("integration_values_return_result_string_err", "ErrorException", 0, "a zhe");
 assert_eq(integration_values_return_val(), "foo");
+integration_values_copies();
+
+class IntegrationValuesDump {
+    public $public = 1;
+    protected $protected = [1.5, true, null];
+    private $private = "foo";
+}
+
+$values = [
+    null,
+    false,
+    100,
+    -1.5,
+    "foo",
+    ["a" => 1, 2 => ["nested" => "bar"], 3 => []],
+    new IntegrationValuesDump(),
+    fopen("php://memory", "r"),
+];
+foreach ($values as $value) {
+    ob_start();
+    var_dump($value);
+    assert_eq(integration_values_dump($value), ob_get_clean());
+}
+
+$recursive = new stdClass();
+$recursive->self = $recursive;
+ob_start();
+var_dump($recursive);
+assert_eq(integration_values_dump($recursive), ob_get_clean());
+
+$payload = str_repeat("a", 1024 * 1024);
+assert_eq(integration_values_borrow_bytes($payload), 1024 * 1024);
+assert_eq(integration_values_borrow_bytes("\xff\x00"), 2);
+assert_eq(integration_values_pass_through($payload), $payload);
+assert_eq(integration_values_borrow_str("foo"), "FOO");
+assert_throw(function () {
+    integration_values_borrow_bytes(1);
+}, "TypeError", 0, "type error: must be of type string, int given");
+
+assert_eq(integration_values_defined_parameters(1), [true, false]);
+assert_eq(integration_values_defined_parameters(1, null), [true, true]);
+assert_eq(integration_values_as_option(null), "none");
+assert_eq(integration_values_as_option(0), 0);
+assert_eq(integration_values_as_option(""), "");
+
+$undef_global = 1;
+unset($undef_global);
+assert_false(array_key_exists("undef_global", integration_values_array_keys($GLOBALS)));
+assert_eq(integration_values_array_defined($GLOBALS, "undef_global"), [false, false]);
+$defined_global = null;
+assert_true(array_key_exists("defined_global", integration_values_array_keys($GLOBALS)));
+assert_eq(integration_values_array_defined($GLOBALS, "defined_global"), [true, true]);
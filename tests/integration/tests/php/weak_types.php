@@ -0,0 +1,32 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+assert_false(integrate_strict_types_caller());
+
+assert_eq(integrate_strict_types_long(42), 42);
+assert_eq(integrate_strict_types_long("42"), 42);
+assert_eq(integrate_strict_types_long(true), 1);
+assert_throw(function () { integrate_strict_types_long("foo"); }, "TypeError", 0, "type error: must be of type int, string given");
+assert_throw(function () { integrate_strict_types_long([]); }, "TypeError", 0, "type error: must be of type int, array given");
+
+assert_eq(integrate_strict_types_double(1), 1.0);
+assert_eq(integrate_strict_types_double("1.5"), 1.5);
+
+assert_eq(integrate_strict_types_bool(1), true);
+assert_eq(integrate_strict_types_bool(""), false);
+
+assert_eq(integrate_strict_types_string(42), "42");
+assert_eq(integrate_strict_types_string(1.5), "1.5");
+assert_throw(function () { integrate_strict_types_string([]); }, "TypeError", 0, "type error: must be of type string, array given");
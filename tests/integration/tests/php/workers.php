@@ -0,0 +1,29 @@
+<?php
+
+// Copyright (c) 2022 PHPER Framework Team
+// PHPER is licensed under Mulan PSL v2.
+// You can use this software according to the terms and conditions of the Mulan
+// PSL v2. You may obtain a copy of Mulan PSL v2 at:
+//          http://license.coscl.org.cn/MulanPSL2
+// THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY
+// KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO
+// NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+// See the Mulan PSL v2 for more details.
+
+
+require_once __DIR__ . '/_common.php';
+
+foreach ([1, 2, 3, 8] as $threads) {
+    assert_eq(integrate_workers_map_squares([1, 2, 3, 4, 5], $threads), [1, 4, 9, 16, 25]);
+    assert_eq(integrate_workers_map_squares(["a" => 2, 10 => 3], $threads), ["a" => 4, 10 => 9]);
+    assert_eq(integrate_workers_map_squares([], $threads), []);
+}
+assert_throw(function () { integrate_workers_map_squares(["foo"], 2); }, "TypeError", 0, "type error: must be of type int, string given");
+
+assert_eq(integrate_workers_count_primes(100), 25);
+assert_eq(integrate_workers_count_primes(10000), 1229);
+
+assert_true(integrate_workers_reused());
+assert_eq(integrate_workers_nested(), 100);
+
+assert_throw(function () { integrate_workers_panic(); }, "ErrorException", 0, "the worker thread panicked");